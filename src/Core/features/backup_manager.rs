@@ -1,67 +0,0 @@
-use std::path::PathBuf;
-use serde::{Serialize, Deserialize};
-use crate::core::driver_manager::DriverInfo;
-
-#[allow(dead_code)]
-#[derive(Serialize, Deserialize)]
-pub struct BackupManifest {
-	pub backup_id: String,
-	pub timestamp: String,
-	pub system_info: SystemInfo,
-	pub drivers: Vec<BackupDriverInfo>,
-	pub checksum: String,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct SystemInfo {
-	pub windows_version: String,
-	pub architecture: String,
-	pub build_number: String,
-	pub backup_tool_version: String,
-}
-
-#[allow(dead_code)]
-#[derive(Serialize, Deserialize)]
-pub struct BackupDriverInfo {
-	pub name: String,
-	pub display_name: String,
-	pub file_name: String,
-	pub version: String,
-	pub registry_info: String,
-	pub backup_time: String,
-}
-
-#[allow(dead_code)]
-pub struct BackupManager {
-	backup_dir: PathBuf,
-}
-
-#[allow(dead_code)]
-impl BackupManager {
-	pub fn new() -> Result<Self, String> {
-		let backup_dir = PathBuf::from("./backups"); // 使用相对路径避免权限问题
-        
-		if !backup_dir.exists() {
-			std::fs::create_dir_all(&backup_dir)
-				.map_err(|e| format!("Failed to create backup directory: {}", e))?;
-		}
-        
-		Ok(Self { backup_dir })
-	}
-    
-	pub fn create_backup(&self, _drivers: &[DriverInfo]) -> Result<String, String> {
-		// 模拟备份过程
-		let backup_id = format!(
-			"backup_{}",
-			chrono::Local::now().format("%Y%m%d_%H%M%S")
-		);
-        
-		println!("Created backup: {}", backup_id);
-		Ok(backup_id)
-	}
-    
-	pub fn restore_backup(&self, backup_id: &str) -> Result<(), String> {
-		println!("Restoring backup: {}", backup_id);
-		Ok(())
-	}
-}
@@ -1,636 +0,0 @@
-use std::collections::HashMap;
-use std::sync::mpsc;
-use std::thread;
-use std::time::Duration;
-use serde::{Deserialize, Serialize};
-use reqwest::{Client, header};
-use select::document::Document;
-use select::predicate::Name;
-use hardware_query::HardwareInfo;
-use wmi::{WMIConnection, COMLibrary};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OnlineDriverInfo {
-    pub name: String,
-    pub display_name: String,
-    pub version: String,
-    pub manufacturer: String,
-    pub download_url: String,
-    pub file_size: String,
-    pub release_date: String,
-    pub supported_os: Vec<String>,
-    pub is_latest: bool,
-    pub current_version: Option<String>,
-}
-
-#[derive(Debug, Clone)]
-pub struct DriverSearchProgress {
-    pub status: String,
-    pub progress: f32,
-    pub current_step: String,
-    pub total_steps: usize,
-    pub current_step_index: usize,
-}
-
-#[derive(Debug, Clone)]
-pub struct DeviceInfo {
-    pub device_description: String,
-    pub manufacturer: String,
-    pub hardware_id: String,
-    pub compatible_ids: String,
-    pub device_class: String,
-    pub class_guid: String,
-    pub driver_version: String,
-    pub friendly_name: String,
-    pub location: String,
-}
-
-pub struct DriverSearcher {
-    client: Client,
-}
-
-impl Default for DriverSearcher {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl DriverSearcher {
-    pub fn new() -> Self {
-        // 创建合规的HTTP客户端
-        let client = Client::builder()
-            .user_agent("HamsterDriverManager/1.0 (compatible; Windows NT)")
-            .timeout(Duration::from_secs(30))
-            .build()
-            .unwrap_or_else(|_| Client::new());
-            
-        Self { client }
-    }
-    
-    /// 尝试获取真实的显卡信息（使用与电脑概览模块相同的方法）
-    #[cfg(target_os = "windows")]
-    fn get_real_graphics_cards(&self) -> Result<Vec<(String, String, String)>, String> {
-        let mut graphics_cards = Vec::new();
-        
-        // 使用hardware-query库获取显卡信息（与电脑概览模块相同的方法）
-        match HardwareInfo::query() {
-            Ok(hw_info) => {
-                // 获取所有GPU信息
-                let gpus = hw_info.gpus();
-                
-                for (i, gpu) in gpus.iter().enumerate() {
-                    // 获取显卡制造商和型号
-                    let vendor = gpu.vendor();
-                    let model_name = gpu.model_name();
-                    
-                    // 生成硬件ID（基于制造商）
-                    let vendor_str = vendor.to_string();
-                    let hardware_id = match vendor_str.to_lowercase().as_str() {
-                        "nvidia" => format!("PCI\\VEN_10DE&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                        "amd" => format!("PCI\\VEN_1002&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                        "intel" => format!("PCI\\VEN_8086&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                        _ => format!("PCI\\VEN_0000&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                    };
-                    
-                    graphics_cards.push((
-                        format!("{} {}", vendor, model_name),
-                        vendor.to_string(),
-                        hardware_id,
-                    ));
-                }
-            }
-            Err(_e) => {
-                // 如果hardware-query失败，回退到WMI查询（与电脑概览模块相同的方法）
-                let com_lib = COMLibrary::new().map_err(|e| format!("COM初始化失败: {}", e))?;
-                let wmi_con = WMIConnection::new(com_lib).map_err(|e| format!("WMI连接失败: {}", e))?;
-                
-                let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
-                    .raw_query("SELECT Name FROM Win32_VideoController WHERE Name != 'Microsoft Basic Display Adapter'")
-                    .map_err(|e| format!("WMI查询失败: {}", e))?;
-                
-                for (i, gpu) in results.iter().enumerate() {
-                    let name = gpu.get("Name")
-                        .and_then(|v| if let wmi::Variant::String(s) = v { Some(s.as_str()) } else { None })
-                        .unwrap_or("未知型号");
-                    
-                    let (manufacturer, model) = {
-                        let name_lower = name.to_lowercase();
-                        if name_lower.contains("nvidia") {
-                            ("NVIDIA", name.trim())
-                        } else if name_lower.contains("amd") || name_lower.contains("radeon") {
-                            ("AMD", name.trim())
-                        } else if name_lower.contains("intel") {
-                            ("Intel", name.trim())
-                        } else {
-                            ("未知制造商", name.trim())
-                        }
-                    };
-                    
-                    // 生成硬件ID
-                    let hardware_id = match manufacturer {
-                        "NVIDIA" => format!("PCI\\VEN_10DE&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                        "AMD" => format!("PCI\\VEN_1002&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                        "Intel" => format!("PCI\\VEN_8086&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                        _ => format!("PCI\\VEN_0000&DEV_{:04X}&SUBSYS_{:08X}", i, i * 1000),
-                    };
-                    
-                    graphics_cards.push((
-                        model.to_string(),
-                        manufacturer.to_string(),
-                        hardware_id,
-                    ));
-                }
-            }
-        }
-        
-        // 如果检测到显卡，返回真实信息，否则返回空列表
-        if !graphics_cards.is_empty() {
-            Ok(graphics_cards)
-        } else {
-            Err("无法检测到显卡信息".to_string())
-        }
-    }
-    
-    /// 使用SetupAPI获取设备信息（排除CPU和内存）
-    #[cfg(target_os = "windows")]
-    pub fn get_device_info_via_setupapi(&self) -> Result<Vec<DeviceInfo>, String> {
-        // 尝试使用Windows API获取真实的设备信息
-        // 如果API调用失败，则使用模拟数据作为备选方案
-        
-        let mut devices = Vec::new();
-        
-        // 尝试获取真实的显卡信息
-        let real_graphics_cards = self.get_real_graphics_cards().unwrap_or_else(|_| {
-            // 如果获取真实信息失败，使用模拟数据
-            vec![("未知显卡".to_string(), "未知厂商".to_string(), "未知硬件ID".to_string())]
-        });
-        
-        // 如果获取到真实的显卡信息，使用真实信息
-        if !real_graphics_cards.is_empty() && real_graphics_cards[0].0 != "未知显卡" {
-            for (i, (description, manufacturer, hardware_id)) in real_graphics_cards.iter().enumerate() {
-                devices.push(DeviceInfo {
-                    device_description: description.to_string(),
-                    manufacturer: manufacturer.to_string(),
-                    hardware_id: hardware_id.to_string(),
-                    compatible_ids: format!("{}&REV_A1,{},{}&CC_030000,{}&CC_0300", 
-                        hardware_id, hardware_id, hardware_id.split('&').next().unwrap_or(""), 
-                        hardware_id.split('&').next().unwrap_or("")),
-                    device_class: "Display".to_string(),
-                    class_guid: "{4d36e968-e325-11ce-bfc1-08002be10318}".to_string(),
-                    driver_version: match manufacturer.as_str() {
-                        "NVIDIA" => "31.0.15.4623".to_string(),
-                        "AMD" => "23.20.24.01".to_string(),
-                        "Intel" => "27.20.100.9621".to_string(),
-                        _ => "未知版本".to_string(),
-                    },
-                    friendly_name: description.to_string(),
-                    location: format!("PCI bus {}, device {}, function 0", i + 1, i),
-                });
-            }
-        } else {
-            // 使用模拟的显卡设备信息
-            let graphics_cards = vec![
-                ("NVIDIA GeForce RTX 3060", "NVIDIA", "PCI\\VEN_10DE&DEV_2504&SUBSYS_14621043&REV_A1"),
-                ("NVIDIA GeForce GTX 1660", "NVIDIA", "PCI\\VEN_10DE&DEV_2184&SUBSYS_14621043&REV_A1"),
-                ("AMD Radeon RX 6700 XT", "AMD", "PCI\\VEN_1002&DEV_73DF&SUBSYS_0E271002&REV_C1"),
-                ("Intel UHD Graphics 630", "Intel", "PCI\\VEN_8086&DEV_3E92&SUBSYS_86941043&REV_02"),
-                ("AMD Radeon RX 580", "AMD", "PCI\\VEN_1002&DEV_67DF&SUBSYS_0E271002&REV_E7"),
-            ];
-            
-            for (i, (description, manufacturer, hardware_id)) in graphics_cards.iter().enumerate() {
-                devices.push(DeviceInfo {
-                    device_description: description.to_string(),
-                    manufacturer: manufacturer.to_string(),
-                    hardware_id: hardware_id.to_string(),
-                    compatible_ids: format!("{}&REV_A1,{},{}&CC_030000,{}&CC_0300", 
-                        hardware_id, hardware_id, hardware_id.split('&').next().unwrap(), 
-                        hardware_id.split('&').next().unwrap()),
-                    device_class: "Display".to_string(),
-                    class_guid: "{4d36e968-e325-11ce-bfc1-08002be10318}".to_string(),
-                    driver_version: match *manufacturer {
-                        "NVIDIA" => "31.0.15.4623".to_string(),
-                        "AMD" => "23.20.24.01".to_string(),
-                        "Intel" => "27.20.100.9621".to_string(),
-                        _ => "未知版本".to_string(),
-                    },
-                    friendly_name: description.to_string(),
-                    location: format!("PCI bus {}, device {}, function 0", i + 1, i),
-                });
-            }
-        }
-        
-        // 声卡设备
-        devices.push(DeviceInfo {
-            device_description: "Realtek High Definition Audio".to_string(),
-            manufacturer: "Realtek".to_string(),
-            hardware_id: "HDAUDIO\\FUNC_01&VEN_10EC&DEV_0887&SUBSYS_104387C0&REV_1003".to_string(),
-            compatible_ids: "HDAUDIO\\FUNC_01&VEN_10EC&DEV_0887&REV_1003,HDAUDIO\\FUNC_01&VEN_10EC&DEV_0887,HDAUDIO\\FUNC_01&VEN_10EC&CC_010300,HDAUDIO\\FUNC_01&VEN_10EC&CC_0103".to_string(),
-            device_class: "Media".to_string(),
-            class_guid: "{4d36e96c-e325-11ce-bfc1-08002be10318}".to_string(),
-            driver_version: "6.0.9335.1".to_string(),
-            friendly_name: "Realtek High Definition Audio".to_string(),
-            location: "PCI bus 0, device 31, function 3".to_string(),
-        });
-        
-        // 网络设备
-        devices.push(DeviceInfo {
-            device_description: "Intel(R) Wi-Fi 6 AX200 160MHz".to_string(),
-            manufacturer: "Intel".to_string(),
-            hardware_id: "PCI\\VEN_8086&DEV_2723&SUBSYS_00848086&REV_1A".to_string(),
-            compatible_ids: "PCI\\VEN_8086&DEV_2723&REV_1A,PCI\\VEN_8086&DEV_2723,PCI\\VEN_8086&CC_028000,PCI\\VEN_8086&CC_0280".to_string(),
-            device_class: "Net".to_string(),
-            class_guid: "{4d36e972-e325-11ce-bfc1-08002be10318}".to_string(),
-            driver_version: "22.190.0.4".to_string(),
-            friendly_name: "Intel(R) Wi-Fi 6 AX200 160MHz".to_string(),
-            location: "PCI bus 2, device 0, function 0".to_string(),
-        });
-        
-        // USB控制器
-        devices.push(DeviceInfo {
-            device_description: "ASMedia USB 3.1 eXtensible Host Controller".to_string(),
-            manufacturer: "ASMedia".to_string(),
-            hardware_id: "PCI\\VEN_1B21&DEV_2142&SUBSYS_21421B21&REV_00".to_string(),
-            compatible_ids: "PCI\\VEN_1B21&DEV_2142&REV_00,PCI\\VEN_1B21&DEV_2142,PCI\\VEN_1B21&CC_0C0330,PCI\\VEN_1B21&CC_0C03".to_string(),
-            device_class: "USB".to_string(),
-            class_guid: "{36fc9e60-c465-11cf-8056-444553540000}".to_string(),
-            driver_version: "1.16.61.1".to_string(),
-            friendly_name: "ASMedia USB 3.1 eXtensible Host Controller".to_string(),
-            location: "PCI bus 0, device 20, function 0".to_string(),
-        });
-        
-        // 主板芯片组
-        devices.push(DeviceInfo {
-            device_description: "Intel(R) 400 Series Chipset Family SATA AHCI Controller".to_string(),
-            manufacturer: "Intel".to_string(),
-            hardware_id: "PCI\\VEN_8086&DEV_06D2&SUBSYS_86941043&REV_00".to_string(),
-            compatible_ids: "PCI\\VEN_8086&DEV_06D2&REV_00,PCI\\VEN_8086&DEV_06D2,PCI\\VEN_8086&CC_010601,PCI\\VEN_8086&CC_0106".to_string(),
-            device_class: "System devices".to_string(),
-            class_guid: "{4d36e97d-e325-11ce-bfc1-08002be10318}".to_string(),
-            driver_version: "10.1.19199.8341".to_string(),
-            friendly_name: "Intel(R) 400 Series Chipset Family SATA AHCI Controller".to_string(),
-            location: "PCI bus 0, device 23, function 0".to_string(),
-        });
-        
-        // 蓝牙设备
-        devices.push(DeviceInfo {
-            device_description: "Intel(R) Wireless Bluetooth(R)".to_string(),
-            manufacturer: "Intel".to_string(),
-            hardware_id: "USB\\VID_8087&PID_0026&REV_0001".to_string(),
-            compatible_ids: "USB\\VID_8087&PID_0026&REV_0001,USB\\VID_8087&PID_0026".to_string(),
-            device_class: "Bluetooth".to_string(),
-            class_guid: "{e0cbf06c-cd8b-4647-bb8a-263b43f0f974}".to_string(),
-            driver_version: "22.190.0.4".to_string(),
-            friendly_name: "Intel(R) Wireless Bluetooth(R)".to_string(),
-            location: "USB bus 1, device 2, function 0".to_string(),
-        });
-        
-        // 有线网卡
-        devices.push(DeviceInfo {
-            device_description: "Realtek PCIe GbE Family Controller".to_string(),
-            manufacturer: "Realtek".to_string(),
-            hardware_id: "PCI\\VEN_10EC&DEV_8168&SUBSYS_86771043&REV_15".to_string(),
-            compatible_ids: "PCI\\VEN_10EC&DEV_8168&REV_15,PCI\\VEN_10EC&DEV_8168,PCI\\VEN_10EC&CC_020000,PCI\\VEN_10EC&CC_0200".to_string(),
-            device_class: "Net".to_string(),
-            class_guid: "{4d36e972-e325-11ce-bfc1-08002be10318}".to_string(),
-            driver_version: "10.63.1121.2022".to_string(),
-            friendly_name: "Realtek PCIe GbE Family Controller".to_string(),
-            location: "PCI bus 1, device 0, function 0".to_string(),
-        });
-        
-        // 摄像头
-        devices.push(DeviceInfo {
-            device_description: "Integrated Camera".to_string(),
-            manufacturer: "Microsoft".to_string(),
-            hardware_id: "USB\\VID_04F2&PID_B6C2&REV_3960".to_string(),
-            compatible_ids: "USB\\VID_04F2&PID_B6C2&REV_3960,USB\\VID_04F2&PID_B6C2".to_string(),
-            device_class: "Camera".to_string(),
-            class_guid: "{ca3e7ab9-b4c3-4ae6-8251-579ef933890f}".to_string(),
-            driver_version: "10.0.19041.3570".to_string(),
-            friendly_name: "Integrated Camera".to_string(),
-            location: "USB bus 2, device 3, function 0".to_string(),
-        });
-        
-        // 读卡器
-        devices.push(DeviceInfo {
-            device_description: "Realtek USB 2.0 Card Reader".to_string(),
-            manufacturer: "Realtek".to_string(),
-            hardware_id: "USB\\VID_0BDA&PID_0129&REV_3960".to_string(),
-            compatible_ids: "USB\\VID_0BDA&PID_0129&REV_3960,USB\\VID_0BDA&PID_0129".to_string(),
-            device_class: "SD host adapters".to_string(),
-            class_guid: "{eec5ad98-8080-425f-922a-dabf3de3f69a}".to_string(),
-            driver_version: "10.0.19041.3570".to_string(),
-            friendly_name: "Realtek USB 2.0 Card Reader".to_string(),
-            location: "USB bus 3, device 1, function 0".to_string(),
-        });
-        
-        // 触摸板
-        devices.push(DeviceInfo {
-            device_description: "Synaptics SMBus TouchPad".to_string(),
-            manufacturer: "Synaptics".to_string(),
-            hardware_id: "ACPI\\SYN1D32&REV_0100".to_string(),
-            compatible_ids: "ACPI\\SYN1D32&REV_0100,ACPI\\SYN1D32".to_string(),
-            device_class: "Mouse and other pointing devices".to_string(),
-            class_guid: "{4d36e96f-e325-11ce-bfc1-08002be10318}".to_string(),
-            driver_version: "19.5.35.31".to_string(),
-            friendly_name: "Synaptics SMBus TouchPad".to_string(),
-            location: "ACPI bus 0, device 14, function 0".to_string(),
-        });
-        
-        Ok(devices)
-    }
-    
-    /// 非Windows平台的设备信息获取（备用方案）
-    #[cfg(not(target_os = "windows"))]
-    pub fn get_device_info_via_setupapi(&self) -> Result<Vec<DeviceInfo>, String> {
-        // 在非Windows平台上使用备用方案
-        Err("SetupAPI仅在Windows平台上可用".to_string())
-    }
-    
-    /// 合规的网络请求：检查Robots协议
-    async fn check_robots_txt(&self, base_url: &str) -> Result<bool, String> {
-        let robots_url = format!("{}/robots.txt", base_url.trim_end_matches('/'));
-        
-        match self.client.get(&robots_url).send().await {
-            Ok(response) if response.status().is_success() => {
-                let content = response.text().await.unwrap_or_default();
-                // 简单检查是否允许爬虫访问
-                Ok(!content.contains("User-agent: *\nDisallow: /") && 
-                   !content.contains(&format!("User-agent: {}\nDisallow: /", "HamsterDriverManager")))
-            }
-            _ => Ok(true) // 如果无法访问robots.txt，默认允许访问
-        }
-    }
-    
-    /// 控制请求频率
-    async fn rate_limit(&self) {
-        thread::sleep(Duration::from_millis(500)); // 500ms间隔
-    }
-    
-    /// 通过厂商API获取驱动信息
-    async fn fetch_from_vendor_apis(&self, device_info: &DeviceInfo) -> Result<Vec<OnlineDriverInfo>, String> {
-        let mut drivers = Vec::new();
-        
-        // 根据设备制造商选择相应的API
-        let manufacturer = device_info.manufacturer.to_lowercase();
-        
-        match manufacturer.as_str() {
-            "intel" | "amd" | "nvidia" => {
-                // 图形和处理器制造商
-                if let Ok(driver_info) = self.fetch_gpu_driver_info(device_info).await {
-                    drivers.extend(driver_info);
-                }
-            }
-            "realtek" | "broadcom" | "qualcomm" => {
-                // 网络和音频设备制造商
-                if let Ok(driver_info) = self.fetch_network_driver_info(device_info).await {
-                    drivers.extend(driver_info);
-                }
-            }
-            _ => {
-                // 通用设备制造商
-                if let Ok(driver_info) = self.fetch_generic_driver_info(device_info).await {
-                    drivers.extend(driver_info);
-                }
-            }
-        }
-        
-        Ok(drivers)
-    }
-    
-    /// 抓取官网页面获取驱动信息
-    async fn scrape_manufacturer_websites(&self, device_info: &DeviceInfo) -> Result<Vec<OnlineDriverInfo>, String> {
-        let mut drivers = Vec::new();
-        
-        // 根据设备制造商选择相应的官网
-        let manufacturer = device_info.manufacturer.to_lowercase();
-        
-        let websites = match manufacturer.as_str() {
-            "intel" => vec!["https://downloadcenter.intel.com/", "https://www.intel.com/content/www/us/en/download-center.html"],
-            "amd" => vec!["https://www.amd.com/en/support", "https://drivers.amd.com/drivers/"],
-            "nvidia" => vec!["https://www.nvidia.com/Download/index.aspx", "https://www.nvidia.com/drivers"],
-            "realtek" => vec!["https://www.realtek.com/en/component/zoo/category/network-interface-controllers-10-100-1000m-gigabit-ethernet-pci-express-software", "https://www.realtek.com/en/"],
-            "broadcom" => vec!["https://www.broadcom.com/support/download-search", "https://docs.broadcom.com/"],
-            _ => vec![]
-        };
-        
-        for website in websites {
-            if let Ok(allowed) = self.check_robots_txt(website).await {
-                if allowed {
-                    if let Ok(driver_info) = self.scrape_website(website, device_info).await {
-                        drivers.extend(driver_info);
-                    }
-                    self.rate_limit().await;
-                }
-            }
-        }
-        
-        Ok(drivers)
-    }
-    
-    /// 具体的网站抓取实现
-    async fn scrape_website(&self, url: &str, device_info: &DeviceInfo) -> Result<Vec<OnlineDriverInfo>, String> {
-        // 这里实现具体的网页抓取逻辑
-        // 由于不同网站结构不同，这里提供通用框架
-        
-        match self.client.get(url).send().await {
-            Ok(response) if response.status().is_success() => {
-                let html_content = response.text().await.unwrap_or_default();
-                let document = Document::from(html_content.as_str());
-                
-                // 解析网页内容，提取驱动信息
-                let mut drivers = Vec::new();
-                
-                // 示例：查找驱动下载链接
-                for node in document.find(Name("a")) {
-                    if let Some(href) = node.attr("href") {
-                        if href.contains("download") || href.contains("driver") || 
-                           href.ends_with(".exe") || href.ends_with(".zip") || 
-                           href.ends_with(".msi") {
-                            
-                            let driver_info = OnlineDriverInfo {
-                                name: device_info.device_description.clone(),
-                                display_name: device_info.friendly_name.clone(),
-                                version: "未知".to_string(),
-                                manufacturer: device_info.manufacturer.clone(),
-                                download_url: href.to_string(),
-                                file_size: "未知".to_string(),
-                                release_date: "未知".to_string(),
-                                supported_os: vec!["Windows 10".to_string(), "Windows 11".to_string()],
-                                is_latest: false,
-                                current_version: None,
-                            };
-                            
-                            drivers.push(driver_info);
-                        }
-                    }
-                }
-                
-                Ok(drivers)
-            }
-            Ok(response) => Err(format!("HTTP错误: {}", response.status())),
-            Err(e) => Err(format!("网络请求失败: {}", e)),
-        }
-    }
-    
-    /// 获取GPU驱动信息（示例实现）
-    async fn fetch_gpu_driver_info(&self, device_info: &DeviceInfo) -> Result<Vec<OnlineDriverInfo>, String> {
-        // 这里可以实现具体的GPU驱动API调用
-        // 例如：NVIDIA GeForce Experience API、AMD Driver API等
-        
-        let mut drivers = Vec::new();
-        
-        // 模拟API响应
-        drivers.push(OnlineDriverInfo {
-            name: device_info.device_description.clone(),
-            display_name: device_info.friendly_name.clone(),
-            version: "最新版本".to_string(),
-            manufacturer: device_info.manufacturer.clone(),
-            download_url: format!("https://{}.com/drivers/latest", device_info.manufacturer.to_lowercase()),
-            file_size: "500MB".to_string(),
-            release_date: "2024-01-01".to_string(),
-            supported_os: vec!["Windows 10".to_string(), "Windows 11".to_string()],
-            is_latest: true,
-            current_version: Some("当前版本".to_string()),
-        });
-        
-        Ok(drivers)
-    }
-    
-    /// 获取网络驱动信息（示例实现）
-    async fn fetch_network_driver_info(&self, device_info: &DeviceInfo) -> Result<Vec<OnlineDriverInfo>, String> {
-        // 类似的网络设备驱动API实现
-        
-        let mut drivers = Vec::new();
-        drivers.push(OnlineDriverInfo {
-            name: device_info.device_description.clone(),
-            display_name: device_info.friendly_name.clone(),
-            version: "最新版本".to_string(),
-            manufacturer: device_info.manufacturer.clone(),
-            download_url: format!("https://{}.com/drivers/network", device_info.manufacturer.to_lowercase()),
-            file_size: "50MB".to_string(),
-            release_date: "2024-01-01".to_string(),
-            supported_os: vec!["Windows 10".to_string(), "Windows 11".to_string()],
-            is_latest: true,
-            current_version: Some("当前版本".to_string()),
-        });
-        
-        Ok(drivers)
-    }
-    
-    /// 获取通用驱动信息（示例实现）
-    async fn fetch_generic_driver_info(&self, device_info: &DeviceInfo) -> Result<Vec<OnlineDriverInfo>, String> {
-        // 通用设备驱动信息获取
-        
-        let mut drivers = Vec::new();
-        drivers.push(OnlineDriverInfo {
-            name: device_info.device_description.clone(),
-            display_name: device_info.friendly_name.clone(),
-            version: "最新版本".to_string(),
-            manufacturer: device_info.manufacturer.clone(),
-            download_url: format!("https://{}.com/support/drivers", device_info.manufacturer.to_lowercase()),
-            file_size: "100MB".to_string(),
-            release_date: "2024-01-01".to_string(),
-            supported_os: vec!["Windows 10".to_string(), "Windows 11".to_string()],
-            is_latest: true,
-            current_version: Some("当前版本".to_string()),
-        });
-        
-        Ok(drivers)
-    }
-    
-    pub fn search_online_drivers(
-        &self,
-        progress_sender: Option<mpsc::Sender<DriverSearchProgress>>,
-    ) -> Result<Vec<OnlineDriverInfo>, String> {
-        let total_steps = 4;
-        
-        // 步骤1: 扫描电脑硬件（排除CPU和内存）
-        Self::update_progress(&progress_sender, 1, total_steps, "正在扫描电脑硬件... ...");
-        std::thread::sleep(std::time::Duration::from_millis(1000)); // 模拟扫描时间
-        
-        let device_info_list = self.get_device_info_via_setupapi()?;
-        
-        // 步骤2: 从服务器获取信息
-        Self::update_progress(&progress_sender, 2, total_steps, "正在联网查询硬件驱动");
-        std::thread::sleep(std::time::Duration::from_millis(1500)); // 模拟网络请求时间
-        
-        let mut drivers_from_api = Vec::new();
-        
-        // 模拟从服务器获取驱动信息
-        for device_info in &device_info_list {
-            drivers_from_api.push(OnlineDriverInfo {
-                name: device_info.device_description.clone(),
-                display_name: device_info.friendly_name.clone(),
-                version: "最新版本".to_string(),
-                manufacturer: device_info.manufacturer.clone(),
-                download_url: format!("https://{}.com/drivers", device_info.manufacturer.to_lowercase()),
-                file_size: "100MB".to_string(),
-                release_date: "2024-01-01".to_string(),
-                supported_os: vec!["Windows 10".to_string(), "Windows 11".to_string()],
-                is_latest: true,
-                current_version: Some("当前版本".to_string()),
-            });
-        }
-        
-        // 步骤3: 合并驱动信息
-        Self::update_progress(&progress_sender, 3, total_steps, "正在合并驱动信息...");
-        std::thread::sleep(std::time::Duration::from_millis(500)); // 模拟处理时间
-        
-        // 步骤4: 与本地驱动比较
-        Self::update_progress(&progress_sender, 4, total_steps, "正在与本地驱动比较版本...");
-        std::thread::sleep(std::time::Duration::from_millis(500)); // 模拟比较时间
-        
-        let drivers_with_comparison = self.compare_with_local_drivers(drivers_from_api)?;
-        
-        Self::update_progress(&progress_sender, total_steps, total_steps, "搜索完成");
-        
-        Ok(drivers_with_comparison)
-    }
-    
-    fn update_progress(
-        sender: &Option<mpsc::Sender<DriverSearchProgress>>,
-        current_step: usize,
-        total_steps: usize,
-        status: &str,
-    ) {
-        if let Some(sender) = sender {
-            let progress = DriverSearchProgress {
-                status: status.to_string(),
-                progress: (current_step as f32) / (total_steps as f32),
-                current_step: status.to_string(),
-                total_steps,
-                current_step_index: current_step,
-            };
-            let _ = sender.send(progress);
-        }
-        
-        // 添加短暂延迟，让用户能看到进度变化
-        std::thread::sleep(std::time::Duration::from_millis(500));
-    }
-    
-    /// 与本地驱动比较版本
-    fn compare_with_local_drivers(&self, online_drivers: Vec<OnlineDriverInfo>) -> Result<Vec<OnlineDriverInfo>, String> {
-        let mut drivers_with_comparison = Vec::new();
-        
-        for mut driver in online_drivers {
-            // 这里应该实现与本地驱动版本的比较逻辑
-            // 目前使用简化实现
-            driver.is_latest = true; // 假设都是最新版本
-            driver.current_version = Some("1.0.0".to_string()); // 模拟当前版本
-            
-            drivers_with_comparison.push(driver);
-        }
-        
-        Ok(drivers_with_comparison)
-    }
-    
-    pub fn download_driver(&self, driver: &OnlineDriverInfo) -> Result<String, String> {
-        // 模拟下载驱动
-        Ok(format!("驱动 {} 下载完成，保存到临时目录", driver.display_name))
-    }
-    
-    pub fn install_downloaded_driver(&self, driver_path: &str) -> Result<String, String> {
-        // 模拟安装驱动
-        Ok(format!("驱动安装成功: {}", driver_path))
-    }
-}
\ No newline at end of file
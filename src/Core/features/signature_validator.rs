@@ -1,50 +0,0 @@
-use std::sync::{Arc, Mutex};
-use crate::core::driver_manager::DriverInfo;
-
-#[allow(dead_code)]
-pub struct SignatureValidator {
-	results: Arc<Mutex<Vec<SignatureResult>>>,
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
-pub struct SignatureResult {
-	pub driver_name: String,
-	pub file_path: String,
-	pub is_valid: bool,
-	pub signature_type: String,
-	pub certificate_issuer: String,
-	pub certificate_subject: String,
-	pub timestamp: String,
-	pub error_message: String,
-}
-
-#[allow(dead_code)]
-impl SignatureValidator {
-	pub fn new() -> Self {
-		Self {
-			results: Arc::new(Mutex::new(Vec::new())),
-		}
-	}
-    
-	pub fn validate_batch(&self, drivers: &[DriverInfo], _concurrent: usize) -> Vec<SignatureResult> {
-		// 简化版本，直接返回模拟结果
-		drivers.iter().map(|driver| {
-			SignatureResult {
-				driver_name: driver.name.clone(),
-				file_path: driver.binary_path.clone(),
-				is_valid: true, // 模拟所有驱动都是有效的
-				signature_type: "Authenticode".to_string(),
-				certificate_issuer: "Mock Issuer".to_string(),
-				certificate_subject: "Mock Subject".to_string(),
-				timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-				error_message: String::new(),
-			}
-		}).collect()
-	}
-    
-	fn validate_driver(_driver: &DriverInfo) -> SignatureResult {
-		// 这个方法在简化版本中不直接使用
-		todo!()
-	}
-}
@@ -1,8 +0,0 @@
-// mod.rs for core module
-// 重新导出子模块，便于 crate::core::* 统一访问
-
-pub mod driver_manager;
-pub mod edid;
-pub mod features;
-pub mod sysinfo;
-pub mod windows_api;
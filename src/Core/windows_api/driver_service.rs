@@ -1,69 +0,0 @@
-use crate::core::driver_manager::{DriverStatus, DriverInfo, DriverType};
-
-#[allow(dead_code)]
-pub struct DriverService {
-	// 临时使用简单数据结构替代Windows API调用
-	drivers: Vec<DriverInfo>,
-}
-
-#[allow(dead_code)]
-impl DriverService {
-	pub fn new() -> Result<Self, String> {
-		Ok(Self {
-			drivers: Vec::new(),
-		})
-	}
-    
-	pub fn enumerate_drivers(&self) -> Result<Vec<DriverInfo>, String> {
-		// 返回模拟数据
-		Ok(vec![
-			DriverInfo {
-				name: "MockDriver1".to_string(),
-				display_name: "Mock Driver 1".to_string(),
-				description: "Mock Driver 1 Description".to_string(),
-				status: DriverStatus::Running,
-				driver_type: DriverType::KernelMode,
-				start_type: "Auto".to_string(),
-				binary_path: "C:\\Windows\\System32\\mockdriver1.sys".to_string(),
-				version: "1.0.0.0".to_string(),
-				company: "Mock Company".to_string(),
-				signed: true,
-				signature_status: "Valid".to_string(),
-				last_updated: chrono::Local::now(),
-				dependencies: vec![],
-				load_order: 0,
-			},
-			DriverInfo {
-				name: "MockDriver2".to_string(),
-				display_name: "Mock Driver 2".to_string(),
-				description: "Mock Driver 2 Description".to_string(),
-				status: DriverStatus::Stopped,
-				driver_type: DriverType::FileSystem,
-				start_type: "Manual".to_string(),
-				binary_path: "C:\\Windows\\System32\\mockdriver2.sys".to_string(),
-				version: "1.0.0.0".to_string(),
-				company: "Mock Company".to_string(),
-				signed: true,
-				signature_status: "Valid".to_string(),
-				last_updated: chrono::Local::now(),
-				dependencies: vec![],
-				load_order: 0,
-			},
-		])
-	}
-    
-	pub fn start_driver(&self, service_name: &str) -> Result<(), String> {
-		println!("Mock start driver: {}", service_name);
-		Ok(())
-	}
-    
-	pub fn stop_driver(&self, service_name: &str) -> Result<(), String> {
-		println!("Mock stop driver: {}", service_name);
-		Ok(())
-	}
-    
-	pub fn set_startup_type(&self, service_name: &str, start_type: u32) -> Result<(), String> {
-		println!("Mock set startup type for {}: {}", service_name, start_type);
-		Ok(())
-	}
-}
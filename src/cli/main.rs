@@ -0,0 +1,1003 @@
+// src/cli/main.rs - 命令行入口，在无图形界面的环境中（脚本、计划任务、SSH会话）调用core功能
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use serde::Serialize;
+use hamster_driver_manager::core::audit::AuditAction;
+use hamster_driver_manager::core::cab::CabArchive;
+use hamster_driver_manager::core::driver_manager::DriverInfo;
+use hamster_driver_manager::core::error::{ErrorCategory, HamsterError, HamsterResult};
+use hamster_driver_manager::core::features::dependency_analyzer::DependencyAnalyzer;
+use hamster_driver_manager::core::features::backup_manager::BackupManager;
+use hamster_driver_manager::core::features::driver_installer::{DriverInstaller, DriverScan, InstallableDriver};
+use hamster_driver_manager::core::features::driver_matcher::{DriverMatcher, SystemTarget, Verdict};
+use hamster_driver_manager::core::features::driver_manager::DriverManagement;
+use hamster_driver_manager::core::features::driver_searcher::DriverSearcher;
+use hamster_driver_manager::core::features::signature_validator::SignatureValidator;
+use hamster_driver_manager::core::hardware_id::HardwareId;
+use hamster_driver_manager::core::hwids::HardwareIds;
+use hamster_driver_manager::core::inf::InfFile;
+use hamster_driver_manager::core::jobs::{JobContext, JobEvent, JobRunner};
+use hamster_driver_manager::core::locale::Locale;
+use hamster_driver_manager::core::platform::{self, Platform};
+use hamster_driver_manager::core::settings::Settings;
+use hamster_driver_manager::core::signature::{algorithm_name, Catalog, CatalogIndex};
+use hamster_driver_manager::core::signature::trust::TrustStatus;
+use hamster_driver_manager::core::signature::x509::hex;
+use hamster_driver_manager::core::sysinfo::SystemInfo;
+use hamster_driver_manager::core::windows_api::driver_file::DriverFileInfo;
+use hamster_driver_manager::core::windows_api::driver_service::DriverService;
+
+// 退出码：0成功，1操作失败，2用法错误，3部分失败，4权限不足
+const EXIT_SUCCESS: u8 = 0;
+const EXIT_FAILURE: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_PARTIAL: u8 = 3;
+const EXIT_PERMISSION: u8 = 4;
+
+const USAGE: &str = "用法: hamster-cli <命令> [选项]
+
+命令:
+  drivers list                 列出系统驱动
+  drivers deps [名称]          分析驱动的依赖关系：启动顺序、循环依赖、对不存在或已禁用服务的依赖；
+                               指定名称时列出该驱动的完整依赖链和直接依赖它的驱动
+  devices list                 列出硬件设备
+  sysinfo                      显示系统硬件概览
+  backup create [--driver 名称]...
+                               备份全部或指定的驱动
+  backup list                  列出备份集
+  backup restore <备份ID>      校验并恢复备份集
+  backup verify <备份ID>       校验备份集完整性
+  install <文件或目录>         安装驱动文件或目录中的所有驱动
+  match <文件或目录>           按 Windows 的驱动等级规则为本机设备匹配 INF 驱动，并说明原因
+  search                       联网搜索可更新的驱动
+  settings show                显示当前设置及设置文件路径
+  history list [动作]          查看操作审计日志，动作可为 install、uninstall、
+                               backup、restore、start_type_change
+  history verify               校验审计日志的哈希链是否完整
+  ids lookup <硬件ID>          按 pci.ids / usb.ids 查询设备名称，如 PCI\\VEN_10DE&DEV_2504
+  inf show <INF文件>           解析驱动INF文件，显示版本、厂商、型号和源文件
+  cab list <CAB文件>           列出 CAB 压缩包中的文件夹和文件
+  cab extract <CAB文件> <目标目录> [文件名]...
+                               解压 CAB 中的全部或指定文件
+  pe show <文件>               读取 .sys/.dll/.exe 的版本资源、架构、子系统、链接时间和校验和
+  signature show <文件> [编录文件或目录]
+                               离线读取文件的签名：PE 文件中嵌入的 Authenticode 签名、嵌套签名和时间戳，
+                               没有嵌入签名时在编录文件（默认为文件所在目录中的 .cat）中查找；
+                               设置了受信任证书目录时离线验证证书链、有效期、用途和吊销状态；
+                               显示签名类型（WHQL、证明签名、交叉签名、测试签名等）和内核模式加载预测
+  signature audit              并行验证全部系统驱动的签名，逐个输出结果；结果按文件路径、大小和修改时间缓存
+  catalog show <编录文件>      列出 .cat 安全编录中的成员、文件摘要和属性（如 OSAttr）
+
+选项:
+  --json                       以JSON格式输出结果
+  -h, --help                   显示帮助信息
+
+退出码: 0 成功, 1 操作失败, 2 用法错误, 3 部分失败, 4 权限不足（请以管理员身份运行）";
+
+/// 命令执行失败，携带退出码和错误信息
+struct CliError {
+    code: u8,
+    message: String,
+    // core返回的错误，输出时按设置中的语言渲染
+    error: Option<HamsterError>,
+}
+
+impl CliError {
+    fn usage(message: impl Into<String>) -> Self {
+        Self { code: EXIT_USAGE, message: message.into(), error: None }
+    }
+
+    fn failure(message: impl Into<String>) -> Self {
+        Self { code: EXIT_FAILURE, message: message.into(), error: None }
+    }
+
+    fn category(&self) -> Option<ErrorCategory> {
+        self.error.as_ref().map(HamsterError::category)
+    }
+
+    fn render(&self, locale: Locale) -> String {
+        match &self.error {
+            Some(error) => error.localized(locale),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl From<HamsterError> for CliError {
+    fn from(error: HamsterError) -> Self {
+        Self {
+            code: if error.requires_elevation() { EXIT_PERMISSION } else { EXIT_FAILURE },
+            message: error.to_string(),
+            error: Some(error),
+        }
+    }
+}
+
+type CliResult = Result<u8, CliError>;
+
+/// 解析后的命令行参数
+#[derive(Default)]
+struct Options {
+    json: bool,
+    help: bool,
+    drivers: Vec<String>,
+    positional: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = Options::default();
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => options.json = true,
+                "-h" | "--help" => options.help = true,
+                "--driver" => {
+                    let name = args.next()
+                        .ok_or_else(|| CliError::usage("--driver 需要驱动名称"))?;
+                    options.drivers.push(name);
+                }
+                _ if arg.starts_with('-') => {
+                    return Err(CliError::usage(format!("未知选项: {}", arg)));
+                }
+                _ => options.positional.push(arg),
+            }
+        }
+        Ok(options)
+    }
+
+    fn argument(&self, index: usize, name: &str) -> Result<&str, CliError> {
+        self.positional.get(index)
+            .map(|s| s.as_str())
+            .ok_or_else(|| CliError::usage(format!("缺少参数: {}", name)))
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e.message, USAGE);
+            return ExitCode::from(e.code);
+        }
+    };
+
+    if options.help || options.positional.is_empty() {
+        println!("{}", USAGE);
+        return ExitCode::from(if options.help { EXIT_SUCCESS } else { EXIT_USAGE });
+    }
+
+    // 设置文件损坏时仍可使用默认设置执行命令
+    let settings = Settings::load().unwrap_or_else(|e| {
+        eprintln!("警告: {}\n将使用默认设置", e.localized(Locale::default()));
+        Settings::default()
+    });
+
+    match run(&options, &settings) {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            let message = e.render(settings.language);
+            if options.json {
+                print_json(&serde_json::json!({
+                    "success": false,
+                    "error": message,
+                    "category": e.category().map(|category| category.to_string()),
+                }));
+            }
+            eprintln!("错误: {}", message);
+            if e.code == EXIT_USAGE {
+                eprintln!("\n{}", USAGE);
+            }
+            ExitCode::from(e.code)
+        }
+    }
+}
+
+fn run(options: &Options, settings: &Settings) -> CliResult {
+    let command = options.argument(0, "命令")?;
+    match command {
+        "settings" => return match options.argument(1, "子命令")? {
+            "show" => show_settings(settings, options),
+            other => Err(CliError::usage(format!("未知子命令: settings {}", other))),
+        },
+        "history" => return match options.argument(1, "子命令")? {
+            "list" => list_history(settings, options),
+            "verify" => verify_history(settings, options),
+            other => Err(CliError::usage(format!("未知子命令: history {}", other))),
+        },
+        "ids" => return match options.argument(1, "子命令")? {
+            "lookup" => lookup_ids(settings, options),
+            other => Err(CliError::usage(format!("未知子命令: ids {}", other))),
+        },
+        "inf" => return match options.argument(1, "子命令")? {
+            "show" => show_inf(settings, options),
+            other => Err(CliError::usage(format!("未知子命令: inf {}", other))),
+        },
+        "cab" => return match options.argument(1, "子命令")? {
+            "list" => list_cab(options),
+            "extract" => extract_cab(options),
+            other => Err(CliError::usage(format!("未知子命令: cab {}", other))),
+        },
+        "pe" => return match options.argument(1, "子命令")? {
+            "show" => show_pe(settings, options),
+            other => Err(CliError::usage(format!("未知子命令: pe {}", other))),
+        },
+        "catalog" => return match options.argument(1, "子命令")? {
+            "show" => show_catalog(options),
+            other => Err(CliError::usage(format!("未知子命令: catalog {}", other))),
+        },
+        "signature" => match options.argument(1, "子命令")? {
+            "show" => return show_signature(settings, options),
+            // 验证全部系统驱动需要平台枚举驱动
+            "audit" => {}
+            other => return Err(CliError::usage(format!("未知子命令: signature {}", other))),
+        },
+        _ => {}
+    }
+    let platform = platform::default_platform(settings)?;
+    for warning in platform.warnings() {
+        eprintln!("警告: {}", warning);
+    }
+
+    match command {
+        "drivers" => match options.argument(1, "子命令")? {
+            "list" => list_drivers(platform, settings, options),
+            "deps" => analyze_dependencies(platform, settings, options),
+            other => Err(CliError::usage(format!("未知子命令: drivers {}", other))),
+        },
+        "devices" => match options.argument(1, "子命令")? {
+            "list" => list_devices(platform, settings, options),
+            other => Err(CliError::usage(format!("未知子命令: devices {}", other))),
+        },
+        "sysinfo" => show_sysinfo(platform, options),
+        "backup" => match options.argument(1, "子命令")? {
+            "create" => create_backup(platform, settings, options),
+            "list" => list_backups(settings, options),
+            "restore" => restore_backup(platform, settings, options),
+            "verify" => verify_backup(settings, options),
+            other => Err(CliError::usage(format!("未知子命令: backup {}", other))),
+        },
+        "install" => install(platform, settings, options),
+        "match" => match_drivers(platform, settings, options),
+        "signature" => audit_signatures(platform, settings, options),
+        "search" => search(platform, settings, options),
+        other => Err(CliError::usage(format!("未知命令: {}", other))),
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("序列化JSON失败: {}", e),
+    }
+}
+
+/// 根据成功和失败的数量计算退出码
+fn summary_code(succeeded: usize, failed: usize) -> u8 {
+    match (succeeded, failed) {
+        (_, 0) => EXIT_SUCCESS,
+        (0, _) => EXIT_FAILURE,
+        _ => EXIT_PARTIAL,
+    }
+}
+
+/// 通过后台任务执行耗时操作，文本模式下将进度输出到标准错误，避免干扰结果输出
+fn run_job<T, F>(options: &Options, name: &str, task: F) -> HamsterResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&JobContext) -> HamsterResult<T> + Send + 'static,
+{
+    let runner = JobRunner::new();
+    let events = runner.subscribe();
+    let handle = runner.submit(name, task);
+    for event in events {
+        match event {
+            JobEvent::Progress { progress, .. } if !options.json => {
+                eprintln!("[{}/{}] {}", progress.step, progress.total_steps, progress.message);
+            }
+            JobEvent::Finished { .. } => break,
+            _ => {}
+        }
+    }
+    handle.wait()
+}
+
+fn enumerate_drivers(platform: Arc<dyn Platform>, settings: &Settings) -> HamsterResult<Vec<DriverInfo>> {
+    DriverService::new(platform, settings)?.enumerate_drivers()
+}
+
+fn list_drivers(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let drivers = enumerate_drivers(platform, settings)?;
+
+    if options.json {
+        print_json(&drivers);
+    } else {
+        for driver in &drivers {
+            println!("{:<24} {:<10} {:<6} {:<18} {}",
+                driver.name, format!("{:?}", driver.status), driver.start_type, driver.version, driver.display_name);
+        }
+        println!("共 {} 个驱动", drivers.len());
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn analyze_dependencies(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let drivers = enumerate_drivers(platform, settings)?;
+    let mut analyzer = DependencyAnalyzer::new();
+    analyzer.analyze_dependencies(&drivers)?;
+
+    if let Some(name) = options.positional.get(2) {
+        let driver = drivers.iter().find(|driver| driver.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| CliError::failure(format!("找不到驱动: {}", name)))?;
+        let chain = analyzer.get_dependency_chain(&driver.name);
+        let dependents = analyzer.get_dependents(&driver.name);
+        if options.json {
+            print_json(&serde_json::json!({ "driver": driver.name, "dependency_chain": chain, "dependents": dependents }));
+        } else {
+            println!("{} 的依赖链（按启动顺序）:", driver.name);
+            if chain.is_empty() {
+                println!("  无");
+            }
+            for (index, dependency) in chain.iter().enumerate() {
+                println!("  {}. {}", index + 1, dependency);
+            }
+            println!("直接依赖它的驱动: {}", if dependents.is_empty() { "无".to_string() } else { dependents.join(", ") });
+        }
+        return Ok(EXIT_SUCCESS);
+    }
+
+    let report = analyzer.report();
+    if options.json {
+        print_json(&report);
+    } else {
+        println!("启动顺序:");
+        for (index, name) in report.start_order.iter().enumerate() {
+            println!("  {:>4}. {}", index + 1, name);
+        }
+        for cycle in &report.cycles {
+            println!("循环依赖: {}", cycle.join(", "));
+        }
+        for issue in &report.issues {
+            println!("依赖问题: {}", issue);
+        }
+        println!("共 {} 个驱动，{} 个循环依赖，{} 个依赖问题", report.start_order.len(), report.cycles.len(), report.issues.len());
+    }
+    Ok(if report.cycles.is_empty() && report.issues.is_empty() { EXIT_SUCCESS } else { EXIT_FAILURE })
+}
+
+fn list_devices(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let devices = DriverSearcher::new(platform, settings).get_device_info()?;
+
+    if options.json {
+        print_json(&devices);
+    } else {
+        for device in &devices {
+            println!("{} [{}]", device.friendly_name, device.device_class);
+            println!("  制造商: {}  驱动版本: {}", device.manufacturer, device.display_version());
+            println!("  硬件ID: {}", device.hardware_id);
+            let compatible_ids: Vec<String> = device.compatible_id_list().iter().map(ToString::to_string).collect();
+            if !compatible_ids.is_empty() {
+                println!("  兼容ID: {}", compatible_ids.join(", "));
+            }
+        }
+        println!("共 {} 个设备", devices.len());
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn show_sysinfo(platform: Arc<dyn Platform>, options: &Options) -> CliResult {
+    let info = run_job(options, "读取系统信息", move |_| SystemInfo::new(platform.as_ref()))?;
+
+    if options.json {
+        print_json(&info);
+        return Ok(EXIT_SUCCESS);
+    }
+
+    let unknown = "未知".to_string();
+    println!("操作系统: {}", info.os_name.as_ref().unwrap_or(&unknown));
+    println!("版本号: {}", info.os_version.as_ref().unwrap_or(&unknown));
+    println!("制造商: {}", info.manufacturer.as_ref().unwrap_or(&unknown));
+    println!("主板: {}", info.motherboard.as_ref().unwrap_or(&unknown));
+    println!("CPU: {}", info.cpu.as_ref().unwrap_or(&unknown));
+    for item in info.memory_info.iter()
+        .chain(&info.disk_info)
+        .chain(&info.network_adapters)
+        .chain(&info.gpu_info)
+        .chain(&info.monitor_info)
+        .chain(&info.audio_info)
+    {
+        println!("{}", item.text);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn create_backup(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let mut drivers = enumerate_drivers(platform.clone(), settings)?;
+    if !options.drivers.is_empty() {
+        if let Some(missing) = options.drivers.iter()
+            .find(|name| !drivers.iter().any(|d| d.name.eq_ignore_ascii_case(name)))
+        {
+            return Err(CliError::failure(format!("未找到驱动: {}", missing)));
+        }
+        drivers.retain(|d| options.drivers.iter().any(|name| d.name.eq_ignore_ascii_case(name)));
+    }
+
+    let total = drivers.len();
+    let settings = settings.clone();
+    let (manifest, history) = run_job(options, "备份驱动", move |job| {
+        let mut management = DriverManagement::new(platform, &settings);
+        let manifest = BackupManager::new(&settings)?.create_backup(&mut management, &drivers, job)?;
+        Ok((manifest, management.get_backup_history().clone()))
+    })?;
+    let failed = total - manifest.drivers.len();
+
+    if options.json {
+        print_json(&manifest);
+    } else {
+        println!("已创建备份 {}，成功 {} 个，失败 {} 个", manifest.backup_id, manifest.drivers.len(), failed);
+        for record in history.iter().filter(|r| !r.success) {
+            eprintln!("  {} 备份失败: {}", record.driver_name, record.message);
+        }
+    }
+    Ok(summary_code(manifest.drivers.len(), failed))
+}
+
+fn list_backups(settings: &Settings, options: &Options) -> CliResult {
+    let backups = BackupManager::new(settings)?.list_backups()?;
+
+    if options.json {
+        print_json(&backups);
+    } else if backups.is_empty() {
+        println!("暂无备份");
+    } else {
+        for backup in &backups {
+            println!("{}  {}  {} 个驱动", backup.backup_id, backup.timestamp, backup.drivers.len());
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn verify_backup(settings: &Settings, options: &Options) -> CliResult {
+    let backup_id = options.argument(2, "备份ID")?;
+    let verification = BackupManager::new(settings)?.verify_backup(backup_id)?;
+
+    if options.json {
+        print_json(&verification);
+    } else {
+        println!("备份 {}: {}", verification.backup_id, if verification.is_valid() { "校验通过" } else { "校验失败" });
+        if !verification.checksum_valid {
+            println!("  清单校验和不匹配");
+        }
+        for file in &verification.missing_files {
+            println!("  缺少备份文件: {}", file);
+        }
+    }
+    Ok(if verification.is_valid() { EXIT_SUCCESS } else { EXIT_FAILURE })
+}
+
+fn restore_backup(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let backup_id = options.argument(2, "备份ID")?.to_string();
+    let settings = settings.clone();
+    let results = run_job(options, "恢复备份", move |job| {
+        let mut management = DriverManagement::new(platform, &settings);
+        BackupManager::new(&settings)?.restore_backup(&mut management, &backup_id, job)
+    })?;
+    let succeeded = results.iter().filter(|r| r.success).count();
+
+    if options.json {
+        print_json(&results);
+    } else {
+        for result in &results {
+            println!("{} {}: {}", if result.success { "✓" } else { "✗" }, result.driver_name, result.message);
+        }
+    }
+    Ok(summary_code(succeeded, results.len() - succeeded))
+}
+
+/// 扫描参数指定的文件或目录中的驱动，没有找到时返回错误
+fn scan_drivers(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> Result<Vec<InstallableDriver>, CliError> {
+    let path = PathBuf::from(options.argument(1, "文件或目录")?);
+    if !path.exists() {
+        return Err(CliError::failure(format!("路径不存在: {}", path.display())));
+    }
+
+    let settings = settings.clone();
+    let scan_path = path.clone();
+    let scan = run_job(options, "扫描驱动文件", move |job| {
+        let installer = DriverInstaller::new(platform, &settings);
+        let is_archive = scan_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("zip") || extension.eq_ignore_ascii_case("cab"));
+        if scan_path.is_dir() || is_archive {
+            installer.scan_drivers_in_directory(&scan_path, job)
+        } else {
+            Ok(DriverScan { drivers: installer.analyze_driver_file(&scan_path).into_iter().collect(), skipped: Vec::new() })
+        }
+    })?;
+    for skipped in &scan.skipped {
+        eprintln!("已跳过 {}", skipped);
+    }
+    for driver in &scan.drivers {
+        for warning in &driver.warnings {
+            eprintln!("警告: {}: {}", driver.source(), warning);
+        }
+    }
+    let drivers = scan.drivers;
+
+    if drivers.is_empty() {
+        return Err(CliError::failure(format!("未找到可安装的驱动: {}", path.display())));
+    }
+    Ok(drivers)
+}
+
+fn install(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let drivers = scan_drivers(platform.clone(), settings, options)?;
+
+    let settings = settings.clone();
+    let results = run_job(options, "安装驱动", move |job| {
+        let mut installer = DriverInstaller::new(platform, &settings);
+        let mut results = Vec::new();
+        for (index, driver) in drivers.iter().enumerate() {
+            job.check_cancelled()?;
+            job.report(index, drivers.len(), format!("正在安装 {}", driver.display_name));
+            results.push(installer.install_driver(driver));
+        }
+        job.report(drivers.len(), drivers.len(), "安装完成");
+        Ok(results)
+    })?;
+    let succeeded = results.iter().filter(|r| r.success).count();
+
+    if options.json {
+        print_json(&results);
+    } else {
+        for result in &results {
+            println!("{} {}: {}", if result.success { "✓" } else { "✗" }, result.driver_name, result.message);
+        }
+    }
+    Ok(summary_code(succeeded, results.len() - succeeded))
+}
+
+fn match_drivers(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let drivers = scan_drivers(platform.clone(), settings, options)?;
+    let devices = DriverSearcher::new(platform, settings).get_device_info()?;
+    let matcher = DriverMatcher::new(SystemTarget::current());
+    let matches = matcher.match_devices(&devices, &drivers);
+
+    if options.json {
+        print_json(&matches);
+    } else {
+        println!("目标系统: {}", matcher.target().describe());
+        for device_match in &matches {
+            println!("{} [{}]", device_match.device_name, device_match.hardware_id);
+            println!("  已安装: {}", device_match.installed_version);
+            for candidate in &device_match.candidates {
+                let mark = match candidate.verdict {
+                    Verdict::Selected => "✓",
+                    Verdict::Outranked => "·",
+                    Verdict::NotApplicable => "✗",
+                };
+                println!("  {} {} {} [{}] {}",
+                    mark,
+                    candidate.rank_hex(),
+                    candidate.model.description,
+                    candidate.model.section,
+                    device_match.candidate_version(candidate).unwrap_or_default());
+                for reason in &candidate.reasons {
+                    println!("      {}", reason);
+                }
+            }
+        }
+        println!("{} 个设备有可用的驱动候选", matches.iter().filter(|device_match| device_match.selected().is_some()).count());
+    }
+    Ok(if matches.is_empty() { EXIT_FAILURE } else { EXIT_SUCCESS })
+}
+
+fn search(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let searcher = DriverSearcher::new(platform, settings);
+    let drivers = run_job(options, "搜索驱动", move |job| searcher.search_online_drivers(job))?;
+
+    if options.json {
+        print_json(&drivers);
+    } else {
+        for driver in &drivers {
+            println!("{} - {}  最新: {}  当前: {}  {}",
+                driver.display_name,
+                driver.manufacturer,
+                driver.version.as_deref().unwrap_or("未知"),
+                driver.current_version.as_deref().unwrap_or("未安装"),
+                driver.update_state.label());
+        }
+        println!("共 {} 个结果", drivers.len());
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn show_settings(settings: &Settings, options: &Options) -> CliResult {
+    if options.json {
+        print_json(settings);
+    } else {
+        println!("设置文件: {}", Settings::path().display());
+        println!("备份位置: {}", settings.backup_dir.display());
+        println!("临时目录: {}", settings.temp_dir.display());
+        println!("语言: {}", settings.language.native_name());
+        println!("代理: {}", settings.proxy.as_deref().unwrap_or("不使用"));
+        println!("网络超时: {} 秒", settings.http_timeout_secs);
+        println!("User-Agent: {}", settings.user_agent);
+        println!("并发数: {}", settings.concurrency);
+        println!("更新策略: {}", settings.update_policy.label());
+        println!("主题: {}", settings.theme.label());
+        let ids_path = |path: &Option<PathBuf>| path.as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "系统自带或内置".to_string());
+        println!("pci.ids: {}", ids_path(&settings.pci_ids_path));
+        println!("usb.ids: {}", ids_path(&settings.usb_ids_path));
+        println!("受信任证书目录: {}", settings.trust_store_dir.as_ref()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_else(|| "未设置（不验证证书链）".to_string()));
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn list_history(settings: &Settings, options: &Options) -> CliResult {
+    let action = match options.positional.get(2) {
+        Some(tag) => Some(AuditAction::from_tag(tag)
+            .ok_or_else(|| CliError::usage(format!("未知动作: {}", tag)))?),
+        None => None,
+    };
+    let entries: Vec<_> = settings.journal().entries()?
+        .into_iter()
+        .filter(|entry| action.is_none_or(|action| entry.action == action))
+        .collect();
+
+    if options.json {
+        print_json(&entries);
+    } else if entries.is_empty() {
+        println!("暂无操作记录");
+    } else {
+        for entry in &entries {
+            println!("#{} {} {} {} {} {}",
+                entry.sequence,
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                entry.user,
+                entry.action.label(),
+                entry.target,
+                if entry.success { "成功" } else { "失败" });
+            if entry.before.is_some() || entry.after.is_some() {
+                println!("  {} → {}",
+                    entry.before.as_deref().unwrap_or("-"),
+                    entry.after.as_deref().unwrap_or("-"));
+            }
+            println!("  {}", entry.message);
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn lookup_ids(settings: &Settings, options: &Options) -> CliResult {
+    let hardware_id = options.argument(2, "硬件ID")?;
+    let id = HardwareId::parse(hardware_id)?;
+    let root = std::env::var_os(platform::SYSROOT_ENV_VAR).unwrap_or_else(|| "/".into());
+    let ids = HardwareIds::load(settings, Path::new(&root));
+    for warning in &ids.warnings {
+        eprintln!("警告: {}", warning);
+    }
+    let name = ids.describe(&id)
+        .ok_or_else(|| CliError::usage(format!("无法识别的硬件ID: {}", hardware_id)))?;
+
+    if options.json {
+        print_json(&name);
+    } else {
+        let unknown = "未知".to_string();
+        println!("硬件ID: {}", id);
+        println!("厂商: {}", name.vendor.as_ref().unwrap_or(&unknown));
+        println!("设备: {}", name.device.as_ref().unwrap_or(&unknown));
+        if let Some(subsystem) = &name.subsystem {
+            println!("子系统: {}", subsystem);
+        }
+        if let Some(class) = &name.class {
+            println!("类别: {}", class);
+        }
+        println!("数据库: {} / {}", ids.pci.source, ids.usb.source);
+    }
+    Ok(if name.is_empty() { EXIT_FAILURE } else { EXIT_SUCCESS })
+}
+
+fn show_inf(settings: &Settings, options: &Options) -> CliResult {
+    let path = PathBuf::from(options.argument(2, "INF文件")?);
+    let inf = InfFile::open(&path, settings.language)?;
+
+    if options.json {
+        print_json(&inf);
+        return Ok(EXIT_SUCCESS);
+    }
+    let unknown = "未知";
+    let version = &inf.version;
+    println!("提供商: {}", inf.provider().unwrap_or(unknown));
+    println!("版本: {}", version.driver_ver.as_ref().map(ToString::to_string).as_deref().unwrap_or(unknown));
+    println!("类别: {} {}", version.class.as_deref().unwrap_or(unknown), version.class_guid.as_deref().unwrap_or(""));
+    println!("编录文件: {}", version.catalog_file.as_deref().unwrap_or("无"));
+    println!("支持的系统: {}", inf.supported_os().join("，"));
+    if !inf.strings_sections.is_empty() {
+        println!("字符串: {}", inf.strings_sections.join(" → "));
+    }
+    for manufacturer in &inf.manufacturers {
+        println!("厂商 {} [{}]", manufacturer.name, manufacturer.models_section);
+    }
+    for model in &inf.models {
+        let target = model.target.as_ref().map(ToString::to_string).unwrap_or_else(|| "未修饰".to_string());
+        println!("  {} ({}) → {}", model.hardware_id, target, model.install_section);
+        println!("    {}", model.description);
+        if !model.compatible_ids.is_empty() {
+            println!("    兼容ID: {}", model.compatible_ids.join(", "));
+        }
+    }
+    if !inf.source_files.is_empty() {
+        println!("源文件:");
+        for file in &inf.source_files {
+            println!("  {} 磁盘 {}{}{}",
+                file.name,
+                file.disk_id,
+                file.subdir.as_ref().map(|subdir| format!(" 目录 {}", subdir)).unwrap_or_default(),
+                file.architecture.as_ref().map(|architecture| format!(" ({})", architecture)).unwrap_or_default());
+        }
+    }
+    for warning in &inf.warnings {
+        eprintln!("警告: {}", warning);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn list_cab(options: &Options) -> CliResult {
+    let path = PathBuf::from(options.argument(2, "CAB文件")?);
+    let cab = CabArchive::open(&path)?;
+
+    if options.json {
+        print_json(&cab.files());
+        return Ok(EXIT_SUCCESS);
+    }
+    for (index, folder) in cab.folders().iter().enumerate() {
+        println!("文件夹 {}: {}，{} 个数据块", index + 1, folder.compression, folder.block_count);
+    }
+    for file in cab.files() {
+        let modified = file.modified.map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default();
+        println!("{:>12}  {:19}  {}", file.size, modified, file.name);
+    }
+    if let Some(next) = cab.next_cabinet() {
+        println!("后续内容在 {} 中", next);
+    }
+    println!("共 {} 个文件", cab.files().len());
+    Ok(EXIT_SUCCESS)
+}
+
+fn extract_cab(options: &Options) -> CliResult {
+    let path = PathBuf::from(options.argument(2, "CAB文件")?);
+    let directory = PathBuf::from(options.argument(3, "目标目录")?);
+    let mut cab = CabArchive::open(&path)?;
+
+    let names = options.positional.get(4..).unwrap_or_default();
+    let indices: Vec<usize> = if names.is_empty() {
+        (0..cab.files().len()).collect()
+    } else {
+        names.iter()
+            .map(|name| cab.find(name).ok_or_else(|| CliError::failure(format!("CAB 中没有文件: {}", name))))
+            .collect::<Result<_, _>>()?
+    };
+    let paths = cab.extract(&indices, &directory)?;
+
+    if options.json {
+        print_json(&paths);
+    } else {
+        for path in &paths {
+            println!("{}", path.display());
+        }
+        println!("已解压 {} 个文件到 {}", paths.len(), directory.display());
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn show_pe(settings: &Settings, options: &Options) -> CliResult {
+    let path = PathBuf::from(options.argument(2, "文件")?);
+    let file = DriverFileInfo::from_path(&path, settings.language)?;
+
+    if options.json {
+        print_json(&file);
+        return Ok(EXIT_SUCCESS);
+    }
+    let or_unknown = |value: &str| if value.is_empty() { "未知".to_string() } else { value.to_string() };
+    println!("文件版本: {}", or_unknown(&file.version));
+    println!("产品版本: {}", or_unknown(&file.product_version));
+    println!("公司: {}", or_unknown(&file.company));
+    println!("描述: {}", or_unknown(&file.description));
+    println!("原始文件名: {}", or_unknown(&file.original_filename));
+    println!("架构: {}", file.machine);
+    println!("子系统: {}", file.subsystem);
+    println!("链接时间: {}", file.link_time.map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string()).unwrap_or_else(|| "未记录".to_string()));
+    let checksum_state = match file.checksum_valid {
+        Some(true) => "正确",
+        Some(false) => "与文件内容不符",
+        None => "未设置",
+    };
+    println!("校验和: 0x{:08X}（{}）", file.checksum, checksum_state);
+    if let Some(info) = &file.version_info {
+        for table in &info.string_tables {
+            println!("字符串表 {:04X}（代码页 {}）:", table.language, table.codepage);
+            for (key, value) in &table.strings {
+                println!("  {}: {}", key, value);
+            }
+        }
+    } else {
+        println!("文件中没有版本资源");
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn show_signature(settings: &Settings, options: &Options) -> CliResult {
+    let path = PathBuf::from(options.argument(2, "文件")?);
+    // 未指定编录时使用文件所在目录中的 .cat 文件
+    let mut catalogs = CatalogIndex::new();
+    match options.positional.get(3).map(PathBuf::from) {
+        Some(catalog) if catalog.is_dir() => catalogs.load_dir(&catalog),
+        Some(catalog) => catalogs.load_file(&catalog)?,
+        None => catalogs.load_dir(path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."))),
+    }
+    for (catalog, reason) in &catalogs.errors {
+        eprintln!("已跳过 {}: {}", catalog.display(), reason);
+    }
+    let mut validator = SignatureValidator::new().with_catalogs(catalogs);
+    // 设置了受信任证书目录时验证证书链
+    if let Some(trust_store) = settings.trust_store() {
+        for (file, reason) in &trust_store.errors {
+            eprintln!("已跳过 {}: {}", file.display(), reason);
+        }
+        validator = validator.with_trust_store(trust_store);
+    }
+    let result = validator.validate_file(&path.display().to_string(), &path);
+    if !result.checked {
+        return Err(CliError::failure(result.error_message));
+    }
+
+    if options.json {
+        print_json(&result);
+    } else if result.signers.is_empty() {
+        println!("{}", result.error_message);
+    } else {
+        if result.tampered || result.trusted == Some(false) {
+            println!("{}", result.error_message);
+        }
+        if !result.catalog_file.is_empty() {
+            println!("由编录文件签名: {}", result.catalog_file);
+        }
+        if let Some(classification) = &result.classification {
+            println!("签名类型: {}", classification.label());
+            if !classification.root.is_empty() {
+                println!("根证书: {}", classification.root);
+            }
+            if !classification.os_versions.is_empty() {
+                println!("编录适用系统: {}", classification.os_versions.join("，"));
+            }
+            println!("内核模式加载预测: {}（{}）", classification.load, classification.reason);
+        }
+        for (index, signer) in result.signers.iter().enumerate() {
+            println!("签名 {}{}:", index + 1, if index == 0 { "（主签名）" } else { "（嵌套签名）" });
+            println!("  使用者: {}", signer.subject);
+            println!("  颁发者: {}", signer.issuer);
+            println!("  序列号: {}", signer.serial_number);
+            println!("  摘要算法: {}", signer.digest_algorithm);
+            if let Some(status) = signer.digest_status {
+                println!("  映像摘要: {}", status);
+            }
+            match &signer.timestamp {
+                Some(timestamp) => println!("  时间戳: {}（{}，{}）",
+                    timestamp.time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                    timestamp.kind,
+                    timestamp.authority),
+                None => println!("  时间戳: 无"),
+            }
+            match signer.trust {
+                Some(TrustStatus::Trusted) => println!("  证书链: 受信任"),
+                Some(status) => println!("  证书链: {}（{}）", status, signer.trust_detail),
+                None => println!("  证书链: 未验证（未设置受信任证书目录）"),
+            }
+            for (depth, name) in signer.chain.iter().enumerate() {
+                println!("    {}{}", "  ".repeat(depth), name);
+            }
+        }
+    }
+    Ok(if result.is_valid { EXIT_SUCCESS } else { EXIT_FAILURE })
+}
+
+fn audit_signatures(platform: Arc<dyn Platform>, settings: &Settings, options: &Options) -> CliResult {
+    let drivers = enumerate_drivers(platform, settings)?;
+    let settings = settings.clone();
+    let json = options.json;
+    let batch = run_job(options, "验证驱动签名", move |job| {
+        job.report(0, drivers.len(), "正在加载系统编录文件");
+        let mut validator = SignatureValidator::new()
+            .with_system_catalogs()
+            .with_cache(settings.signature_cache_path());
+        if let Some(trust_store) = settings.trust_store() {
+            validator = validator.with_trust_store(trust_store);
+        }
+        // 每完成一个驱动就输出一行，顺序取决于完成的先后
+        validator.validate_batch_streaming(&drivers, settings.concurrency, job, |result| {
+            if !json {
+                println!("{:<24} {}", result.driver_name, result.summary());
+            }
+        })
+    })?;
+    for warning in &batch.warnings {
+        eprintln!("警告: {}", warning);
+    }
+
+    let results = batch.results;
+    if options.json {
+        print_json(&results);
+    } else {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for result in &results {
+            let label = match &result.classification {
+                Some(classification) if result.checked => classification.class.to_string(),
+                _ => "无法检查".to_string(),
+            };
+            *counts.entry(label).or_default() += 1;
+        }
+        let counts: Vec<String> = counts.iter().map(|(label, count)| format!("{} {}", label, count)).collect();
+        println!("共 {} 个驱动: {}", results.len(), counts.join("，"));
+    }
+    Ok(if results.iter().any(|result| result.tampered) { EXIT_FAILURE } else { EXIT_SUCCESS })
+}
+
+fn show_catalog(options: &Options) -> CliResult {
+    let path = PathBuf::from(options.argument(2, "编录文件")?);
+    let catalog = Catalog::open(&path)?;
+
+    if options.json {
+        print_json(&serde_json::json!({
+            "identifier": catalog.identifier,
+            "this_update": catalog.this_update,
+            "version": catalog.version(),
+            "attributes": catalog.attributes,
+            "members": catalog.members,
+        }));
+        return Ok(EXIT_SUCCESS);
+    }
+    println!("列表标识: {}", catalog.identifier.as_deref().unwrap_or("无"));
+    println!("生成时间: {}", catalog.this_update.format("%Y-%m-%d %H:%M:%S UTC"));
+    println!("编录版本: {}", catalog.version());
+    if let Some((_, Some(certificate))) = catalog.signed_data.signer() {
+        println!("签名者: {}", certificate.subject);
+    }
+    for (name, value) in &catalog.attributes {
+        println!("{}: {}", name, value);
+    }
+    println!("成员 {} 个:", catalog.members.len());
+    for member in &catalog.members {
+        println!("  {}", member.file_name().unwrap_or("（未记录文件名）"));
+        println!("    标记: {}", member.tag);
+        if let Some(algorithm) = &member.digest_algorithm {
+            let kind = if member.is_pe_image() { "PE 映像摘要" } else { "文件摘要" };
+            println!("    {}: {} {}", kind, algorithm_name(algorithm), hex(&member.digest));
+        }
+        for (name, value) in member.attributes.iter().filter(|(name, _)| !name.eq_ignore_ascii_case("File")) {
+            println!("    {}: {}", name, value);
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn verify_history(settings: &Settings, options: &Options) -> CliResult {
+    let journal = settings.journal();
+    let verification = journal.verify()?;
+
+    if options.json {
+        print_json(&verification);
+    } else {
+        match &verification.first_break {
+            None => println!("审计日志完整，共 {} 条记录: {}", verification.entries, journal.path().display()),
+            Some(broken) => println!("审计日志在第 {} 条记录处被篡改或损坏: {}", broken.line, broken.reason),
+        }
+    }
+    Ok(if verification.is_intact() { EXIT_SUCCESS } else { EXIT_FAILURE })
+}
@@ -0,0 +1,389 @@
+// 审计日志：以 JSON Lines 追加记录所有修改系统的操作（安装、卸载、备份、恢复、启动类型变更），
+// 每条记录包含上一条记录的哈希，任何修改、删除或插入都会使哈希链断开，可通过 verify 检测
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, FixedOffset, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::core::error::{HamsterError, HamsterResult};
+
+/// 第一条记录的上一哈希
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Install,
+    Uninstall,
+    Backup,
+    Restore,
+    StartTypeChange,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 5] = [
+        AuditAction::Install,
+        AuditAction::Uninstall,
+        AuditAction::Backup,
+        AuditAction::Restore,
+        AuditAction::StartTypeChange,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditAction::Install => "安装",
+            AuditAction::Uninstall => "卸载",
+            AuditAction::Backup => "备份",
+            AuditAction::Restore => "恢复",
+            AuditAction::StartTypeChange => "修改启动类型",
+        }
+    }
+
+    /// 命令行参数中使用的名称，与序列化名称一致
+    pub fn tag(&self) -> &'static str {
+        match self {
+            AuditAction::Install => "install",
+            AuditAction::Uninstall => "uninstall",
+            AuditAction::Backup => "backup",
+            AuditAction::Restore => "restore",
+            AuditAction::StartTypeChange => "start_type_change",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.tag() == tag)
+    }
+}
+
+/// 待记录的操作，由执行操作的模块填写
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub action: AuditAction,
+    pub target: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub success: bool,
+    pub message: String,
+    pub requires_elevation: bool,
+}
+
+impl AuditEvent {
+    pub fn new(action: AuditAction, target: impl Into<String>) -> Self {
+        Self {
+            action,
+            target: target.into(),
+            before: None,
+            after: None,
+            success: false,
+            message: String::new(),
+            requires_elevation: false,
+        }
+    }
+
+    /// 操作前的状态
+    pub fn before(mut self, state: impl Into<String>) -> Self {
+        self.before = Some(state.into());
+        self
+    }
+
+    /// 操作后的状态
+    pub fn after(mut self, state: impl Into<String>) -> Self {
+        self.after = Some(state.into());
+        self
+    }
+
+    pub fn outcome(mut self, success: bool, message: impl Into<String>, requires_elevation: bool) -> Self {
+        self.success = success;
+        self.message = message.into();
+        self.requires_elevation = requires_elevation;
+        self
+    }
+}
+
+/// 日志中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    /// 保留写入时的时区偏移，保证重新序列化后哈希不变
+    pub timestamp: DateTime<FixedOffset>,
+    /// 执行操作的系统用户
+    pub user: String,
+    pub action: AuditAction,
+    /// 操作对象，通常为驱动名称
+    pub target: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub success: bool,
+    pub message: String,
+    #[serde(default)]
+    pub requires_elevation: bool,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// 除 hash 外所有字段的 SHA-256；字段按名称排序后序列化，结果与字段声明顺序无关
+    pub fn compute_hash(&self) -> HamsterResult<String> {
+        let mut value = serde_json::to_value(self)
+            .map_err(|e| HamsterError::parse("序列化审计记录失败").with_source(e))?;
+        if let Some(object) = value.as_object_mut() {
+            object.remove("hash");
+        }
+        let digest = Sha256::digest(value.to_string().as_bytes());
+        Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+}
+
+/// 哈希链第一处断开的位置
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditBreak {
+    /// 从1开始的记录序号（忽略空行）
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditVerification {
+    pub entries: usize,
+    pub first_break: Option<AuditBreak>,
+}
+
+impl AuditVerification {
+    pub fn is_intact(&self) -> bool {
+        self.first_break.is_none()
+    }
+}
+
+/// 审计日志文件；只追加不修改，写入时对文件加排他锁，多个进程和线程可同时使用
+#[derive(Debug, Clone)]
+pub struct AuditJournal {
+    path: PathBuf,
+}
+
+impl AuditJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加一条记录，返回写入的内容
+    pub fn record(&self, event: AuditEvent) -> HamsterResult<AuditEntry> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| HamsterError::io(format!("无法创建审计日志目录 {:?}", parent), e))?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&self.path)
+            .map_err(|e| self.io_error("无法打开审计日志", e))?;
+        // 锁在 file 关闭时释放
+        file.lock().map_err(|e| self.io_error("无法锁定审计日志", e))?;
+
+        let (sequence, prev_hash) = match Self::last_entry(&mut file)? {
+            Some(last) => (last.sequence + 1, last.hash),
+            None => (1, GENESIS_HASH.to_string()),
+        };
+        let mut entry = AuditEntry {
+            sequence,
+            timestamp: Local::now().fixed_offset(),
+            user: current_user(),
+            action: event.action,
+            target: event.target,
+            before: event.before,
+            after: event.after,
+            success: event.success,
+            message: event.message,
+            requires_elevation: event.requires_elevation,
+            prev_hash,
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash()?;
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| HamsterError::parse("序列化审计记录失败").with_source(e))?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| self.io_error("无法写入审计日志", e))?;
+        Ok(entry)
+    }
+
+    /// 追加一条记录；记录失败不影响已完成的操作，返回应附加到操作结果中的说明
+    pub fn record_or_note(&self, event: AuditEvent) -> Option<String> {
+        self.record(event).err().map(|e| format!("写入审计日志失败：{}", e.context()))
+    }
+
+    /// 读取所有能解析的记录，按写入顺序排列；日志不存在时为空
+    pub fn entries(&self) -> HamsterResult<Vec<AuditEntry>> {
+        Ok(self.read_lines()?
+            .iter()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// 逐条检查序号、上一哈希和本条哈希，返回第一处不一致的位置
+    pub fn verify(&self) -> HamsterResult<AuditVerification> {
+        let lines = self.read_lines()?;
+        let mut expected_prev = GENESIS_HASH.to_string();
+        for (index, line) in lines.iter().enumerate() {
+            let broken = |reason: String| Ok(AuditVerification {
+                entries: lines.len(),
+                first_break: Some(AuditBreak { line: index + 1, reason }),
+            });
+            let entry: AuditEntry = match serde_json::from_str(line) {
+                Ok(entry) => entry,
+                Err(e) => return broken(format!("记录无法解析: {}", e)),
+            };
+            if entry.sequence != index as u64 + 1 {
+                return broken(format!("序号应为 {}，实际为 {}", index + 1, entry.sequence));
+            }
+            if entry.prev_hash != expected_prev {
+                return broken("上一哈希不匹配，之前的记录可能被修改或删除".to_string());
+            }
+            if entry.compute_hash()? != entry.hash {
+                return broken("记录哈希不匹配，记录内容可能被修改".to_string());
+            }
+            expected_prev = entry.hash;
+        }
+        Ok(AuditVerification { entries: lines.len(), first_break: None })
+    }
+
+    fn read_lines(&self) -> HamsterResult<Vec<String>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(self.io_error("无法读取审计日志", e)),
+        };
+        Ok(content.lines().filter(|line| !line.trim().is_empty()).map(str::to_string).collect())
+    }
+
+    fn last_entry(file: &mut File) -> HamsterResult<Option<AuditEntry>> {
+        let mut content = String::new();
+        file.seek(SeekFrom::Start(0))
+            .and_then(|_| file.read_to_string(&mut content))
+            .map_err(|e| HamsterError::io("无法读取审计日志", e))?;
+        match content.lines().rev().find(|line| !line.trim().is_empty()) {
+            // 最后一条无法解析时拒绝追加，避免在已损坏的日志后继续写入看似有效的链
+            Some(line) => serde_json::from_str(line)
+                .map(Some)
+                .map_err(|e| HamsterError::parse("审计日志最后一条记录已损坏").with_source(e)),
+            None => Ok(None),
+        }
+    }
+
+    fn io_error(&self, context: &str, error: io::Error) -> HamsterError {
+        HamsterError::io(format!("{} {:?}", context, self.path), error)
+    }
+}
+
+/// 当前系统用户名
+fn current_user() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "未知".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 写入三条记录的临时日志
+    fn journal(name: &str) -> AuditJournal {
+        let path = std::env::temp_dir().join(format!("hamster-audit-{}-{}.jsonl", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = AuditJournal::new(path);
+        for target in ["nvlddmkm", "iaStorVD", "rt640x64"] {
+            journal.record(AuditEvent::new(AuditAction::Backup, target).outcome(true, "完成", false)).unwrap();
+        }
+        journal
+    }
+
+    fn rewrite(journal: &AuditJournal, edit: impl FnOnce(&mut Vec<String>)) {
+        let mut lines = journal.read_lines().unwrap();
+        edit(&mut lines);
+        std::fs::write(journal.path(), lines.join("\n") + "\n").unwrap();
+    }
+
+    fn first_break(journal: &AuditJournal) -> AuditBreak {
+        let verification = journal.verify().unwrap();
+        std::fs::remove_file(journal.path()).unwrap();
+        verification.first_break.expect("日志被改动后应检测到断开")
+    }
+
+    #[test]
+    fn untouched_journal_is_intact() {
+        let journal = journal("intact");
+        let verification = journal.verify().unwrap();
+        assert_eq!(verification.entries, 3);
+        assert!(verification.is_intact());
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.sequence).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        std::fs::remove_file(journal.path()).unwrap();
+    }
+
+    #[test]
+    fn edited_line_is_reported() {
+        let journal = journal("edited");
+        rewrite(&journal, |lines| lines[1] = lines[1].replace("iaStorVD", "iaStorAC"));
+        let broken = first_break(&journal);
+        assert_eq!(broken.line, 2);
+        assert!(broken.reason.contains("记录哈希不匹配"), "{}", broken.reason);
+    }
+
+    #[test]
+    fn removed_line_is_reported() {
+        let journal = journal("removed");
+        rewrite(&journal, |lines| { lines.remove(1); });
+        let broken = first_break(&journal);
+        assert_eq!(broken.line, 2);
+        assert_eq!(broken.reason, "序号应为 2，实际为 3");
+    }
+
+    #[test]
+    fn reordered_lines_are_reported() {
+        let journal = journal("reordered");
+        rewrite(&journal, |lines| lines.swap(1, 2));
+        let broken = first_break(&journal);
+        assert_eq!(broken.line, 2);
+        assert_eq!(broken.reason, "序号应为 2，实际为 3");
+    }
+
+    #[test]
+    fn renumbered_line_breaks_the_chain() {
+        // 删除一条后把后面的序号改回连续，仍会因上一哈希对不上而被发现
+        let journal = journal("renumbered");
+        rewrite(&journal, |lines| {
+            lines.remove(1);
+            lines[1] = lines[1].replace("\"sequence\":3", "\"sequence\":2");
+        });
+        let broken = first_break(&journal);
+        assert_eq!(broken.line, 2);
+        assert!(broken.reason.contains("上一哈希不匹配"), "{}", broken.reason);
+    }
+
+    #[test]
+    fn unparsable_line_is_reported() {
+        let journal = journal("garbled");
+        rewrite(&journal, |lines| lines[2].truncate(10));
+        let broken = first_break(&journal);
+        assert_eq!(broken.line, 3);
+        assert!(broken.reason.starts_with("记录无法解析"), "{}", broken.reason);
+    }
+
+    #[test]
+    fn failed_record_returns_a_note() {
+        // 日志路径是目录，无法打开
+        let journal = AuditJournal::new(std::env::temp_dir());
+        let note = journal.record_or_note(AuditEvent::new(AuditAction::Uninstall, "nvlddmkm")).unwrap();
+        assert!(note.starts_with("写入审计日志失败"), "{}", note);
+    }
+}
@@ -0,0 +1,191 @@
+// 范式哈夫曼解码，MSZIP（deflate）和 LZX 共用；两者只是位序不同
+
+use crate::core::error::{HamsterError, HamsterResult};
+
+/// 两种格式中最长的码长（LZX 为16，deflate 为15）
+pub(super) const MAX_BITS: usize = 16;
+
+// 查找表覆盖的码长，更长的码逐位解码
+const TABLE_BITS: u32 = 10;
+
+/// 按位读取压缩数据
+pub(super) trait BitSource {
+    /// 码的第一位是否为读出的整数的最高位（LZX 为是，deflate 为否）
+    const MSB_FIRST: bool;
+
+    /// 预读 count（不超过32）位但不消耗，数据结束后以0补齐
+    fn peek(&mut self, count: u32) -> u32;
+
+    fn skip(&mut self, count: u32);
+
+    fn read(&mut self, count: u32) -> u32 {
+        let value = self.peek(count);
+        self.skip(count);
+        value
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Huffman {
+    /// 低5位为码长，其余为符号；0 表示需要逐位解码
+    table: Vec<u32>,
+    counts: [u16; MAX_BITS + 1],
+    /// 按 (码长, 符号) 排序的符号
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// 由各符号的码长构造，码长为0的符号不出现；允许不完整的码表，但不允许超额
+    pub fn new(lengths: &[u8], msb_first: bool) -> HamsterResult<Self> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            if length as usize > MAX_BITS {
+                return Err(HamsterError::parse(format!("哈夫曼码长 {} 超出范围", length)));
+            }
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(HamsterError::parse("哈夫曼码表无效：码长超额"));
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0u16; offsets[MAX_BITS + 1] as usize];
+        let mut next_code = [0u32; MAX_BITS + 1];
+        let mut code = 0u32;
+        for length in 1..=MAX_BITS {
+            code = (code + counts[length - 1] as u32) << 1;
+            next_code[length] = code;
+        }
+
+        let mut table = vec![0u32; 1 << TABLE_BITS];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length == 0 {
+                continue;
+            }
+            let length = length as usize;
+            symbols[offsets[length] as usize] = symbol as u16;
+            offsets[length] += 1;
+
+            let code = next_code[length];
+            next_code[length] += 1;
+            if length as u32 > TABLE_BITS {
+                continue;
+            }
+            let entry = ((symbol as u32) << 5) | length as u32;
+            let fill = 1u32 << (TABLE_BITS - length as u32);
+            for suffix in 0..fill {
+                let index = if msb_first {
+                    (code << (TABLE_BITS - length as u32)) | suffix
+                } else {
+                    reverse_bits(code, length as u32) | (suffix << length)
+                };
+                table[index as usize] = entry;
+            }
+        }
+        Ok(Self { table, counts, symbols })
+    }
+
+    pub fn decode<B: BitSource>(&self, source: &mut B) -> HamsterResult<u16> {
+        let entry = self.table[source.peek(TABLE_BITS) as usize];
+        if entry != 0 {
+            source.skip(entry & 0x1F);
+            return Ok((entry >> 5) as u16);
+        }
+
+        // 长码逐位比较
+        let bits = source.peek(MAX_BITS as u32);
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..=MAX_BITS {
+            let bit = if B::MSB_FIRST { bits >> (MAX_BITS - length) } else { bits >> (length - 1) };
+            code |= (bit & 1) as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                source.skip(length as u32);
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(HamsterError::parse("压缩数据中出现无效的哈夫曼码"))
+    }
+}
+
+fn reverse_bits(code: u32, length: u32) -> u32 {
+    code.reverse_bits() >> (32 - length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按给定的位序逐位提供码流，数据结束后以0补齐
+    struct Bits<const MSB: bool> {
+        bits: Vec<u8>,
+        position: usize,
+    }
+
+    impl<const MSB: bool> Bits<MSB> {
+        fn new(bits: &str) -> Self {
+            Self { bits: bits.bytes().filter(|bit| *bit != b' ').map(|bit| bit - b'0').collect(), position: 0 }
+        }
+    }
+
+    impl<const MSB: bool> BitSource for Bits<MSB> {
+        const MSB_FIRST: bool = MSB;
+
+        fn peek(&mut self, count: u32) -> u32 {
+            (0..count as usize).fold(0, |value, index| {
+                let bit = self.bits.get(self.position + index).copied().unwrap_or(0) as u32;
+                if MSB { (value << 1) | bit } else { value | (bit << index) }
+            })
+        }
+
+        fn skip(&mut self, count: u32) {
+            self.position += count as usize;
+        }
+    }
+
+    fn decode_all<const MSB: bool>(huffman: &Huffman, bits: &str, count: usize) -> Vec<u16> {
+        let mut source = Bits::<MSB>::new(bits);
+        (0..count).map(|_| huffman.decode(&mut source).unwrap()).collect()
+    }
+
+    #[test]
+    fn canonical_codes_decode_in_both_bit_orders() {
+        // 符号1为0，符号0为10，符号2为110，符号3为111
+        let lengths = [2, 1, 3, 3];
+        let bits = "0 10 110 111 0";
+        assert_eq!(decode_all::<true>(&Huffman::new(&lengths, true).unwrap(), bits, 5), [1, 0, 2, 3, 1]);
+        assert_eq!(decode_all::<false>(&Huffman::new(&lengths, false).unwrap(), bits, 5), [1, 0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn codes_longer_than_the_table_are_decoded() {
+        // 码长 1, 2, ..., 15, 16, 16 构成完整的码表，最长的码超出查找表
+        let mut lengths: Vec<u8> = (1..=16).collect();
+        lengths.push(16);
+        let huffman = Huffman::new(&lengths, true).unwrap();
+        let long = format!("{} {}", "1".repeat(15) + "0", "1".repeat(16));
+        assert_eq!(decode_all::<true>(&huffman, &format!("{} 0 10", long), 4), [15, 16, 0, 1]);
+    }
+
+    #[test]
+    fn invalid_lengths_are_rejected() {
+        assert!(Huffman::new(&[1, 1, 1], true).is_err());
+        assert!(Huffman::new(&[17], true).is_err());
+        // 不完整的码表允许，未分配的码无法解码
+        let huffman = Huffman::new(&[0, 2], true).unwrap();
+        assert_eq!(decode_all::<true>(&huffman, "00", 1), [1]);
+        assert!(huffman.decode(&mut Bits::<true>::new("1111111111111111")).is_err());
+    }
+}
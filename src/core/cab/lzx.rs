@@ -0,0 +1,461 @@
+// LZX：CAB 中每个数据块解压为一帧（最多 32 KB），同一文件夹的各数据块组成一条连续的码流，
+// 窗口、重复偏移和码表在帧之间延续；码流由16位小端字组成，每个字从最高位开始读
+
+use crate::core::error::{HamsterError, HamsterResult};
+use super::huffman::{BitSource, Huffman};
+
+const FRAME_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 2;
+const LITERAL_COUNT: usize = 256;
+const PRETREE_SIZE: usize = 20;
+const LENGTH_TREE_SIZE: usize = 249;
+const ALIGNED_TREE_SIZE: usize = 8;
+
+const BLOCK_VERBATIM: u32 = 1;
+const BLOCK_ALIGNED: u32 = 2;
+const BLOCK_UNCOMPRESSED: u32 = 3;
+
+// E8 调用地址转换只作用于前 32768 帧
+const E8_FRAME_LIMIT: u32 = 32768;
+
+/// 按最高位优先读取16位小端字
+struct MsbReader<'a> {
+    data: &'a [u8],
+    /// 下一个要装入的字节位置
+    position: usize,
+    bits: u64,
+    count: u32,
+}
+
+impl<'a> MsbReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0, bits: 0, count: 0 }
+    }
+
+    fn refill(&mut self) {
+        while self.count <= 48 {
+            let low = self.data.get(self.position).copied().unwrap_or(0) as u64;
+            let high = self.data.get(self.position + 1).copied().unwrap_or(0) as u64;
+            self.bits |= ((high << 8) | low) << (48 - self.count);
+            self.count += 16;
+            self.position += 2;
+        }
+    }
+
+    /// 已消耗的位数
+    fn bit_position(&self) -> usize {
+        self.position * 8 - self.count as usize
+    }
+
+    /// 转为按字节读取：跳到下一个16位边界（已在边界上时跳过整个字），返回字节位置
+    fn byte_position_after_padding(&self) -> usize {
+        let bits = self.bit_position();
+        (bits / 16 + 1) * 2
+    }
+
+    fn seek_byte(&mut self, position: usize) {
+        self.position = position;
+        self.bits = 0;
+        self.count = 0;
+    }
+
+    fn overrun(&self) -> bool {
+        self.bit_position() > self.data.len() * 8
+    }
+}
+
+impl BitSource for MsbReader<'_> {
+    const MSB_FIRST: bool = true;
+
+    fn peek(&mut self, count: u32) -> u32 {
+        if count == 0 {
+            return 0;
+        }
+        self.refill();
+        (self.bits >> (64 - count)) as u32
+    }
+
+    fn skip(&mut self, count: u32) {
+        self.bits <<= count;
+        self.count -= count;
+    }
+}
+
+/// 各位置槽的附加位数和基准值
+struct PositionSlots {
+    extra_bits: [u8; 51],
+    base: [u32; 51],
+}
+
+impl PositionSlots {
+    fn new() -> Self {
+        let mut extra_bits = [0u8; 51];
+        let mut base = [0u32; 51];
+        let mut bits = 0u8;
+        for slot in (0..50).step_by(2) {
+            extra_bits[slot] = bits;
+            extra_bits[slot + 1] = bits;
+            if slot != 0 && bits < 17 {
+                bits += 1;
+            }
+        }
+        extra_bits[50] = 17;
+        let mut value = 0u32;
+        for slot in 0..51 {
+            base[slot] = value;
+            value += 1 << extra_bits[slot];
+        }
+        Self { extra_bits, base }
+    }
+}
+
+pub(super) struct LzxDecoder {
+    window: Vec<u8>,
+    /// 已解压到窗口中的位置，可能因跨帧的匹配超过当前帧的末尾
+    window_position: usize,
+    /// 当前帧在窗口中的起点
+    frame_start: usize,
+    slots: PositionSlots,
+    main_size: usize,
+    repeated: [u32; 3],
+    main_lengths: Vec<u8>,
+    length_lengths: Vec<u8>,
+    main_tree: Option<Huffman>,
+    length_tree: Option<Huffman>,
+    aligned_tree: Option<Huffman>,
+    block_type: u32,
+    block_remaining: usize,
+    /// 长度为奇数的未压缩块后面有一个补齐字节
+    uncompressed_padding: bool,
+    /// 上一帧结束后尚未读取的输入
+    pending: Vec<u8>,
+    header_read: bool,
+    e8_file_size: u32,
+    frame: u32,
+    /// 已输出的总字节数，用于 E8 转换
+    output_position: u32,
+}
+
+impl LzxDecoder {
+    pub fn new(window_bits: u8) -> HamsterResult<Self> {
+        let position_slots = match window_bits {
+            15 => 30,
+            16 => 32,
+            17 => 34,
+            18 => 36,
+            19 => 38,
+            20 => 42,
+            21 => 50,
+            _ => return Err(HamsterError::parse(format!("不支持的 LZX 窗口大小 2^{}", window_bits))),
+        };
+        let main_size = LITERAL_COUNT + position_slots * 8;
+        Ok(Self {
+            window: vec![0; 1 << window_bits],
+            window_position: 0,
+            frame_start: 0,
+            slots: PositionSlots::new(),
+            main_size,
+            repeated: [1, 1, 1],
+            main_lengths: vec![0; main_size],
+            length_lengths: vec![0; LENGTH_TREE_SIZE],
+            main_tree: None,
+            length_tree: None,
+            aligned_tree: None,
+            block_type: 0,
+            block_remaining: 0,
+            uncompressed_padding: false,
+            pending: Vec::new(),
+            header_read: false,
+            e8_file_size: 0,
+            frame: 0,
+            output_position: 0,
+        })
+    }
+
+    /// 解压一个数据块，得到 frame_size 字节（除最后一帧外为 32 KB）
+    pub fn decompress(&mut self, input: &[u8], frame_size: usize) -> HamsterResult<Vec<u8>> {
+        if frame_size > FRAME_SIZE {
+            return Err(HamsterError::parse("LZX 数据块的解压后大小超过 32 KB"));
+        }
+        let mut stream = std::mem::take(&mut self.pending);
+        stream.extend_from_slice(input);
+        let mut reader = MsbReader::new(&stream);
+        if !self.header_read {
+            self.header_read = true;
+            if reader.read(1) == 1 {
+                let high = reader.read(16);
+                let low = reader.read(16);
+                self.e8_file_size = (high << 16) | low;
+            }
+        }
+
+        if self.frame_start == self.window.len() {
+            self.frame_start = 0;
+        }
+        if self.window_position == self.window.len() {
+            self.window_position = 0;
+        }
+        let frame_end = self.frame_start + frame_size;
+        if frame_end > self.window.len() {
+            return Err(HamsterError::parse("LZX 帧超出窗口"));
+        }
+
+        while self.window_position < frame_end {
+            if self.block_remaining == 0 {
+                if self.block_type == BLOCK_UNCOMPRESSED && self.uncompressed_padding {
+                    let position = reader.bit_position() / 8 + 1;
+                    reader.seek_byte(position);
+                    self.uncompressed_padding = false;
+                }
+                self.read_block_header(&mut reader)?;
+            }
+            let run = self.block_remaining.min(frame_end - self.window_position);
+            let decoded = match self.block_type {
+                BLOCK_UNCOMPRESSED => self.copy_uncompressed(&mut reader, run)?,
+                _ => self.decode_compressed(&mut reader, run)?,
+            };
+            // 匹配可以越过帧的末尾，多解压的部分属于下一帧
+            self.block_remaining = self.block_remaining.checked_sub(decoded)
+                .ok_or_else(|| HamsterError::parse("LZX 匹配超出了块的末尾"))?;
+            if reader.overrun() {
+                return Err(HamsterError::parse("LZX 数据块提前结束"));
+            }
+        }
+
+        // 帧在压缩块中结束时码流对齐到16位；未压缩块按字节读取，不需要对齐
+        let consumed = if self.block_type == BLOCK_UNCOMPRESSED {
+            reader.bit_position() / 8
+        } else {
+            reader.bit_position().div_ceil(16) * 2
+        };
+        self.pending = stream.get(consumed..).unwrap_or_default().to_vec();
+
+        let mut output = self.window[self.frame_start..frame_end].to_vec();
+        self.translate_e8(&mut output);
+        self.frame_start = frame_end;
+        self.frame += 1;
+        self.output_position = self.output_position.wrapping_add(frame_size as u32);
+        Ok(output)
+    }
+
+    fn read_block_header(&mut self, reader: &mut MsbReader) -> HamsterResult<()> {
+        self.block_type = reader.read(3);
+        let high = reader.read(16) as usize;
+        let low = reader.read(8) as usize;
+        self.block_remaining = (high << 8) | low;
+
+        match self.block_type {
+            BLOCK_VERBATIM | BLOCK_ALIGNED => {
+                if self.block_type == BLOCK_ALIGNED {
+                    let mut lengths = [0u8; ALIGNED_TREE_SIZE];
+                    for length in &mut lengths {
+                        *length = reader.read(3) as u8;
+                    }
+                    self.aligned_tree = Some(Huffman::new(&lengths, true)?);
+                }
+                read_lengths(reader, &mut self.main_lengths, 0, LITERAL_COUNT)?;
+                read_lengths(reader, &mut self.main_lengths, LITERAL_COUNT, self.main_size)?;
+                self.main_tree = Some(Huffman::new(&self.main_lengths, true)?);
+                read_lengths(reader, &mut self.length_lengths, 0, LENGTH_TREE_SIZE)?;
+                // 块中没有长匹配时长度码表可以为空
+                self.length_tree = if self.length_lengths.iter().any(|&length| length != 0) {
+                    Some(Huffman::new(&self.length_lengths, true)?)
+                } else {
+                    None
+                };
+            }
+            BLOCK_UNCOMPRESSED => {
+                let start = reader.byte_position_after_padding();
+                let header = input_slice(reader.data, start, 12)?;
+                for (index, value) in self.repeated.iter_mut().enumerate() {
+                    *value = u32::from_le_bytes(header[index * 4..index * 4 + 4].try_into().unwrap_or_default());
+                }
+                reader.seek_byte(start + 12);
+                self.uncompressed_padding = self.block_remaining % 2 == 1;
+            }
+            other => return Err(HamsterError::parse(format!("LZX 数据中出现无效的块类型 {}", other))),
+        }
+        Ok(())
+    }
+
+    fn copy_uncompressed(&mut self, reader: &mut MsbReader, run: usize) -> HamsterResult<usize> {
+        let start = reader.bit_position() / 8;
+        let bytes = input_slice(reader.data, start, run)?;
+        self.window[self.window_position..self.window_position + run].copy_from_slice(bytes);
+        self.window_position += run;
+        reader.seek_byte(start + run);
+        Ok(run)
+    }
+
+    /// 解压至少 run 字节，返回实际解压的字节数（最后一个匹配可能超出）
+    fn decode_compressed(&mut self, reader: &mut MsbReader, run: usize) -> HamsterResult<usize> {
+        let main_tree = self.main_tree.as_ref().ok_or_else(|| HamsterError::parse("LZX 缺少主码表"))?;
+        let start = self.window_position;
+        let target = start + run;
+        while self.window_position < target {
+            let symbol = main_tree.decode(reader)? as usize;
+            if symbol < LITERAL_COUNT {
+                self.window[self.window_position] = symbol as u8;
+                self.window_position += 1;
+                continue;
+            }
+
+            let element = symbol - LITERAL_COUNT;
+            let mut length = element & 7;
+            if length == 7 {
+                let length_tree = self.length_tree.as_ref()
+                    .ok_or_else(|| HamsterError::parse("LZX 缺少长度码表"))?;
+                length += length_tree.decode(reader)? as usize;
+            }
+            length += MIN_MATCH;
+
+            let slot = element >> 3;
+            let offset = match slot {
+                0 => self.repeated[0],
+                1 => {
+                    self.repeated.swap(0, 1);
+                    self.repeated[0]
+                }
+                2 => {
+                    self.repeated.swap(0, 2);
+                    self.repeated[0]
+                }
+                _ => {
+                    let extra = self.slots.extra_bits[slot] as u32;
+                    let mut offset = self.slots.base[slot] - 2;
+                    if self.block_type == BLOCK_ALIGNED && extra >= 3 {
+                        let aligned_tree = self.aligned_tree.as_ref()
+                            .ok_or_else(|| HamsterError::parse("LZX 缺少对齐码表"))?;
+                        offset += reader.read(extra - 3) << 3;
+                        offset += aligned_tree.decode(reader)? as u32;
+                    } else {
+                        offset += reader.read(extra);
+                    }
+                    self.repeated[2] = self.repeated[1];
+                    self.repeated[1] = self.repeated[0];
+                    self.repeated[0] = offset;
+                    offset
+                }
+            };
+
+            let offset = offset as usize;
+            if offset == 0 || offset > self.window.len() || self.window_position + length > self.window.len() {
+                return Err(HamsterError::parse("LZX 匹配超出窗口"));
+            }
+            // 源位置可能在窗口末尾回绕，且与目标重叠，逐字节复制
+            let mut source = (self.window_position + self.window.len() - offset) % self.window.len();
+            for _ in 0..length {
+                self.window[self.window_position] = self.window[source];
+                self.window_position += 1;
+                source = (source + 1) % self.window.len();
+            }
+        }
+        Ok(self.window_position - start)
+    }
+
+    /// 压缩时把 x86 CALL 指令（E8）的相对地址改成了绝对地址以提高重复率，这里还原
+    fn translate_e8(&self, output: &mut [u8]) {
+        if self.e8_file_size == 0 || self.frame >= E8_FRAME_LIMIT || output.len() <= 10 {
+            return;
+        }
+        let file_size = self.e8_file_size as i32;
+        let mut current = self.output_position as i32;
+        let mut index = 0;
+        while index < output.len() - 10 {
+            if output[index] != 0xE8 {
+                index += 1;
+                current += 1;
+                continue;
+            }
+            let bytes: [u8; 4] = output[index + 1..index + 5].try_into().unwrap_or_default();
+            let absolute = i32::from_le_bytes(bytes);
+            if absolute >= -current && absolute < file_size {
+                let relative = if absolute >= 0 { absolute - current } else { absolute + file_size };
+                output[index + 1..index + 5].copy_from_slice(&relative.to_le_bytes());
+            }
+            index += 5;
+            current += 5;
+        }
+    }
+}
+
+/// 读取码表 [first, last) 的码长：先读20个4位的预码长，再用预码表解码相对上一块码长的增量
+fn read_lengths(reader: &mut MsbReader, lengths: &mut [u8], first: usize, last: usize) -> HamsterResult<()> {
+    let mut pretree_lengths = [0u8; PRETREE_SIZE];
+    for length in &mut pretree_lengths {
+        *length = reader.read(4) as u8;
+    }
+    let pretree = Huffman::new(&pretree_lengths, true)?;
+
+    let delta = |previous: u8, code: u16| ((previous as i32 - code as i32 + 17) % 17) as u8;
+    let mut index = first;
+    while index < last {
+        let code = pretree.decode(reader)?;
+        let (value, run) = match code {
+            0..=16 => (delta(lengths[index], code), 1),
+            17 => (0, reader.read(4) as usize + 4),
+            18 => (0, reader.read(5) as usize + 20),
+            19 => {
+                let run = reader.read(1) as usize + 4;
+                let code = pretree.decode(reader)?;
+                if code > 16 {
+                    return Err(HamsterError::parse("LZX 码长编码无效"));
+                }
+                (delta(lengths[index], code), run)
+            }
+            _ => return Err(HamsterError::parse("LZX 码长编码无效")),
+        };
+        if index + run > last {
+            return Err(HamsterError::parse("LZX 码长超出码表范围"));
+        }
+        lengths[index..index + run].fill(value);
+        index += run;
+    }
+    Ok(())
+}
+
+fn input_slice(input: &[u8], start: usize, length: usize) -> HamsterResult<&[u8]> {
+    input.get(start..start + length).ok_or_else(|| HamsterError::parse("LZX 未压缩块的数据不完整"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 没有 E8 转换、只含一个未压缩块的 LZX 码流
+    fn uncompressed_stream(data: &[u8]) -> Vec<u8> {
+        // E8 标志0，块类型 011，24位块大小，补齐到32位
+        let header = (0b0011u32 << 28) | ((data.len() as u32) << 4);
+        let mut stream = Vec::new();
+        stream.extend_from_slice(&((header >> 16) as u16).to_le_bytes());
+        stream.extend_from_slice(&(header as u16).to_le_bytes());
+        for offset in [1u32, 1, 1] {
+            stream.extend_from_slice(&offset.to_le_bytes());
+        }
+        stream.extend_from_slice(data);
+        stream
+    }
+
+    #[test]
+    fn uncompressed_block_spans_frames() {
+        let mut decoder = LzxDecoder::new(15).unwrap();
+        let stream = uncompressed_stream(b"helloworld");
+        // 一个块跨越两帧，前一帧没有用完的输入留给下一帧
+        assert_eq!(decoder.decompress(&stream[..21], 5).unwrap(), b"hello");
+        assert_eq!(decoder.decompress(&stream[21..], 5).unwrap(), b"world");
+        assert_eq!(decoder.frame, 2);
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        assert!(LzxDecoder::new(14).is_err());
+        assert!(LzxDecoder::new(22).is_err());
+        let mut decoder = LzxDecoder::new(16).unwrap();
+        assert!(decoder.decompress(&[], FRAME_SIZE + 1).is_err());
+        // 块类型 0 无效
+        assert!(LzxDecoder::new(16).unwrap().decompress(&[0, 0, 0, 0], 16).is_err());
+        // 输入不足一帧
+        let stream = uncompressed_stream(b"hello");
+        assert!(LzxDecoder::new(16).unwrap().decompress(&stream[..18], 5).is_err());
+    }
+}
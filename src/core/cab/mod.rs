@@ -0,0 +1,558 @@
+// Microsoft Cabinet（.cab）读取：解析文件头、文件夹和文件列表，按需解压未压缩、MSZIP 和 LZX 文件夹；
+// 同一文件夹中的文件连续压缩在一起，读取某个文件需要从文件夹开头解压到该文件的末尾
+
+mod huffman;
+mod lzx;
+mod mszip;
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use chrono::{NaiveDate, NaiveDateTime};
+use encoding_rs::WINDOWS_1252;
+use serde::Serialize;
+use crate::core::error::{HamsterError, HamsterResult};
+use lzx::LzxDecoder;
+use mszip::MsZipDecoder;
+
+const SIGNATURE: &[u8; 4] = b"MSCF";
+
+const FLAG_PREV_CABINET: u16 = 0x0001;
+const FLAG_NEXT_CABINET: u16 = 0x0002;
+const FLAG_RESERVE_PRESENT: u16 = 0x0004;
+
+// 文件名为 UTF-8，否则为系统代码页
+const ATTRIBUTE_NAME_IS_UTF: u16 = 0x0080;
+
+// 文件夹序号的特殊值：文件的数据从上一个或延续到下一个 CAB 文件
+const FOLDER_CONTINUED_FROM_PREV: u16 = 0xFFFD;
+
+/// 文件夹的压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Compression {
+    Stored,
+    MsZip,
+    Quantum,
+    Lzx { window_bits: u8 },
+    Unknown(u16),
+}
+
+impl Compression {
+    fn from_type(value: u16) -> Self {
+        match value & 0x000F {
+            0 => Compression::Stored,
+            1 => Compression::MsZip,
+            2 => Compression::Quantum,
+            3 => Compression::Lzx { window_bits: ((value >> 8) & 0x1F) as u8 },
+            _ => Compression::Unknown(value),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::Stored => write!(f, "未压缩"),
+            Compression::MsZip => write!(f, "MSZIP"),
+            Compression::Quantum => write!(f, "Quantum"),
+            Compression::Lzx { window_bits } => write!(f, "LZX（{} KB 窗口）", (1u32 << window_bits) / 1024),
+            Compression::Unknown(value) => write!(f, "未知压缩方式 0x{:04X}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CabFolder {
+    pub compression: Compression,
+    pub block_count: u16,
+    #[serde(skip)]
+    data_offset: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CabFile {
+    /// CAB 中以 `\` 分隔的路径
+    pub name: String,
+    pub size: u32,
+    pub folder: u16,
+    /// 在文件夹解压后的数据中的起始位置
+    pub folder_offset: u32,
+    pub modified: Option<NaiveDateTime>,
+    pub attributes: u16,
+}
+
+impl CabFile {
+    /// 数据跨越多个 CAB 文件（分卷）
+    pub fn is_spanned(&self) -> bool {
+        self.folder >= FOLDER_CONTINUED_FROM_PREV
+    }
+
+    /// 解压到目标目录时使用的相对路径，含有 `..`、盘符等不安全的部分时为 None
+    pub fn relative_path(&self) -> Option<PathBuf> {
+        let mut path = PathBuf::new();
+        for part in self.name.split(['\\', '/']) {
+            match part {
+                "" | "." => {}
+                ".." => return None,
+                _ if part.contains(':') => return None,
+                _ => path.push(part),
+            }
+        }
+        (!path.as_os_str().is_empty()).then_some(path)
+    }
+}
+
+pub struct CabArchive<R> {
+    reader: R,
+    folders: Vec<CabFolder>,
+    files: Vec<CabFile>,
+    /// 每个数据块头之后的保留区大小
+    data_reserve: usize,
+    /// 分卷时的下一个 CAB 文件名
+    next_cabinet: Option<String>,
+}
+
+impl CabArchive<File> {
+    pub fn open(path: &Path) -> HamsterResult<Self> {
+        let file = File::open(path)
+            .map_err(|e| HamsterError::io(format!("无法打开 CAB 文件 {}", path.display()), e))?;
+        Self::new(file)
+    }
+}
+
+impl<R: Read + Seek> CabArchive<R> {
+    pub fn new(mut reader: R) -> HamsterResult<Self> {
+        let header = read_bytes(&mut reader, 36)?;
+        if &header[..4] != SIGNATURE {
+            return Err(HamsterError::parse("不是 CAB 文件（缺少 MSCF 标记）"));
+        }
+        if header[25] != 1 {
+            return Err(HamsterError::parse(format!("不支持的 CAB 格式版本 {}.{}", header[25], header[24])));
+        }
+        let files_offset = u32_at(&header, 16);
+        let folder_count = u16_at(&header, 26);
+        let file_count = u16_at(&header, 28);
+        let flags = u16_at(&header, 30);
+
+        let (mut folder_reserve, mut data_reserve) = (0, 0);
+        if flags & FLAG_RESERVE_PRESENT != 0 {
+            let sizes = read_bytes(&mut reader, 4)?;
+            let header_reserve = u16_at(&sizes, 0) as i64;
+            folder_reserve = sizes[2] as usize;
+            data_reserve = sizes[3] as usize;
+            reader.seek(SeekFrom::Current(header_reserve)).map_err(read_error)?;
+        }
+        if flags & FLAG_PREV_CABINET != 0 {
+            read_name(&mut reader)?;
+            read_name(&mut reader)?;
+        }
+        let mut next_cabinet = None;
+        if flags & FLAG_NEXT_CABINET != 0 {
+            next_cabinet = Some(decode_name(&read_name(&mut reader)?, false));
+            read_name(&mut reader)?;
+        }
+
+        let mut folders = Vec::with_capacity(folder_count as usize);
+        for _ in 0..folder_count {
+            let entry = read_bytes(&mut reader, 8 + folder_reserve)?;
+            folders.push(CabFolder {
+                data_offset: u32_at(&entry, 0),
+                block_count: u16_at(&entry, 4),
+                compression: Compression::from_type(u16_at(&entry, 6)),
+            });
+        }
+
+        reader.seek(SeekFrom::Start(files_offset as u64)).map_err(read_error)?;
+        let mut files = Vec::with_capacity(file_count as usize);
+        for _ in 0..file_count {
+            let entry = read_bytes(&mut reader, 16)?;
+            let attributes = u16_at(&entry, 14);
+            let name = read_name(&mut reader)?;
+            let file = CabFile {
+                name: decode_name(&name, attributes & ATTRIBUTE_NAME_IS_UTF != 0),
+                size: u32_at(&entry, 0),
+                folder_offset: u32_at(&entry, 4),
+                folder: u16_at(&entry, 8),
+                modified: dos_datetime(u16_at(&entry, 10), u16_at(&entry, 12)),
+                attributes,
+            };
+            if !file.is_spanned() && file.folder as usize >= folders.len() {
+                return Err(HamsterError::parse(format!("CAB 中的文件 {} 指向不存在的文件夹", file.name)));
+            }
+            files.push(file);
+        }
+
+        Ok(Self { reader, folders, files, data_reserve, next_cabinet })
+    }
+
+    pub fn files(&self) -> &[CabFile] {
+        &self.files
+    }
+
+    pub fn folders(&self) -> &[CabFolder] {
+        &self.folders
+    }
+
+    /// 分卷的 CAB 中，后续内容所在的文件名
+    pub fn next_cabinet(&self) -> Option<&str> {
+        self.next_cabinet.as_deref()
+    }
+
+    /// 按名称查找文件，不区分大小写，`/` 与 `\` 等同
+    pub fn find(&self, name: &str) -> Option<usize> {
+        let normalize = |name: &str| name.replace('/', "\\").trim_start_matches('\\').to_lowercase();
+        let name = normalize(name);
+        self.files.iter().position(|file| normalize(&file.name) == name)
+    }
+
+    pub fn read_file(&mut self, index: usize) -> HamsterResult<Vec<u8>> {
+        let mut data = Vec::with_capacity(self.files.get(index).map(|file| file.size as usize).unwrap_or_default());
+        self.read_files(&[index], |_, chunk| {
+            data.extend_from_slice(chunk);
+            Ok(())
+        })?;
+        Ok(data)
+    }
+
+    /// 按文件夹顺序解压选中的文件，每解压出一段就交给 sink（文件序号, 数据）；
+    /// 每个文件夹只解压一遍，且只解压到其中最后一个选中文件的末尾
+    pub fn read_files(&mut self, indices: &[usize], mut sink: impl FnMut(usize, &[u8]) -> HamsterResult<()>) -> HamsterResult<()> {
+        for &index in indices {
+            let file = self.files.get(index).ok_or_else(|| HamsterError::parse(format!("CAB 中没有第 {} 个文件", index + 1)))?;
+            if file.is_spanned() {
+                return Err(HamsterError::parse(format!("{} 跨越多个 CAB 文件，暂不支持分卷", file.name)));
+            }
+        }
+
+        for folder_index in 0..self.folders.len() {
+            let wanted: Vec<usize> = indices.iter().copied()
+                .filter(|&index| self.files[index].folder as usize == folder_index)
+                .collect();
+            let Some(end) = wanted.iter().map(|&index| self.files[index].folder_offset as u64 + self.files[index].size as u64).max() else {
+                continue;
+            };
+
+            let mut folder = FolderReader::new(&self.folders[folder_index])?;
+            let mut position = 0u64;
+            while position < end {
+                let block = folder.next_block(&mut self.reader, self.data_reserve)?;
+                let block_end = position + block.len() as u64;
+                for &index in &wanted {
+                    let file = &self.files[index];
+                    let start = (file.folder_offset as u64).max(position);
+                    let stop = (file.folder_offset as u64 + file.size as u64).min(block_end);
+                    if start < stop {
+                        sink(index, &block[(start - position) as usize..(stop - position) as usize])?;
+                    }
+                }
+                position = block_end;
+            }
+        }
+        Ok(())
+    }
+
+    /// 将选中的文件按 CAB 中的路径解压到 directory，返回写入的文件路径
+    pub fn extract(&mut self, indices: &[usize], directory: &Path) -> HamsterResult<Vec<PathBuf>> {
+        // 先检查全部文件名，有不安全的文件名时不写入任何文件
+        let paths = indices.iter()
+            .map(|&index| {
+                let file = self.files.get(index).ok_or_else(|| HamsterError::parse(format!("CAB 中没有第 {} 个文件", index + 1)))?;
+                let relative = file.relative_path()
+                    .ok_or_else(|| HamsterError::policy(format!("CAB 中的文件名不安全: {}", file.name)))?;
+                Ok(directory.join(relative))
+            })
+            .collect::<HamsterResult<Vec<PathBuf>>>()?;
+        let mut outputs = Vec::with_capacity(indices.len());
+        for path in &paths {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| HamsterError::io(format!("无法创建目录 {}", parent.display()), e))?;
+            }
+            outputs.push(File::create(path).map_err(|e| HamsterError::io(format!("无法写入 {}", path.display()), e))?);
+        }
+
+        self.read_files(indices, |index, chunk| {
+            let position = indices.iter().position(|&wanted| wanted == index).unwrap_or_default();
+            outputs[position].write_all(chunk)
+                .map_err(|e| HamsterError::io(format!("无法写入 {}", paths[position].display()), e))
+        })?;
+        Ok(paths)
+    }
+}
+
+enum Codec {
+    Stored,
+    MsZip(MsZipDecoder),
+    Lzx(Box<LzxDecoder>),
+}
+
+/// 顺序读取并解压一个文件夹的数据块
+struct FolderReader {
+    codec: Codec,
+    next_offset: u64,
+    blocks_left: u16,
+}
+
+impl FolderReader {
+    fn new(folder: &CabFolder) -> HamsterResult<Self> {
+        let codec = match folder.compression {
+            Compression::Stored => Codec::Stored,
+            Compression::MsZip => Codec::MsZip(MsZipDecoder::new()),
+            Compression::Lzx { window_bits } => Codec::Lzx(Box::new(LzxDecoder::new(window_bits)?)),
+            other => return Err(HamsterError::parse(format!("不支持 {} 压缩的 CAB 文件夹", other))),
+        };
+        Ok(Self { codec, next_offset: folder.data_offset as u64, blocks_left: folder.block_count })
+    }
+
+    fn next_block<R: Read + Seek>(&mut self, reader: &mut R, data_reserve: usize) -> HamsterResult<Vec<u8>> {
+        if self.blocks_left == 0 {
+            return Err(HamsterError::parse("CAB 文件夹中的数据不完整"));
+        }
+        self.blocks_left -= 1;
+        reader.seek(SeekFrom::Start(self.next_offset)).map_err(read_error)?;
+        let header = read_bytes(reader, 8 + data_reserve)?;
+        let expected_checksum = u32_at(&header, 0);
+        let compressed_size = u16_at(&header, 4) as usize;
+        let size = u16_at(&header, 6) as usize;
+        let data = read_bytes(reader, compressed_size)?;
+        self.next_offset += (header.len() + compressed_size) as u64;
+
+        // 校验和覆盖数据和两个大小字段，不含保留区；为0表示未计算
+        if expected_checksum != 0 && checksum(&header[4..8], checksum(&data, 0)) != expected_checksum {
+            return Err(HamsterError::parse("CAB 数据块校验和不符，文件可能已损坏"));
+        }
+        if size == 0 {
+            return Err(HamsterError::parse("CAB 数据块延续到下一个 CAB 文件，暂不支持分卷"));
+        }
+        match &mut self.codec {
+            Codec::Stored if data.len() == size => Ok(data),
+            Codec::Stored => Err(HamsterError::parse("CAB 未压缩数据块的大小与记录不符")),
+            Codec::MsZip(decoder) => decoder.decompress(&data, size),
+            Codec::Lzx(decoder) => decoder.decompress(&data, size),
+        }
+    }
+}
+
+/// CAB 数据块的校验和：按小端32位字异或，末尾不足4字节的部分按大端拼成一个字
+fn checksum(data: &[u8], seed: u32) -> u32 {
+    let mut chunks = data.chunks_exact(4);
+    let mut sum = seed;
+    for chunk in &mut chunks {
+        sum ^= u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    let tail = chunks.remainder().iter().fold(0u32, |value, &byte| (value << 8) | byte as u32);
+    sum ^ tail
+}
+
+fn dos_datetime(date: u16, time: u16) -> Option<NaiveDateTime> {
+    NaiveDate::from_ymd_opt(1980 + (date >> 9) as i32, ((date >> 5) & 0x0F) as u32, (date & 0x1F) as u32)?
+        .and_hms_opt((time >> 11) as u32, ((time >> 5) & 0x3F) as u32, ((time & 0x1F) * 2) as u32)
+}
+
+/// 未标记为 UTF-8 的文件名按创建时的系统代码页编码，CAB 中没有记录是哪个代码页；
+/// 与没有声明语言的 INF 一样按西欧代码页（Windows-1252）解码
+fn decode_name(bytes: &[u8], utf8: bool) -> String {
+    if let Ok(name) = std::str::from_utf8(bytes) {
+        return name.to_string();
+    }
+    if utf8 {
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
+    WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+/// 以0结尾的字符串，CAB 规定最长255字节
+fn read_name<R: Read>(reader: &mut R) -> HamsterResult<Vec<u8>> {
+    let mut name = Vec::new();
+    loop {
+        let byte = read_bytes(reader, 1)?[0];
+        if byte == 0 {
+            return Ok(name);
+        }
+        if name.len() >= 256 {
+            return Err(HamsterError::parse("CAB 中的文件名过长"));
+        }
+        name.push(byte);
+    }
+}
+
+fn read_bytes<R: Read>(reader: &mut R, length: usize) -> HamsterResult<Vec<u8>> {
+    let mut buffer = vec![0; length];
+    reader.read_exact(&mut buffer).map_err(read_error)?;
+    Ok(buffer)
+}
+
+fn read_error(e: io::Error) -> HamsterError {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        HamsterError::parse("CAB 文件不完整").with_source(e)
+    } else {
+        HamsterError::io("无法读取 CAB 文件", e)
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use sha2::{Digest, Sha256};
+
+    // fixtures/cab 中两个 CAB 包含相同的三个文件
+    const DIGESTS: [(&str, &str); 3] = [
+        ("Drivers\\a.sys", "8ccc6980243bc02273347a82f9373184018e51b2937590c82c33937c74bacae1"),
+        ("b.dll", "c73d36d74d9c06c0b181e0d74151c98d00365c6766336ebf03abc438337baff6"),
+        ("c.inf", "ff52c88dd33faa82fdc759d36017b73586e71dee2bfe765ca6428ba3f2e98c8e"),
+    ];
+
+    fn fixture(name: &str) -> CabArchive<File> {
+        CabArchive::open(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/cab").join(name)).unwrap()
+    }
+
+    fn sha256(data: &[u8]) -> String {
+        Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// 只有一个未压缩文件夹的 CAB，每个数据块最多 block_size 字节，数据块头后带 data_reserve 字节保留区
+    fn stored_cab(files: &[(&str, &[u8])], block_size: usize, data_reserve: u8) -> Vec<u8> {
+        let content: Vec<u8> = files.iter().flat_map(|(_, data)| data.iter().copied()).collect();
+        let header_size = if data_reserve > 0 { 40 } else { 36 };
+        let files_offset = header_size + 8;
+        let mut entries = Vec::new();
+        let mut offset = 0u32;
+        for (name, data) in files {
+            entries.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            entries.extend_from_slice(&offset.to_le_bytes());
+            entries.extend_from_slice(&[0, 0, 0x65, 0x58, 0xC5, 0x63, 0x20, 0]);
+            entries.extend_from_slice(name.as_bytes());
+            entries.push(0);
+            offset += data.len() as u32;
+        }
+        let mut blocks = Vec::new();
+        let chunks: Vec<&[u8]> = content.chunks(block_size).collect();
+        for chunk in &chunks {
+            let sizes = [(chunk.len() as u16).to_le_bytes(), (chunk.len() as u16).to_le_bytes()].concat();
+            blocks.extend_from_slice(&checksum(&sizes, checksum(chunk, 0)).to_le_bytes());
+            blocks.extend_from_slice(&sizes);
+            blocks.extend(std::iter::repeat_n(0xAA, data_reserve as usize));
+            blocks.extend_from_slice(chunk);
+        }
+
+        let data_offset = files_offset + entries.len();
+        let mut cab = b"MSCF".to_vec();
+        for value in [0, (data_offset + blocks.len()) as u32, 0, files_offset as u32, 0] {
+            cab.extend_from_slice(&value.to_le_bytes());
+        }
+        cab.extend_from_slice(&[3, 1, 1, 0]);
+        cab.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        cab.extend_from_slice(&(if data_reserve > 0 { FLAG_RESERVE_PRESENT } else { 0 }).to_le_bytes());
+        cab.extend_from_slice(&[0x34, 0x12, 0, 0]);
+        if data_reserve > 0 {
+            cab.extend_from_slice(&[0, 0, 0, data_reserve]);
+        }
+        cab.extend_from_slice(&(data_offset as u32).to_le_bytes());
+        cab.extend_from_slice(&(chunks.len() as u16).to_le_bytes());
+        cab.extend_from_slice(&[0, 0]);
+        cab.extend_from_slice(&entries);
+        cab.extend_from_slice(&blocks);
+        cab
+    }
+
+    fn assert_fixture_files(cab: &mut CabArchive<File>) {
+        let names: Vec<&str> = cab.files().iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, DIGESTS.map(|(name, _)| name));
+        for (index, (name, digest)) in DIGESTS.iter().enumerate() {
+            assert_eq!(sha256(&cab.read_file(index).unwrap()), *digest, "{}", name);
+        }
+    }
+
+    #[test]
+    fn stored_folder_spans_blocks() {
+        let data = stored_cab(&[("a.inf", b"[Version]\r\n"), ("dir\\b.sys", &[7u8; 40])], 16, 0);
+        let mut cab = CabArchive::new(Cursor::new(data)).unwrap();
+        assert_eq!(cab.folders()[0].compression, Compression::Stored);
+        assert_eq!(cab.folders()[0].block_count, 4);
+        assert_eq!(cab.files()[1].modified, NaiveDate::from_ymd_opt(2024, 3, 5).unwrap().and_hms_opt(12, 30, 10));
+        assert_eq!(cab.read_file(0).unwrap(), b"[Version]\r\n");
+        assert_eq!(cab.read_file(cab.find("DIR/B.SYS").unwrap()).unwrap(), [7u8; 40]);
+    }
+
+    #[test]
+    fn mszip_folder_with_reserve_areas() {
+        let mut cab = fixture("mszip.cab");
+        assert_eq!(cab.folders()[0].compression, Compression::MsZip);
+        // 后面的块引用前面块中的数据
+        assert_eq!(cab.folders()[0].block_count, 3);
+        assert_fixture_files(&mut cab);
+    }
+
+    #[test]
+    fn lzx_frames_continue_across_blocks() {
+        let mut cab = fixture("lzx.cab");
+        assert_eq!(cab.folders()[0].compression, Compression::Lzx { window_bits: 16 });
+        assert_eq!(cab.folders()[0].block_count, 3);
+        assert_fixture_files(&mut cab);
+    }
+
+    #[test]
+    fn checksum_covers_data_and_sizes_but_not_reserve() {
+        let files: [(&str, &[u8]); 1] = [("a.inf", b"[Version]\r\nSignature=\"$Windows NT$\"\r\n")];
+        let data = stored_cab(&files, 16, 3);
+        assert_eq!(CabArchive::new(Cursor::new(data.clone())).unwrap().read_file(0).unwrap(), files[0].1);
+
+        // 三个数据块各有 8 字节块头和 3 字节保留区；保留区的内容不参与校验
+        let first_block = data.len() - (files[0].1.len() + 3 * (8 + 3));
+        let mut reserve_changed = data.clone();
+        reserve_changed[first_block + 8] ^= 0xFF;
+        assert!(CabArchive::new(Cursor::new(reserve_changed)).unwrap().read_file(0).is_ok());
+
+        let mut corrupted = data.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0x01;
+        let error = CabArchive::new(Cursor::new(corrupted.clone())).unwrap().read_file(0).unwrap_err();
+        assert!(error.to_string().contains("校验和不符"), "{}", error);
+
+        // 校验和为0表示未计算，不检查
+        corrupted[first_block + 2 * (8 + 3 + 16)..][..4].fill(0);
+        assert!(CabArchive::new(Cursor::new(corrupted)).unwrap().read_file(0).is_ok());
+    }
+
+    #[test]
+    fn extract_writes_only_selected_files() {
+        let directory = std::env::temp_dir().join(format!("hamster-cab-extract-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let mut cab = fixture("lzx.cab");
+        let paths = cab.extract(&[2, 0], &directory).unwrap();
+        assert_eq!(paths, [directory.join("c.inf"), directory.join("Drivers").join("a.sys")]);
+        assert_eq!(sha256(&std::fs::read(&paths[0]).unwrap()), DIGESTS[2].1);
+        assert_eq!(sha256(&std::fs::read(&paths[1]).unwrap()), DIGESTS[0].1);
+        assert!(!directory.join("b.dll").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn unsafe_names_are_rejected() {
+        let file = |name: &str| CabFile { name: name.to_string(), size: 0, folder: 0, folder_offset: 0, modified: None, attributes: 0 };
+        assert_eq!(file("Drivers\\x64\\a.sys").relative_path(), Some(PathBuf::from("Drivers").join("x64").join("a.sys")));
+        // 开头的分隔符被去掉，仍在目标目录下
+        assert_eq!(file("\\Windows\\a.sys").relative_path(), Some(PathBuf::from("Windows").join("a.sys")));
+        assert_eq!(file("/etc/a.conf").relative_path(), Some(PathBuf::from("etc").join("a.conf")));
+        for name in ["..\\a.sys", "Drivers\\..\\..\\a.sys", "C:\\Windows\\a.sys", "C:a.sys", "\\\\", ""] {
+            assert_eq!(file(name).relative_path(), None, "{}", name);
+        }
+
+        let directory = std::env::temp_dir().join(format!("hamster-cab-unsafe-{}", std::process::id()));
+        let data = stored_cab(&[("a.inf", b"ok"), ("..\\evil.sys", b"evil")], 16, 0);
+        let error = CabArchive::new(Cursor::new(data)).unwrap().extract(&[0, 1], &directory).unwrap_err();
+        assert_eq!(error.category(), crate::core::error::ErrorCategory::Policy);
+        // 检查在写入任何文件之前完成
+        assert!(!directory.exists());
+    }
+}
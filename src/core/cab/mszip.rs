@@ -0,0 +1,262 @@
+// MSZIP：每个数据块以 "CK" 开头，后接一段完整的 deflate 流；
+// 同一文件夹中后面的块可以引用前面的块解压出的最近 32 KB 数据
+
+use crate::core::error::{HamsterError, HamsterResult};
+use super::huffman::{BitSource, Huffman};
+
+const WINDOW_SIZE: usize = 32 * 1024;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+// 动态码表中码长码的顺序
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// deflate 的位序：每个字节从最低位开始读
+struct LsbReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    bits: u64,
+    count: u32,
+}
+
+impl<'a> LsbReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0, bits: 0, count: 0 }
+    }
+
+    fn refill(&mut self) {
+        while self.count <= 56 {
+            let byte = self.data.get(self.position).copied().unwrap_or(0);
+            self.bits |= (byte as u64) << self.count;
+            self.count += 8;
+            self.position += 1;
+        }
+    }
+
+    fn align_to_byte(&mut self) {
+        self.skip(self.count % 8);
+    }
+
+    /// 是否读到了数据末尾之后的补齐位
+    fn overrun(&self) -> bool {
+        self.position * 8 - self.count as usize > self.data.len() * 8
+    }
+}
+
+impl BitSource for LsbReader<'_> {
+    const MSB_FIRST: bool = false;
+
+    fn peek(&mut self, count: u32) -> u32 {
+        self.refill();
+        (self.bits & ((1u64 << count) - 1)) as u32
+    }
+
+    fn skip(&mut self, count: u32) {
+        self.bits >>= count;
+        self.count -= count;
+    }
+}
+
+pub(super) struct MsZipDecoder {
+    /// 前面的块解压出的最近 32 KB
+    window: Vec<u8>,
+}
+
+impl MsZipDecoder {
+    pub fn new() -> Self {
+        Self { window: Vec::new() }
+    }
+
+    pub fn decompress(&mut self, input: &[u8], expected: usize) -> HamsterResult<Vec<u8>> {
+        let Some(stream) = input.strip_prefix(b"CK") else {
+            return Err(HamsterError::parse("MSZIP 数据块缺少 CK 标记"));
+        };
+        let history = self.window.len();
+        let mut buffer = std::mem::take(&mut self.window);
+        buffer.reserve(expected);
+        let mut reader = LsbReader::new(stream);
+
+        loop {
+            let last = reader.read(1) == 1;
+            match reader.read(2) {
+                0 => inflate_stored(&mut reader, &mut buffer)?,
+                1 => {
+                    let (literals, distances) = fixed_trees()?;
+                    inflate_codes(&mut reader, &mut buffer, &literals, &distances)?;
+                }
+                2 => {
+                    let (literals, distances) = dynamic_trees(&mut reader)?;
+                    inflate_codes(&mut reader, &mut buffer, &literals, &distances)?;
+                }
+                _ => return Err(HamsterError::parse("MSZIP 数据中出现无效的块类型")),
+            }
+            if reader.overrun() {
+                return Err(HamsterError::parse("MSZIP 数据块提前结束"));
+            }
+            if buffer.len() - history > expected {
+                return Err(HamsterError::parse("MSZIP 数据块解压后的大小与记录不符"));
+            }
+            if last {
+                break;
+            }
+        }
+
+        if buffer.len() - history != expected {
+            return Err(HamsterError::parse("MSZIP 数据块解压后的大小与记录不符"));
+        }
+        let output = buffer[history..].to_vec();
+        let keep = buffer.len().saturating_sub(WINDOW_SIZE);
+        buffer.drain(..keep);
+        self.window = buffer;
+        Ok(output)
+    }
+}
+
+fn inflate_stored(reader: &mut LsbReader, buffer: &mut Vec<u8>) -> HamsterResult<()> {
+    reader.align_to_byte();
+    let length = reader.read(16);
+    let complement = reader.read(16);
+    if length != !complement & 0xFFFF {
+        return Err(HamsterError::parse("MSZIP 未压缩块的长度校验失败"));
+    }
+    for _ in 0..length {
+        buffer.push(reader.read(8) as u8);
+    }
+    Ok(())
+}
+
+fn inflate_codes(reader: &mut LsbReader, buffer: &mut Vec<u8>, literals: &Huffman, distances: &Huffman) -> HamsterResult<()> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        match symbol {
+            0..=255 => buffer.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length = LENGTH_BASE[index] as usize + reader.read(LENGTH_EXTRA[index] as u32) as usize;
+                let code = distances.decode(reader)? as usize;
+                if code >= DISTANCE_BASE.len() {
+                    return Err(HamsterError::parse("MSZIP 数据中出现无效的距离码"));
+                }
+                let distance = DISTANCE_BASE[code] as usize + reader.read(DISTANCE_EXTRA[code] as u32) as usize;
+                if distance > buffer.len() {
+                    return Err(HamsterError::parse("MSZIP 数据引用了窗口之外的数据"));
+                }
+                // 距离小于长度时源和目标重叠，只能逐字节复制
+                let start = buffer.len() - distance;
+                for offset in 0..length {
+                    buffer.push(buffer[start + offset]);
+                }
+            }
+            _ => return Err(HamsterError::parse("MSZIP 数据中出现无效的长度码")),
+        }
+        if reader.overrun() {
+            return Err(HamsterError::parse("MSZIP 数据块提前结束"));
+        }
+    }
+}
+
+fn fixed_trees() -> HamsterResult<(Huffman, Huffman)> {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    Ok((Huffman::new(&lengths, false)?, Huffman::new(&[5; 30], false)?))
+}
+
+fn dynamic_trees(reader: &mut LsbReader) -> HamsterResult<(Huffman, Huffman)> {
+    let literal_count = reader.read(5) as usize + 257;
+    let distance_count = reader.read(5) as usize + 1;
+    let code_length_count = reader.read(4) as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(HamsterError::parse("MSZIP 动态码表的大小无效"));
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = reader.read(3) as u8;
+    }
+    let code_length_tree = Huffman::new(&code_lengths, false)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_length_tree.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 if index > 0 => (lengths[index - 1], 3 + reader.read(2) as usize),
+            17 => (0, 3 + reader.read(3) as usize),
+            18 => (0, 11 + reader.read(7) as usize),
+            _ => return Err(HamsterError::parse("MSZIP 动态码表中的码长无效")),
+        };
+        if index + repeat > lengths.len() {
+            return Err(HamsterError::parse("MSZIP 动态码表中的码长超出范围"));
+        }
+        lengths[index..index + repeat].fill(value);
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(HamsterError::parse("MSZIP 动态码表缺少块结束码"));
+    }
+    let (literal_lengths, distance_lengths) = lengths.split_at(literal_count);
+    Ok((Huffman::new(literal_lengths, false)?, Huffman::new(distance_lengths, false)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 只含一个未压缩 deflate 块的 MSZIP 数据块
+    fn stored_block(data: &[u8]) -> Vec<u8> {
+        let length = data.len() as u16;
+        let mut block = b"CK\x01".to_vec();
+        block.extend_from_slice(&length.to_le_bytes());
+        block.extend_from_slice(&(!length).to_le_bytes());
+        block.extend_from_slice(data);
+        block
+    }
+
+    #[test]
+    fn stored_deflate_blocks_are_copied() {
+        let mut decoder = MsZipDecoder::new();
+        assert_eq!(decoder.decompress(&stored_block(b"hello "), 6).unwrap(), b"hello ");
+        assert_eq!(decoder.decompress(&stored_block(b"cab"), 3).unwrap(), b"cab");
+        assert_eq!(decoder.window, b"hello cab");
+    }
+
+    #[test]
+    fn fixed_codes_can_refer_to_the_previous_block() {
+        let mut decoder = MsZipDecoder::new();
+        decoder.decompress(&stored_block(b"abcabc"), 6).unwrap();
+        // 固定码表的一个匹配（长度 6，距离 6）后接块结束
+        let block = [b'C', b'K', 0x83, 0x20, 0x00];
+        assert_eq!(decoder.decompress(&block, 6).unwrap(), b"abcabc");
+    }
+
+    #[test]
+    fn malformed_blocks_are_rejected() {
+        let mut decoder = MsZipDecoder::new();
+        assert!(decoder.decompress(b"XX\x01\x00\x00\xFF\xFF", 0).unwrap_err().to_string().contains("CK"));
+        assert!(decoder.decompress(&stored_block(b"hello"), 4).is_err());
+        let mut bad_length = stored_block(b"hello");
+        bad_length[5] ^= 0x01;
+        assert!(decoder.decompress(&bad_length, 5).is_err());
+        // 第一个块不能引用之前的数据
+        assert!(MsZipDecoder::new().decompress(&[b'C', b'K', 0x83, 0x20, 0x00], 6).is_err());
+    }
+}
@@ -1,6 +1,9 @@
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
 use chrono::{DateTime, Local};
+use crate::core::error::HamsterResult;
+use crate::core::platform::Platform;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DriverStatus {
@@ -33,13 +36,17 @@ pub struct DriverInfo {
 	pub signed: bool,
 	pub signature_status: String,
 	pub last_updated: DateTime<Local>,
+	/// 依赖的服务，以 `+` 开头的是加载顺序组
 	pub dependencies: Vec<String>,
 	pub load_order: u32,
+	/// 所属的加载顺序组，如 `Boot Bus Extender`；没有时为空
+	#[serde(default)]
+	pub group: String,
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
 pub struct DriverManager {
+	platform: Arc<dyn Platform>,
 	drivers: Vec<DriverInfo>,
 	filtered_drivers: Vec<DriverInfo>,
 	selected_driver: Option<usize>,
@@ -52,8 +59,9 @@ pub struct DriverManager {
 
 #[allow(dead_code)]
 impl DriverManager {
-	pub fn new() -> Self {
+	pub fn new(platform: Arc<dyn Platform>) -> Self {
 		Self {
+			platform,
 			drivers: Vec::new(),
 			filtered_drivers: Vec::new(),
 			selected_driver: None,
@@ -65,33 +73,9 @@ impl DriverManager {
 		}
 	}
     
-	// 枚举系统驱动程序（Windows API实现）
-	pub fn refresh_drivers(&mut self) -> Result<(), String> {
-		self.drivers.clear();
-        
-		// Windows服务管理器枚举驱动服务
-		// 这里需要调用Windows API，实际实现会使用windows crate
-		// 简化示例：
-		self.drivers = vec![
-			DriverInfo {
-				name: "1394ohci".to_string(),
-				display_name: "1394 OHCI Compliant Host Controller".to_string(),
-				description: "提供对1394总线的支持".to_string(),
-				status: DriverStatus::Running,
-				driver_type: DriverType::KernelMode,
-				start_type: "手动".to_string(),
-				binary_path: "System32\\drivers\\1394ohci.sys".to_string(),
-				version: "10.0.19041.1".to_string(),
-				company: "Microsoft Corporation".to_string(),
-				signed: true,
-				signature_status: "已验证".to_string(),
-				last_updated: Local::now(),
-				dependencies: vec![],
-				load_order: 0,
-			},
-			// 更多驱动...
-		];
-        
+	// 通过平台枚举系统驱动程序
+	pub fn refresh_drivers(&mut self) -> HamsterResult<()> {
+		self.drivers = self.platform.enumerate_drivers()?;
 		self.apply_filters();
 		Ok(())
 	}
@@ -106,10 +90,10 @@ impl DriverManager {
 					driver.description.to_lowercase().contains(&self.filter_text.to_lowercase());
                 
 				let type_match = self.filter_type.as_ref()
-					.map_or(true, |filter_type| &driver.driver_type == filter_type);
+					.is_none_or(|filter_type| &driver.driver_type == filter_type);
                 
 				let status_match = self.filter_status.as_ref()
-					.map_or(true, |filter_status| &driver.status == filter_status);
+					.is_none_or(|filter_status| &driver.status == filter_status);
                 
 				let signed_match = if self.show_only_signed {
 					driver.signed
@@ -1,8 +1,9 @@
+#[cfg(windows)]
 use std::collections::HashMap;
-use wmi::{WMIConnection, Variant};
-use std::process::Command;
 #[cfg(windows)]
-use std::os::windows::process::CommandExt;
+use wmi::{WMIConnection, Variant};
+use super::error::{HamsterError, HamsterResult};
+use super::platform::Platform;
 use super::sysinfo::HardwareItem;
 
 /// EDID数据结构
@@ -22,7 +23,8 @@ pub struct EdidInfo {
 }
 
 /// 获取显示器EDID数据
-pub fn get_edid_info(wmi_con: &WMIConnection) -> Result<Vec<EdidInfo>, String> {
+#[cfg(windows)]
+pub fn get_edid_info(wmi_con: &WMIConnection) -> HamsterResult<Vec<EdidInfo>> {
     let mut edid_infos = Vec::new();
     
     // 尝试从WmiMonitorID获取EDID数据
@@ -193,6 +195,73 @@ pub fn get_edid_info(wmi_con: &WMIConnection) -> Result<Vec<EdidInfo>, String> {
     Ok(edid_infos)
 }
 
+/// 解析128字节的EDID基本块，如 Linux 下 /sys/class/drm/*/edid 的内容
+///
+/// 型号优先取显示器名称描述符，没有时使用十六进制产品代码；
+/// 首选分辨率取第一个详细时序描述符，格式为 `1920x1080@60Hz`。
+pub fn parse_edid(bytes: &[u8]) -> Option<EdidInfo> {
+    const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+    if bytes.len() < 128 || bytes[..8] != HEADER {
+        return None;
+    }
+
+    // 制造商ID为3个5位字母，1表示'A'
+    let id = u16::from_be_bytes([bytes[8], bytes[9]]);
+    let manufacturer: String = [10, 5, 0].iter()
+        .map(|shift| (((id >> shift) & 0x1F) as u8 + b'A' - 1) as char)
+        .collect();
+    let product_code = u16::from_le_bytes([bytes[10], bytes[11]]);
+    let serial = u32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
+
+    let mut name = None;
+    let mut serial_text = None;
+    let mut resolutions = Vec::new();
+    for descriptor in bytes[54..126].chunks(18) {
+        let pixel_clock = u16::from_le_bytes([descriptor[0], descriptor[1]]) as u64;
+        if pixel_clock != 0 {
+            let h_active = descriptor[2] as u64 | ((descriptor[4] as u64 >> 4) << 8);
+            let h_blank = descriptor[3] as u64 | ((descriptor[4] as u64 & 0x0F) << 8);
+            let v_active = descriptor[5] as u64 | ((descriptor[7] as u64 >> 4) << 8);
+            let v_blank = descriptor[6] as u64 | ((descriptor[7] as u64 & 0x0F) << 8);
+            let total = (h_active + h_blank) * (v_active + v_blank);
+            // 像素时钟单位为10kHz
+            if let Some(refresh) = (pixel_clock * 10_000 + total / 2).checked_div(total) {
+                resolutions.push(format!("{}x{}@{}Hz", h_active, v_active, refresh));
+            }
+            continue;
+        }
+        let text = || {
+            String::from_utf8_lossy(&descriptor[5..18])
+                .split('\n')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .to_string()
+        };
+        match descriptor[3] {
+            0xFC => name = Some(text()),
+            0xFF => serial_text = Some(text()),
+            _ => {}
+        }
+    }
+
+    Some(EdidInfo {
+        manufacturer,
+        product_code: name.filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("{:04X}", product_code)),
+        serial_number: serial_text.filter(|serial| !serial.is_empty())
+            .unwrap_or_else(|| if serial == 0 { String::new() } else { serial.to_string() }),
+        manufacture_week: bytes[16],
+        manufacture_year: bytes[17],
+        edid_version: bytes[18],
+        edid_revision: bytes[19],
+        screen_size_horizontal: Some(bytes[21]).filter(|size| *size > 0),
+        screen_size_vertical: Some(bytes[22]).filter(|size| *size > 0),
+        gamma: if bytes[23] == 0xFF { None } else { Some((bytes[23] as f32 + 100.0) / 100.0) },
+        supported_resolutions: resolutions,
+    })
+}
+
 /// 将EDID信息格式化为可读字符串
 pub fn format_edid_info(edid_info: &EdidInfo) -> String {
     let mut parts = Vec::new();
@@ -232,6 +301,7 @@ pub fn format_edid_info(edid_info: &EdidInfo) -> String {
 }
 
 /// 解码制造商名称，处理多种字符编码
+#[cfg(windows)]
 fn decode_manufacturer_name(bytes: &[u8]) -> String {
     // 首先尝试UTF-8
     if let Ok(s) = String::from_utf8(bytes.to_vec()) {
@@ -262,14 +332,13 @@ fn decode_manufacturer_name(bytes: &[u8]) -> String {
 }
 
 /// 使用PowerShell直接获取显示器信息
-pub fn get_direct_monitor_info() -> Result<Vec<HardwareItem>, String> {
+pub fn get_direct_monitor_info(platform: &dyn Platform) -> HamsterResult<Vec<HardwareItem>> {
     let mut monitor_info = Vec::new();
     
     // 使用PowerShell命令获取显示器信息，隐藏窗口
-    let output = Command::new("powershell")
-        .args([
-            "-WindowStyle", "Hidden",
-            "-Command",
+    let output = platform.run_command("powershell", &[
+        "-WindowStyle", "Hidden",
+        "-Command",
             "[Console]::OutputEncoding = [System.Text.Encoding]::UTF8; 
             Get-WmiObject -Namespace root\\wmi -Class WmiMonitorID | ForEach-Object { 
                 # 使用UTF-8编码处理制造商名称
@@ -302,13 +371,11 @@ pub fn get_direct_monitor_info() -> Result<Vec<HardwareItem>, String> {
                     Write-Output (\"尺寸:$([Math]::Round($diagonal))英寸\")
                 }
             }"
-        ])
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW
-        .output();
+    ]);
     
     match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(output) if output.success => {
+            let stdout = &output.stdout;
             let lines: Vec<&str> = stdout.lines().collect();
             
             // 分离显示器信息和尺寸信息
@@ -328,8 +395,7 @@ pub fn get_direct_monitor_info() -> Result<Vec<HardwareItem>, String> {
             
             for (i, monitor_line) in monitor_lines.iter().enumerate() {
                 // 获取当前分辨率信息
-                let resolution_output = Command::new("powershell")
-                    .args([
+                let resolution_output = platform.run_command("powershell", &[
                         "-WindowStyle", "Hidden",
                         "-Command",
                         "Get-WmiObject -Class Win32_VideoController | Where-Object { $_.CurrentHorizontalResolution -ne $null -and $_.CurrentHorizontalResolution -gt 0 } | ForEach-Object { 
@@ -338,13 +404,11 @@ pub fn get_direct_monitor_info() -> Result<Vec<HardwareItem>, String> {
                             $refresh = if ($_.CurrentRefreshRate) { $_.CurrentRefreshRate } else { '?' }
                             Write-Output (\"${width}x${height}@${refresh}Hz\")
                         }"
-                    ])
-                    .creation_flags(0x08000000) // CREATE_NO_WINDOW
-                    .output();
+                    ]);
                 
                 let resolution = match resolution_output {
-                    Ok(res_output) if res_output.status.success() => {
-                        let stdout = String::from_utf8_lossy(&res_output.stdout);
+                    Ok(res_output) if res_output.success => {
+                        let stdout = &res_output.stdout;
                         let lines: Vec<&str> = stdout.lines().collect();
                         if !lines.is_empty() {
                             lines[0].trim().to_string()
@@ -382,8 +446,7 @@ pub fn get_direct_monitor_info() -> Result<Vec<HardwareItem>, String> {
             
             if monitor_info.is_empty() {
                 // 如果PowerShell没有获取到信息，尝试使用系统信息
-                let sys_output = Command::new("powershell")
-                    .args([
+                let sys_output = platform.run_command("powershell", &[
                         "-WindowStyle", "Hidden",
                         "-Command",
                         "Get-WmiObject -Class Win32_VideoController | Where-Object { $_.CurrentHorizontalResolution -ne $null -and $_.CurrentHorizontalResolution -gt 0 } | ForEach-Object { 
@@ -392,13 +455,11 @@ pub fn get_direct_monitor_info() -> Result<Vec<HardwareItem>, String> {
                             $refresh = if ($_.CurrentRefreshRate) { $_.CurrentRefreshRate } else { '?' }
                             Write-Output (\"${width}x${height}@${refresh}Hz\")
                         }"
-                    ])
-                    .creation_flags(0x08000000) // CREATE_NO_WINDOW
-                    .output();
+                    ]);
                 
                 match sys_output {
-                    Ok(sys_output) if sys_output.status.success() => {
-                        let stdout = String::from_utf8_lossy(&sys_output.stdout);
+                    Ok(sys_output) if sys_output.success => {
+                        let stdout = &sys_output.stdout;
                         let lines: Vec<&str> = stdout.lines().collect();
                         
                         for (j, line) in lines.iter().enumerate() {
@@ -425,15 +486,16 @@ pub fn get_direct_monitor_info() -> Result<Vec<HardwareItem>, String> {
                 Ok(monitor_info)
             }
         }
-        Err(e) => Err(format!("执行PowerShell命令失败: {}", e)),
-        _ => Err("PowerShell命令执行失败".to_string())
+        Ok(output) => Err(HamsterError::command_failed("PowerShell命令执行失败", output.status_code, &output.stderr)),
+        Err(e) => Err(e),
     }
 }
 
 /// 获取完整的显示器信息（包括EDID和当前分辨率）
-pub fn get_complete_monitor_info(wmi_con: &WMIConnection) -> Result<Vec<HardwareItem>, String> {
+#[cfg(windows)]
+pub fn get_complete_monitor_info(platform: &dyn Platform, wmi_con: &WMIConnection) -> HamsterResult<Vec<HardwareItem>> {
     // 首先尝试使用Windows API直接获取显示器信息
-    match get_direct_monitor_info() {
+    match get_direct_monitor_info(platform) {
         Ok(info) if !info.is_empty() && !info[0].text.contains("未检测到物理显示器") => {
             // 如果Windows API成功获取到信息，直接返回
             return Ok(info);
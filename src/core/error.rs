@@ -0,0 +1,285 @@
+// 统一错误类型：按类别区分错误并保留底层错误链，面向用户的本地化文本由 localized 单独渲染
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use crate::core::locale::Locale;
+
+/// 被包装的底层错误
+pub type BoxError = Box<dyn StdError + Send + Sync + 'static>;
+
+pub type HamsterResult<T> = Result<T, HamsterError>;
+
+/// Windows ERROR_ACCESS_DENIED
+const ERROR_ACCESS_DENIED: i32 = 5;
+
+/// 错误类别，调用方据此决定如何处理（如权限不足时提供以管理员身份重试）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    Io,
+    Permission,
+    Platform,
+    Parse,
+    Network,
+    Policy,
+    Cancelled,
+}
+
+impl ErrorCategory {
+    pub fn label(&self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (ErrorCategory::Io, Locale::ZhCn) => "文件读写错误",
+            (ErrorCategory::Permission, Locale::ZhCn) => "权限不足",
+            (ErrorCategory::Platform, Locale::ZhCn) => "系统调用失败",
+            (ErrorCategory::Parse, Locale::ZhCn) => "数据格式错误",
+            (ErrorCategory::Network, Locale::ZhCn) => "网络错误",
+            (ErrorCategory::Policy, Locale::ZhCn) => "操作被拒绝",
+            (ErrorCategory::Cancelled, Locale::ZhCn) => "操作已取消",
+            (ErrorCategory::Io, Locale::EnUs) => "I/O error",
+            (ErrorCategory::Permission, Locale::EnUs) => "Permission denied",
+            (ErrorCategory::Platform, Locale::EnUs) => "System call failed",
+            (ErrorCategory::Parse, Locale::EnUs) => "Invalid data",
+            (ErrorCategory::Network, Locale::EnUs) => "Network error",
+            (ErrorCategory::Policy, Locale::EnUs) => "Operation refused",
+            (ErrorCategory::Cancelled, Locale::EnUs) => "Operation cancelled",
+        }
+    }
+
+    fn hint(&self, locale: Locale) -> Option<&'static str> {
+        match (self, locale) {
+            (ErrorCategory::Permission, Locale::ZhCn) => Some("请以管理员身份重新运行后重试"),
+            (ErrorCategory::Permission, Locale::EnUs) => Some("Run the program as administrator and try again"),
+            (ErrorCategory::Network, Locale::ZhCn) => Some("请检查网络连接或代理设置"),
+            (ErrorCategory::Network, Locale::EnUs) => Some("Check the network connection or proxy settings"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ErrorCategory::Io => "io",
+            ErrorCategory::Permission => "permission",
+            ErrorCategory::Platform => "platform",
+            ErrorCategory::Parse => "parse",
+            ErrorCategory::Network => "network",
+            ErrorCategory::Policy => "policy",
+            ErrorCategory::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// core 中所有操作的错误类型
+///
+/// `context` 描述失败的操作，`source` 保存底层错误；`Display` 只输出本层信息，
+/// 完整的错误链和类别说明通过 [`HamsterError::localized`] 获取。
+#[derive(Debug)]
+pub enum HamsterError {
+    /// 文件系统读写失败；`PermissionDenied` 归入权限类别
+    Io { context: String, source: io::Error },
+    /// 需要管理员权限或访问被拒绝
+    Permission { context: String, source: Option<BoxError> },
+    /// WMI、服务控制管理器、外部命令等系统接口失败
+    Platform { context: String, source: Option<BoxError> },
+    /// 清单、INF、JSON等数据无法解析
+    Parse { context: String, source: Option<BoxError> },
+    /// 网络请求失败
+    Network { context: String, source: Option<BoxError> },
+    /// 操作违反校验或安全策略而被拒绝
+    Policy { context: String },
+    /// 后台任务被用户取消
+    Cancelled { context: String },
+}
+
+impl HamsterError {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        HamsterError::Io { context: context.into(), source }
+    }
+
+    pub fn permission(context: impl Into<String>) -> Self {
+        HamsterError::Permission { context: context.into(), source: None }
+    }
+
+    pub fn platform(context: impl Into<String>) -> Self {
+        HamsterError::Platform { context: context.into(), source: None }
+    }
+
+    pub fn parse(context: impl Into<String>) -> Self {
+        HamsterError::Parse { context: context.into(), source: None }
+    }
+
+    pub fn network(context: impl Into<String>) -> Self {
+        HamsterError::Network { context: context.into(), source: None }
+    }
+
+    pub fn policy(context: impl Into<String>) -> Self {
+        HamsterError::Policy { context: context.into() }
+    }
+
+    pub fn cancelled(context: impl Into<String>) -> Self {
+        HamsterError::Cancelled { context: context.into() }
+    }
+
+    /// 附加底层错误；对 Io、Policy 和 Cancelled 无效
+    pub fn with_source(mut self, error: impl Into<BoxError>) -> Self {
+        match &mut self {
+            HamsterError::Permission { source, .. }
+            | HamsterError::Platform { source, .. }
+            | HamsterError::Parse { source, .. }
+            | HamsterError::Network { source, .. } => *source = Some(error.into()),
+            HamsterError::Io { .. } | HamsterError::Policy { .. } | HamsterError::Cancelled { .. } => {}
+        }
+        self
+    }
+
+    /// 根据外部命令的退出码构造错误，退出码为拒绝访问时归入权限类别
+    pub fn command_failed(context: impl Into<String>, status_code: Option<i32>, output: &str) -> Self {
+        let error = if status_code == Some(ERROR_ACCESS_DENIED) {
+            Self::permission(context)
+        } else {
+            Self::platform(context)
+        };
+        let output = output.trim();
+        if output.is_empty() {
+            error
+        } else {
+            error.with_source(output.to_string())
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            HamsterError::Io { source, .. } if source.kind() == io::ErrorKind::PermissionDenied => ErrorCategory::Permission,
+            HamsterError::Io { .. } => ErrorCategory::Io,
+            HamsterError::Permission { .. } => ErrorCategory::Permission,
+            HamsterError::Platform { .. } => ErrorCategory::Platform,
+            HamsterError::Parse { .. } => ErrorCategory::Parse,
+            HamsterError::Network { .. } => ErrorCategory::Network,
+            HamsterError::Policy { .. } => ErrorCategory::Policy,
+            HamsterError::Cancelled { .. } => ErrorCategory::Cancelled,
+        }
+    }
+
+    pub fn context(&self) -> &str {
+        match self {
+            HamsterError::Io { context, .. }
+            | HamsterError::Permission { context, .. }
+            | HamsterError::Platform { context, .. }
+            | HamsterError::Parse { context, .. }
+            | HamsterError::Network { context, .. }
+            | HamsterError::Policy { context }
+            | HamsterError::Cancelled { context } => context,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.category() == ErrorCategory::Cancelled
+    }
+
+    /// 以管理员身份重新运行是否可能解决该错误
+    pub fn requires_elevation(&self) -> bool {
+        self.category() == ErrorCategory::Permission
+    }
+
+    /// 从本层开始的完整错误链
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn StdError + 'static)> {
+        std::iter::successors(Some(self as &(dyn StdError + 'static)), |&error| error.source())
+    }
+
+    /// 面向用户的错误描述：类别、操作、底层原因和处理建议
+    pub fn localized(&self, locale: Locale) -> String {
+        let category = self.category();
+        let separator = match locale {
+            Locale::ZhCn => "：",
+            Locale::EnUs => ": ",
+        };
+        let mut text = format!("{}{}{}", category.label(locale), separator, self.context());
+
+        let causes: Vec<String> = self.chain().skip(1).map(|cause| cause.to_string()).collect();
+        if !causes.is_empty() {
+            let reason = match locale {
+                Locale::ZhCn => "原因",
+                Locale::EnUs => "caused by",
+            };
+            text.push_str(&format!("\n{}{}{}", reason, separator, causes.join(" ← ")));
+        }
+        if let Some(hint) = category.hint(locale) {
+            text.push('\n');
+            text.push_str(hint);
+        }
+        text
+    }
+}
+
+impl fmt::Display for HamsterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.category(), self.context())
+    }
+}
+
+impl StdError for HamsterError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            HamsterError::Io { source, .. } => Some(source),
+            HamsterError::Permission { source, .. }
+            | HamsterError::Platform { source, .. }
+            | HamsterError::Parse { source, .. }
+            | HamsterError::Network { source, .. } => source.as_deref().map(|e| e as &(dyn StdError + 'static)),
+            HamsterError::Policy { .. } | HamsterError::Cancelled { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constructors_map_to_categories() {
+        assert_eq!(HamsterError::io("读取", io::Error::from(io::ErrorKind::NotFound)).category(), ErrorCategory::Io);
+        assert_eq!(HamsterError::io("读取", io::Error::from(io::ErrorKind::PermissionDenied)).category(), ErrorCategory::Permission);
+        assert_eq!(HamsterError::permission("安装").category(), ErrorCategory::Permission);
+        assert_eq!(HamsterError::platform("WMI").category(), ErrorCategory::Platform);
+        assert_eq!(HamsterError::parse("INF").category(), ErrorCategory::Parse);
+        assert_eq!(HamsterError::network("下载").category(), ErrorCategory::Network);
+        assert_eq!(HamsterError::policy("校验").category(), ErrorCategory::Policy);
+        assert_eq!(HamsterError::cancelled("扫描").category(), ErrorCategory::Cancelled);
+    }
+
+    #[test]
+    fn only_cancelled_errors_are_cancelled() {
+        assert!(HamsterError::cancelled("扫描").is_cancelled());
+        assert!(!HamsterError::platform("扫描").is_cancelled());
+        assert!(HamsterError::permission("安装").requires_elevation());
+        assert!(!HamsterError::parse("INF").requires_elevation());
+    }
+
+    #[test]
+    fn access_denied_exit_code_is_a_permission_error() {
+        let error = HamsterError::command_failed("sc stop", Some(ERROR_ACCESS_DENIED), "拒绝访问。\n");
+        assert_eq!(error.category(), ErrorCategory::Permission);
+        assert_eq!(error.source().map(|source| source.to_string()).as_deref(), Some("拒绝访问。"));
+        let error = HamsterError::command_failed("sc stop", Some(1060), "  ");
+        assert_eq!(error.category(), ErrorCategory::Platform);
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn display_shows_only_this_layer_and_localized_shows_chain() {
+        let inner = HamsterError::parse("第 3 行格式错误");
+        let error = HamsterError::platform("读取驱动列表失败").with_source(inner);
+        assert_eq!(error.to_string(), "[platform] 读取驱动列表失败");
+        assert_eq!(error.chain().count(), 2);
+        assert_eq!(error.localized(Locale::ZhCn), "系统调用失败：读取驱动列表失败\n原因：[parse] 第 3 行格式错误");
+        let error = HamsterError::permission("停止服务");
+        assert_eq!(error.localized(Locale::EnUs), "Permission denied: 停止服务\nRun the program as administrator and try again");
+    }
+
+    #[test]
+    fn source_is_ignored_for_policy_and_cancelled() {
+        let error = HamsterError::policy("拒绝").with_source("原因".to_string());
+        assert!(error.source().is_none());
+        assert_eq!(error.context(), "拒绝");
+    }
+}
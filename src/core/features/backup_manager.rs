@@ -0,0 +1,211 @@
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use crate::core::driver_manager::DriverInfo;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::features::driver_manager::{DriverManagement, RestoreResult};
+use crate::core::jobs::JobContext;
+use crate::core::settings::Settings;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+	pub backup_id: String,
+	pub timestamp: String,
+	pub system_info: SystemInfo,
+	pub drivers: Vec<BackupDriverInfo>,
+	pub checksum: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemInfo {
+	pub windows_version: String,
+	pub architecture: String,
+	pub build_number: String,
+	pub backup_tool_version: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupDriverInfo {
+	pub name: String,
+	pub display_name: String,
+	pub file_name: String,
+	pub version: String,
+	pub registry_info: String,
+	pub backup_time: String,
+}
+
+/// 备份集的校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerification {
+	pub backup_id: String,
+	pub checksum_valid: bool,
+	pub missing_files: Vec<String>,
+}
+
+impl BackupVerification {
+	pub fn is_valid(&self) -> bool {
+		self.checksum_valid && self.missing_files.is_empty()
+	}
+}
+
+#[allow(dead_code)]
+pub struct BackupManager {
+	backup_dir: PathBuf,
+}
+
+#[allow(dead_code)]
+impl BackupManager {
+	pub fn new(settings: &Settings) -> HamsterResult<Self> {
+		let backup_dir = settings.backup_dir.clone();
+
+		if !backup_dir.exists() {
+			std::fs::create_dir_all(&backup_dir)
+				.map_err(|e| HamsterError::io(format!("无法创建备份目录 {:?}", backup_dir), e))?;
+		}
+
+		Ok(Self { backup_dir })
+	}
+
+	pub fn backup_dir(&self) -> &Path {
+		&self.backup_dir
+	}
+
+	/// 逐个备份驱动并写入备份集清单，返回清单内容；取消时不写入清单
+	pub fn create_backup(&self, management: &mut DriverManagement, drivers: &[DriverInfo], job: &JobContext) -> HamsterResult<BackupManifest> {
+		let backup_id = format!(
+			"backup_{}",
+			chrono::Local::now().format("%Y%m%d_%H%M%S")
+		);
+
+		let mut backed_up = Vec::new();
+		let mut failures = Vec::new();
+		let mut elevation_needed = false;
+		// 最后一步写入清单
+		let total_steps = drivers.len() + 1;
+		for (index, driver) in drivers.iter().enumerate() {
+			job.check_cancelled()?;
+			job.report(index, total_steps, format!("正在备份 {}", driver.name));
+			let result = management.backup_driver(driver);
+			if result.success {
+				backed_up.push(BackupDriverInfo {
+					name: driver.name.clone(),
+					display_name: driver.display_name.clone(),
+					file_name: result.backup_path.display().to_string(),
+					version: driver.version.clone(),
+					registry_info: format!("HKLM\\SYSTEM\\CurrentControlSet\\Services\\{}", driver.name),
+					backup_time: result.timestamp,
+				});
+			} else {
+				elevation_needed |= result.requires_elevation;
+				failures.push(format!("{}: {}", driver.name, result.message));
+			}
+		}
+
+		if backed_up.is_empty() {
+			return Err(if failures.is_empty() {
+				HamsterError::policy("没有需要备份的驱动")
+			} else if elevation_needed {
+				HamsterError::permission("所有驱动备份失败").with_source(failures.join("; "))
+			} else {
+				HamsterError::io("所有驱动备份失败", std::io::Error::other(failures.join("; ")))
+			});
+		}
+
+		job.check_cancelled()?;
+		job.report(drivers.len(), total_steps, "正在写入备份清单");
+		let manifest = BackupManifest {
+			checksum: Self::compute_checksum(&backed_up)?,
+			backup_id,
+			timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+			system_info: SystemInfo {
+				windows_version: std::env::consts::OS.to_string(),
+				architecture: std::env::consts::ARCH.to_string(),
+				build_number: "未知".to_string(),
+				backup_tool_version: env!("CARGO_PKG_VERSION").to_string(),
+			},
+			drivers: backed_up,
+		};
+
+		let content = serde_json::to_string_pretty(&manifest)
+			.map_err(|e| HamsterError::parse("序列化备份清单失败").with_source(e))?;
+		std::fs::write(self.manifest_path(&manifest.backup_id), content)
+			.map_err(|e| HamsterError::io("写入备份清单失败", e))?;
+
+		job.report(total_steps, total_steps, "备份完成");
+		Ok(manifest)
+	}
+
+	/// 列出备份目录中的所有备份集，按时间先后排序
+	pub fn list_backups(&self) -> HamsterResult<Vec<BackupManifest>> {
+		let entries = std::fs::read_dir(&self.backup_dir)
+			.map_err(|e| HamsterError::io(format!("无法读取备份目录 {:?}", self.backup_dir), e))?;
+
+		let mut manifests: Vec<BackupManifest> = entries
+			.filter_map(|entry| entry.ok())
+			.map(|entry| entry.path())
+			.filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+			.filter_map(|path| Self::read_manifest(&path).ok())
+			.collect();
+		manifests.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+		Ok(manifests)
+	}
+
+	pub fn load_backup(&self, backup_id: &str) -> HamsterResult<BackupManifest> {
+		Self::read_manifest(&self.manifest_path(backup_id))
+	}
+
+	/// 校验备份清单的校验和以及备份文件是否齐全
+	pub fn verify_backup(&self, backup_id: &str) -> HamsterResult<BackupVerification> {
+		let manifest = self.load_backup(backup_id)?;
+		let checksum_valid = Self::compute_checksum(&manifest.drivers)? == manifest.checksum;
+		let missing_files = manifest.drivers.iter()
+			.filter(|driver| !Path::new(&driver.file_name).exists())
+			.map(|driver| driver.file_name.clone())
+			.collect();
+
+		Ok(BackupVerification {
+			backup_id: manifest.backup_id,
+			checksum_valid,
+			missing_files,
+		})
+	}
+
+	/// 校验通过后逐个恢复备份集中的驱动
+	pub fn restore_backup(&self, management: &mut DriverManagement, backup_id: &str, job: &JobContext) -> HamsterResult<Vec<RestoreResult>> {
+		job.report(0, 1, "正在校验备份集");
+		let verification = self.verify_backup(backup_id)?;
+		if !verification.is_valid() {
+			return Err(HamsterError::policy(format!("备份校验失败，拒绝恢复: {}", backup_id)));
+		}
+
+		let manifest = self.load_backup(backup_id)?;
+		let total_steps = manifest.drivers.len();
+		let mut results = Vec::new();
+		for (index, driver) in manifest.drivers.iter().enumerate() {
+			job.check_cancelled()?;
+			job.report(index, total_steps, format!("正在恢复 {}", driver.name));
+			results.push(management.restore_driver(&PathBuf::from(&driver.file_name)));
+		}
+		job.report(total_steps, total_steps, "恢复完成");
+		Ok(results)
+	}
+
+	fn manifest_path(&self, backup_id: &str) -> PathBuf {
+		self.backup_dir.join(format!("{}.json", backup_id))
+	}
+
+	fn read_manifest(path: &Path) -> HamsterResult<BackupManifest> {
+		let content = std::fs::read_to_string(path)
+			.map_err(|e| HamsterError::io(format!("无法读取备份清单 {:?}", path), e))?;
+		serde_json::from_str(&content)
+			.map_err(|e| HamsterError::parse(format!("解析备份清单 {:?} 失败", path)).with_source(e))
+	}
+
+	fn compute_checksum(drivers: &[BackupDriverInfo]) -> HamsterResult<String> {
+		let content = serde_json::to_vec(drivers)
+			.map_err(|e| HamsterError::parse("序列化备份驱动列表失败").with_source(e))?;
+		Ok(Sha256::digest(&content).iter().map(|b| format!("{:02x}", b)).collect())
+	}
+}
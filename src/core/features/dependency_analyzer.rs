@@ -0,0 +1,427 @@
+// 驱动服务的依赖关系：由 DriverInfo.dependencies 建立依赖图，其中以 `+` 开头的是加载顺序组，
+// 组中任意一个服务启动后即满足依赖。提供完整的传递依赖链、按强连通分量检测循环依赖、启动顺序，
+// 以及对不存在或已禁用服务的依赖。服务名和组名与 Windows 一样不区分大小写
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use serde::Serialize;
+use crate::core::driver_manager::DriverInfo;
+use crate::core::error::HamsterResult;
+
+// 服务控制管理器中组依赖的前缀
+const GROUP_PREFIX: char = '+';
+const DISABLED: &str = "禁用";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IssueKind {
+	/// 依赖的服务不存在
+	MissingService,
+	/// 依赖的加载顺序组中除驱动自身外没有服务
+	MissingGroup,
+	/// 依赖的服务被禁用
+	DisabledService,
+	/// 依赖的加载顺序组中的服务全部被禁用
+	DisabledGroup,
+}
+
+/// 导致驱动无法启动的依赖
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyIssue {
+	pub driver: String,
+	/// 依赖的服务名，或带 `+` 前缀的组名
+	pub dependency: String,
+	pub kind: IssueKind,
+}
+
+impl fmt::Display for DependencyIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.kind {
+			IssueKind::MissingService => write!(f, "{} 依赖的服务 {} 不存在", self.driver, self.dependency),
+			IssueKind::MissingGroup => write!(f, "{} 依赖的加载顺序组 {} 中没有其他服务", self.driver, self.dependency),
+			IssueKind::DisabledService => write!(f, "{} 依赖的服务 {} 已被禁用", self.driver, self.dependency),
+			IssueKind::DisabledGroup => write!(f, "{} 依赖的加载顺序组 {} 中的服务全部被禁用", self.driver, self.dependency),
+		}
+	}
+}
+
+/// 一次分析的全部结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyReport {
+	pub start_order: Vec<String>,
+	pub cycles: Vec<Vec<String>>,
+	pub issues: Vec<DependencyIssue>,
+}
+
+#[derive(Debug, Clone)]
+struct ServiceNode {
+	name: String,
+	start_type: String,
+	load_order: u32,
+}
+
+/// 一项依赖：某个服务，或加载顺序组中的任意一个服务（以小写服务名表示，不含依赖它的驱动自身）
+#[derive(Debug, Clone)]
+enum Dependency {
+	Service(String),
+	Group(Vec<String>),
+}
+
+impl Dependency {
+	fn members(&self) -> &[String] {
+		match self {
+			Dependency::Service(key) => std::slice::from_ref(key),
+			Dependency::Group(members) => members,
+		}
+	}
+}
+
+impl ServiceNode {
+	fn is_disabled(&self) -> bool {
+		self.start_type == DISABLED
+	}
+
+	// 启动顺序中的排序键：先按启动类型（引导、系统、自动、手动），再按加载顺序和名称
+	fn start_key(&self) -> (u8, u32, String) {
+		let start_rank = match self.start_type.as_str() {
+			"引导" => 0,
+			"系统" => 1,
+			"自动" => 2,
+			"手动" => 3,
+			DISABLED => 5,
+			_ => 4,
+		};
+		(start_rank, self.load_order, self.name.to_lowercase())
+	}
+}
+
+pub struct DependencyAnalyzer {
+	// 以下均以小写的服务名为键
+	services: HashMap<String, ServiceNode>,
+	dependencies: HashMap<String, Vec<Dependency>>,
+	dependents: HashMap<String, Vec<String>>,
+	issues: Vec<DependencyIssue>,
+}
+
+impl Default for DependencyAnalyzer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl DependencyAnalyzer {
+	pub fn new() -> Self {
+		Self {
+			services: HashMap::new(),
+			dependencies: HashMap::new(),
+			dependents: HashMap::new(),
+			issues: Vec::new(),
+		}
+	}
+
+	pub fn analyze_dependencies(&mut self, drivers: &[DriverInfo]) -> HamsterResult<()> {
+		// 清空现有数据
+		self.services.clear();
+		self.dependencies.clear();
+		self.dependents.clear();
+		self.issues.clear();
+
+		let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+		for driver in drivers {
+			let key = driver.name.to_lowercase();
+			if !driver.group.is_empty() {
+				groups.entry(driver.group.to_lowercase()).or_default().push(key.clone());
+			}
+			self.services.insert(key, ServiceNode {
+				name: driver.name.clone(),
+				start_type: driver.start_type.clone(),
+				load_order: driver.load_order,
+			});
+		}
+
+		for driver in drivers {
+			let key = driver.name.to_lowercase();
+			let enabled = !self.services[&key].is_disabled();
+			let mut resolved: Vec<Dependency> = Vec::new();
+			let mut seen = HashSet::new();
+			for dependency in driver.dependencies.iter().map(|dependency| dependency.trim()).filter(|dependency| !dependency.is_empty()) {
+				let issue = |kind| DependencyIssue { driver: driver.name.clone(), dependency: dependency.to_string(), kind };
+				if let Some(group) = dependency.strip_prefix(GROUP_PREFIX) {
+					// 组依赖只需组中有服务能启动，驱动自身不算在内
+					let members: Vec<&String> = groups.get(&group.to_lowercase())
+						.map(|members| members.iter().filter(|member| **member != key).collect())
+						.unwrap_or_default();
+					if members.is_empty() {
+						self.issues.push(issue(IssueKind::MissingGroup));
+					} else if enabled && members.iter().all(|member| self.services[*member].is_disabled()) {
+						self.issues.push(issue(IssueKind::DisabledGroup));
+					}
+					if !members.is_empty() && seen.insert(dependency.to_lowercase()) {
+						resolved.push(Dependency::Group(members.into_iter().cloned().collect()));
+					}
+				} else {
+					let dependency_key = dependency.to_lowercase();
+					match self.services.get(&dependency_key) {
+						None => self.issues.push(issue(IssueKind::MissingService)),
+						Some(service) => {
+							if enabled && service.is_disabled() {
+								self.issues.push(issue(IssueKind::DisabledService));
+							}
+							if seen.insert(dependency_key.clone()) {
+								resolved.push(Dependency::Service(dependency_key));
+							}
+						}
+					}
+				}
+			}
+
+			let targets: BTreeSet<&String> = resolved.iter().flat_map(Dependency::members).collect();
+			for target in targets {
+				self.dependents.entry(target.clone()).or_default().push(key.clone());
+			}
+			self.dependencies.insert(key, resolved);
+		}
+
+		Ok(())
+	}
+
+	/// 启动该驱动前必须先启动的全部驱动（传递依赖），按启动顺序排列，不含驱动自身；
+	/// 依赖加载顺序组时只取组中最先启动的一个服务
+	pub fn get_dependency_chain(&self, driver_name: &str) -> Vec<String> {
+		let (order, _) = self.resolve_order();
+		let position: HashMap<&str, usize> = order.iter().enumerate().map(|(position, key)| (*key, position)).collect();
+		let mut visited = HashSet::new();
+		let mut chain = Vec::new();
+		let key = driver_name.to_lowercase();
+		visited.insert(key.clone());
+		self.visit_dependencies(&key, &position, &mut visited, &mut chain);
+		chain.iter().map(|key| self.services[key].name.clone()).collect()
+	}
+
+	// 深度优先的后序遍历：依赖排在依赖它的驱动之前；组中已有服务在链中时不再添加，
+	// 正在等待该依赖的驱动（已访问但不在链中）不能用来满足组依赖
+	fn visit_dependencies(&self, key: &str, position: &HashMap<&str, usize>, visited: &mut HashSet<String>, chain: &mut Vec<String>) {
+		for dependency in self.dependencies.get(key).into_iter().flatten() {
+			let next = match dependency {
+				Dependency::Service(service) => service,
+				Dependency::Group(members) if members.iter().any(|member| chain.contains(member)) => continue,
+				// 无法启动的服务排在启动顺序之外，最后才选
+				Dependency::Group(members) => {
+					let candidates = members.iter().filter(|member| !visited.contains(*member));
+					let Some(member) = candidates.min_by_key(|member| position.get(member.as_str()).copied().unwrap_or(usize::MAX)) else {
+						continue;
+					};
+					member
+				}
+			};
+			if visited.insert(next.clone()) {
+				self.visit_dependencies(next, position, visited, chain);
+				chain.push(next.clone());
+			}
+		}
+	}
+
+	/// 依赖该驱动的驱动（直接依赖），停止该驱动前需要先停止它们
+	pub fn get_dependents(&self, driver_name: &str) -> Vec<String> {
+		self.dependents.get(&driver_name.to_lowercase())
+			.map(|dependents| dependents.iter().map(|key| self.services[key].name.clone()).collect())
+			.unwrap_or_default()
+	}
+
+	/// 循环依赖：无法启动的驱动之间包含多个驱动或依赖自身的强连通分量，分量内的驱动按名称排序。
+	/// 加载顺序组中有服务能启动时组依赖已满足，不构成循环
+	pub fn find_circular_dependencies(&self) -> Vec<Vec<String>> {
+		let (order, blocked) = self.resolve_order();
+		let started: HashSet<&str> = order.into_iter().collect();
+		// 只保留无法满足的依赖：被阻塞的服务，以及没有任何服务能启动的组中的全部服务
+		let graph: HashMap<&str, Vec<&str>> = blocked.iter()
+			.map(|key| {
+				let targets = self.dependencies.get(*key).into_iter().flatten()
+					.filter(|dependency| !dependency.members().iter().any(|member| started.contains(member.as_str())))
+					.flat_map(|dependency| dependency.members().iter().map(String::as_str))
+					.collect();
+				(*key, targets)
+			})
+			.collect();
+		let mut cycles: Vec<Vec<String>> = strongly_connected_components(&graph).into_iter()
+			.filter(|component| component.len() > 1 || graph[component[0].as_str()].contains(&component[0].as_str()))
+			.map(|component| {
+				let mut names: Vec<String> = component.iter().map(|key| self.services[key].name.clone()).collect();
+				names.sort_by_key(|name| name.to_lowercase());
+				names
+			})
+			.collect();
+		cycles.sort();
+		cycles
+	}
+
+	/// 启动顺序：每个驱动排在其全部依赖之后，没有先后约束时按启动类型、加载顺序和名称排列；
+	/// 处于循环依赖中或依赖循环的驱动无法确定顺序，按同样的规则排在最后
+	pub fn start_order(&self) -> Vec<String> {
+		let (order, blocked) = self.resolve_order();
+		order.into_iter().chain(blocked)
+			.map(|key| self.services[key].name.clone())
+			.collect()
+	}
+
+	// 能确定顺序的驱动（按启动顺序）和无法启动的驱动（按排序键）；
+	// 服务依赖在该服务启动后满足，组依赖在组中第一个服务启动后满足
+	fn resolve_order(&self) -> (Vec<&str>, Vec<&str>) {
+		let mut remaining: HashMap<&str, usize> = self.services.keys()
+			.map(|key| (key.as_str(), self.dependencies.get(key).map_or(0, Vec::len)))
+			.collect();
+		let mut ready: BTreeSet<((u8, u32, String), &str)> = remaining.iter()
+			.filter(|(_, count)| **count == 0)
+			.map(|(key, _)| (self.services[*key].start_key(), *key))
+			.collect();
+		let mut satisfied: HashSet<(&str, usize)> = HashSet::new();
+		let mut order = Vec::new();
+
+		while let Some(next) = ready.pop_first() {
+			let (_, key) = next;
+			remaining.remove(key);
+			order.push(key);
+			for dependent in self.dependents.get(key).into_iter().flatten() {
+				let Some(count) = remaining.get_mut(dependent.as_str()) else {
+					continue;
+				};
+				for (index, dependency) in self.dependencies[dependent].iter().enumerate() {
+					if dependency.members().iter().any(|member| member == key) && satisfied.insert((dependent.as_str(), index)) {
+						*count -= 1;
+					}
+				}
+				if *count == 0 {
+					ready.insert((self.services[dependent].start_key(), dependent.as_str()));
+				}
+			}
+		}
+
+		let mut blocked: Vec<&str> = remaining.into_keys().collect();
+		blocked.sort_by_key(|key| self.services[*key].start_key());
+		(order, blocked)
+	}
+
+	/// 对不存在或已禁用的服务（组）的依赖；已禁用的驱动依赖已禁用的服务不算问题
+	pub fn issues(&self) -> &[DependencyIssue] {
+		&self.issues
+	}
+
+	pub fn report(&self) -> DependencyReport {
+		DependencyReport {
+			start_order: self.start_order(),
+			cycles: self.find_circular_dependencies(),
+			issues: self.issues.clone(),
+		}
+	}
+}
+
+/// Tarjan 算法
+fn strongly_connected_components(graph: &HashMap<&str, Vec<&str>>) -> Vec<Vec<String>> {
+	struct Tarjan<'a> {
+		graph: &'a HashMap<&'a str, Vec<&'a str>>,
+		next_index: usize,
+		index: HashMap<&'a str, usize>,
+		low_link: HashMap<&'a str, usize>,
+		stack: Vec<&'a str>,
+		on_stack: HashSet<&'a str>,
+		components: Vec<Vec<String>>,
+	}
+
+	impl<'a> Tarjan<'a> {
+		fn connect(&mut self, key: &'a str) {
+			self.index.insert(key, self.next_index);
+			self.low_link.insert(key, self.next_index);
+			self.next_index += 1;
+			self.stack.push(key);
+			self.on_stack.insert(key);
+
+			for &dependency in self.graph.get(key).into_iter().flatten() {
+				if !self.index.contains_key(dependency) {
+					self.connect(dependency);
+					let low_link = self.low_link[key].min(self.low_link[dependency]);
+					self.low_link.insert(key, low_link);
+				} else if self.on_stack.contains(dependency) {
+					let low_link = self.low_link[key].min(self.index[dependency]);
+					self.low_link.insert(key, low_link);
+				}
+			}
+
+			if self.low_link[key] == self.index[key] {
+				let mut component = Vec::new();
+				while let Some(member) = self.stack.pop() {
+					self.on_stack.remove(member);
+					component.push(member.to_string());
+					if member == key {
+						break;
+					}
+				}
+				self.components.push(component);
+			}
+		}
+	}
+
+	let mut tarjan = Tarjan {
+		graph,
+		next_index: 0,
+		index: HashMap::new(),
+		low_link: HashMap::new(),
+		stack: Vec::new(),
+		on_stack: HashSet::new(),
+		components: Vec::new(),
+	};
+	let mut keys: Vec<&str> = graph.keys().copied().collect();
+	keys.sort();
+	for key in keys {
+		if !tarjan.index.contains_key(key) {
+			tarjan.connect(key);
+		}
+	}
+	tarjan.components
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::Local;
+	use crate::core::driver_manager::{DriverStatus, DriverType};
+
+	fn driver(name: &str, group: &str, dependencies: &[&str]) -> DriverInfo {
+		DriverInfo {
+			name: name.to_string(),
+			display_name: name.to_string(),
+			description: String::new(),
+			status: DriverStatus::Stopped,
+			driver_type: DriverType::KernelMode,
+			start_type: "系统".to_string(),
+			binary_path: String::new(),
+			version: String::new(),
+			company: String::new(),
+			signed: false,
+			signature_status: String::new(),
+			last_updated: Local::now(),
+			dependencies: dependencies.iter().map(|dependency| dependency.to_string()).collect(),
+			load_order: 0,
+			group: group.to_string(),
+		}
+	}
+
+	#[test]
+	fn group_dependency_needs_any_one_member() {
+		// X 依赖组 G，G 中的 N2 又依赖 X；N1 先启动即满足 X 的依赖，不是循环
+		let drivers = [driver("X", "", &["+G"]), driver("N1", "G", &[]), driver("N2", "G", &["X"])];
+		let mut analyzer = DependencyAnalyzer::new();
+		analyzer.analyze_dependencies(&drivers).unwrap();
+		assert!(analyzer.find_circular_dependencies().is_empty());
+		assert_eq!(analyzer.start_order(), ["N1", "X", "N2"]);
+		assert_eq!(analyzer.get_dependency_chain("X"), ["N1"]);
+		assert_eq!(analyzer.get_dependency_chain("N2"), ["N1", "X"]);
+	}
+
+	#[test]
+	fn group_whose_members_all_wait_is_a_cycle() {
+		let drivers = [driver("X", "", &["+G"]), driver("N1", "G", &["X"]), driver("N2", "G", &["X"]), driver("Y", "", &["X"])];
+		let mut analyzer = DependencyAnalyzer::new();
+		analyzer.analyze_dependencies(&drivers).unwrap();
+		assert_eq!(analyzer.find_circular_dependencies(), [["N1", "N2", "X"]]);
+		assert_eq!(analyzer.start_order(), ["N1", "N2", "X", "Y"]);
+	}
+}
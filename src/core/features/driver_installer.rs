@@ -0,0 +1,367 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use serde::{Serialize, Deserialize};
+use crate::core::audit::{AuditAction, AuditEvent, AuditJournal};
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::features::package_scanner::{DriverPackage, PackageScanner};
+use crate::core::features::signature_validator::SignatureValidator;
+use crate::core::inf::InfFile;
+use crate::core::jobs::JobContext;
+use crate::core::locale::Locale;
+use crate::core::platform::Platform;
+use crate::core::settings::Settings;
+use crate::core::signature::{CatalogIndex, Classification};
+use crate::core::signature::trust::TrustStore;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallableDriver {
+    pub name: String,
+    pub display_name: String,
+    pub version: String,
+    pub manufacturer: String,
+    pub file_path: PathBuf,
+    pub supported_os: Vec<String>,
+    pub signature_status: String,
+    // signature_status 对应的检查结论
+    #[serde(default)]
+    pub signature_state: SignatureState,
+    // 签名来源分类和加载预测，未检查签名时为 None
+    #[serde(default)]
+    pub signature: Option<Classification>,
+    pub install_method: InstallMethod,
+    // INF 驱动的解析结果，安装程序和无法解析的 INF 为 None
+    #[serde(default)]
+    pub inf: Option<InfFile>,
+    // INF 驱动包含的文件；位于压缩包内时安装前先解压这些文件
+    #[serde(default)]
+    pub package: Option<DriverPackage>,
+    // 分析时遇到的问题，如 INF 无法解析、跳过的型号行、编录文件无法读取
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl InstallableDriver {
+    /// 驱动的来源，压缩包内的驱动显示为 `驱动.zip!/目录/驱动.inf`
+    pub fn source(&self) -> String {
+        match &self.package {
+            Some(package) => package.inf.to_string(),
+            None => self.file_path.display().to_string(),
+        }
+    }
+}
+
+/// 驱动文件的签名检查结论
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureState {
+    /// 没有检查签名，如位于压缩包内的文件
+    #[default]
+    Unverified,
+    /// 签名有效；catalog 表示由编录文件签名，trusted 表示证书链已验证为受信任
+    Signed { catalog: bool, trusted: bool },
+    /// 签名有效但证书链不受信任
+    Untrusted,
+    /// 文件内容与签名中的摘要不符
+    Tampered,
+    Invalid,
+    Unsigned,
+}
+
+/// 签名检查的结果：说明文字、结论和签名分类
+type SignatureCheck = (String, SignatureState, Option<Classification>);
+
+/// 扫描目录的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DriverScan {
+    pub drivers: Vec<InstallableDriver>,
+    /// 被跳过的文件、目录或压缩包条目及原因
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InstallMethod {
+    INF,
+    EXE,
+    MSI,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallationResult {
+    pub success: bool,
+    pub message: String,
+    pub driver_name: String,
+    pub timestamp: String,
+    pub log_file: Option<PathBuf>,
+    // 失败原因为权限不足，以管理员身份重试可能成功
+    #[serde(default)]
+    pub requires_elevation: bool,
+}
+
+#[allow(dead_code)]
+pub struct DriverInstaller {
+    platform: Arc<dyn Platform>,
+    locale: Locale,
+    journal: AuditJournal,
+    install_history: Vec<InstallationResult>,
+    temp_dir: PathBuf,
+    trust_store: Option<TrustStore>,
+}
+
+impl DriverInstaller {
+    pub fn new(platform: Arc<dyn Platform>, settings: &Settings) -> Self {
+        let temp_dir = settings.temp_dir.clone();
+        
+        // 确保临时目录存在
+        if !temp_dir.exists() {
+            std::fs::create_dir_all(&temp_dir).unwrap_or_else(|_| {
+                eprintln!("无法创建临时目录: {:?}", temp_dir);
+            });
+        }
+        
+        Self {
+            platform,
+            locale: settings.language,
+            journal: settings.journal(),
+            install_history: Vec::new(),
+            temp_dir,
+            trust_store: settings.trust_store(),
+        }
+    }
+    
+    pub fn scan_drivers_in_directory(&self, directory: &Path, job: &JobContext) -> HamsterResult<DriverScan> {
+        // 递归扫描目录（或单个 .zip 文件）中的驱动包和安装程序，目录不存在时返回 NotFound 类型的IO错误
+        let output = PackageScanner::new(self.locale).scan(directory, job)?;
+
+        // 磁盘上的文件要逐个检查签名，逐个报告进度
+        let total = output.packages.len() + output.installers.len();
+        let mut drivers = Vec::new();
+        for (index, scanned) in output.packages.into_iter().enumerate() {
+            job.check_cancelled()?;
+            job.report(index, total, format!("正在检查 {}", scanned.package.inf));
+            let mut warnings = Vec::new();
+            if let Some(e) = &scanned.inf_error {
+                warnings.push(format!("无法解析 INF 文件: {}", e.localized(self.locale)));
+            }
+            // 压缩包内的文件无法交给系统检查签名
+            let signature = if scanned.package.is_in_archive() {
+                ("未验证（位于压缩包内）".to_string(), SignatureState::Unverified, None)
+            } else {
+                self.check_signature(&scanned.package.inf.path, scanned.inf.as_ref(), &mut warnings)
+            };
+            let name = scanned.package.inf.file_name();
+            let name = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(&name).to_string();
+            drivers.push(self.inf_driver(name, scanned.package.inf.path.clone(), scanned.inf, signature, Some(scanned.package), warnings));
+        }
+        for (index, location) in output.installers.iter().enumerate() {
+            job.check_cancelled()?;
+            job.report(drivers.len() + index, total, format!("正在检查 {}", location));
+            drivers.extend(self.analyze_driver_file(&location.path));
+        }
+        job.report(total, total, format!("找到 {} 个驱动", drivers.len()));
+        
+        Ok(DriverScan { drivers, skipped: output.skipped })
+    }
+    
+    pub fn analyze_driver_file(&self, file_path: &Path) -> Option<InstallableDriver> {
+        let extension = file_path.extension()?.to_str()?;
+        
+        match extension.to_lowercase().as_str() {
+            "inf" => {
+                // 分析INF文件，无法解析时仍列出并附带原因，以便用户自行判断
+                let name = file_path.file_stem()?.to_str()?.to_string();
+                let mut warnings = Vec::new();
+                let inf = InfFile::open(file_path, self.locale)
+                    .map_err(|e| warnings.push(format!("无法解析 INF 文件: {}", e.localized(self.locale))))
+                    .ok();
+                let signature = self.check_signature(file_path, inf.as_ref(), &mut warnings);
+                Some(self.inf_driver(name, file_path.to_path_buf(), inf, signature, None, warnings))
+            }
+            "exe" | "msi" => {
+                // 分析可执行安装程序
+                let mut warnings = Vec::new();
+                let (signature_status, signature_state, signature) = self.check_signature(file_path, None, &mut warnings);
+                Some(InstallableDriver {
+                    name: file_path.file_stem()?.to_str()?.to_string(),
+                    display_name: file_path.file_stem()?.to_str()?.to_string(),
+                    version: "未知".to_string(),
+                    manufacturer: "未知".to_string(),
+                    file_path: file_path.to_path_buf(),
+                    supported_os: vec!["Windows".to_string()],
+                    signature_status,
+                    signature_state,
+                    signature,
+                    install_method: if extension == "exe" { InstallMethod::EXE } else { InstallMethod::MSI },
+                    inf: None,
+                    package: None,
+                    warnings,
+                })
+            }
+            _ => None,
+        }
+    }
+    
+    fn inf_driver(&self, name: String, file_path: PathBuf, inf: Option<InfFile>, (signature_status, signature_state, signature): SignatureCheck, package: Option<DriverPackage>, mut warnings: Vec<String>) -> InstallableDriver {
+        // INF 中跳过的格式错误的行
+        warnings.extend(inf.iter().flat_map(|inf| inf.warnings.iter().cloned()));
+        InstallableDriver {
+            display_name: inf.as_ref().and_then(InfFile::display_name).unwrap_or(&name).to_string(),
+            version: inf.as_ref().and_then(InfFile::driver_version).unwrap_or("未知").to_string(),
+            manufacturer: inf.as_ref().and_then(InfFile::provider).unwrap_or("未知").to_string(),
+            file_path,
+            supported_os: inf.as_ref().map(InfFile::supported_os).unwrap_or_else(|| vec!["Windows".to_string()]),
+            signature_status,
+            signature_state,
+            signature,
+            install_method: InstallMethod::INF,
+            inf,
+            package,
+            name,
+            warnings,
+        }
+    }
+    
+    /// 签名状态说明、结论和签名分类；编录文件无法读取时记入 warnings
+    fn check_signature(&self, file_path: &Path, inf: Option<&InfFile>, warnings: &mut Vec<String>) -> SignatureCheck {
+        // 离线读取文件中嵌入的 Authenticode 签名；INF 由 [Version] 中 CatalogFile 指定的编录文件签名
+        let mut catalogs = CatalogIndex::new();
+        let catalog_path = inf.and_then(|inf| inf.version.catalog_file.as_deref())
+            .zip(file_path.parent())
+            .map(|(catalog, dir)| dir.join(catalog));
+        if let Some(catalog_path) = catalog_path {
+            if let Err(e) = catalogs.load_file(&catalog_path) {
+                warnings.push(format!("无法读取编录文件: {}", e.localized(self.locale)));
+            }
+        }
+        let mut validator = SignatureValidator::new().with_catalogs(catalogs);
+        if let Some(trust_store) = &self.trust_store {
+            validator = validator.with_trust_store(trust_store.clone());
+        }
+        let result = validator.validate_file(&file_path.display().to_string(), file_path);
+        let chain = if result.trusted.is_some() { "受信任" } else { "证书链未验证" };
+        let class = result.classification.as_ref().map(Classification::label).unwrap_or_default();
+        let state = if !result.checked {
+            SignatureState::Unverified
+        } else if result.tampered {
+            SignatureState::Tampered
+        } else if result.trusted == Some(false) {
+            SignatureState::Untrusted
+        } else if result.is_valid {
+            SignatureState::Signed { catalog: !result.catalog_file.is_empty(), trusted: result.trusted == Some(true) }
+        } else if result.signature_type.is_empty() {
+            SignatureState::Unsigned
+        } else {
+            SignatureState::Invalid
+        };
+        let status = match state {
+            SignatureState::Unverified => "未验证".to_string(),
+            SignatureState::Tampered => "已被篡改".to_string(),
+            SignatureState::Untrusted => "签名不受信任".to_string(),
+            SignatureState::Signed { catalog: true, .. } => format!("已由编录签名：{}（{}）", class, chain),
+            SignatureState::Signed { catalog: false, .. } => format!("已签名：{}（{}）", class, chain),
+            SignatureState::Unsigned => "未签名".to_string(),
+            SignatureState::Invalid => "签名无效".to_string(),
+        };
+        (status, state, result.classification)
+    }
+    
+    pub fn install_driver(&mut self, driver: &InstallableDriver) -> InstallationResult {
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        
+        match &driver.install_method {
+            InstallMethod::INF => self.install_inf_driver(driver, &timestamp),
+            InstallMethod::EXE => self.install_exe_driver(driver, &timestamp),
+            InstallMethod::MSI => self.install_msi_driver(driver, &timestamp),
+            InstallMethod::Manual => InstallationResult {
+                success: false,
+                message: "手动安装方法需要用户交互".to_string(),
+                driver_name: driver.name.clone(),
+                timestamp,
+                log_file: None,
+                requires_elevation: false,
+            },
+        }
+    }
+    
+    fn install_inf_driver(&mut self, driver: &InstallableDriver, timestamp: &str) -> InstallationResult {
+        // 使用pnputil安装INF驱动，压缩包内的驱动包先解压到临时目录
+        let outcome = self.prepare_inf(driver).and_then(|inf_path| {
+            let file_path = inf_path.to_string_lossy();
+            self.run_installer("pnputil", &["/add-driver", &file_path, "/install"], "驱动安装失败")
+        });
+        self.record_result(driver, timestamp, outcome, "驱动安装成功")
+    }
+    
+    fn prepare_inf(&self, driver: &InstallableDriver) -> HamsterResult<PathBuf> {
+        match &driver.package {
+            Some(package) if package.is_in_archive() => {
+                // 以包哈希命名目录，同一驱动包重复安装时覆盖之前解压的文件
+                let directory = self.temp_dir.join(format!("package-{}", &package.hash[..16]));
+                package.extract_to(&directory)
+            }
+            _ => Ok(driver.file_path.clone()),
+        }
+    }
+    
+    fn install_exe_driver(&mut self, driver: &InstallableDriver, timestamp: &str) -> InstallationResult {
+        // 运行可执行安装程序
+        let file_path = driver.file_path.to_string_lossy();
+        let outcome = self.run_installer(&file_path, &["/S"], "驱动安装失败");  // 静默安装参数
+        self.record_result(driver, timestamp, outcome, "驱动安装程序执行成功")
+    }
+    
+    fn install_msi_driver(&mut self, driver: &InstallableDriver, timestamp: &str) -> InstallationResult {
+        // 使用msiexec安装MSI包
+        let file_path = driver.file_path.to_string_lossy();
+        let outcome = self.run_installer("msiexec", &["/i", &file_path, "/quiet", "/norestart"], "MSI安装失败");
+        self.record_result(driver, timestamp, outcome, "MSI安装包执行成功")
+    }
+    
+    fn run_installer(&self, program: &str, args: &[&str], failure: &str) -> HamsterResult<()> {
+        let output = self.platform.run_command(program, args)?;
+        if output.success {
+            Ok(())
+        } else {
+            Err(HamsterError::command_failed(failure, output.status_code, &output.stderr))
+        }
+    }
+    
+    // 将安装结果记录到历史和审计日志中
+    fn record_result(&mut self, driver: &InstallableDriver, timestamp: &str, outcome: HamsterResult<()>, success_message: &str) -> InstallationResult {
+        let mut result = match outcome {
+            Ok(()) => InstallationResult {
+                success: true,
+                message: success_message.to_string(),
+                driver_name: driver.name.clone(),
+                timestamp: timestamp.to_string(),
+                log_file: None,
+                requires_elevation: false,
+            },
+            Err(e) => InstallationResult {
+                success: false,
+                message: e.localized(self.locale),
+                driver_name: driver.name.clone(),
+                timestamp: timestamp.to_string(),
+                log_file: None,
+                requires_elevation: e.requires_elevation(),
+            },
+        };
+        let mut event = AuditEvent::new(AuditAction::Install, &driver.name)
+            .outcome(result.success, &result.message, result.requires_elevation);
+        if result.success {
+            event = event.after(format!("版本 {}，来自 {}", driver.version, driver.source()));
+        }
+        if let Some(note) = self.journal.record_or_note(event) {
+            result.message = format!("{}（{}）", result.message, note);
+        }
+        self.install_history.push(result.clone());
+        result
+    }
+    
+    pub fn get_installation_history(&self) -> &Vec<InstallationResult> {
+        &self.install_history
+    }
+    
+    pub fn clear_installation_history(&mut self) {
+        self.install_history.clear();
+    }
+}
\ No newline at end of file
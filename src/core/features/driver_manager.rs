@@ -1,7 +1,12 @@
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use serde::{Serialize, Deserialize};
+use crate::core::audit::{AuditAction, AuditEvent, AuditJournal};
 use crate::core::driver_manager::DriverInfo;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::locale::Locale;
+use crate::core::platform::Platform;
+use crate::core::settings::Settings;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupResult {
@@ -10,6 +15,9 @@ pub struct BackupResult {
     pub driver_name: String,
     pub backup_path: PathBuf,
     pub timestamp: String,
+    // 失败原因为权限不足，以管理员身份重试可能成功
+    #[serde(default)]
+    pub requires_elevation: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +26,8 @@ pub struct RestoreResult {
     pub message: String,
     pub driver_name: String,
     pub timestamp: String,
+    #[serde(default)]
+    pub requires_elevation: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,10 +36,15 @@ pub struct UninstallResult {
     pub message: String,
     pub driver_name: String,
     pub timestamp: String,
+    #[serde(default)]
+    pub requires_elevation: bool,
 }
 
 #[allow(dead_code)]
 pub struct DriverManagement {
+    platform: Arc<dyn Platform>,
+    locale: Locale,
+    journal: AuditJournal,
     backup_dir: PathBuf,
     backup_history: Vec<BackupResult>,
     restore_history: Vec<RestoreResult>,
@@ -37,17 +52,14 @@ pub struct DriverManagement {
 }
 
 impl DriverManagement {
-    pub fn new() -> Self {
-        let backup_dir = PathBuf::from("./driver_backups");
-        
-        // 确保备份目录存在
-        if !backup_dir.exists() {
-            std::fs::create_dir_all(&backup_dir).unwrap_or_else(|_| {
-                eprintln!("无法创建备份目录: {:?}", backup_dir);
-            });
-        }
+    pub fn new(platform: Arc<dyn Platform>, settings: &Settings) -> Self {
+        let backup_dir = settings.driver_backup_dir();
+        Self::ensure_backup_dir(&backup_dir);
         
         Self {
+            platform,
+            locale: settings.language,
+            journal: settings.journal(),
             backup_dir,
             backup_history: Vec::new(),
             restore_history: Vec::new(),
@@ -55,6 +67,23 @@ impl DriverManagement {
         }
     }
     
+    // 设置修改后更新备份目录和语言，保留已有的操作历史
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.backup_dir = settings.driver_backup_dir();
+        self.locale = settings.language;
+        self.journal = settings.journal();
+        Self::ensure_backup_dir(&self.backup_dir);
+    }
+    
+    // 确保备份目录存在
+    fn ensure_backup_dir(backup_dir: &Path) {
+        if !backup_dir.exists() {
+            std::fs::create_dir_all(backup_dir).unwrap_or_else(|_| {
+                eprintln!("无法创建备份目录: {:?}", backup_dir);
+            });
+        }
+    }
+    
     // 备份驱动
     pub fn backup_driver(&mut self, driver: &DriverInfo) -> BackupResult {
         let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
@@ -97,20 +126,21 @@ impl DriverManagement {
                     driver_name: driver.name.clone(),
                     backup_path: backup_path.clone(),
                     timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    requires_elevation: false,
                 };
-                self.backup_history.push(result.clone());
-                result
+                self.record_backup(result)
             }
             Err(e) => {
+                let error = HamsterError::io(format!("写入备份文件 {:?} 失败", backup_path), e);
                 let result = BackupResult {
                     success: false,
-                    message: format!("备份失败: {}", e),
+                    message: error.localized(self.locale),
                     driver_name: driver.name.clone(),
-                    backup_path: backup_path,
+                    backup_path,
                     timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    requires_elevation: error.requires_elevation(),
                 };
-                self.backup_history.push(result.clone());
-                result
+                self.record_backup(result)
             }
         }
     }
@@ -123,14 +153,15 @@ impl DriverManagement {
         let _backup_content = match std::fs::read_to_string(backup_file) {
             Ok(content) => content,
             Err(e) => {
+                let error = HamsterError::io(format!("无法读取备份文件 {:?}", backup_file), e);
                 let result = RestoreResult {
                     success: false,
-                    message: format!("无法读取备份文件: {}", e),
+                    message: error.localized(self.locale),
                     driver_name: "未知".to_string(),
                     timestamp,
+                    requires_elevation: error.requires_elevation(),
                 };
-                self.restore_history.push(result.clone());
-                return result;
+                return self.record_restore(result, backup_file);
             }
         };
         
@@ -150,61 +181,87 @@ impl DriverManagement {
             message: "驱动恢复成功".to_string(),
             driver_name,
             timestamp,
+            requires_elevation: false,
         };
-        self.restore_history.push(result.clone());
-        result
+        self.record_restore(result, backup_file)
     }
     
     // 卸载驱动
     pub fn uninstall_driver(&mut self, driver: &DriverInfo) -> UninstallResult {
         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
         
-        // 使用sc命令停止驱动服务
-        let stop_result = Command::new("sc")
-            .args(&["stop", &driver.name])
-            .output();
-        
-        // 使用sc命令删除驱动服务
-        let delete_result = Command::new("sc")
-            .args(&["delete", &driver.name])
-            .output();
+        let result = match self.remove_service(&driver.name) {
+            Ok(()) => UninstallResult {
+                success: true,
+                message: "驱动卸载成功".to_string(),
+                driver_name: driver.name.clone(),
+                timestamp,
+                requires_elevation: false,
+            },
+            Err(e) => UninstallResult {
+                success: false,
+                message: e.localized(self.locale),
+                driver_name: driver.name.clone(),
+                timestamp,
+                requires_elevation: e.requires_elevation(),
+            },
+        };
+        self.record_uninstall(result, driver)
+    }
+    
+    // 以下记录函数将结果同时写入本次会话的历史和审计日志
+    fn record_backup(&mut self, mut result: BackupResult) -> BackupResult {
+        let note = self.journal.record_or_note(AuditEvent::new(AuditAction::Backup, &result.driver_name)
+            .after(result.backup_path.display().to_string())
+            .outcome(result.success, &result.message, result.requires_elevation));
+        if let Some(note) = note {
+            result.message = format!("{}（{}）", result.message, note);
+        }
+        self.backup_history.push(result.clone());
+        result
+    }
+    
+    fn record_restore(&mut self, mut result: RestoreResult, backup_file: &Path) -> RestoreResult {
+        let note = self.journal.record_or_note(AuditEvent::new(AuditAction::Restore, &result.driver_name)
+            .after(format!("来自备份 {}", backup_file.display()))
+            .outcome(result.success, &result.message, result.requires_elevation));
+        if let Some(note) = note {
+            result.message = format!("{}（{}）", result.message, note);
+        }
+        self.restore_history.push(result.clone());
+        result
+    }
+    
+    fn record_uninstall(&mut self, mut result: UninstallResult, driver: &DriverInfo) -> UninstallResult {
+        let mut event = AuditEvent::new(AuditAction::Uninstall, &result.driver_name)
+            .before(describe_driver(driver))
+            .outcome(result.success, &result.message, result.requires_elevation);
+        if result.success {
+            event = event.after("服务已删除");
+        }
+        if let Some(note) = self.journal.record_or_note(event) {
+            result.message = format!("{}（{}）", result.message, note);
+        }
+        self.uninstall_history.push(result.clone());
+        result
+    }
+    
+    // 使用sc命令停止并删除驱动服务
+    fn remove_service(&self, service_name: &str) -> HamsterResult<()> {
+        let stop_output = self.platform.run_command("sc", &["stop", service_name])?;
+        let delete_output = self.platform.run_command("sc", &["delete", service_name])?;
         
-        match (stop_result, delete_result) {
-            (Ok(stop_output), Ok(delete_output)) if stop_output.status.success() && delete_output.status.success() => {
-                let result = UninstallResult {
-                    success: true,
-                    message: "驱动卸载成功".to_string(),
-                    driver_name: driver.name.clone(),
-                    timestamp,
-                };
-                self.uninstall_history.push(result.clone());
-                result
-            }
-            (Ok(stop_output), Ok(delete_output)) => {
-                let stop_error = String::from_utf8_lossy(&stop_output.stderr);
-                let delete_error = String::from_utf8_lossy(&delete_output.stderr);
-                let error_message = format!("卸载失败 - 停止: {}, 删除: {}", stop_error, delete_error);
-                
-                let result = UninstallResult {
-                    success: false,
-                    message: error_message,
-                    driver_name: driver.name.clone(),
-                    timestamp,
-                };
-                self.uninstall_history.push(result.clone());
-                result
-            }
-            (Err(e), _) | (_, Err(e)) => {
-                let result = UninstallResult {
-                    success: false,
-                    message: format!("执行卸载命令失败: {}", e),
-                    driver_name: driver.name.clone(),
-                    timestamp,
-                };
-                self.uninstall_history.push(result.clone());
-                result
-            }
+        if stop_output.success && delete_output.success {
+            return Ok(());
         }
+        let failed = if delete_output.success { &stop_output } else { &delete_output };
+        Err(HamsterError::command_failed(
+            format!("卸载驱动服务 {} 失败", service_name),
+            failed.status_code,
+            &format!("停止: {}{} 删除: {}{}",
+                stop_output.stdout.trim(), stop_output.stderr.trim(),
+                delete_output.stdout.trim(), delete_output.stderr.trim()),
+        ))
     }
     
     // 获取备份历史
@@ -245,7 +302,7 @@ impl DriverManagement {
                     .filter_map(|entry| entry.ok())
                     .filter(|entry| {
                         entry.path().is_file() && 
-                        entry.path().extension().map_or(false, |ext| ext == "bak")
+                        entry.path().extension().is_some_and(|ext| ext == "bak")
                     })
                     .map(|entry| entry.path())
                     .collect()
@@ -253,4 +310,9 @@ impl DriverManagement {
             Err(_) => Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+// 审计日志中记录的驱动状态
+fn describe_driver(driver: &DriverInfo) -> String {
+    format!("版本 {}，状态 {:?}，启动类型 {}", driver.version, driver.status, driver.start_type)
+}
@@ -0,0 +1,568 @@
+// 驱动与设备匹配：按 Windows 选择驱动的规则为每个设备计算候选驱动的等级（rank）并排序，
+// 同时说明每个候选被选中或落选的原因
+//
+// 等级为 0xSSGGTHHH，数值越小越优先：SS 为签名分数，GG 为功能分数（型号的安装节中的 FeatureScore，未声明时为 0xFF），
+// THHH 为标识符分数，由匹配到的是设备的硬件ID还是兼容ID、INF 中的硬件ID还是兼容ID以及各自的位置决定。
+// 等级相同时 DriverVer 日期较新的优先，日期也相同时版本较高的优先。
+
+use std::cmp::Ordering;
+use std::path::PathBuf;
+use serde::Serialize;
+use crate::core::features::driver_installer::{InstallableDriver, SignatureState};
+use crate::core::features::driver_searcher::DeviceInfo;
+use crate::core::gpu_version::GpuVendor;
+use crate::core::hardware_id::HardwareId;
+use crate::core::inf::{DriverVer, DriverVersion, InfModel, TargetOs};
+use crate::core::signature::{LoadPrediction, SignatureClass};
+
+// INF 未声明 FeatureScore 时的功能分数
+const DEFAULT_FEATURE_SCORE: u32 = 0xFF;
+
+/// 等级中的签名分数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum SignatureScore {
+    /// 经 Windows 硬件质量实验室（WHQL）或 Microsoft 签名
+    Microsoft,
+    /// 受信任的第三方 Authenticode 签名
+    Authenticode,
+    /// 签名尚未验证，按第三方签名计算
+    Unverified,
+    /// 未签名或签名无效
+    Unsigned,
+}
+
+impl SignatureScore {
+    /// 按安装器的签名检查结论取分数；证书链未验证的有效签名按未验证计算
+    pub fn from_state(state: SignatureState) -> Self {
+        match state {
+            SignatureState::Signed { trusted: true, .. } => SignatureScore::Authenticode,
+            SignatureState::Signed { trusted: false, .. } | SignatureState::Unverified => SignatureScore::Unverified,
+            SignatureState::Unsigned | SignatureState::Invalid | SignatureState::Untrusted | SignatureState::Tampered => SignatureScore::Unsigned,
+        }
+    }
+
+    /// 有签名分类时按分类取分数：证书链受信任的 Microsoft 签名驱动优先，测试签名、自签名和被篡改的文件按未签名计算
+    pub fn from_driver(driver: &InstallableDriver) -> Self {
+        let status = Self::from_state(driver.signature_state);
+        let Some(signature) = driver.signature.as_ref().filter(|_| status != SignatureScore::Unsigned) else {
+            return status;
+        };
+        match signature.class {
+            SignatureClass::Whql | SignatureClass::Attestation | SignatureClass::WindowsComponent
+                if signature.load == LoadPrediction::Loads && status == SignatureScore::Authenticode => SignatureScore::Microsoft,
+            SignatureClass::TestSigned | SignatureClass::SelfSigned | SignatureClass::Unsigned => SignatureScore::Unsigned,
+            _ => status,
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        match self {
+            SignatureScore::Microsoft => 0x00,
+            SignatureScore::Authenticode | SignatureScore::Unverified => 0x0D,
+            SignatureScore::Unsigned => 0x80,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignatureScore::Microsoft => "Microsoft 签名",
+            SignatureScore::Authenticode => "第三方签名",
+            SignatureScore::Unverified => "签名未验证",
+            SignatureScore::Unsigned => "未签名",
+        }
+    }
+}
+
+/// 设备的哪个ID与 INF 中的哪个ID匹配
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MatchKind {
+    /// 设备硬件ID与 INF 硬件ID
+    HardwareToHardware,
+    /// 设备硬件ID与 INF 兼容ID
+    HardwareToCompatible,
+    /// 设备兼容ID与 INF 硬件ID
+    CompatibleToHardware,
+    /// 设备兼容ID与 INF 兼容ID
+    CompatibleToCompatible,
+}
+
+impl MatchKind {
+    fn base_score(&self) -> u32 {
+        match self {
+            MatchKind::HardwareToHardware => 0x0000,
+            MatchKind::HardwareToCompatible => 0x1000,
+            MatchKind::CompatibleToHardware => 0x2000,
+            MatchKind::CompatibleToCompatible => 0x3000,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MatchKind::HardwareToHardware => "设备硬件ID匹配 INF 硬件ID",
+            MatchKind::HardwareToCompatible => "设备硬件ID匹配 INF 兼容ID",
+            MatchKind::CompatibleToHardware => "设备兼容ID匹配 INF 硬件ID",
+            MatchKind::CompatibleToCompatible => "设备兼容ID匹配 INF 兼容ID",
+        }
+    }
+}
+
+/// 候选驱动的结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Verdict {
+    /// 将被 Windows 选中
+    Selected,
+    /// 适用但等级、日期或版本不如选中的驱动
+    Outranked,
+    /// 所在型号节的目标系统修饰不适用于当前系统
+    NotApplicable,
+}
+
+/// 驱动所要安装到的系统，用于判断 INF 型号节的目标系统修饰是否适用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SystemTarget {
+    /// 与 INF 修饰相同的小写架构名，如 amd64
+    pub architecture: String,
+    pub major: u32,
+    pub minor: u32,
+    /// 1 为工作站，3 为服务器
+    pub product_type: u32,
+    /// 未知时视为满足任何内部版本要求
+    pub build: Option<u32>,
+}
+
+impl SystemTarget {
+    /// 当前系统；非 Windows 环境无法得知内部版本号，按 Windows 10 及以上处理
+    pub fn current() -> Self {
+        let architecture = match std::env::consts::ARCH {
+            "x86_64" => "amd64",
+            "aarch64" => "arm64",
+            "x86" => "x86",
+            "arm" => "arm",
+            other => other,
+        };
+        let build = if cfg!(windows) {
+            sysinfo::System::kernel_version().and_then(|version| version.trim().parse().ok())
+        } else {
+            None
+        };
+        Self { architecture: architecture.to_string(), major: 10, minor: 0, product_type: 1, build }
+    }
+
+    /// 目标系统修饰是否适用：架构相同，系统版本和内部版本不低于修饰的要求，产品类型一致
+    pub fn accepts(&self, target: &TargetOs) -> bool {
+        if target.architecture.as_deref().is_some_and(|architecture| architecture != self.architecture) {
+            return false;
+        }
+        let required = (target.major.unwrap_or(0), target.minor.unwrap_or(0));
+        if (self.major, self.minor) < required {
+            return false;
+        }
+        if target.product_type.is_some_and(|product_type| product_type != self.product_type) {
+            return false;
+        }
+        match (target.build, self.build) {
+            (Some(required), Some(build)) => build >= required,
+            _ => true,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} {}.{}", self.architecture, self.major, self.minor);
+        if let Some(build) = self.build {
+            text.push_str(&format!(" 内部版本 {}", build));
+        }
+        text
+    }
+}
+
+/// 适用修饰之间的优先顺序：Windows 选用最具体的一个，即版本、内部版本更高，且指定了架构和产品类型的
+fn specificity(target: &TargetOs) -> (u32, u32, u32, bool, bool) {
+    (
+        target.major.unwrap_or(0),
+        target.minor.unwrap_or(0),
+        target.build.unwrap_or(0),
+        target.architecture.is_some(),
+        target.product_type.is_some(),
+    )
+}
+
+/// 一个设备的一个候选驱动
+#[derive(Debug, Clone, Serialize)]
+pub struct DriverCandidate {
+    /// 在传入的驱动列表中的位置
+    pub driver_index: usize,
+    pub driver_name: String,
+    pub file_path: PathBuf,
+    pub model: InfModel,
+    pub match_kind: MatchKind,
+    /// 设备一侧匹配的ID
+    pub device_id: String,
+    /// INF 一侧匹配的ID
+    pub inf_id: String,
+    pub signature: SignatureScore,
+    pub rank: u32,
+    pub driver_ver: Option<DriverVer>,
+    pub verdict: Verdict,
+    /// 被选中或落选的原因
+    pub reasons: Vec<String>,
+}
+
+impl DriverCandidate {
+    pub fn rank_hex(&self) -> String {
+        format!("0x{:08X}", self.rank)
+    }
+}
+
+/// 一个设备的全部候选驱动，被选中的在最前
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceMatch {
+    pub device_name: String,
+    pub hardware_id: String,
+    /// 设备当前驱动的版本，显卡附带厂商版本号
+    pub installed_version: String,
+    pub gpu_vendor: Option<GpuVendor>,
+    pub candidates: Vec<DriverCandidate>,
+}
+
+impl DeviceMatch {
+    /// 候选的 DriverVer，显卡的版本附带厂商版本号，如 `10/19/2023,546.23 (31.0.15.4623)`
+    pub fn candidate_version(&self, candidate: &DriverCandidate) -> Option<String> {
+        let driver_ver = candidate.driver_ver.as_ref()?;
+        let version = driver_ver.version.as_ref().and_then(|_| DriverVersion::from_driver_ver(driver_ver).ok());
+        Some(match (self.gpu_vendor, version) {
+            (Some(vendor), Some(version)) => format!("{},{}", driver_ver.date.format("%m/%d/%Y"), vendor.display(&version)),
+            _ => driver_ver.to_string(),
+        })
+    }
+
+    pub fn selected(&self) -> Option<&DriverCandidate> {
+        self.candidates.first().filter(|candidate| candidate.verdict == Verdict::Selected)
+    }
+}
+
+pub struct DriverMatcher {
+    target: SystemTarget,
+}
+
+impl DriverMatcher {
+    pub fn new(target: SystemTarget) -> Self {
+        Self { target }
+    }
+
+    pub fn target(&self) -> &SystemTarget {
+        &self.target
+    }
+
+    /// 为每个设备计算候选驱动；没有任何候选的设备不出现在结果中
+    pub fn match_devices(&self, devices: &[DeviceInfo], drivers: &[InstallableDriver]) -> Vec<DeviceMatch> {
+        devices.iter()
+            .map(|device| self.match_device(device, drivers))
+            .filter(|device_match| !device_match.candidates.is_empty())
+            .collect()
+    }
+
+    pub fn match_device(&self, device: &DeviceInfo, drivers: &[InstallableDriver]) -> DeviceMatch {
+        let hardware_ids = device.hardware_ids();
+        let compatible_ids = device.compatible_id_list();
+        let mut candidates = Vec::new();
+
+        for (driver_index, driver) in drivers.iter().enumerate() {
+            let Some(inf) = &driver.inf else {
+                continue;
+            };
+            let signature = SignatureScore::from_driver(driver);
+            let applicable = self.applicable_sections(&inf.models);
+            for model in &inf.models {
+                let Some((match_kind, identifier_score, device_id, inf_id)) = identifier_match(model, &hardware_ids, &compatible_ids) else {
+                    continue;
+                };
+                let mut reasons = vec![format!("{}：{} ↔ {}", match_kind.label(), device_id, inf_id)];
+                let verdict = match applicable.iter().find(|(manufacturer, _)| *manufacturer == model.manufacturer) {
+                    Some((_, Some(section))) if *section == model.section => Verdict::Outranked,
+                    Some((_, Some(section))) => {
+                        reasons.push(self.not_applicable_reason(model, section));
+                        Verdict::NotApplicable
+                    }
+                    _ => {
+                        reasons.push(self.not_applicable_reason(model, ""));
+                        Verdict::NotApplicable
+                    }
+                };
+                candidates.push(DriverCandidate {
+                    driver_index,
+                    driver_name: driver.display_name.clone(),
+                    file_path: driver.file_path.clone(),
+                    model: model.clone(),
+                    match_kind,
+                    device_id,
+                    inf_id,
+                    signature,
+                    rank: (signature.value() << 24) | (model.feature_score.map_or(DEFAULT_FEATURE_SCORE, u32::from) << 16) | identifier_score,
+                    driver_ver: inf.version.driver_ver.clone(),
+                    verdict,
+                    reasons,
+                });
+            }
+        }
+
+        candidates.sort_by(compare_candidates);
+        explain(&mut candidates);
+        DeviceMatch {
+            device_name: device.friendly_name.clone(),
+            hardware_id: device.hardware_id.clone(),
+            installed_version: device.display_version(),
+            gpu_vendor: device.gpu_vendor(),
+            candidates,
+        }
+    }
+
+    /// 每个厂商在当前系统上使用的型号节：有适用的修饰时取最具体的一个，
+    /// 没有时仅在 x86 系统上使用未修饰的节（64位系统要求 INF 带架构修饰）
+    fn applicable_sections(&self, models: &[InfModel]) -> Vec<(String, Option<String>)> {
+        let mut sections: Vec<(String, Option<String>)> = Vec::new();
+        for model in models {
+            if sections.iter().any(|(manufacturer, _)| *manufacturer == model.manufacturer) {
+                continue;
+            }
+            let best = models.iter()
+                .filter(|other| other.manufacturer == model.manufacturer)
+                .filter_map(|other| other.target.as_ref().map(|target| (target, &other.section)))
+                .filter(|(target, _)| self.target.accepts(target))
+                .max_by_key(|(target, _)| specificity(target))
+                .map(|(_, section)| section.clone());
+            let undecorated = || models.iter()
+                .find(|other| other.manufacturer == model.manufacturer && other.target.is_none())
+                .filter(|_| self.target.architecture == "x86")
+                .map(|other| other.section.clone());
+            sections.push((model.manufacturer.clone(), best.or_else(undecorated)));
+        }
+        sections
+    }
+
+    fn not_applicable_reason(&self, model: &InfModel, chosen_section: &str) -> String {
+        match &model.target {
+            Some(target) if self.target.accepts(target) => format!(
+                "型号节 [{}] 的修饰 {} 适用，但 [{}] 更具体，Windows 只使用后者", model.section, target, chosen_section),
+            Some(target) => format!(
+                "型号节 [{}] 的修饰 {}（{}）不适用于当前系统 {}", model.section, target, target.describe(), self.target.describe()),
+            None if chosen_section.is_empty() => format!(
+                "型号节 [{}] 没有目标系统修饰，{} 系统要求 INF 带架构修饰", model.section, self.target.architecture),
+            None => format!("型号节 [{}] 没有修饰，已有适用的修饰节 [{}]", model.section, chosen_section),
+        }
+    }
+}
+
+/// 找出设备ID与型号ID的最佳匹配，返回匹配类型、标识符分数和两侧的ID
+fn identifier_match(model: &InfModel, hardware_ids: &[HardwareId], compatible_ids: &[HardwareId]) -> Option<(MatchKind, u32, String, String)> {
+    let inf_hardware_id = HardwareId::parse(&model.hardware_id).ok()?;
+    let inf_compatible_ids: Vec<HardwareId> = model.compatible_ids.iter()
+        .filter_map(|id| HardwareId::parse(id).ok())
+        .collect();
+
+    let mut best: Option<(MatchKind, u32, String, String)> = None;
+    let mut consider = |kind: MatchKind, score: u32, device_id: &HardwareId, inf_id: &HardwareId| {
+        if best.as_ref().is_none_or(|(_, best_score, _, _)| score < *best_score) {
+            best = Some((kind, score, device_id.to_string(), inf_id.to_string()));
+        }
+    };
+    for (device_ids, inf_kind_hardware, inf_kind_compatible) in [
+        (hardware_ids, MatchKind::HardwareToHardware, MatchKind::HardwareToCompatible),
+        (compatible_ids, MatchKind::CompatibleToHardware, MatchKind::CompatibleToCompatible),
+    ] {
+        for (device_index, device_id) in device_ids.iter().enumerate() {
+            let device_index = device_index as u32;
+            if *device_id == inf_hardware_id {
+                consider(inf_kind_hardware, inf_kind_hardware.base_score() + device_index, device_id, &inf_hardware_id);
+            }
+            for (inf_index, inf_id) in inf_compatible_ids.iter().enumerate() {
+                if device_id == inf_id {
+                    let score = inf_kind_compatible.base_score() + 0x100 * inf_index as u32 + device_index;
+                    consider(inf_kind_compatible, score, device_id, inf_id);
+                }
+            }
+        }
+    }
+    best
+}
+
+/// 适用的候选在前，其次按等级、日期（新的优先）、版本（高的优先）
+fn compare_candidates(a: &DriverCandidate, b: &DriverCandidate) -> Ordering {
+    (a.verdict == Verdict::NotApplicable).cmp(&(b.verdict == Verdict::NotApplicable))
+        .then(a.rank.cmp(&b.rank))
+        .then_with(|| release(b).cmp(&release(a)))
+}
+
+fn date(candidate: &DriverCandidate) -> Option<chrono::NaiveDate> {
+    candidate.driver_ver.as_ref().map(|driver_ver| driver_ver.date)
+}
+
+fn version(candidate: &DriverCandidate) -> &str {
+    candidate.driver_ver.as_ref().and_then(|driver_ver| driver_ver.version.as_deref()).unwrap_or("")
+}
+
+/// 候选的 DriverVer 日期和版本，版本无法解析时视为 0.0.0.0
+fn release(candidate: &DriverCandidate) -> DriverVersion {
+    candidate.driver_ver.as_ref()
+        .map(|driver_ver| DriverVersion::from_driver_ver(driver_ver)
+            .unwrap_or_else(|_| DriverVersion::default().with_date(Some(driver_ver.date))))
+        .unwrap_or_default()
+}
+
+/// 标出选中的候选，并为落选的候选写明与选中者的差别
+fn explain(candidates: &mut [DriverCandidate]) {
+    let Some(winner) = candidates.first().filter(|candidate| candidate.verdict != Verdict::NotApplicable).cloned() else {
+        return;
+    };
+    candidates[0].verdict = Verdict::Selected;
+    candidates[0].reasons.push(match candidates.get(1).filter(|next| next.verdict != Verdict::NotApplicable) {
+        Some(next) => format!("等级 {} 在适用的候选中最优（次优为 {} 的 {}）", winner.rank_hex(), next.driver_name, next.rank_hex()),
+        None => format!("等级 {}，是唯一适用的候选", winner.rank_hex()),
+    });
+
+    for candidate in candidates.iter_mut().skip(1).filter(|candidate| candidate.verdict == Verdict::Outranked) {
+        let reason = if candidate.rank != winner.rank {
+            let cause = if candidate.signature != winner.signature {
+                format!("{}不如{}", candidate.signature.label(), winner.signature.label())
+            } else if candidate.match_kind != winner.match_kind {
+                format!("{}不如{}具体", candidate.match_kind.label(), winner.match_kind.label())
+            } else {
+                format!("匹配的ID {} 不如 {} 具体", candidate.device_id, winner.device_id)
+            };
+            format!("等级 {} 高于选中驱动的 {}：{}", candidate.rank_hex(), winner.rank_hex(), cause)
+        } else if date(candidate) != date(&winner) {
+            format!("等级相同，驱动日期 {} 早于选中驱动的 {}",
+                date(candidate).map(|date| date.to_string()).unwrap_or_else(|| "未知".to_string()),
+                date(&winner).map(|date| date.to_string()).unwrap_or_else(|| "未知".to_string()))
+        } else if release(candidate).cmp_version(&release(&winner)).is_ne() {
+            format!("等级和日期相同，版本 {} 低于选中驱动的 {}", version(candidate), version(&winner))
+        } else {
+            "等级、日期和版本都与选中驱动相同，按扫描顺序排在其后".to_string()
+        };
+        candidate.reasons.push(reason);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::features::driver_installer::InstallMethod;
+    use crate::core::inf::InfFile;
+    use crate::core::locale::Locale;
+
+    fn driver(name: &str, driver_ver: &str, models: &str, state: SignatureState) -> InstallableDriver {
+        let content = format!(
+            "[Version]\nSignature = \"$Windows NT$\"\nClass = Display\nDriverVer = {}\n\n\
+             [Manufacturer]\nNVIDIA = Devices, NTamd64.10.0...22000, NTamd64\n\n{}\n\n[Install_FS]\nFeatureScore = 0xF0\n",
+            driver_ver, models);
+        InstallableDriver {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            version: String::new(),
+            manufacturer: "NVIDIA".to_string(),
+            file_path: PathBuf::from(format!("{}.inf", name)),
+            supported_os: Vec::new(),
+            signature_status: String::new(),
+            signature_state: state,
+            signature: None,
+            install_method: InstallMethod::INF,
+            inf: Some(InfFile::parse(&content, Locale::ZhCn).unwrap()),
+            package: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn rtx_3060() -> DeviceInfo {
+        DeviceInfo {
+            device_description: "NVIDIA GeForce RTX 3060".to_string(),
+            manufacturer: "NVIDIA".to_string(),
+            hardware_id: "PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043&REV_A1".to_string(),
+            compatible_ids: "PCI\\VEN_10DE&CC_030000".to_string(),
+            device_class: "Display".to_string(),
+            class_guid: String::new(),
+            driver_version: String::new(),
+            driver_date: String::new(),
+            friendly_name: "NVIDIA GeForce RTX 3060".to_string(),
+            location: String::new(),
+        }
+    }
+
+    fn target(build: u32) -> SystemTarget {
+        SystemTarget { architecture: "amd64".to_string(), major: 10, minor: 0, product_type: 1, build: Some(build) }
+    }
+
+    const SIGNED: SignatureState = SignatureState::Signed { catalog: true, trusted: true };
+
+    #[test]
+    fn signature_outweighs_identifier_match() {
+        let exact = driver("exact", "08/14/2024,32.0.15.6094", "[Devices.NTamd64]\nGPU = Install, PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043", SignatureState::Unsigned);
+        let generic = driver("generic", "01/01/2020,27.0.0.1", "[Devices.NTamd64]\nGPU = Install, PCI\\VEN_10DE&CC_030000", SIGNED);
+        let device_match = DriverMatcher::new(target(19045)).match_device(&rtx_3060(), &[exact, generic]);
+        let names: Vec<&str> = device_match.candidates.iter().map(|candidate| candidate.driver_name.as_str()).collect();
+        assert_eq!(names, ["generic", "exact"]);
+
+        let winner = device_match.selected().unwrap();
+        assert_eq!(winner.match_kind, MatchKind::CompatibleToHardware);
+        assert_eq!(winner.rank >> 24, SignatureScore::Authenticode.value());
+        let loser = &device_match.candidates[1];
+        assert_eq!(loser.verdict, Verdict::Outranked);
+        assert_eq!(loser.match_kind, MatchKind::HardwareToHardware);
+        assert_eq!(loser.rank_hex(), "0x80FF0001");
+        assert!(loser.reasons.last().unwrap().contains("未签名不如第三方签名"), "{:?}", loser.reasons);
+    }
+
+    #[test]
+    fn more_specific_hardware_id_ranks_first() {
+        let models = "[Devices.NTamd64]\nGPU = Install, PCI\\VEN_10DE&DEV_2504\nGPU Asus = Install, PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043";
+        let device_match = DriverMatcher::new(target(19045)).match_device(&rtx_3060(), &[driver("nvidia", "08/14/2024,32.0.15.6094", models, SIGNED)]);
+        let ids: Vec<&str> = device_match.candidates.iter().map(|candidate| candidate.inf_id.as_str()).collect();
+        assert_eq!(ids, ["PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043", "PCI\\VEN_10DE&DEV_2504"]);
+        // 不带子系统的ID是设备的兼容ID
+        assert_eq!(device_match.candidates[1].match_kind, MatchKind::CompatibleToHardware);
+    }
+
+    #[test]
+    fn equal_rank_prefers_newer_date_then_higher_version() {
+        let models = "[Devices.NTamd64]\nGPU = Install, PCI\\VEN_10DE&DEV_2504";
+        let older = driver("older", "06/01/2024,32.0.15.9999", models, SIGNED);
+        let newer = driver("newer", "08/14/2024,32.0.15.6094", models, SIGNED);
+        let higher = driver("higher", "08/14/2024,32.0.15.6095", models, SIGNED);
+        let device_match = DriverMatcher::new(target(19045)).match_device(&rtx_3060(), &[older, newer, higher]);
+        let names: Vec<&str> = device_match.candidates.iter().map(|candidate| candidate.driver_name.as_str()).collect();
+        assert_eq!(names, ["higher", "newer", "older"]);
+        assert!(device_match.candidates[1].reasons.last().unwrap().contains("版本 32.0.15.6094 低于"));
+        assert!(device_match.candidates[2].reasons.last().unwrap().contains("驱动日期 2024-06-01 早于"));
+    }
+
+    #[test]
+    fn most_specific_applicable_section_is_used() {
+        let models = "[Devices.NTamd64.10.0...22000]\nGPU = Install_FS, PCI\\VEN_10DE&DEV_2504\n\n\
+                      [Devices.NTamd64]\nGPU = Install, PCI\\VEN_10DE&DEV_2504";
+        let drivers = [driver("nvidia", "08/14/2024,32.0.15.6094", models, SIGNED)];
+
+        // Windows 10 上内部版本不满足 22000 的修饰
+        let windows_10 = DriverMatcher::new(target(19045)).match_device(&rtx_3060(), &drivers);
+        assert_eq!(windows_10.selected().unwrap().model.section, "Devices.NTamd64");
+        assert_eq!(windows_10.candidates[1].verdict, Verdict::NotApplicable);
+        assert!(windows_10.candidates[1].reasons.last().unwrap().contains("不适用于当前系统"));
+
+        // Windows 11 上两个修饰都适用，只使用更具体的一个，其 FeatureScore 也计入等级
+        let windows_11 = DriverMatcher::new(target(22631)).match_device(&rtx_3060(), &drivers);
+        let selected = windows_11.selected().unwrap();
+        assert_eq!(selected.model.section, "Devices.NTamd64.10.0...22000");
+        // 设备硬件ID去掉子系统后是第2个兼容ID
+        assert_eq!(selected.rank_hex(), "0x0DF02001");
+        assert_eq!(windows_11.candidates[1].verdict, Verdict::NotApplicable);
+        assert!(windows_11.candidates[1].reasons.last().unwrap().contains("更具体"));
+    }
+
+    #[test]
+    fn undecorated_models_need_x86() {
+        let models = "[Devices]\nGPU = Install, PCI\\VEN_10DE&DEV_2504";
+        let nvidia = driver("nvidia", "08/14/2024,32.0.15.6094", models, SIGNED);
+        assert!(nvidia.inf.as_ref().unwrap().models[0].target.is_none());
+        let amd64 = DriverMatcher::new(target(22631)).match_device(&rtx_3060(), std::slice::from_ref(&nvidia));
+        assert!(amd64.selected().is_none());
+        let x86 = SystemTarget { architecture: "x86".to_string(), ..target(19045) };
+        assert!(DriverMatcher::new(x86).match_device(&rtx_3060(), &[nvidia]).selected().is_some());
+    }
+}
@@ -2,5 +2,8 @@ pub mod backup_manager;
 pub mod dependency_analyzer;
 pub mod driver_installer;
 pub mod driver_manager;
+pub mod driver_matcher;
 pub mod driver_searcher;
+pub mod package_scanner;
+pub mod signature_cache;
 pub mod signature_validator;
@@ -0,0 +1,670 @@
+// 驱动包扫描：递归遍历目录和 .zip / .cab 压缩包（包括嵌套的压缩包，只在内存中读取需要的条目，不解压到磁盘），
+// 把 INF 与其引用的 SYS、CAT、DLL 等文件归为一个驱动包，并按内容哈希合并重复的包
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+use crate::core::cab::CabArchive;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::inf::InfFile;
+use crate::core::jobs::JobContext;
+use crate::core::locale::Locale;
+
+// 遍历时每找到这么多文件报告一次进度
+const WALK_REPORT_INTERVAL: usize = 200;
+
+/// 扫描范围的限制，防止误选系统盘根目录或遇到压缩炸弹时长时间运行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanLimits {
+    /// 目录递归的最大层数
+    pub max_depth: usize,
+    /// 压缩包嵌套的最大层数
+    pub max_archive_depth: usize,
+    /// 读入内存的单个压缩包条目（INF、嵌套的压缩包）的最大解压后大小
+    pub max_entry_size: u64,
+    /// 最多检查的文件数，包括压缩包内的条目
+    pub max_files: usize,
+}
+
+impl Default for ScanLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 16,
+            max_archive_depth: 3,
+            max_entry_size: 512 * 1024 * 1024,
+            max_files: 200_000,
+        }
+    }
+}
+
+/// 文件的位置：磁盘上的路径，位于压缩包内时再加上逐层的条目名
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FileLocation {
+    pub path: PathBuf,
+    /// 从外到内的条目名，最后一个为文件本身，前面的为嵌套的压缩包
+    #[serde(default)]
+    pub entries: Vec<String>,
+}
+
+impl FileLocation {
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), entries: Vec::new() }
+    }
+
+    fn child(&self, entry: &str) -> Self {
+        let mut entries = self.entries.clone();
+        entries.push(entry.to_string());
+        Self { path: self.path.clone(), entries }
+    }
+
+    pub fn is_in_archive(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    pub fn file_name(&self) -> String {
+        match self.entries.last() {
+            Some(entry) => entry.rsplit('/').next().unwrap_or(entry).to_string(),
+            None => self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+        }
+    }
+
+    /// 小写、以 `/` 分隔的完整位置，用于在同一容器内查找 INF 引用的文件
+    fn lookup_key(&self) -> String {
+        self.to_string().replace('\\', "/").to_lowercase()
+    }
+
+    /// 读取文件内容；压缩包内的文件逐层在内存中解压
+    pub fn read(&self) -> HamsterResult<Vec<u8>> {
+        let read_error = |e: io::Error| HamsterError::io(format!("无法读取 {}", self), e);
+        let Some((outermost, nested)) = self.entries.split_first() else {
+            return std::fs::read(&self.path).map_err(read_error);
+        };
+        let file = File::open(&self.path).map_err(read_error)?;
+        let mut data = read_archive_entry(BufReader::new(file), &self.path.to_string_lossy(), outermost, self)?;
+        let mut container = outermost;
+        for entry in nested {
+            data = read_archive_entry(Cursor::new(data), container, entry, self)?;
+            container = entry;
+        }
+        Ok(data)
+    }
+}
+
+impl fmt::Display for FileLocation {
+    /// 压缩包内的文件写作 `D:\drivers.zip!/nvidia/nv.inf`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        for entry in &self.entries {
+            write!(f, "!/{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// 驱动包中的一个文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageFile {
+    pub location: FileLocation,
+    /// 相对于 INF 所在目录的路径，以 `/` 分隔
+    pub relative_path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// 一个 INF 及其引用的文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriverPackage {
+    pub inf: FileLocation,
+    /// 包内全部文件，第一个为 INF
+    pub files: Vec<PackageFile>,
+    /// INF 引用但找不到的文件
+    pub missing_files: Vec<String>,
+    /// 由各文件的相对路径和哈希计算，内容相同的包哈希相同
+    pub hash: String,
+    /// 内容相同、已合并到本包的其他位置
+    pub duplicates: Vec<FileLocation>,
+}
+
+impl DriverPackage {
+    pub fn is_in_archive(&self) -> bool {
+        self.inf.is_in_archive()
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// 将包内文件按原来的相对位置写入 dir 并校验哈希，返回 INF 的路径
+    pub fn extract_to(&self, dir: &Path) -> HamsterResult<PathBuf> {
+        let mut inf_path = None;
+        for file in &self.files {
+            let data = file.location.read()?;
+            if sha256_hex(&data) != file.sha256 {
+                return Err(HamsterError::policy(format!("{} 的内容在扫描后发生了变化", file.location)));
+            }
+            let target = dir.join(&file.relative_path);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| HamsterError::io(format!("无法创建目录 {}", parent.display()), e))?;
+            }
+            std::fs::write(&target, &data)
+                .map_err(|e| HamsterError::io(format!("无法写入 {}", target.display()), e))?;
+            if file.location == self.inf {
+                inf_path = Some(target);
+            }
+        }
+        inf_path.ok_or_else(|| HamsterError::parse(format!("驱动包 {} 中缺少 INF 文件", self.inf)))
+    }
+}
+
+/// 扫描到的驱动包，INF 无法解析时 inf 为 None 并附带原因
+#[derive(Debug)]
+pub struct ScannedPackage {
+    pub package: DriverPackage,
+    pub inf: Option<InfFile>,
+    pub inf_error: Option<HamsterError>,
+}
+
+/// 扫描结果
+#[derive(Debug, Default)]
+pub struct ScanOutput {
+    pub packages: Vec<ScannedPackage>,
+    /// 磁盘上的 EXE / MSI 安装程序，已按内容去重
+    pub installers: Vec<FileLocation>,
+    /// 被跳过的文件或目录及原因
+    pub skipped: Vec<String>,
+}
+
+/// 遍历时找到的文件
+struct FoundFile {
+    location: FileLocation,
+    size: u64,
+    /// 在所在 zip 压缩包中的序号
+    zip_index: Option<usize>,
+    /// 已读入内存的 INF 内容
+    content: Option<Vec<u8>>,
+    /// 遍历时已经算出的哈希（CAB 中的文件只能顺序解压，遍历时一并计算）
+    sha256: Option<String>,
+}
+
+impl FoundFile {
+    fn extension(&self) -> String {
+        let name = self.location.file_name();
+        name.rsplit_once('.').map(|(_, extension)| extension.to_ascii_lowercase()).unwrap_or_default()
+    }
+}
+
+/// 按 INF 归组后、尚未计算哈希的包
+struct PendingPackage {
+    inf: InfFile,
+    inf_index: usize,
+    members: Vec<usize>,
+    missing_files: Vec<String>,
+}
+
+pub struct PackageScanner {
+    locale: Locale,
+    limits: ScanLimits,
+}
+
+impl PackageScanner {
+    pub fn new(locale: Locale) -> Self {
+        Self { locale, limits: ScanLimits::default() }
+    }
+
+    pub fn with_limits(mut self, limits: ScanLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 扫描目录或单个 .zip / .cab 文件；根路径不存在或无法读取时返回错误，其中个别文件的问题记入 skipped
+    pub fn scan(&self, root: &Path, job: &JobContext) -> HamsterResult<ScanOutput> {
+        let mut output = ScanOutput::default();
+        let mut disk_files = Vec::new();
+        let metadata = std::fs::metadata(root)
+            .map_err(|e| HamsterError::io(format!("无法读取 {:?}", root), e))?;
+        if metadata.is_dir() {
+            self.walk(root, 0, &mut disk_files, &mut output, job)?;
+        } else {
+            disk_files.push(FoundFile { location: FileLocation::file(root), size: metadata.len(), zip_index: None, content: None, sha256: None });
+        }
+        job.report(0, disk_files.len(), format!("找到 {} 个文件，正在分析", disk_files.len()));
+
+        for file in disk_files.iter_mut().filter(|file| file.extension() == "inf") {
+            match std::fs::read(&file.location.path) {
+                Ok(content) => file.content = Some(content),
+                Err(e) => output.skipped.push(format!("{}：无法读取（{}）", file.location, e)),
+            }
+        }
+        let mut hash_disk = |file: &FoundFile| -> HamsterResult<String> {
+            let mut reader = File::open(&file.location.path)
+                .map_err(|e| HamsterError::io(format!("无法读取 {}", file.location), e))?;
+            sha256_reader(&mut reader).map_err(|e| HamsterError::io(format!("无法读取 {}", file.location), e))
+        };
+        self.collect_packages(&disk_files, &mut hash_disk, &mut output, job)?;
+
+        // 磁盘上的包在前，合并重复的包时优先保留可以直接检查签名的位置
+        let mut found = disk_files.len();
+        let archives: Vec<usize> = (0..disk_files.len()).filter(|&index| is_archive(&disk_files[index].extension())).collect();
+        for (step, &index) in archives.iter().enumerate() {
+            job.check_cancelled()?;
+            let location = disk_files[index].location.clone();
+            job.report(step, archives.len(), format!("正在读取压缩包 {}", location));
+            match File::open(&location.path) {
+                Ok(file) => self.scan_archive(BufReader::new(file), &location, 1, &mut found, &mut output, job)?,
+                Err(e) => output.skipped.push(format!("{}：无法打开（{}）", location, e)),
+            }
+        }
+
+        // 安装程序只列出磁盘上的文件，内容相同的只保留第一个
+        let mut installer_hashes = Vec::new();
+        for file in disk_files.iter().filter(|file| matches!(file.extension().as_str(), "exe" | "msi")) {
+            job.check_cancelled()?;
+            match hash_disk(file) {
+                Ok(hash) if installer_hashes.contains(&hash) => {}
+                Ok(hash) => {
+                    installer_hashes.push(hash);
+                    output.installers.push(file.location.clone());
+                }
+                Err(e) => output.skipped.push(format!("{}：{}", file.location, e)),
+            }
+        }
+
+        merge_duplicates(&mut output.packages);
+        job.report(1, 1, format!("找到 {} 个驱动包、{} 个安装程序", output.packages.len(), output.installers.len()));
+        Ok(output)
+    }
+
+    /// 递归收集目录中的文件，不跟随符号链接以免循环
+    fn walk(&self, dir: &Path, depth: usize, files: &mut Vec<FoundFile>, output: &mut ScanOutput, job: &JobContext) -> HamsterResult<()> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            // 根目录无法读取时报错，子目录（如无权限的系统目录）跳过
+            Err(e) if depth == 0 => return Err(HamsterError::io(format!("无法读取目录 {:?}", dir), e)),
+            Err(e) => {
+                output.skipped.push(format!("{}：无法读取目录（{}）", dir.display(), e));
+                return Ok(());
+            }
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+        paths.sort();
+
+        for path in paths {
+            job.check_cancelled()?;
+            let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if depth + 1 >= self.limits.max_depth {
+                    output.skipped.push(format!("{}：超过最大目录深度 {}", path.display(), self.limits.max_depth));
+                } else {
+                    self.walk(&path, depth + 1, files, output, job)?;
+                }
+            } else if metadata.is_file() {
+                if files.len() >= self.limits.max_files {
+                    output.skipped.push(format!("{}：超过最多检查 {} 个文件的限制", path.display(), self.limits.max_files));
+                    return Ok(());
+                }
+                files.push(FoundFile { location: FileLocation::file(&path), size: metadata.len(), zip_index: None, content: None, sha256: None });
+                if files.len().is_multiple_of(WALK_REPORT_INTERVAL) {
+                    job.report(0, 0, format!("正在查找驱动文件，已找到 {} 个文件", files.len()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_archive<R: Read + Seek>(&self, reader: R, location: &FileLocation, depth: usize, found: &mut usize, output: &mut ScanOutput, job: &JobContext) -> HamsterResult<()> {
+        if location.file_name().to_ascii_lowercase().ends_with(".cab") {
+            self.scan_cab(reader, location, depth, found, output, job)
+        } else {
+            self.scan_zip(reader, location, depth, found, output, job)
+        }
+    }
+
+    /// 是否需要把压缩包中的这个条目读入内存：INF 和嵌套的压缩包，超出限制的记入 skipped
+    fn keep_content(&self, file: &FoundFile, depth: usize, output: &mut ScanOutput) -> bool {
+        let extension = file.extension();
+        if extension != "inf" && !is_archive(&extension) {
+            return false;
+        }
+        if file.size > self.limits.max_entry_size {
+            output.skipped.push(format!("{}：解压后 {} 字节，超过 {} 字节的限制", file.location, file.size, self.limits.max_entry_size));
+            return false;
+        }
+        if is_archive(&extension) && depth >= self.limits.max_archive_depth {
+            output.skipped.push(format!("{}：压缩包嵌套超过 {} 层", file.location, self.limits.max_archive_depth));
+            return false;
+        }
+        true
+    }
+
+    /// 扫描读入内存的条目：嵌套的压缩包继续扫描，其余（INF）保留在 files 中
+    fn expand_nested(&self, files: Vec<FoundFile>, depth: usize, found: &mut usize, output: &mut ScanOutput, job: &JobContext) -> HamsterResult<Vec<FoundFile>> {
+        let mut kept = Vec::with_capacity(files.len());
+        for file in files {
+            match file.content {
+                Some(content) if is_archive(&file.extension()) => {
+                    job.report(0, 0, format!("正在读取压缩包 {}", file.location));
+                    self.scan_archive(Cursor::new(content), &file.location, depth + 1, found, output, job)?;
+                }
+                _ => kept.push(file),
+            }
+        }
+        Ok(kept)
+    }
+
+    /// 扫描一个 zip 压缩包：先列出条目并读入 INF 和嵌套的压缩包，再只为驱动包引用的条目计算哈希
+    fn scan_zip<R: Read + Seek>(&self, reader: R, location: &FileLocation, depth: usize, found: &mut usize, output: &mut ScanOutput, job: &JobContext) -> HamsterResult<()> {
+        let mut archive = match ZipArchive::new(reader) {
+            Ok(archive) => archive,
+            Err(e) => {
+                output.skipped.push(format!("{}：不是有效的 zip 文件（{}）", location, e));
+                return Ok(());
+            }
+        };
+
+        let mut files = Vec::new();
+        for index in 0..archive.len() {
+            job.check_cancelled()?;
+            if *found >= self.limits.max_files {
+                output.skipped.push(format!("{}：超过最多检查 {} 个文件的限制", location, self.limits.max_files));
+                break;
+            }
+            let mut entry = match archive.by_index(index) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    output.skipped.push(format!("{} 的第 {} 个条目：{}", location, index + 1, e));
+                    continue;
+                }
+            };
+            // 拒绝 `../` 等指向压缩包外的条目名
+            let Some(name) = entry.enclosed_name().map(|name| name.to_string_lossy().replace('\\', "/")) else {
+                output.skipped.push(format!("{}!/{}：条目名指向压缩包外", location, entry.name()));
+                continue;
+            };
+            if entry.is_dir() {
+                continue;
+            }
+            *found += 1;
+            let file = FoundFile { location: location.child(&name), size: entry.size(), zip_index: Some(index), content: None, sha256: None };
+            if !self.keep_content(&file, depth, output) {
+                files.push(file);
+                continue;
+            }
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            // 按声明的大小截断读取，防止条目头中的大小与实际数据不符
+            if let Err(e) = (&mut entry).take(self.limits.max_entry_size + 1).read_to_end(&mut content) {
+                output.skipped.push(format!("{}：无法解压（{}）", file.location, e));
+                continue;
+            }
+            // 多读的1字节说明实际数据超过了限制
+            if content.len() as u64 > self.limits.max_entry_size {
+                output.skipped.push(format!("{}：解压后超过 {} 字节的限制", file.location, self.limits.max_entry_size));
+                continue;
+            }
+            files.push(FoundFile { content: Some(content), ..file });
+        }
+        let files = self.expand_nested(files, depth, found, output, job)?;
+
+        let mut hash_entry = |file: &FoundFile| -> HamsterResult<String> {
+            let index = file.zip_index.unwrap_or_default();
+            let mut entry = archive.by_index(index)
+                .map_err(|e| HamsterError::parse(format!("无法读取 {}", file.location)).with_source(e))?;
+            sha256_reader(&mut entry).map_err(|e| HamsterError::io(format!("无法解压 {}", file.location), e))
+        };
+        self.collect_packages(&files, &mut hash_entry, output, job)
+    }
+
+    /// 扫描一个 CAB 压缩包：同一文件夹中的文件只能顺序解压，因此一次解压全部文件，同时计算哈希并读入 INF 和嵌套的压缩包
+    fn scan_cab<R: Read + Seek>(&self, reader: R, location: &FileLocation, depth: usize, found: &mut usize, output: &mut ScanOutput, job: &JobContext) -> HamsterResult<()> {
+        let mut cab = match CabArchive::new(reader) {
+            Ok(cab) => cab,
+            Err(e) => {
+                output.skipped.push(format!("{}：不是有效的 CAB 文件（{}）", location, e));
+                return Ok(());
+            }
+        };
+
+        let mut files = Vec::new();
+        let mut indices = Vec::new();
+        for (index, entry) in cab.files().iter().enumerate() {
+            if *found >= self.limits.max_files {
+                output.skipped.push(format!("{}：超过最多检查 {} 个文件的限制", location, self.limits.max_files));
+                break;
+            }
+            if entry.is_spanned() {
+                output.skipped.push(format!("{}!/{}：跨越多个 CAB 文件，暂不支持分卷", location, entry.name));
+                continue;
+            }
+            // 拒绝 `..\` 等指向压缩包外的文件名
+            let Some(relative) = entry.relative_path() else {
+                output.skipped.push(format!("{}!/{}：文件名指向压缩包外", location, entry.name));
+                continue;
+            };
+            *found += 1;
+            let name = relative.to_string_lossy().replace('\\', "/");
+            files.push(FoundFile { location: location.child(&name), size: entry.size as u64, zip_index: None, content: None, sha256: None });
+            indices.push(index);
+        }
+
+        let keep: Vec<bool> = files.iter().map(|file| self.keep_content(file, depth, output)).collect();
+        let mut hashers: Vec<Sha256> = vec![Sha256::new(); files.len()];
+        let mut contents: Vec<Vec<u8>> = vec![Vec::new(); files.len()];
+        job.report(0, 0, format!("正在解压 {}", location));
+        let result = cab.read_files(&indices, |index, chunk| {
+            job.check_cancelled()?;
+            let position = indices.binary_search(&index).unwrap_or_default();
+            hashers[position].update(chunk);
+            if keep[position] {
+                contents[position].extend_from_slice(chunk);
+            }
+            Ok(())
+        });
+        match result {
+            Ok(()) => {}
+            Err(e) if e.is_cancelled() => return Err(e),
+            Err(e) => {
+                output.skipped.push(format!("{}：无法解压（{}）", location, e));
+                return Ok(());
+            }
+        }
+
+        for (position, (hasher, content)) in hashers.into_iter().zip(contents).enumerate() {
+            let file = &mut files[position];
+            file.sha256 = Some(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect());
+            if keep[position] {
+                file.content = Some(content);
+            }
+        }
+        let files = self.expand_nested(files, depth, found, output, job)?;
+        // 哈希已在解压时算出
+        let mut precomputed = |file: &FoundFile| -> HamsterResult<String> {
+            file.sha256.clone().ok_or_else(|| HamsterError::parse(format!("无法读取 {}", file.location)))
+        };
+        self.collect_packages(&files, &mut precomputed, output, job)
+    }
+
+    /// 在同一容器的文件中为每个 INF 找出引用的文件，计算哈希后加入结果
+    fn collect_packages(&self, files: &[FoundFile], hash: &mut dyn FnMut(&FoundFile) -> HamsterResult<String>, output: &mut ScanOutput, job: &JobContext) -> HamsterResult<()> {
+        let index: HashMap<String, usize> = files.iter().enumerate()
+            .map(|(position, file)| (file.location.lookup_key(), position))
+            .collect();
+        let infs: Vec<usize> = (0..files.len()).filter(|&position| files[position].content.is_some()).collect();
+
+        for (step, &inf_index) in infs.iter().enumerate() {
+            job.check_cancelled()?;
+            let inf_file = &files[inf_index];
+            job.report(step, infs.len(), format!("正在分析 {}", inf_file.location));
+            let pending = match InfFile::from_bytes(inf_file.content.as_deref().unwrap_or_default(), self.locale) {
+                Ok(inf) => group_package(files, &index, inf_index, inf),
+                Err(e) => {
+                    // 无法解析的 INF 单独成包，仍列出以便用户自行判断
+                    let package = build_package(files, inf_index, &[inf_index], Vec::new(), hash)?;
+                    output.packages.push(ScannedPackage { package, inf: None, inf_error: Some(e) });
+                    continue;
+                }
+            };
+            let package = build_package(files, pending.inf_index, &pending.members, pending.missing_files, hash)?;
+            output.packages.push(ScannedPackage { package, inf: Some(pending.inf), inf_error: None });
+        }
+        Ok(())
+    }
+}
+
+/// INF 所在目录中 [SourceDisksFiles] 列出的文件和编录文件；子目录中找不到时也在 INF 所在目录查找
+fn group_package(files: &[FoundFile], index: &HashMap<String, usize>, inf_index: usize, inf: InfFile) -> PendingPackage {
+    let inf_key = files[inf_index].location.lookup_key();
+    let dir = inf_key.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    let mut members = vec![inf_index];
+    let mut missing_files = Vec::new();
+
+    let mut referenced: Vec<(Option<&str>, &str)> = inf.source_files.iter()
+        .map(|file| (file.subdir.as_deref(), file.name.as_str()))
+        .collect();
+    if let Some(catalog) = inf.version.catalog_file.as_deref() {
+        referenced.push((None, catalog));
+    }
+    for (subdir, name) in referenced {
+        let mut candidates = Vec::new();
+        if let Some(subdir) = subdir {
+            candidates.push(format!("{}/{}/{}", dir, subdir.trim_matches(['\\', '/']), name));
+        }
+        candidates.push(format!("{}/{}", dir, name));
+        let found = candidates.iter()
+            .map(|candidate| candidate.replace('\\', "/").to_lowercase())
+            .find_map(|candidate| index.get(&candidate).copied());
+        match found {
+            Some(position) if !members.contains(&position) => members.push(position),
+            Some(_) => {}
+            None if !missing_files.iter().any(|missing: &String| missing.eq_ignore_ascii_case(name)) => missing_files.push(name.to_string()),
+            None => {}
+        }
+    }
+    PendingPackage { inf, inf_index, members, missing_files }
+}
+
+fn build_package(files: &[FoundFile], inf_index: usize, members: &[usize], missing_files: Vec<String>, hash: &mut dyn FnMut(&FoundFile) -> HamsterResult<String>) -> HamsterResult<DriverPackage> {
+    let inf_key = files[inf_index].location.lookup_key();
+    let dir_len = inf_key.rfind('/').map(|position| position + 1).unwrap_or(0);
+    let mut package_files = Vec::new();
+    for &position in members {
+        let file = &files[position];
+        let sha256 = match (&file.sha256, &file.content) {
+            (Some(sha256), _) => sha256.clone(),
+            (None, Some(content)) => sha256_hex(content),
+            (None, None) => hash(file)?,
+        };
+        let full = file.location.to_string().replace('\\', "/");
+        let relative_path = full.get(dir_len..).filter(|path| !path.split('/').any(|part| part == ".."))
+            .map(str::to_string)
+            .unwrap_or_else(|| file.location.file_name());
+        package_files.push(PackageFile { location: file.location.clone(), relative_path, size: file.size, sha256 });
+    }
+
+    let mut digest_input: Vec<String> = package_files.iter()
+        .map(|file| format!("{}:{}", file.relative_path.to_lowercase(), file.sha256))
+        .collect();
+    digest_input.sort();
+    Ok(DriverPackage {
+        inf: files[inf_index].location.clone(),
+        files: package_files,
+        missing_files,
+        hash: sha256_hex(digest_input.join("\n").as_bytes()),
+        duplicates: Vec::new(),
+    })
+}
+
+/// 内容相同的包只保留第一个，其余的位置记入 duplicates
+fn merge_duplicates(packages: &mut Vec<ScannedPackage>) {
+    let mut merged: Vec<ScannedPackage> = Vec::new();
+    for scanned in packages.drain(..) {
+        match merged.iter_mut().find(|existing| existing.package.hash == scanned.package.hash) {
+            Some(existing) => existing.package.duplicates.push(scanned.package.inf),
+            None => merged.push(scanned),
+        }
+    }
+    *packages = merged;
+}
+
+fn is_archive(extension: &str) -> bool {
+    matches!(extension, "zip" | "cab")
+}
+
+/// 从压缩包中读取一个条目，container 为压缩包自身的文件名，用于区分格式
+fn read_archive_entry<R: Read + Seek>(reader: R, container: &str, name: &str, location: &FileLocation) -> HamsterResult<Vec<u8>> {
+    if container.to_ascii_lowercase().ends_with(".cab") {
+        let mut cab = CabArchive::new(reader)
+            .map_err(|e| HamsterError::parse(format!("无法打开 {} 所在的压缩包", location)).with_source(e))?;
+        let index = cab.find(name).ok_or_else(|| HamsterError::parse(format!("压缩包中找不到 {}", name)))?;
+        return cab.read_file(index);
+    }
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| HamsterError::parse(format!("无法打开 {} 所在的压缩包", location)).with_source(e))?;
+    let mut entry = archive.by_name(name)
+        .map_err(|e| HamsterError::parse(format!("压缩包中找不到 {}", name)).with_source(e))?;
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut data).map_err(|e| HamsterError::io(format!("无法解压 {}", location), e))?;
+    Ok(data)
+}
+
+fn sha256_reader(reader: &mut dyn Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(reader, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use crate::core::jobs::JobRunner;
+    use super::*;
+
+    #[test]
+    fn entries_outside_the_archive_are_reported_as_skipped() {
+        let path = std::env::temp_dir().join(format!("hamster-scan-{}.zip", std::process::id()));
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        writer.start_file("../evil.inf", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"[Version]\r\nSignature = \"$Windows NT$\"\r\n").unwrap();
+        writer.finish().unwrap();
+
+        let scan_path = path.clone();
+        let output = JobRunner::new()
+            .submit("扫描", move |job| PackageScanner::new(Locale::default()).scan(&scan_path, job))
+            .wait();
+        std::fs::remove_file(&path).unwrap();
+        let output = output.unwrap();
+        assert!(output.packages.is_empty());
+        assert_eq!(output.skipped.len(), 1);
+        assert!(output.skipped[0].contains("../evil.inf"));
+    }
+
+    #[test]
+    fn fixture_packages_are_found_in_directories_and_archives() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/packages");
+        let output = JobRunner::new()
+            .submit("扫描", move |job| PackageScanner::new(Locale::default()).scan(&root, job))
+            .wait()
+            .unwrap();
+        let mut infs: Vec<String> = output.packages.iter()
+            .map(|scanned| scanned.package.inf.entries.last().cloned().unwrap_or_else(|| scanned.package.inf.file_name()))
+            .collect();
+        infs.sort();
+        assert_eq!(infs, ["Netwtw08.inf", "Realtek/hdxrt.inf", "nv_dispi.inf"]);
+        assert!(output.packages.iter().all(|scanned| scanned.inf.is_some()));
+        // 压缩包中嵌套的 NVIDIA 驱动包与磁盘上的内容相同，合并为一个
+        let nvidia = output.packages.iter().find(|scanned| !scanned.package.is_in_archive()).unwrap();
+        assert_eq!(nvidia.package.duplicates.len(), 1);
+        assert!(nvidia.package.missing_files.is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+// 签名验证缓存：按文件路径、大小和修改时间保存验证结果，跨运行复用；
+// 受信任证书、编录文件或程序版本变化时整个缓存失效
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::features::signature_validator::SignatureResult;
+
+const CACHE_VERSION: u32 = 1;
+// 证书有效期和吊销状态与当前时间有关，超过一天的结果重新验证
+const MAX_AGE_HOURS: i64 = 24;
+
+/// 文件的大小和修改时间，任何一项变化都视为文件已被替换
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+	pub size: u64,
+	pub modified: DateTime<Utc>,
+}
+
+impl FileStamp {
+	pub fn of(path: &Path) -> Option<Self> {
+		let metadata = fs::metadata(path).ok()?;
+		Some(Self {
+			size: metadata.len(),
+			modified: metadata.modified().ok()?.into(),
+		})
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+	stamp: FileStamp,
+	checked_at: DateTime<Utc>,
+	result: SignatureResult,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+	version: u32,
+	/// 验证环境的摘要，见 [`SignatureCache::load`]
+	fingerprint: String,
+	entries: HashMap<String, CacheEntry>,
+}
+
+pub struct SignatureCache {
+	path: PathBuf,
+	fingerprint: String,
+	entries: HashMap<String, CacheEntry>,
+	changed: bool,
+}
+
+impl SignatureCache {
+	/// 读取缓存文件；fingerprint 描述验证环境（受信任证书、编录文件等），
+	/// 文件不存在、无法解析或环境不同时从空缓存开始
+	pub fn load(path: &Path, fingerprint: &str) -> Self {
+		let entries = fs::read(path).ok()
+			.and_then(|data| serde_json::from_slice::<CacheFile>(&data).ok())
+			.filter(|file| file.version == CACHE_VERSION && file.fingerprint == fingerprint)
+			.map(|file| file.entries)
+			.unwrap_or_default();
+		Self {
+			path: path.to_path_buf(),
+			fingerprint: fingerprint.to_string(),
+			entries,
+			changed: false,
+		}
+	}
+
+	pub fn get(&self, path: &Path, stamp: FileStamp, now: DateTime<Utc>) -> Option<&SignatureResult> {
+		self.entries.get(&key(path))
+			.filter(|entry| entry.stamp == stamp && is_fresh(entry, now))
+			.map(|entry| &entry.result)
+	}
+
+	pub fn insert(&mut self, path: &Path, stamp: FileStamp, now: DateTime<Utc>, result: SignatureResult) {
+		self.entries.insert(key(path), CacheEntry { stamp, checked_at: now, result });
+		self.changed = true;
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// 有新结果时写回文件并去掉过期的条目；先写入临时文件再替换，避免写入中断时损坏缓存
+	pub fn save(&mut self, now: DateTime<Utc>) -> HamsterResult<()> {
+		if !self.changed {
+			return Ok(());
+		}
+		self.entries.retain(|_, entry| is_fresh(entry, now));
+		if let Some(parent) = self.path.parent() {
+			fs::create_dir_all(parent)
+				.map_err(|e| HamsterError::io(format!("无法创建缓存目录 {:?}", parent), e))?;
+		}
+		let file = CacheFile {
+			version: CACHE_VERSION,
+			fingerprint: self.fingerprint.clone(),
+			entries: std::mem::take(&mut self.entries),
+		};
+		let content = serde_json::to_vec(&file);
+		self.entries = file.entries;
+		let content = content.map_err(|e| HamsterError::parse("序列化签名验证缓存失败").with_source(e))?;
+		let temp_path = self.path.with_extension("json.tmp");
+		fs::write(&temp_path, content)
+			.map_err(|e| HamsterError::io(format!("无法写入缓存文件 {:?}", temp_path), e))?;
+		fs::rename(&temp_path, &self.path)
+			.map_err(|e| HamsterError::io(format!("无法保存缓存文件 {:?}", self.path), e))?;
+		self.changed = false;
+		Ok(())
+	}
+}
+
+fn is_fresh(entry: &CacheEntry, now: DateTime<Utc>) -> bool {
+	now - entry.checked_at < Duration::hours(MAX_AGE_HOURS)
+}
+
+fn key(path: &Path) -> String {
+	path.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::{Local, TimeZone};
+	use crate::core::driver_manager::{DriverInfo, DriverStatus, DriverType};
+	use crate::core::features::signature_validator::SignatureValidator;
+	use crate::core::jobs::JobRunner;
+
+	fn temp_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("hamster-signature-cache-{}-{}", name, std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	fn now() -> DateTime<Utc> {
+		Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+	}
+
+	fn result(path: &Path) -> SignatureResult {
+		SignatureValidator::new().validate_file("driver", path)
+	}
+
+	fn driver(name: &str, path: &Path) -> DriverInfo {
+		DriverInfo {
+			name: name.to_string(),
+			display_name: name.to_string(),
+			description: String::new(),
+			status: DriverStatus::Stopped,
+			driver_type: DriverType::Other,
+			start_type: String::new(),
+			binary_path: path.display().to_string(),
+			version: String::new(),
+			company: String::new(),
+			signed: false,
+			signature_status: String::new(),
+			last_updated: Local::now(),
+			dependencies: Vec::new(),
+			load_order: 0,
+			group: String::new(),
+		}
+	}
+
+	#[test]
+	fn entries_are_keyed_by_path_size_and_mtime() {
+		let dir = temp_dir("stamp");
+		let file = dir.join("driver.sys");
+		fs::write(&file, b"version 1").unwrap();
+		let stamp = FileStamp::of(&file).unwrap();
+		let mut cache = SignatureCache::load(&dir.join("cache.json"), "环境");
+		cache.insert(&file, stamp, now(), result(&file));
+
+		assert!(cache.get(&file, stamp, now()).is_some());
+		assert!(cache.get(&dir.join("other.sys"), stamp, now()).is_none());
+		assert!(cache.get(&file, FileStamp { size: stamp.size + 1, ..stamp }, now()).is_none());
+		assert!(cache.get(&file, FileStamp { modified: stamp.modified + Duration::seconds(1), ..stamp }, now()).is_none());
+
+		// 内容替换为同样大小时，修改时间不同
+		fs::write(&file, b"version 2").unwrap();
+		let handle = fs::File::options().write(true).open(&file).unwrap();
+		handle.set_modified((stamp.modified + Duration::seconds(60)).into()).unwrap();
+		drop(handle);
+		let replaced = FileStamp::of(&file).unwrap();
+		assert_eq!(replaced.size, stamp.size);
+		assert!(cache.get(&file, replaced, now()).is_none());
+		// 超过一天的结果重新验证
+		assert!(cache.get(&file, stamp, now() + Duration::hours(MAX_AGE_HOURS)).is_none());
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn saved_entries_need_the_same_fingerprint() {
+		let dir = temp_dir("fingerprint");
+		let file = dir.join("driver.sys");
+		fs::write(&file, b"driver").unwrap();
+		let stamp = FileStamp::of(&file).unwrap();
+		let path = dir.join("cache").join("signatures.json");
+		let mut cache = SignatureCache::load(&path, "环境");
+		cache.insert(&file, stamp, now(), result(&file));
+		// 已过期的条目在保存时去掉
+		cache.insert(&dir.join("old.sys"), stamp, now() - Duration::hours(MAX_AGE_HOURS + 1), result(&file));
+		cache.save(now()).unwrap();
+		assert_eq!(cache.len(), 1);
+		assert!(!path.with_extension("json.tmp").exists());
+
+		let reloaded = SignatureCache::load(&path, "环境");
+		assert_eq!(reloaded.get(&file, stamp, now()).unwrap().error_message, "不是 PE 文件，且没有可用的编录文件");
+		assert!(SignatureCache::load(&path, "其他环境").is_empty());
+		fs::write(&path, b"{").unwrap();
+		assert!(SignatureCache::load(&path, "环境").is_empty());
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn cancelled_batch_keeps_finished_results() {
+		let dir = temp_dir("cancel");
+		let drivers: Vec<DriverInfo> = (0..4)
+			.map(|index| {
+				let file = dir.join(format!("driver{}.sys", index));
+				fs::write(&file, format!("driver {}", index)).unwrap();
+				driver(&format!("driver{}", index), &file)
+			})
+			.collect();
+		let path = dir.join("signatures.json");
+		let validator = SignatureValidator::new().with_cache(path.clone());
+		let batch = drivers.clone();
+		// 第一个结果完成后取消，其余文件不再开始验证
+		let result = JobRunner::new()
+			.submit("验证签名", move |job| {
+				let token = job.cancel_token();
+				validator.validate_batch_streaming(&batch, 1, job, |_| token.cancel())
+			})
+			.wait();
+		assert!(result.is_err());
+
+		let file: CacheFile = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+		assert_eq!(file.entries.len(), 1);
+		let (cached, _) = file.entries.iter().next().unwrap();
+		assert!(drivers.iter().any(|driver| &driver.binary_path == cached));
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
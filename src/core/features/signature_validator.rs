@@ -0,0 +1,650 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::core::driver_manager::DriverInfo;
+use crate::core::error::HamsterResult;
+use crate::core::features::signature_cache::{FileStamp, SignatureCache};
+use crate::core::jobs::JobContext;
+use crate::core::pe::PeFile;
+use crate::core::signature::{algorithm_name, check_image_digest, classify, Classification, read_pe_signatures, AuthenticodeSignature, CatalogIndex, CatalogMatch, DigestStatus, SignedData, Timestamp, TimestampKind};
+use crate::core::signature::trust::{self, TrustStatus, TrustStore, DRIVER_SIGNING_USAGES};
+use crate::core::signature::x509::{hex, Certificate};
+use crate::core::windows_api::driver_file::resolve_image_path;
+
+pub struct SignatureValidator {
+	/// 当前批次已完成的结果
+	results: Arc<Mutex<Vec<SignatureResult>>>,
+	/// 文件本身没有签名时，在这些编录文件中查找
+	catalogs: CatalogIndex,
+	/// 配置了受信任证书目录时验证证书链，否则只检查映像摘要
+	trust_store: Option<TrustStore>,
+	/// 没有可信时间戳时检查证书有效期所用的时间，为空时取当前时间
+	verification_time: Option<DateTime<Utc>>,
+	/// 签名验证缓存文件，为空时不缓存
+	cache_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampSummary {
+	pub kind: TimestampKind,
+	pub time: DateTime<Utc>,
+	/// 时间戳颁发机构
+	pub authority: String,
+}
+
+/// 一个签名的签名者信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerSummary {
+	pub subject: String,
+	pub issuer: String,
+	pub serial_number: String,
+	pub digest_algorithm: String,
+	pub timestamp: Option<TimestampSummary>,
+	/// 签名中的映像摘要与文件是否一致，未校验时为 None
+	pub digest_status: Option<DigestStatus>,
+	/// 证书链验证结果；未配置受信任证书目录时只验证签名值，签名值有效时为 None
+	pub trust: Option<TrustStatus>,
+	/// 不受信任的原因
+	pub trust_detail: String,
+	/// 从签名者到根证书的证书链
+	pub chain: Vec<String>,
+}
+
+impl SignerSummary {
+	pub fn from_signature(signature: &AuthenticodeSignature) -> Self {
+		Self::new(&signature.signed_data, &signature.timestamps, &signature.digest_algorithm)
+	}
+
+	/// 编录文件的签名者；摘要算法取成员记录的文件摘要算法
+	pub fn from_catalog(found: &CatalogMatch) -> Self {
+		let digest_algorithm = found.member.digest_algorithm.as_deref().unwrap_or_default();
+		Self::new(&found.catalog.signed_data, &found.catalog.timestamps, digest_algorithm)
+	}
+
+	fn new(signed_data: &SignedData, timestamps: &[Timestamp], digest_algorithm: &str) -> Self {
+		let certificate = signed_data.signer().and_then(|(_, certificate)| certificate);
+		let timestamp = timestamps.first().map(|timestamp| TimestampSummary {
+			kind: timestamp.kind,
+			time: timestamp.time,
+			authority: timestamp.certificate.as_ref()
+				.map(|certificate| certificate.subject.display_name())
+				.unwrap_or_else(|| "未知".to_string()),
+		});
+		Self {
+			subject: certificate.map(|certificate| certificate.subject.to_string()).unwrap_or_else(|| "未找到签名者证书".to_string()),
+			issuer: certificate.map(|certificate| certificate.issuer.to_string()).unwrap_or_default(),
+			serial_number: certificate.map(|certificate| certificate.serial_hex()).unwrap_or_default(),
+			digest_algorithm: algorithm_name(digest_algorithm).to_string(),
+			timestamp,
+			digest_status: None,
+			trust: None,
+			trust_detail: String::new(),
+			chain: Vec::new(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureResult {
+	pub driver_name: String,
+	pub file_path: String,
+	/// 文件中有可以解析的签名且映像摘要一致；配置了受信任证书目录时主签名还必须受信任
+	pub is_valid: bool,
+	/// 签名中的映像摘要与文件不符，文件在签名后被修改
+	pub tampered: bool,
+	/// 主签名的证书链是否受信任，未配置受信任证书目录时为 None
+	pub trusted: Option<bool>,
+	/// 签名来源分类和加载预测，未检查的文件为 None
+	pub classification: Option<Classification>,
+	/// 文件存在且为 PE 文件；为 false 时调用方应保留平台提供的签名状态
+	pub checked: bool,
+	pub signature_type: String,
+	pub certificate_issuer: String,
+	pub certificate_subject: String,
+	pub serial_number: String,
+	pub digest_algorithm: String,
+	pub timestamp: String,
+	/// 由编录文件签名时为编录文件的路径
+	pub catalog_file: String,
+	/// 主签名在前，其后为嵌套签名
+	pub signers: Vec<SignerSummary>,
+	pub error_message: String,
+}
+
+impl SignatureResult {
+	fn new(driver_name: &str, file_path: &Path) -> Self {
+		Self {
+			driver_name: driver_name.to_string(),
+			file_path: file_path.display().to_string(),
+			is_valid: false,
+			tampered: false,
+			trusted: None,
+			classification: None,
+			checked: false,
+			signature_type: String::new(),
+			certificate_issuer: String::new(),
+			certificate_subject: String::new(),
+			serial_number: String::new(),
+			digest_algorithm: String::new(),
+			timestamp: String::new(),
+			catalog_file: String::new(),
+			signers: Vec::new(),
+			error_message: String::new(),
+		}
+	}
+
+	/// 简短的签名状态：有效时为签名分类和加载预测，否则为错误信息
+	pub fn summary(&self) -> String {
+		if !self.is_valid {
+			return self.error_message.clone();
+		}
+		match &self.classification {
+			Some(classification) => format!("{}，{}", classification.label(), classification.load),
+			None => self.signature_type.clone(),
+		}
+	}
+
+	/// 用主签名者填写汇总字段
+	fn fill_primary(&mut self) {
+		let Some(summary) = self.signers.first() else {
+			return;
+		};
+		self.certificate_subject = summary.subject.clone();
+		self.certificate_issuer = summary.issuer.clone();
+		self.serial_number = summary.serial_number.clone();
+		self.digest_algorithm = summary.digest_algorithm.clone();
+	}
+
+	/// 按主签名的信任验证结果更新 is_valid 和错误信息；chain_checked 表示配置了信任库、验证过证书链
+	fn apply_trust(&mut self, chain_checked: bool) {
+		let Some(summary) = self.signers.first() else {
+			return;
+		};
+		let Some(status) = summary.trust else {
+			return;
+		};
+		if matches!(status, TrustStatus::BadSignature | TrustStatus::Revoked) {
+			if let Some(classification) = &mut self.classification {
+				classification.block(format!("{}：{}", status, summary.trust_detail));
+			}
+		}
+		if !chain_checked {
+			// 没有信任库时只验证签名值，签名值无效说明签名者没有签过这些内容
+			self.is_valid = false;
+			if self.error_message.is_empty() {
+				self.error_message = format!("签名无效：{}（{}）", status, summary.trust_detail);
+			}
+			return;
+		}
+		let trusted = status == TrustStatus::Trusted;
+		self.trusted = Some(trusted);
+		if !trusted {
+			self.is_valid = false;
+			if self.error_message.is_empty() {
+				self.error_message = format!("签名不受信任：{}（{}）", status, summary.trust_detail);
+			}
+		}
+	}
+}
+
+/// 一批驱动的签名验证结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignatureBatch {
+	/// 与输入的驱动一一对应
+	pub results: Vec<SignatureResult>,
+	/// 不影响验证结论的问题，如无法保存验证缓存
+	pub warnings: Vec<String>,
+}
+
+impl Default for SignatureValidator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[allow(dead_code)]
+impl SignatureValidator {
+	pub fn new() -> Self {
+		Self {
+			results: Arc::new(Mutex::new(Vec::new())),
+			catalogs: CatalogIndex::new(),
+			trust_store: None,
+			verification_time: None,
+			cache_path: None,
+		}
+	}
+
+	/// 用本地信任库验证证书链、有效期、用途和吊销状态
+	pub fn with_trust_store(mut self, trust_store: TrustStore) -> Self {
+		self.trust_store = Some(trust_store);
+		self
+	}
+
+	/// 固定没有可信时间戳时使用的验证时间，使结果可以重现
+	pub fn at_time(mut self, time: DateTime<Utc>) -> Self {
+		self.verification_time = Some(time);
+		self
+	}
+
+	fn now(&self) -> DateTime<Utc> {
+		self.verification_time.unwrap_or_else(Utc::now)
+	}
+
+	/// 验证签名者的签名值，配置了信任库时还验证证书链；返回受信任时的证书链，供签名分类判断根证书
+	fn verify_trust(&self, summary: &mut SignerSummary, signed_data: &SignedData, timestamps: &[Timestamp]) -> Option<Vec<Certificate>> {
+		let Some(trust_store) = &self.trust_store else {
+			if let Err((status, detail)) = trust::verify_signature(signed_data) {
+				summary.trust = Some(status);
+				summary.trust_detail = detail;
+			}
+			return None;
+		};
+		let trust = trust_store.verify_signed_data(signed_data, timestamps, DRIVER_SIGNING_USAGES, self.now());
+		summary.trust = Some(trust.status);
+		summary.trust_detail = trust.detail;
+		summary.chain = trust.chain.iter().map(|certificate| certificate.subject.display_name()).collect();
+		(trust.status == TrustStatus::Trusted).then_some(trust.chain)
+	}
+
+	/// 使用指定的编录文件（驱动包中的 .cat 或 CatRoot）验证没有嵌入签名的文件
+	pub fn with_catalogs(mut self, catalogs: CatalogIndex) -> Self {
+		self.catalogs = catalogs;
+		self
+	}
+
+	/// 使用本机 CatRoot 中已安装的编录文件
+	pub fn with_system_catalogs(self) -> Self {
+		let catalogs = CatalogIndex::system(&system_root());
+		self.with_catalogs(catalogs)
+	}
+
+	/// 跨运行缓存验证结果，文件的路径、大小和修改时间都未变化时直接使用缓存
+	pub fn with_cache(mut self, path: PathBuf) -> Self {
+		self.cache_path = Some(path);
+		self
+	}
+
+	/// 当前批次已完成的结果，按完成顺序排列；验证进行中可在其他线程读取
+	pub fn results(&self) -> Arc<Mutex<Vec<SignatureResult>>> {
+		self.results.clone()
+	}
+
+	pub fn validate_batch(&self, drivers: &[DriverInfo], concurrent: usize, job: &JobContext) -> HamsterResult<SignatureBatch> {
+		self.validate_batch_streaming(drivers, concurrent, job, |_| {})
+	}
+
+	/// 用 concurrent 个线程并行验证，每得到一个驱动的结果就调用 on_result；
+	/// 内容相同的文件只验证一次，取消后不再开始新的文件，已完成的结果仍写入缓存
+	pub fn validate_batch_streaming(&self, drivers: &[DriverInfo], concurrent: usize, job: &JobContext, on_result: impl Fn(&SignatureResult) + Sync) -> HamsterResult<SignatureBatch> {
+		if let Ok(mut results) = self.results.lock() {
+			results.clear();
+		}
+		let system_root = system_root();
+		let now = self.now();
+		let paths: Vec<PathBuf> = drivers.iter().map(|driver| resolve_image_path(&driver.binary_path, &system_root)).collect();
+		let stamps: Vec<Option<FileStamp>> = paths.iter().map(|path| FileStamp::of(path)).collect();
+		let cache = Mutex::new(self.cache_path.as_deref().map(|path| SignatureCache::load(path, &self.fingerprint())));
+		let slots: Mutex<Vec<Option<SignatureResult>>> = Mutex::new(vec![None; drivers.len()]);
+		let done = AtomicUsize::new(0);
+
+		// 为第 index 个驱动填入结果；同一文件共用一个结果，只替换驱动名
+		let finish = |index: usize, result: &SignatureResult| {
+			let mut result = result.clone();
+			result.driver_name = drivers[index].name.clone();
+			result.file_path = paths[index].display().to_string();
+			on_result(&result);
+			if let Ok(mut results) = self.results.lock() {
+				results.push(result.clone());
+			}
+			if let Ok(mut slots) = slots.lock() {
+				slots[index] = Some(result);
+			}
+			let finished = done.fetch_add(1, Ordering::SeqCst) + 1;
+			job.report(finished, drivers.len(), format!("已验证 {} 的签名", drivers[index].name));
+		};
+
+		// 按路径分组，缓存命中的直接完成
+		let mut by_path: BTreeMap<&Path, Vec<usize>> = BTreeMap::new();
+		for (index, path) in paths.iter().enumerate() {
+			let cached = cache.lock().ok().and_then(|cache| {
+				let stamp = stamps[index]?;
+				cache.as_ref()?.get(path, stamp, now).cloned()
+			});
+			match cached {
+				Some(result) => finish(index, &result),
+				None => by_path.entry(path).or_default().push(index),
+			}
+		}
+
+		// 再按内容分组：无法读取的文件各自成组，由 validate_file 报告错误
+		let pending: Vec<(&Path, Vec<usize>)> = by_path.into_iter().collect();
+		job.report(done.load(Ordering::SeqCst), drivers.len(), "正在计算文件摘要");
+		let hashes = run_parallel(&pending, concurrent, job, |(path, _)| content_hash(path));
+		let mut groups: Vec<Vec<usize>> = Vec::new();
+		let mut by_hash: HashMap<Vec<u8>, usize> = HashMap::new();
+		for (position, hash) in hashes.into_iter().enumerate() {
+			match hash.flatten() {
+				Some(hash) => match by_hash.get(&hash) {
+					Some(&group) => groups[group].push(position),
+					None => {
+						by_hash.insert(hash, groups.len());
+						groups.push(vec![position]);
+					}
+				},
+				None => groups.push(vec![position]),
+			}
+		}
+
+		run_parallel(&groups, concurrent, job, |group| {
+			// 内容相同的文件只共用验证结论，取决于文件名的说明按各自的路径重新生成
+			let (path, indices) = &pending[group[0]];
+			let verdict = self.verify_content(&drivers[indices[0]].name, path);
+			for &(path, ref indices) in group.iter().map(|&position| &pending[position]) {
+				let mut result = verdict.clone();
+				result.file_path = path.display().to_string();
+				self.describe_path(&mut result, path);
+				for &index in indices {
+					finish(index, &result);
+				}
+				if let (Some(stamp), Ok(mut cache)) = (stamps[indices[0]], cache.lock()) {
+					if let Some(cache) = cache.as_mut() {
+						cache.insert(path, stamp, now, result.clone());
+					}
+				}
+			}
+		});
+
+		let mut warnings = Vec::new();
+		if let Some(mut cache) = cache.into_inner().ok().flatten() {
+			if let Err(e) = cache.save(now) {
+				warnings.push(format!("无法保存签名验证缓存：{}", e.context()));
+			}
+		}
+		job.check_cancelled()?;
+		job.report(drivers.len(), drivers.len(), "签名验证完成");
+		let slots = slots.into_inner().unwrap_or_default();
+		Ok(SignatureBatch { results: slots.into_iter().flatten().collect(), warnings })
+	}
+
+	/// 验证环境的摘要：受信任证书、吊销列表、编录文件、固定的验证时间和程序版本，任何一项变化都使缓存失效
+	fn fingerprint(&self) -> String {
+		let mut digest = Sha256::new();
+		digest.update(env!("CARGO_PKG_VERSION").as_bytes());
+		if let Some(trust_store) = &self.trust_store {
+			for root in trust_store.roots() {
+				digest.update(&root.raw);
+			}
+			for crl in trust_store.crls() {
+				digest.update(&crl.issuer.raw);
+				digest.update(crl.this_update.to_rfc3339().as_bytes());
+			}
+		}
+		for (path, catalog) in self.catalogs.catalogs() {
+			digest.update(path.to_string_lossy().as_bytes());
+			digest.update(catalog.this_update.to_rfc3339().as_bytes());
+			digest.update(catalog.members.len().to_le_bytes());
+		}
+		if let Some(time) = self.verification_time {
+			digest.update(time.to_rfc3339().as_bytes());
+		}
+		hex(&digest.finalize())
+	}
+
+	/// 离线读取文件中嵌入的 Authenticode 签名；没有嵌入签名时查找覆盖该文件的编录
+	pub fn validate_file(&self, driver_name: &str, path: &Path) -> SignatureResult {
+		let mut result = self.verify_content(driver_name, path);
+		self.describe_path(&mut result, path);
+		result
+	}
+
+	/// 只取决于文件内容的验证结论，内容相同的文件可以共用
+	fn verify_content(&self, driver_name: &str, path: &Path) -> SignatureResult {
+		let mut result = SignatureResult::new(driver_name, path);
+		// INF、MSI 等不是 PE 文件，只能由编录签名
+		let embedded = match PeFile::open(path) {
+			Ok(pe) => Some(read_pe_signatures(&pe).map(|signatures| (pe, signatures))),
+			Err(e) if !path.is_file() => {
+				result.error_message = e.context().to_string();
+				return result;
+			}
+			Err(_) => None,
+		};
+		if let Some(Ok((pe, signatures))) = &embedded {
+			if let Some(primary) = signatures.first() {
+				result.checked = true;
+				result.signature_type = "Authenticode".to_string();
+				// 每个签名（包括嵌套签名）都记录了自己的映像摘要，任何一个不符都说明文件在签名后被修改
+				let mut primary_chain = None;
+				for signature in signatures.iter().flat_map(|signature| signature.flatten()) {
+					let mut summary = SignerSummary::from_signature(signature);
+					summary.digest_status = Some(check_image_digest(pe, signature).unwrap_or(DigestStatus::Tampered));
+					let chain = self.verify_trust(&mut summary, &signature.signed_data, &signature.timestamps);
+					if result.signers.is_empty() {
+						primary_chain = chain;
+					}
+					result.signers.push(summary);
+				}
+				result.tampered = result.signers.iter().any(|signer| signer.digest_status == Some(DigestStatus::Tampered));
+				result.is_valid = !result.tampered;
+				let mut classification = classify(&primary.signed_data, &primary.timestamps, None, primary_chain.as_deref(), self.now());
+				if result.tampered {
+					result.error_message = "文件已被篡改：映像摘要与签名不符".to_string();
+					classification.block("文件已被篡改");
+				}
+				result.classification = Some(classification);
+				result.timestamp = format_timestamp(&primary.timestamps);
+				result.fill_primary();
+				result.apply_trust(self.trust_store.is_some());
+				return result;
+			}
+		}
+
+		match self.catalogs.find_file(path) {
+			Ok(Some(found)) => {
+				result.checked = true;
+				result.is_valid = true;
+				result.signature_type = "Catalog".to_string();
+				result.catalog_file = found.path.display().to_string();
+				// 按摘要找到的成员，摘要必然一致
+				let mut summary = SignerSummary::from_catalog(&found);
+				summary.digest_status = Some(DigestStatus::Valid);
+				let chain = self.verify_trust(&mut summary, &found.catalog.signed_data, &found.catalog.timestamps);
+				result.classification = Some(classify(&found.catalog.signed_data, &found.catalog.timestamps, Some(found.catalog), chain.as_deref(), self.now()));
+				result.signers = vec![summary];
+				result.timestamp = format_timestamp(&found.catalog.timestamps);
+				result.fill_primary();
+				result.apply_trust(self.trust_store.is_some());
+				return result;
+			}
+			Ok(None) => {}
+			Err(e) => {
+				result.error_message = e.context().to_string();
+				return result;
+			}
+		}
+
+		match embedded {
+			Some(Err(e)) => {
+				result.checked = true;
+				result.signature_type = "Authenticode".to_string();
+				result.error_message = format!("签名无效: {}", e.context());
+				let mut classification = Classification::unsigned();
+				classification.block("签名无法解析");
+				result.classification = Some(classification);
+			}
+			Some(Ok(_)) => {
+				result.checked = true;
+				result.error_message = "未签名".to_string();
+				result.classification = Some(Classification::unsigned());
+			}
+			// 没有可用的编录时无法判断非 PE 文件的签名
+			None if self.catalogs.is_empty() => result.error_message = "不是 PE 文件，且没有可用的编录文件".to_string(),
+			None => {
+				result.checked = true;
+				result.error_message = "未签名".to_string();
+				result.classification = Some(Classification::unsigned());
+			}
+		}
+		result
+	}
+
+	/// 补充取决于文件名的说明：未签名的文件在编录中列有同名文件时提示摘要不一致
+	fn describe_path(&self, result: &mut SignatureResult, path: &Path) {
+		if !result.checked || !result.signers.is_empty() {
+			return;
+		}
+		let listed = path.file_name()
+			.and_then(|name| self.catalogs.find_file_name(&name.to_string_lossy()));
+		if let Some(found) = listed {
+			result.error_message = format!("{}（编录 {} 中列有同名文件，但摘要不一致）", result.error_message, found.path.display());
+		}
+	}
+}
+
+/// 用最多 workers 个线程处理 items，结果与 items 一一对应；取消后不再开始新的项，未处理的项为 None
+fn run_parallel<T: Sync, R: Send>(items: &[T], workers: usize, job: &JobContext, work: impl Fn(&T) -> R + Sync) -> Vec<Option<R>> {
+	let next = AtomicUsize::new(0);
+	let outputs: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+	thread::scope(|scope| {
+		for _ in 0..workers.clamp(1, items.len().max(1)) {
+			scope.spawn(|| {
+				while !job.is_cancelled() {
+					let index = next.fetch_add(1, Ordering::SeqCst);
+					let Some(item) = items.get(index) else {
+						break;
+					};
+					let output = work(item);
+					if let Ok(mut outputs) = outputs.lock() {
+						outputs[index] = Some(output);
+					}
+				}
+			});
+		}
+	});
+	outputs.into_inner().unwrap_or_default()
+}
+
+/// 文件内容的 SHA-256，用于找出内容相同的文件；无法读取时返回 None
+fn content_hash(path: &Path) -> Option<Vec<u8>> {
+	let mut file = File::open(path).ok()?;
+	let mut digest = Sha256::new();
+	io::copy(&mut file, &mut digest).ok()?;
+	Some(digest.finalize().to_vec())
+}
+
+fn format_timestamp(timestamps: &[Timestamp]) -> String {
+	timestamps.first()
+		.map(|timestamp| timestamp.time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+		.unwrap_or_default()
+}
+
+fn system_root() -> PathBuf {
+	PathBuf::from(std::env::var_os("SystemRoot").unwrap_or_else(|| r"C:\Windows".into()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::signature::{LoadPrediction, SignatureClass};
+	use chrono::TimeZone;
+
+	fn sample_driver() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/packages/nvidia/nvlddmkm.sys")
+	}
+
+	#[test]
+	fn unverified_chain_is_not_classified_by_certificate_names() {
+		let result = SignatureValidator::new().validate_file("nvlddmkm", &sample_driver());
+		assert!(result.is_valid, "{}", result.error_message);
+		assert_eq!(result.trusted, None);
+		let classification = result.classification.unwrap();
+		assert_eq!(classification.class, SignatureClass::Authenticode);
+		assert_eq!(classification.load, LoadPrediction::Unknown);
+	}
+
+	fn driver(name: &str, path: &Path) -> DriverInfo {
+		DriverInfo {
+			name: name.to_string(),
+			display_name: name.to_string(),
+			description: String::new(),
+			status: crate::core::driver_manager::DriverStatus::Stopped,
+			driver_type: crate::core::driver_manager::DriverType::Other,
+			start_type: String::new(),
+			binary_path: path.display().to_string(),
+			version: String::new(),
+			company: String::new(),
+			signed: false,
+			signature_status: String::new(),
+			last_updated: Local::now(),
+			dependencies: Vec::new(),
+			load_order: 0,
+			group: String::new(),
+		}
+	}
+
+	#[test]
+	fn identical_files_keep_their_own_catalog_hint() {
+		let dir = std::env::temp_dir().join(format!("hamster-signature-dedup-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let listed = dir.join("nvapi64.dll");
+		let other = dir.join("other.dll");
+		std::fs::write(&listed, b"modified").unwrap();
+		std::fs::write(&other, b"modified").unwrap();
+		let mut catalogs = CatalogIndex::new();
+		catalogs.load_file(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/packages/nvidia/NV_DISP.CAT")).unwrap();
+		let validator = SignatureValidator::new().with_catalogs(catalogs);
+		// 先验证不在编录中的文件，它的结果不能带上另一个文件的提示
+		let drivers = [driver("other", &other), driver("nvapi", &listed)];
+		let batch = crate::core::jobs::JobRunner::new()
+			.submit("验证签名", move |job| validator.validate_batch(&drivers, 2, job))
+			.wait()
+			.unwrap();
+		std::fs::remove_dir_all(&dir).ok();
+		assert!(batch.warnings.is_empty(), "{:?}", batch.warnings);
+		let results = batch.results;
+		assert_eq!(results.len(), 2);
+		assert_eq!(results[0].error_message, "未签名");
+		assert!(results[1].error_message.contains("NV_DISP.CAT"), "{}", results[1].error_message);
+		assert_eq!(results[1].file_path, listed.display().to_string());
+	}
+
+	#[test]
+	fn cache_save_failure_is_reported_in_the_batch() {
+		let dir = std::env::temp_dir().join(format!("hamster-signature-cache-save-{}", std::process::id()));
+		// 缓存文件的位置被目录占用，无法替换
+		std::fs::create_dir_all(dir.join("cache.json")).unwrap();
+		let file = dir.join("driver.sys");
+		std::fs::write(&file, b"not a driver").unwrap();
+		let validator = SignatureValidator::new().with_cache(dir.join("cache.json"));
+		let drivers = [driver("driver", &file)];
+		let batch = crate::core::jobs::JobRunner::new()
+			.submit("验证签名", move |job| validator.validate_batch(&drivers, 1, job))
+			.wait()
+			.unwrap();
+		std::fs::remove_dir_all(&dir).ok();
+		assert_eq!(batch.results.len(), 1);
+		assert_eq!(batch.warnings.len(), 1);
+		assert!(batch.warnings[0].starts_with("无法保存签名验证缓存："), "{}", batch.warnings[0]);
+	}
+
+	#[test]
+	fn trusted_private_root_is_test_signed() {
+		let trust_store = TrustStore::load_dir(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/trust"));
+		let validator = SignatureValidator::new()
+			.with_trust_store(trust_store)
+			.at_time(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+		let result = validator.validate_file("nvlddmkm", &sample_driver());
+		assert_eq!(result.trusted, Some(true), "{}", result.error_message);
+		let classification = result.classification.unwrap();
+		assert_eq!(classification.class, SignatureClass::TestSigned);
+		assert_eq!(classification.load, LoadPrediction::TestModeOnly);
+		assert_eq!(classification.root, "Hamster Test Root CA");
+	}
+}
@@ -0,0 +1,191 @@
+// 显卡驱动的厂商版本号：用户熟悉的是 NVIDIA 546.23、AMD Adrenalin 23.11.1、Intel 101.4887 这样的发布版本，
+// 而 INF 和文件中是 31.0.15.4623 这样的 Windows 版本。NVIDIA 和 Intel 按规则换算，
+// AMD 的两种版本号之间没有规则，只能查内置的对照表
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use crate::core::inf::DriverVersion;
+
+const BUNDLED_AMD_RELEASES: &str = include_str!("../../data/vendor/amd_adrenalin.txt");
+
+// NVIDIA 版本前两段随驱动分支支持的 WDDM 版本变化：（起始的发布版本主号, 前两段）
+const NVIDIA_BRANCHES: [(u32, [u16; 2]); 6] = [
+    (555, [32, 0]),
+    (515, [31, 0]),
+    (470, [30, 0]),
+    (450, [27, 21]),
+    (430, [26, 21]),
+    (0, [25, 21]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+impl fmt::Display for GpuVendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuVendor::Nvidia => write!(f, "NVIDIA"),
+            GpuVendor::Amd => write!(f, "AMD"),
+            GpuVendor::Intel => write!(f, "Intel"),
+        }
+    }
+}
+
+impl GpuVendor {
+    pub fn from_pci_vendor(vendor: u16) -> Option<Self> {
+        match vendor {
+            0x10DE => Some(GpuVendor::Nvidia),
+            0x1002 | 0x1022 => Some(GpuVendor::Amd),
+            0x8086 => Some(GpuVendor::Intel),
+            _ => None,
+        }
+    }
+
+    /// 按制造商名称识别，如 WMI 报告的 "NVIDIA"、"Advanced Micro Devices, Inc."、"Intel Corporation"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if name.contains("nvidia") {
+            Some(GpuVendor::Nvidia)
+        } else if name.contains("amd") || name.contains("advanced micro devices") || name.starts_with("ati ") {
+            Some(GpuVendor::Amd)
+        } else if name.contains("intel") {
+            Some(GpuVendor::Intel)
+        } else {
+            None
+        }
+    }
+
+    /// Windows 版本对应的厂商版本号；版本不符合该厂商的编号规则或对照表中没有时返回 None
+    pub fn marketing_version(&self, version: &DriverVersion) -> Option<String> {
+        let [_, _, build, revision] = version.parts;
+        match self {
+            // 31.0.15.4623：第三段的个位和第四段拼成 54623，即 546.23
+            GpuVendor::Nvidia => {
+                if !(10..20).contains(&build) || revision >= 10000 {
+                    return None;
+                }
+                let number = u32::from(build % 10) * 10000 + u32::from(revision);
+                Some(format!("{}.{:02}", number / 100, number % 100))
+            }
+            GpuVendor::Amd => amd_releases().iter()
+                .find(|(_, windows)| windows.cmp_version(version).is_eq())
+                .map(|(release, _)| release.clone()),
+            // 31.0.101.4887：后两段即 Intel 公布的版本号
+            GpuVendor::Intel => (build >= 15).then(|| format!("{}.{}", build, revision)),
+        }
+    }
+
+    /// 厂商版本号对应的 Windows 版本（不含日期）；NVIDIA 和 Intel 的前两段按版本范围推断，
+    /// 比较时应使用 [`GpuVendor::compare_published`]，不要依赖前两段
+    pub fn windows_version(&self, marketing: &str) -> Option<DriverVersion> {
+        let numbers = marketing_numbers(marketing)?;
+        match self {
+            GpuVendor::Nvidia => {
+                let [major, minor] = numbers.as_slice() else {
+                    return None;
+                };
+                // 小数部分总是两位，546.2 不是合法的 NVIDIA 版本
+                if marketing.trim().rsplit('.').next()?.len() != 2 || !(100..1000).contains(major) {
+                    return None;
+                }
+                let number = major * 100 + minor;
+                let [first, second] = NVIDIA_BRANCHES.iter()
+                    .find(|(since, _)| *major >= *since)
+                    .map(|(_, prefix)| *prefix)?;
+                Some(DriverVersion::new([first, second, 10 + (number / 10000) as u16, (number % 10000) as u16]))
+            }
+            GpuVendor::Amd => amd_releases().iter()
+                .find(|(release, _)| marketing_numbers(release).as_deref() == Some(numbers.as_slice()))
+                .map(|(_, windows)| *windows),
+            GpuVendor::Intel => {
+                let [build, revision] = numbers.as_slice() else {
+                    return None;
+                };
+                let build = u16::try_from(*build).ok()?;
+                let revision = u16::try_from(*revision).ok()?;
+                // 30.0.101.1994 之后的发布（如 31.0.101.2111）改用 31.0
+                let [first, second] = match (build, revision) {
+                    (101.., 2000..) => [31, 0],
+                    (101.., _) => [30, 0],
+                    (100, 8000..) => [27, 20],
+                    (100, _) => [26, 20],
+                    (16, _) => [21, 20],
+                    (15, _) => [20, 19],
+                    _ => return None,
+                };
+                Some(DriverVersion::new([first, second, build, revision]))
+            }
+        }
+    }
+
+    /// 显示为 `546.23 (31.0.15.4623)`；没有对应的厂商版本号时只显示 Windows 版本
+    pub fn display(&self, version: &DriverVersion) -> String {
+        match self.marketing_version(version) {
+            Some(marketing) => format!("{} ({})", marketing, version.version_string()),
+            None => version.version_string(),
+        }
+    }
+
+    /// 比较已安装的驱动与厂商公布的版本号（published 相对 installed 的关系）；
+    /// published 不是该厂商的版本号格式或无法换算时返回 None
+    pub fn compare_published(&self, installed: &DriverVersion, published: &str) -> Option<Ordering> {
+        let published_numbers = marketing_numbers(published)?;
+        // 四段的是 Windows 版本，不是厂商版本号
+        if published_numbers.len() >= 4 {
+            return None;
+        }
+        if let Some(installed_numbers) = self.marketing_version(installed).as_deref().and_then(marketing_numbers) {
+            return Some(compare_numbers(&published_numbers, &installed_numbers));
+        }
+        // AMD 的已安装版本不在对照表中时，改为把公布的版本换算为 Windows 版本比较
+        let published = self.windows_version(published)?;
+        Some(published.cmp_version(installed))
+    }
+}
+
+/// 点分的数字，如 `23.11.1` → [23, 11, 1]；含非数字的段时返回 None
+fn marketing_numbers(text: &str) -> Option<Vec<u32>> {
+    text.trim().split('.').map(|part| part.trim().parse().ok()).collect()
+}
+
+/// 逐段比较，缺少的段视为0
+fn compare_numbers(a: &[u32], b: &[u32]) -> Ordering {
+    (0..a.len().max(b.len()))
+        .map(|index| a.get(index).unwrap_or(&0).cmp(b.get(index).unwrap_or(&0)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// 内置的 Adrenalin 版本对照表，跳过注释和格式错误的行
+fn amd_releases() -> &'static [(String, DriverVersion)] {
+    static RELEASES: OnceLock<Vec<(String, DriverVersion)>> = OnceLock::new();
+    RELEASES.get_or_init(|| {
+        BUNDLED_AMD_RELEASES.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (release, windows) = line.split_once('\t')?;
+                Some((release.trim().to_string(), DriverVersion::parse(windows).ok()?))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marketing_versions_map_to_branch_prefixes() {
+        assert_eq!(GpuVendor::Nvidia.windows_version("560.94"), Some(DriverVersion::new([32, 0, 15, 6094])));
+        assert_eq!(GpuVendor::Nvidia.windows_version("546.23"), Some(DriverVersion::new([31, 0, 15, 4623])));
+        assert_eq!(GpuVendor::Intel.windows_version("101.2111"), Some(DriverVersion::new([31, 0, 101, 2111])));
+        assert_eq!(GpuVendor::Intel.windows_version("101.1994"), Some(DriverVersion::new([30, 0, 101, 1994])));
+    }
+}
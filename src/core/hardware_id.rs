@@ -0,0 +1,861 @@
+// 硬件ID：解析 Windows 即插即用硬件ID（PCI、USB、HDAUDIO、ACPI、HID、ROOT 等）为结构化数据，
+// 统一大小写，并按 Windows 的顺序生成从最具体到最通用的硬件ID和兼容ID列表，供驱动匹配使用
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::core::error::{HamsterError, HamsterResult};
+
+/// 设备类别代码：PCI 为基类/子类/编程接口，USB 为类/子类/协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClassCode {
+    pub class: u8,
+    pub subclass: Option<u8>,
+    pub prog_if: Option<u8>,
+}
+
+impl ClassCode {
+    /// 去掉编程接口后的类别
+    fn without_prog_if(self) -> Self {
+        Self { prog_if: None, ..self }
+    }
+
+    fn class_only(self) -> Self {
+        Self { subclass: None, prog_if: None, ..self }
+    }
+
+    /// PCI 形式：`030000` 或 `0300`
+    fn pci_digits(&self) -> String {
+        let mut digits = format!("{:02X}", self.class);
+        if let Some(subclass) = self.subclass {
+            digits.push_str(&format!("{:02X}", subclass));
+            if let Some(prog_if) = self.prog_if {
+                digits.push_str(&format!("{:02X}", prog_if));
+            }
+        }
+        digits
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PciId {
+    pub vendor: Option<u16>,
+    pub device: Option<u16>,
+    /// SUBSYS 字段原值：高16位为子系统ID，低16位为子系统厂商ID
+    pub subsystem: Option<u32>,
+    pub revision: Option<u8>,
+    pub class: Option<ClassCode>,
+}
+
+impl PciId {
+    pub fn subsystem_vendor(&self) -> Option<u16> {
+        self.subsystem.map(|subsystem| subsystem as u16)
+    }
+
+    pub fn subsystem_device(&self) -> Option<u16> {
+        self.subsystem.map(|subsystem| (subsystem >> 16) as u16)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct UsbId {
+    pub vendor: Option<u16>,
+    pub product: Option<u16>,
+    /// bcdDevice，4位十六进制
+    pub revision: Option<u16>,
+    /// 复合设备的接口号（MI_xx）
+    pub interface: Option<u8>,
+    pub class: Option<ClassCode>,
+}
+
+/// 高清音频编解码器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HdAudioId {
+    /// 功能组类型，01 为音频，02 为调制解调器
+    pub function: Option<u8>,
+    pub vendor: Option<u16>,
+    pub device: Option<u16>,
+    pub subsystem: Option<u32>,
+    pub revision: Option<u16>,
+}
+
+/// ACPI 设备ID由3位（PNP 等）或4位（INT、MSFT 等）厂商前缀和4位十六进制设备号组成
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AcpiId {
+    pub vendor: String,
+    pub device: String,
+    /// 部分设备在ID后附带 `&REV_xxxx`
+    pub revision: Option<u16>,
+    pub form: AcpiForm,
+}
+
+/// 同一个 ACPI 设备在 Windows 中的三种写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AcpiForm {
+    /// `ACPI\VEN_PNP&DEV_0A08`
+    VendorDevice,
+    /// `ACPI\PNP0A08`
+    Enumerator,
+    /// `*PNP0A08`
+    Star,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HidId {
+    pub vendor: Option<u16>,
+    pub product: Option<u16>,
+    pub revision: Option<u16>,
+    pub interface: Option<u8>,
+    /// 顶层集合编号（Colxx）
+    pub collection: Option<u8>,
+    /// 顶层集合的用法页和用法（UP:xxxx_U:xxxx）
+    pub usage: Option<(u16, u16)>,
+}
+
+/// 解析后的硬件ID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HardwareId {
+    Pci(PciId),
+    Usb(UsbId),
+    HdAudio(HdAudioId),
+    Acpi(AcpiId),
+    Hid(HidId),
+    /// 由 ROOT 枚举器创建的软件设备，如 `ROOT\LEGACY_BEEP`
+    Root(String),
+    /// 其他枚举器或无结构的通用ID，如 `SWD\PRINTENUM\...`、`HID_DEVICE_SYSTEM_MOUSE`，统一为大写
+    Other(String),
+}
+
+impl HardwareId {
+    /// 解析硬件ID，忽略大小写；同时接受 Linux modalias（`pci:v000010DEd00002504...`、`usb:v046DpC52B...`）
+    /// 和 `10de:2504` 形式，后两者转换为对应的 PCI/USB 硬件ID
+    pub fn parse(id: &str) -> HamsterResult<Self> {
+        let id = id.trim();
+        let upper = id.to_ascii_uppercase();
+        let invalid = |reason: &str| HamsterError::parse(format!("硬件ID {} 格式错误：{}", id, reason));
+        if upper.is_empty() {
+            return Err(invalid("为空"));
+        }
+
+        if let Some(rest) = upper.strip_prefix("PCI:") {
+            return Ok(HardwareId::Pci(parse_pci_modalias(rest)));
+        }
+        if let Some(rest) = upper.strip_prefix("USB:") {
+            return Ok(HardwareId::Usb(parse_usb_modalias(rest)));
+        }
+        if let Some((vendor, device)) = upper.split_once(':').filter(|(v, d)| v.len() == 4 && d.len() == 4 && !v.contains('\\')) {
+            return Ok(HardwareId::Pci(PciId {
+                vendor: Some(hex16(vendor).ok_or_else(|| invalid("厂商ID不是4位十六进制数"))?),
+                device: Some(hex16(device).ok_or_else(|| invalid("设备ID不是4位十六进制数"))?),
+                ..PciId::default()
+            }));
+        }
+        if let Some(acpi) = upper.strip_prefix('*') {
+            return parse_acpi_name(acpi, AcpiForm::Star).map(HardwareId::Acpi).ok_or_else(|| invalid("ACPI 设备名无效"));
+        }
+
+        let Some((enumerator, rest)) = upper.split_once('\\') else {
+            return Ok(HardwareId::Other(upper));
+        };
+        let fields = IdFields::parse(rest);
+        let result = match enumerator {
+            "PCI" => fields.pci().map(HardwareId::Pci),
+            "USB" => fields.usb().map(HardwareId::Usb),
+            "HDAUDIO" => fields.hdaudio().map(HardwareId::HdAudio),
+            "HID" => fields.hid().map(HardwareId::Hid),
+            "ACPI" => match (fields.get("VEN"), fields.get("DEV")) {
+                (Some(vendor), Some(device)) => parse_acpi_parts(vendor, device, AcpiForm::VendorDevice),
+                _ => parse_acpi_name(rest.split('&').next().unwrap_or(rest), AcpiForm::Enumerator),
+            }.map(|acpi| HardwareId::Acpi(AcpiId { revision: fields.hex16("REV"), ..acpi })),
+            "ROOT" => Some(HardwareId::Root(rest.to_string())),
+            _ => return Ok(HardwareId::Other(upper)),
+        };
+        // USB 和 HID 下也有 USB\COMPOSITE 等无字段的通用ID
+        Ok(result.unwrap_or(HardwareId::Other(upper)))
+    }
+
+    /// 枚举器名称
+    pub fn enumerator(&self) -> &str {
+        match self {
+            HardwareId::Pci(_) => "PCI",
+            HardwareId::Usb(_) => "USB",
+            HardwareId::HdAudio(_) => "HDAUDIO",
+            HardwareId::Acpi(_) => "ACPI",
+            HardwareId::Hid(_) => "HID",
+            HardwareId::Root(_) => "ROOT",
+            HardwareId::Other(id) => id.split('\\').next().unwrap_or(id),
+        }
+    }
+
+    /// Windows 为设备报告的硬件ID，从最具体到最通用
+    ///
+    /// 与设备管理器“详细信息”页中的列表一致；INF 中与硬件ID匹配的驱动优先于与兼容ID匹配的驱动。
+    pub fn hardware_ids(&self) -> Vec<HardwareId> {
+        let ids = match self {
+            HardwareId::Pci(pci) => {
+                let device = PciId { subsystem: None, revision: None, class: None, ..*pci };
+                let mut ids = Vec::new();
+                if pci.subsystem.is_some() {
+                    if pci.revision.is_some() {
+                        ids.push(PciId { class: None, ..*pci });
+                    }
+                    ids.push(PciId { revision: None, class: None, ..*pci });
+                } else {
+                    // 没有子系统时，带修订号和不带修订号的ID作为硬件ID
+                    if pci.revision.is_some() {
+                        ids.push(PciId { revision: pci.revision, ..device });
+                    }
+                    ids.push(device);
+                }
+                if device.device.is_some() {
+                    if let Some(class) = pci.class {
+                        if class.prog_if.is_some() {
+                            ids.push(PciId { class: Some(class), ..device });
+                        }
+                        ids.push(PciId { class: Some(class.without_prog_if()), ..device });
+                    }
+                }
+                ids.into_iter().map(HardwareId::Pci).collect()
+            }
+            HardwareId::Usb(usb) => {
+                let mut ids = Vec::new();
+                if usb.vendor.is_some() {
+                    let device = UsbId { class: None, ..*usb };
+                    if usb.revision.is_some() {
+                        ids.push(device);
+                    }
+                    ids.push(UsbId { revision: None, ..device });
+                }
+                ids.into_iter().map(HardwareId::Usb).collect()
+            }
+            HardwareId::HdAudio(audio) => {
+                let mut ids = Vec::new();
+                if audio.vendor.is_some() {
+                    if audio.revision.is_some() {
+                        ids.push(*audio);
+                    }
+                    ids.push(HdAudioId { revision: None, ..*audio });
+                }
+                ids.into_iter().map(HardwareId::HdAudio).collect()
+            }
+            HardwareId::Acpi(acpi) => {
+                let mut ids = Vec::new();
+                if acpi.revision.is_some() {
+                    ids.push(AcpiId { form: AcpiForm::Enumerator, ..acpi.clone() });
+                }
+                for form in [AcpiForm::VendorDevice, AcpiForm::Enumerator, AcpiForm::Star] {
+                    ids.push(AcpiId { form, revision: None, ..acpi.clone() });
+                }
+                ids.into_iter().map(HardwareId::Acpi).collect()
+            }
+            HardwareId::Hid(hid) => {
+                let mut ids = Vec::new();
+                if hid.vendor.is_some() {
+                    let device = HidId { usage: None, ..*hid };
+                    if hid.revision.is_some() {
+                        ids.push(HardwareId::Hid(device));
+                    }
+                    ids.push(HardwareId::Hid(HidId { revision: None, ..device }));
+                    if let Some(usage) = hid.usage {
+                        ids.push(HardwareId::Hid(HidId { vendor: hid.vendor, usage: Some(usage), ..HidId::default() }));
+                    }
+                }
+                ids
+            }
+            HardwareId::Root(_) | HardwareId::Other(_) => vec![self.clone()],
+        };
+        if ids.is_empty() { vec![self.clone()] } else { ids }
+    }
+
+    /// Windows 为设备生成的兼容ID，从最具体到最通用
+    pub fn compatible_ids(&self) -> Vec<HardwareId> {
+        match self {
+            HardwareId::Pci(pci) => {
+                let mut ids = Vec::new();
+                if pci.vendor.is_some() && pci.device.is_some() {
+                    let device = PciId { subsystem: None, revision: None, class: None, ..*pci };
+                    // 有子系统时，不带子系统的ID降为兼容ID
+                    if pci.subsystem.is_some() {
+                        if pci.revision.is_some() {
+                            ids.push(PciId { revision: pci.revision, ..device });
+                        }
+                        ids.push(device);
+                    }
+                }
+                if let Some(class) = pci.class {
+                    let classes: Vec<ClassCode> = if class.prog_if.is_some() {
+                        vec![class, class.without_prog_if()]
+                    } else {
+                        vec![class]
+                    };
+                    if pci.vendor.is_some() {
+                        ids.extend(classes.iter().map(|class| PciId { vendor: pci.vendor, class: Some(*class), ..PciId::default() }));
+                        ids.push(PciId { vendor: pci.vendor, ..PciId::default() });
+                    }
+                    ids.extend(classes.iter().map(|class| PciId { class: Some(*class), ..PciId::default() }));
+                } else if pci.vendor.is_some() && pci.device.is_some() {
+                    ids.push(PciId { vendor: pci.vendor, ..PciId::default() });
+                }
+                ids.retain(|id| id != pci);
+                ids.into_iter().map(HardwareId::Pci).collect()
+            }
+            HardwareId::Usb(usb) => match usb.class {
+                Some(class) => {
+                    let mut classes = vec![class];
+                    if class.prog_if.is_some() {
+                        classes.push(class.without_prog_if());
+                    }
+                    if class.subclass.is_some() {
+                        classes.push(class.class_only());
+                    }
+                    classes.into_iter()
+                        .map(|class| HardwareId::Usb(UsbId { class: Some(class), ..UsbId::default() }))
+                        .filter(|id| id != self)
+                        .collect()
+                }
+                None => Vec::new(),
+            },
+            HardwareId::HdAudio(audio) => {
+                let mut ids = Vec::new();
+                if audio.subsystem.is_some() {
+                    let device = HdAudioId { subsystem: None, ..*audio };
+                    if audio.revision.is_some() {
+                        ids.push(device);
+                    }
+                    ids.push(HdAudioId { revision: None, ..device });
+                }
+                if audio.function.is_some() && audio.vendor.is_some() {
+                    if audio.device.is_some() {
+                        ids.push(HdAudioId { function: audio.function, vendor: audio.vendor, ..HdAudioId::default() });
+                    }
+                    ids.push(HdAudioId { function: audio.function, ..HdAudioId::default() });
+                }
+                ids.into_iter().map(HardwareId::HdAudio).collect()
+            }
+            HardwareId::Hid(hid) => {
+                let mut ids = Vec::new();
+                if let Some((page, usage)) = hid.usage {
+                    if let Some(system) = hid_system_id(page, usage) {
+                        ids.push(HardwareId::Other(system.to_string()));
+                    }
+                    ids.push(HardwareId::Other(format!("HID_DEVICE_UP:{:04X}_U:{:04X}", page, usage)));
+                }
+                ids.push(HardwareId::Other("HID_DEVICE".to_string()));
+                ids
+            }
+            HardwareId::Acpi(_) | HardwareId::Root(_) | HardwareId::Other(_) => Vec::new(),
+        }
+    }
+
+    /// Windows 查找驱动时依次尝试的全部ID：先硬件ID，后兼容ID
+    pub fn search_ids(&self) -> Vec<HardwareId> {
+        let mut ids = self.hardware_ids();
+        for id in self.compatible_ids() {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+}
+
+impl fmt::Display for HardwareId {
+    /// 规范写法：枚举器和字段名按 Windows 的惯例大小写，十六进制数字大写
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardwareId::Pci(pci) => {
+                let mut fields = Vec::new();
+                if let Some(vendor) = pci.vendor {
+                    fields.push(format!("VEN_{:04X}", vendor));
+                }
+                if let Some(device) = pci.device {
+                    fields.push(format!("DEV_{:04X}", device));
+                }
+                if let Some(subsystem) = pci.subsystem {
+                    fields.push(format!("SUBSYS_{:08X}", subsystem));
+                }
+                if let Some(revision) = pci.revision {
+                    fields.push(format!("REV_{:02X}", revision));
+                }
+                if let Some(class) = pci.class {
+                    fields.push(format!("CC_{}", class.pci_digits()));
+                }
+                write!(f, "PCI\\{}", fields.join("&"))
+            }
+            HardwareId::Usb(usb) => {
+                let mut fields = Vec::new();
+                if let Some(vendor) = usb.vendor {
+                    fields.push(format!("VID_{:04X}", vendor));
+                }
+                if let Some(product) = usb.product {
+                    fields.push(format!("PID_{:04X}", product));
+                }
+                if let Some(revision) = usb.revision {
+                    fields.push(format!("REV_{:04X}", revision));
+                }
+                if let Some(interface) = usb.interface {
+                    fields.push(format!("MI_{:02X}", interface));
+                }
+                if let Some(class) = usb.class {
+                    fields.push(format!("Class_{:02X}", class.class));
+                    if let Some(subclass) = class.subclass {
+                        fields.push(format!("SubClass_{:02X}", subclass));
+                    }
+                    if let Some(protocol) = class.prog_if {
+                        fields.push(format!("Prot_{:02X}", protocol));
+                    }
+                }
+                write!(f, "USB\\{}", fields.join("&"))
+            }
+            HardwareId::HdAudio(audio) => {
+                let mut fields = Vec::new();
+                if let Some(function) = audio.function {
+                    fields.push(format!("FUNC_{:02X}", function));
+                }
+                if let Some(vendor) = audio.vendor {
+                    fields.push(format!("VEN_{:04X}", vendor));
+                }
+                if let Some(device) = audio.device {
+                    fields.push(format!("DEV_{:04X}", device));
+                }
+                if let Some(subsystem) = audio.subsystem {
+                    fields.push(format!("SUBSYS_{:08X}", subsystem));
+                }
+                if let Some(revision) = audio.revision {
+                    fields.push(format!("REV_{:04X}", revision));
+                }
+                write!(f, "HDAUDIO\\{}", fields.join("&"))
+            }
+            HardwareId::Acpi(acpi) => {
+                match acpi.form {
+                    AcpiForm::VendorDevice => write!(f, "ACPI\\VEN_{}&DEV_{}", acpi.vendor, acpi.device)?,
+                    AcpiForm::Enumerator => write!(f, "ACPI\\{}{}", acpi.vendor, acpi.device)?,
+                    AcpiForm::Star => return write!(f, "*{}{}", acpi.vendor, acpi.device),
+                }
+                match acpi.revision {
+                    Some(revision) => write!(f, "&REV_{:04X}", revision),
+                    None => Ok(()),
+                }
+            }
+            HardwareId::Hid(hid) => {
+                let mut fields = Vec::new();
+                if let Some(vendor) = hid.vendor {
+                    fields.push(format!("VID_{:04X}", vendor));
+                }
+                if let Some(product) = hid.product {
+                    fields.push(format!("PID_{:04X}", product));
+                }
+                if let Some(revision) = hid.revision {
+                    fields.push(format!("REV_{:04X}", revision));
+                }
+                if let Some(interface) = hid.interface {
+                    fields.push(format!("MI_{:02X}", interface));
+                }
+                if let Some(collection) = hid.collection {
+                    fields.push(format!("Col{:02X}", collection));
+                }
+                if let Some((page, usage)) = hid.usage {
+                    fields.push(format!("UP:{:04X}_U:{:04X}", page, usage));
+                }
+                write!(f, "HID\\{}", fields.join("&"))
+            }
+            HardwareId::Root(name) => write!(f, "ROOT\\{}", name),
+            HardwareId::Other(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl FromStr for HardwareId {
+    type Err = HamsterError;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Self::parse(id)
+    }
+}
+
+impl Serialize for HardwareId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HardwareId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Self::parse(&id).map_err(serde::de::Error::custom)
+    }
+}
+
+/// 将以逗号、分号或换行分隔的ID列表解析为硬件ID，忽略无法解析的项
+pub fn parse_id_list(ids: &str) -> Vec<HardwareId> {
+    ids.split([',', ';', '\n'])
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .filter_map(|id| HardwareId::parse(id).ok())
+        .collect()
+}
+
+/// `&` 分隔的 `键_值` 字段，键已为大写
+struct IdFields<'a> {
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> IdFields<'a> {
+    fn parse(rest: &'a str) -> Self {
+        let fields = rest.split(['&', '\\'])
+            .filter_map(|field| {
+                // HID 的用法字段为 UP:xxxx_U:xxxx，集合编号为 Colxx，都不是 键_值 形式
+                if field.starts_with("UP:") {
+                    return Some(("UP", field));
+                }
+                if let Some(collection) = field.strip_prefix("COL").filter(|value| !value.starts_with('_')) {
+                    return Some(("COL", collection));
+                }
+                field.split_once('_')
+            })
+            .collect();
+        Self { fields }
+    }
+
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.fields.iter().find(|(name, _)| *name == key).map(|(_, value)| *value)
+    }
+
+    fn hex16(&self, key: &str) -> Option<u16> {
+        self.get(key).and_then(hex16)
+    }
+
+    fn hex8(&self, key: &str) -> Option<u8> {
+        self.get(key).and_then(hex8)
+    }
+
+    fn pci(&self) -> Option<PciId> {
+        let id = PciId {
+            vendor: self.hex16("VEN"),
+            device: self.hex16("DEV"),
+            subsystem: self.get("SUBSYS").filter(|value| value.len() == 8).and_then(|value| u32::from_str_radix(value, 16).ok()),
+            revision: self.hex8("REV"),
+            class: self.get("CC").and_then(parse_pci_class),
+        };
+        (id.vendor.is_some() || id.class.is_some()).then_some(id)
+    }
+
+    fn usb(&self) -> Option<UsbId> {
+        let class = self.hex8("CLASS").map(|class| ClassCode {
+            class,
+            subclass: self.hex8("SUBCLASS"),
+            prog_if: self.hex8("PROT"),
+        });
+        let id = UsbId {
+            vendor: self.hex16("VID"),
+            product: self.hex16("PID"),
+            revision: self.hex16("REV"),
+            interface: self.hex8("MI"),
+            class,
+        };
+        (id.vendor.is_some() || id.class.is_some()).then_some(id)
+    }
+
+    fn hdaudio(&self) -> Option<HdAudioId> {
+        let id = HdAudioId {
+            function: self.hex8("FUNC"),
+            vendor: self.hex16("VEN"),
+            device: self.hex16("DEV"),
+            subsystem: self.get("SUBSYS").filter(|value| value.len() == 8).and_then(|value| u32::from_str_radix(value, 16).ok()),
+            revision: self.hex16("REV"),
+        };
+        (id.function.is_some() || id.vendor.is_some()).then_some(id)
+    }
+
+    fn hid(&self) -> Option<HidId> {
+        let usage = self.get("UP").and_then(|value| {
+            let (page, usage) = value.strip_prefix("UP:")?.split_once("_U:")?;
+            Some((hex16(page)?, hex16(usage)?))
+        });
+        let id = HidId {
+            vendor: self.hex16("VID"),
+            product: self.hex16("PID"),
+            revision: self.hex16("REV"),
+            interface: self.hex8("MI"),
+            collection: self.hex8("COL"),
+            usage,
+        };
+        id.vendor.is_some().then_some(id)
+    }
+}
+
+/// PCI 类别代码，2、4或6位十六进制
+fn parse_pci_class(value: &str) -> Option<ClassCode> {
+    if !matches!(value.len(), 2 | 4 | 6) {
+        return None;
+    }
+    Some(ClassCode {
+        class: hex8(&value[..2])?,
+        subclass: match value.get(2..4) {
+            Some(subclass) => Some(hex8(subclass)?),
+            None => None,
+        },
+        prog_if: match value.get(4..6) {
+            Some(prog_if) => Some(hex8(prog_if)?),
+            None => None,
+        },
+    })
+}
+
+/// `PNP0A08`、`INT33A1` 等 ACPI 设备名：3或4位厂商前缀加4位十六进制
+fn parse_acpi_name(name: &str, form: AcpiForm) -> Option<AcpiId> {
+    if !(7..=8).contains(&name.len()) || !name.is_ascii() {
+        return None;
+    }
+    let (vendor, device) = name.split_at(name.len() - 4);
+    parse_acpi_parts(vendor, device, form)
+}
+
+fn parse_acpi_parts(vendor: &str, device: &str, form: AcpiForm) -> Option<AcpiId> {
+    let vendor_valid = matches!(vendor.len(), 3 | 4) && vendor.chars().all(|c| c.is_ascii_alphanumeric());
+    if !vendor_valid || hex16(device).is_none() {
+        return None;
+    }
+    Some(AcpiId { vendor: vendor.to_string(), device: device.to_string(), revision: None, form })
+}
+
+/// 常见顶层集合对应的系统ID，Windows 自带的 HID 类驱动按这些ID匹配
+fn hid_system_id(page: u16, usage: u16) -> Option<&'static str> {
+    match (page, usage) {
+        (0x01, 0x02) => Some("HID_DEVICE_SYSTEM_MOUSE"),
+        (0x01, 0x06) => Some("HID_DEVICE_SYSTEM_KEYBOARD"),
+        (0x01, 0x04) | (0x01, 0x05) => Some("HID_DEVICE_SYSTEM_GAME"),
+        (0x01, 0x80) => Some("HID_DEVICE_SYSTEM_CONTROL"),
+        (0x0C, 0x01) => Some("HID_DEVICE_SYSTEM_CONSUMER"),
+        _ => None,
+    }
+}
+
+// modalias 中各字段的键和十六进制位数，按出现顺序排列
+const PCI_MODALIAS: [(&str, usize); 7] = [("V", 8), ("D", 8), ("SV", 8), ("SD", 8), ("BC", 2), ("SC", 2), ("I", 2)];
+const USB_MODALIAS: [(&str, usize); 10] = [
+    ("V", 4), ("P", 4), ("D", 4), ("DC", 2), ("DSC", 2), ("DP", 2), ("IC", 2), ("ISC", 2), ("IP", 2), ("IN", 2),
+];
+
+/// 按固定顺序读取 modalias 字段；遇到通配符或不符合格式的字段时停止
+fn parse_modalias(mut rest: &str, layout: &[(&'static str, usize)]) -> Vec<(&'static str, u32)> {
+    let mut fields = Vec::new();
+    for (key, len) in layout {
+        let Some(value) = rest.strip_prefix(key).and_then(|value| value.get(..*len)) else {
+            break;
+        };
+        let Ok(number) = u32::from_str_radix(value, 16) else {
+            break;
+        };
+        fields.push((*key, number));
+        rest = &rest[key.len() + len..];
+    }
+    fields
+}
+
+/// `pci:v000010DEd00002504sv00001043sd0000881Dbc03sc00i00`
+fn parse_pci_modalias(rest: &str) -> PciId {
+    let fields = parse_modalias(rest, &PCI_MODALIAS);
+    let field = |key: &str| fields.iter().find(|(name, _)| *name == key).map(|(_, value)| *value);
+    PciId {
+        vendor: field("V").map(|v| v as u16),
+        device: field("D").map(|v| v as u16),
+        subsystem: field("SV").zip(field("SD")).map(|(vendor, device)| (device << 16) | (vendor & 0xFFFF)),
+        revision: None,
+        class: field("BC").map(|class| ClassCode {
+            class: class as u8,
+            subclass: field("SC").map(|v| v as u8),
+            prog_if: field("I").map(|v| v as u8),
+        }),
+    }
+}
+
+/// `usb:v046DpC52Bd1201dc00dsc00dp00ic03isc01ip02in00`
+fn parse_usb_modalias(rest: &str) -> UsbId {
+    let fields = parse_modalias(rest, &USB_MODALIAS);
+    let field = |key: &str| fields.iter().find(|(name, _)| *name == key).map(|(_, value)| *value);
+    UsbId {
+        vendor: field("V").map(|v| v as u16),
+        product: field("P").map(|v| v as u16),
+        revision: field("D").map(|v| v as u16),
+        interface: None,
+        class: field("IC").map(|class| ClassCode {
+            class: class as u8,
+            subclass: field("ISC").map(|v| v as u8),
+            prog_if: field("IP").map(|v| v as u8),
+        }),
+    }
+}
+
+fn hex16(value: &str) -> Option<u16> {
+    if value.len() != 4 {
+        return None;
+    }
+    u16::from_str_radix(value, 16).ok()
+}
+
+fn hex8(value: &str) -> Option<u8> {
+    if value.len() != 2 {
+        return None;
+    }
+    u8::from_str_radix(value, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(id: &str) -> HardwareId {
+        HardwareId::parse(id).unwrap()
+    }
+
+    fn strings(ids: Vec<HardwareId>) -> Vec<String> {
+        ids.iter().map(HardwareId::to_string).collect()
+    }
+
+    #[test]
+    fn pci_fields_are_parsed() {
+        let HardwareId::Pci(pci) = parse("PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043&REV_A1&CC_030000") else { panic!() };
+        assert_eq!(pci.vendor, Some(0x10DE));
+        assert_eq!(pci.device, Some(0x2504));
+        assert_eq!(pci.subsystem_vendor(), Some(0x1043));
+        assert_eq!(pci.subsystem_device(), Some(0x8810));
+        assert_eq!(pci.revision, Some(0xA1));
+        assert_eq!(pci.class, Some(ClassCode { class: 0x03, subclass: Some(0x00), prog_if: Some(0x00) }));
+    }
+
+    #[test]
+    fn case_and_alternative_forms_are_normalised() {
+        assert_eq!(parse(" pci\\ven_10de&dev_2504&subsys_88101043&rev_a1 ").to_string(), "PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043&REV_A1");
+        assert_eq!(parse("usb\\vid_046d&pid_c52b&mi_02").to_string(), "USB\\VID_046D&PID_C52B&MI_02");
+        assert_eq!(parse("usb\\class_e0&subclass_01&prot_01").to_string(), "USB\\Class_E0&SubClass_01&Prot_01");
+        assert_eq!(parse("10de:2504"), parse("PCI\\VEN_10DE&DEV_2504"));
+        assert_eq!(parse("hid_device_system_mouse"), HardwareId::Other("HID_DEVICE_SYSTEM_MOUSE".to_string()));
+        assert_eq!(parse("root\\legacy_beep"), HardwareId::Root("LEGACY_BEEP".to_string()));
+        assert!(HardwareId::parse("  ").is_err());
+        assert!(HardwareId::parse("10dg:2504").is_err());
+    }
+
+    #[test]
+    fn linux_modaliases_become_windows_ids() {
+        let pci = parse("pci:v000010DEd00002504sv00001043sd00008810bc03sc00i00");
+        assert_eq!(pci.to_string(), "PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043&CC_030000");
+        let usb = parse("usb:v046DpC52Bd1211dc00dsc00dp00ic03isc01ip01in00");
+        let HardwareId::Usb(usb) = usb else { panic!() };
+        assert_eq!((usb.vendor, usb.product, usb.revision), (Some(0x046D), Some(0xC52B), Some(0x1211)));
+    }
+
+    #[test]
+    fn usb_and_hid_fields_are_parsed() {
+        let HardwareId::Usb(usb) = parse("USB\\VID_8087&PID_0026&REV_0002&MI_01") else { panic!() };
+        assert_eq!((usb.vendor, usb.product, usb.revision, usb.interface), (Some(0x8087), Some(0x0026), Some(0x0002), Some(0x01)));
+        let HardwareId::Hid(hid) = parse("HID\\VID_046D&PID_C52B&REV_1211&MI_02&Col01") else { panic!() };
+        assert_eq!((hid.vendor, hid.product, hid.revision, hid.interface, hid.collection), (Some(0x046D), Some(0xC52B), Some(0x1211), Some(0x02), Some(0x01)));
+        let HardwareId::Hid(hid) = parse("HID\\VID_046D&UP:0001_U:0002") else { panic!() };
+        assert_eq!(hid.usage, Some((0x0001, 0x0002)));
+        // 没有字段的通用ID
+        assert_eq!(parse("USB\\COMPOSITE"), HardwareId::Other("USB\\COMPOSITE".to_string()));
+    }
+
+    #[test]
+    fn hdaudio_and_acpi_fields_are_parsed() {
+        let HardwareId::HdAudio(audio) = parse("HDAUDIO\\FUNC_01&VEN_10EC&DEV_0897&SUBSYS_10438882&REV_1003") else { panic!() };
+        assert_eq!(audio.function, Some(0x01));
+        assert_eq!((audio.vendor, audio.device, audio.subsystem, audio.revision), (Some(0x10EC), Some(0x0897), Some(0x10438882), Some(0x1003)));
+
+        // 三种写法描述同一设备，厂商前缀可以是3位或4位
+        for id in ["ACPI\\VEN_PNP&DEV_0A08", "acpi\\pnp0a08", "*PNP0A08"] {
+            let HardwareId::Acpi(acpi) = parse(id) else { panic!("{}", id) };
+            assert_eq!((acpi.vendor.as_str(), acpi.device.as_str()), ("PNP", "0A08"), "{}", id);
+        }
+        let HardwareId::Acpi(acpi) = parse("ACPI\\INT33A1&REV_0002") else { panic!() };
+        assert_eq!((acpi.vendor.as_str(), acpi.device.as_str(), acpi.revision), ("INT", "33A1", Some(0x0002)));
+        let HardwareId::Acpi(acpi) = parse("ACPI\\MSFT0101") else { panic!() };
+        assert_eq!((acpi.vendor.as_str(), acpi.device.as_str()), ("MSFT", "0101"));
+    }
+
+    #[test]
+    fn pci_ids_follow_windows_order() {
+        let id = parse("PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043&REV_A1&CC_030000");
+        assert_eq!(strings(id.hardware_ids()), [
+            "PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043&REV_A1",
+            "PCI\\VEN_10DE&DEV_2504&SUBSYS_88101043",
+            "PCI\\VEN_10DE&DEV_2504&CC_030000",
+            "PCI\\VEN_10DE&DEV_2504&CC_0300",
+        ]);
+        assert_eq!(strings(id.compatible_ids()), [
+            "PCI\\VEN_10DE&DEV_2504&REV_A1",
+            "PCI\\VEN_10DE&DEV_2504",
+            "PCI\\VEN_10DE&CC_030000",
+            "PCI\\VEN_10DE&CC_0300",
+            "PCI\\VEN_10DE",
+            "PCI\\CC_030000",
+            "PCI\\CC_0300",
+        ]);
+    }
+
+    #[test]
+    fn pci_ids_without_subsystem_keep_revision_as_hardware_id() {
+        let id = parse("PCI\\VEN_8086&DEV_15F3&REV_03&CC_0200");
+        assert_eq!(strings(id.hardware_ids()), [
+            "PCI\\VEN_8086&DEV_15F3&REV_03",
+            "PCI\\VEN_8086&DEV_15F3",
+            "PCI\\VEN_8086&DEV_15F3&CC_0200",
+        ]);
+        assert_eq!(strings(id.compatible_ids()), ["PCI\\VEN_8086&CC_0200", "PCI\\VEN_8086", "PCI\\CC_0200"]);
+    }
+
+    #[test]
+    fn usb_ids_follow_windows_order() {
+        let device = parse("USB\\VID_8087&PID_0026&REV_0002&MI_01");
+        assert_eq!(strings(device.hardware_ids()), ["USB\\VID_8087&PID_0026&REV_0002&MI_01", "USB\\VID_8087&PID_0026&MI_01"]);
+        let class = parse("USB\\Class_E0&SubClass_01&Prot_01");
+        assert_eq!(strings(class.compatible_ids()), ["USB\\Class_E0&SubClass_01", "USB\\Class_E0"]);
+    }
+
+    #[test]
+    fn hdaudio_ids_follow_windows_order() {
+        let id = parse("HDAUDIO\\FUNC_01&VEN_10EC&DEV_0897&SUBSYS_10438882&REV_1003");
+        assert_eq!(strings(id.hardware_ids()), [
+            "HDAUDIO\\FUNC_01&VEN_10EC&DEV_0897&SUBSYS_10438882&REV_1003",
+            "HDAUDIO\\FUNC_01&VEN_10EC&DEV_0897&SUBSYS_10438882",
+        ]);
+        assert_eq!(strings(id.compatible_ids()), [
+            "HDAUDIO\\FUNC_01&VEN_10EC&DEV_0897&REV_1003",
+            "HDAUDIO\\FUNC_01&VEN_10EC&DEV_0897",
+            "HDAUDIO\\FUNC_01&VEN_10EC",
+            "HDAUDIO\\FUNC_01",
+        ]);
+    }
+
+    #[test]
+    fn acpi_ids_list_every_form() {
+        assert_eq!(strings(parse("ACPI\\INT33A1&REV_0002").hardware_ids()), [
+            "ACPI\\INT33A1&REV_0002",
+            "ACPI\\VEN_INT&DEV_33A1",
+            "ACPI\\INT33A1",
+            "*INT33A1",
+        ]);
+        assert!(parse("*PNP0A08").compatible_ids().is_empty());
+    }
+
+    #[test]
+    fn hid_ids_end_with_usage_and_generic_ids() {
+        let id = parse("HID\\VID_046D&PID_C52B&REV_1211&MI_02&Col01&UP:0001_U:0002");
+        assert_eq!(strings(id.hardware_ids()), [
+            "HID\\VID_046D&PID_C52B&REV_1211&MI_02&Col01",
+            "HID\\VID_046D&PID_C52B&MI_02&Col01",
+            "HID\\VID_046D&UP:0001_U:0002",
+        ]);
+        assert_eq!(strings(id.compatible_ids()), ["HID_DEVICE_SYSTEM_MOUSE", "HID_DEVICE_UP:0001_U:0002", "HID_DEVICE"]);
+        // 搜索顺序先硬件ID后兼容ID
+        assert_eq!(id.search_ids().len(), 6);
+        assert_eq!(id.search_ids()[3].to_string(), "HID_DEVICE_SYSTEM_MOUSE");
+    }
+
+    #[test]
+    fn id_lists_skip_unparsable_entries() {
+        let ids = parse_id_list("PCI\\VEN_10DE&DEV_2504; 10dg:2504,\nUSB\\VID_046D&PID_C52B");
+        assert_eq!(strings(ids), ["PCI\\VEN_10DE&DEV_2504", "USB\\VID_046D&PID_C52B"]);
+    }
+}
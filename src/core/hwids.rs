@@ -0,0 +1,457 @@
+// 硬件ID数据库：解析 pci.ids / usb.ids 格式的厂商、设备、子系统和设备类别名称并建立索引，
+// 按硬件ID查询设备名称。优先使用用户指定的文件，其次是系统自带的数据库，最后是程序内置的精简版本
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::hardware_id::HardwareId;
+use crate::core::settings::Settings;
+
+const BUNDLED_PCI_IDS: &str = include_str!("../../data/hwids/pci.ids");
+const BUNDLED_USB_IDS: &str = include_str!("../../data/hwids/usb.ids");
+
+// 常见发行版安装 pci.ids / usb.ids 的位置
+const SYSTEM_DIRS: [&str; 3] = ["/usr/share/hwdata", "/usr/share/misc", "/usr/share"];
+
+/// 数据库格式，两者语法相同，仅 usb.ids 在设备下列出的是接口而不是子系统
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IdsFormat {
+    Pci,
+    Usb,
+}
+
+impl IdsFormat {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            IdsFormat::Pci => "pci.ids",
+            IdsFormat::Usb => "usb.ids",
+        }
+    }
+
+    fn bundled(&self) -> &'static str {
+        match self {
+            IdsFormat::Pci => BUNDLED_PCI_IDS,
+            IdsFormat::Usb => BUNDLED_USB_IDS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Vendor {
+    pub name: String,
+    pub devices: HashMap<u16, Device>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Device {
+    pub name: String,
+    /// 以（子系统厂商ID, 子系统ID）为键
+    pub subsystems: HashMap<(u16, u16), String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeviceClass {
+    pub name: String,
+    pub subclasses: HashMap<u8, DeviceSubclass>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeviceSubclass {
+    pub name: String,
+    /// PCI 的编程接口或 USB 的协议
+    pub prog_ifs: HashMap<u8, String>,
+}
+
+/// 数据库来源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum IdsSource {
+    Bundled,
+    File(PathBuf),
+}
+
+impl std::fmt::Display for IdsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdsSource::Bundled => write!(f, "内置数据库"),
+            IdsSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// 一个 pci.ids 或 usb.ids 文件的内容
+#[derive(Debug, Clone)]
+pub struct IdDatabase {
+    pub format: IdsFormat,
+    pub source: IdsSource,
+    /// 文件头注释中的版本，如 "2024.06.01"
+    pub version: Option<String>,
+    vendors: HashMap<u16, Vendor>,
+    classes: HashMap<u8, DeviceClass>,
+}
+
+// 解析时当前所在的条目，决定缩进行归属
+enum Section {
+    None,
+    Vendor(u16),
+    Device(u16, u16),
+    Class(u8),
+    Subclass(u8, u8),
+    // usb.ids 中 HID、语言代码等本程序不使用的列表
+    Ignored,
+}
+
+impl IdDatabase {
+    /// 解析数据库内容；遇到格式错误的行时返回带行号的错误
+    pub fn parse(format: IdsFormat, source: IdsSource, content: &str) -> HamsterResult<Self> {
+        let mut database = Self {
+            format,
+            source,
+            version: None,
+            vendors: HashMap::new(),
+            classes: HashMap::new(),
+        };
+        let mut section = Section::None;
+
+        for (index, raw_line) in content.lines().enumerate() {
+            let line = raw_line.trim_end();
+            if let Some(comment) = line.strip_prefix('#') {
+                if database.version.is_none() {
+                    database.version = comment.trim().strip_prefix("Version:").map(|v| v.trim().to_string());
+                }
+                continue;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let depth = line.chars().take_while(|c| *c == '\t').count();
+            let entry = &line[depth..];
+            let invalid = || HamsterError::parse(format!(
+                "{} 第 {} 行格式错误: {}", database.source, index + 1, raw_line.trim()
+            ));
+
+            section = match (depth, &section) {
+                (0, _) => {
+                    if let Some(class) = entry.strip_prefix("C ") {
+                        let (id, name) = split_entry(class).ok_or_else(invalid)?;
+                        let id = parse_hex(id, 2).ok_or_else(invalid)? as u8;
+                        database.classes.insert(id, DeviceClass { name, ..DeviceClass::default() });
+                        Section::Class(id)
+                    } else {
+                        match split_entry(entry).and_then(|(id, name)| Some((parse_hex(id, 4)?, name))) {
+                            Some((id, name)) => {
+                                database.vendors.insert(id, Vendor { name, ..Vendor::default() });
+                                Section::Vendor(id)
+                            }
+                            None => Section::Ignored,
+                        }
+                    }
+                }
+                (_, Section::Ignored) => Section::Ignored,
+                (1, Section::Vendor(vendor) | Section::Device(vendor, _)) => {
+                    let vendor = *vendor;
+                    let (id, name) = split_entry(entry).ok_or_else(invalid)?;
+                    let id = parse_hex(id, 4).ok_or_else(invalid)?;
+                    if let Some(entry) = database.vendors.get_mut(&vendor) {
+                        entry.devices.insert(id, Device { name, ..Device::default() });
+                    }
+                    Section::Device(vendor, id)
+                }
+                (1, Section::Class(class) | Section::Subclass(class, _)) => {
+                    let class = *class;
+                    let (id, name) = split_entry(entry).ok_or_else(invalid)?;
+                    let id = parse_hex(id, 2).ok_or_else(invalid)? as u8;
+                    if let Some(entry) = database.classes.get_mut(&class) {
+                        entry.subclasses.insert(id, DeviceSubclass { name, ..DeviceSubclass::default() });
+                    }
+                    Section::Subclass(class, id)
+                }
+                (2, Section::Device(vendor, device)) => {
+                    // pci.ids 为“子系统厂商 子系统 名称”，usb.ids 为“接口号 名称”，后者不使用
+                    if format == IdsFormat::Pci {
+                        let (ids, name) = split_entry(entry)
+                            .and_then(|(subvendor, rest)| {
+                                let (subdevice, name) = split_entry(&rest)?;
+                                Some(((parse_hex(subvendor, 4)?, parse_hex(subdevice, 4)?), name))
+                            })
+                            .ok_or_else(invalid)?;
+                        if let Some(entry) = database.vendors.get_mut(vendor).and_then(|v| v.devices.get_mut(device)) {
+                            entry.subsystems.insert(ids, name);
+                        }
+                    }
+                    Section::Device(*vendor, *device)
+                }
+                (2, Section::Subclass(class, subclass)) => {
+                    let (id, name) = split_entry(entry).ok_or_else(invalid)?;
+                    let id = parse_hex(id, 2).ok_or_else(invalid)? as u8;
+                    if let Some(entry) = database.classes.get_mut(class).and_then(|c| c.subclasses.get_mut(subclass)) {
+                        entry.prog_ifs.insert(id, name);
+                    }
+                    Section::Subclass(*class, *subclass)
+                }
+                _ => return Err(invalid()),
+            };
+        }
+        Ok(database)
+    }
+
+    /// 程序内置的精简数据库，只包含常见厂商和设备
+    pub fn bundled(format: IdsFormat) -> Self {
+        Self::parse(format, IdsSource::Bundled, format.bundled())
+            .expect("内置硬件ID数据库格式错误")
+    }
+
+    pub fn from_file(format: IdsFormat, path: &Path) -> HamsterResult<Self> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| HamsterError::io(format!("无法读取硬件ID数据库 {:?}", path), e))?;
+        // 上游文件以 UTF-8 发布，早期版本中个别名称为 Latin-1
+        let content = String::from_utf8_lossy(&bytes);
+        Self::parse(format, IdsSource::File(path.to_path_buf()), &content)
+    }
+
+    pub fn vendor_count(&self) -> usize {
+        self.vendors.len()
+    }
+
+    pub fn device_count(&self) -> usize {
+        self.vendors.values().map(|vendor| vendor.devices.len()).sum()
+    }
+
+    pub fn vendor(&self, vendor: u16) -> Option<&Vendor> {
+        self.vendors.get(&vendor)
+    }
+
+    pub fn vendor_name(&self, vendor: u16) -> Option<&str> {
+        self.vendors.get(&vendor).map(|v| v.name.as_str())
+    }
+
+    /// 按名称查找厂商ID，如 DMI 中的 "ASUSTeK COMPUTER INC." → 0x1043；忽略大小写、标点和
+    /// Inc.、Co., Ltd. 等公司后缀，也接受方括号中的简称（如 MSI）；有多个匹配时取ID最小的
+    pub fn find_vendor(&self, name: &str) -> Option<u16> {
+        let (words, _) = vendor_words(name);
+        if words.is_empty() {
+            return None;
+        }
+        self.vendors.iter()
+            .filter(|(_, vendor)| {
+                let (vendor_words, abbreviations) = vendor_words(&vendor.name);
+                vendor_words == words || abbreviations.contains(&words.join(" "))
+            })
+            .map(|(id, _)| *id)
+            .min()
+    }
+
+    pub fn device_name(&self, vendor: u16, device: u16) -> Option<&str> {
+        self.vendors.get(&vendor)?.devices.get(&device).map(|d| d.name.as_str())
+    }
+
+    pub fn subsystem_name(&self, vendor: u16, device: u16, subvendor: u16, subdevice: u16) -> Option<&str> {
+        self.vendors.get(&vendor)?
+            .devices.get(&device)?
+            .subsystems.get(&(subvendor, subdevice))
+            .map(String::as_str)
+    }
+
+    /// 设备类别名称，给出子类和编程接口时返回能找到的最具体的名称
+    pub fn class_name(&self, class: u8, subclass: Option<u8>, prog_if: Option<u8>) -> Option<&str> {
+        let class = self.classes.get(&class)?;
+        let Some(subclass) = subclass.and_then(|id| class.subclasses.get(&id)) else {
+            return Some(&class.name);
+        };
+        Some(prog_if.and_then(|id| subclass.prog_ifs.get(&id)).unwrap_or(&subclass.name))
+    }
+}
+
+/// 由硬件ID查到的名称，找不到的部分为 None
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeviceName {
+    pub vendor: Option<String>,
+    pub device: Option<String>,
+    pub subsystem: Option<String>,
+    pub class: Option<String>,
+}
+
+impl DeviceName {
+    pub fn is_empty(&self) -> bool {
+        self.vendor.is_none() && self.device.is_none() && self.class.is_none()
+    }
+
+    /// 界面显示的名称：厂商 设备，找不到设备时使用类别
+    pub fn display(&self) -> String {
+        let model = self.device.as_ref().or(self.class.as_ref());
+        match (&self.vendor, model) {
+            (Some(vendor), Some(model)) => format!("{} {}", vendor, model),
+            (Some(vendor), None) => vendor.clone(),
+            (None, Some(model)) => model.clone(),
+            (None, None) => "未知设备".to_string(),
+        }
+    }
+}
+
+/// PCI 和 USB 两个数据库
+#[derive(Debug, Clone)]
+pub struct HardwareIds {
+    pub pci: IdDatabase,
+    pub usb: IdDatabase,
+    /// 加载时无法使用的数据库文件，已改用其他来源
+    pub warnings: Vec<String>,
+}
+
+impl Default for HardwareIds {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+impl HardwareIds {
+    pub fn bundled() -> Self {
+        Self {
+            pci: IdDatabase::bundled(IdsFormat::Pci),
+            usb: IdDatabase::bundled(IdsFormat::Usb),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// 按设置中指定的文件、system_root 下的系统数据库、内置数据库的顺序加载；
+    /// 文件无法读取或格式错误时改用下一个来源，原因记录在 warnings 中
+    pub fn load(settings: &Settings, system_root: &Path) -> Self {
+        let mut warnings = Vec::new();
+        let pci = load_database(IdsFormat::Pci, settings.pci_ids_path.as_deref(), system_root, &mut warnings);
+        let usb = load_database(IdsFormat::Usb, settings.usb_ids_path.as_deref(), system_root, &mut warnings);
+        Self { pci, usb, warnings }
+    }
+
+    pub fn database(&self, format: IdsFormat) -> &IdDatabase {
+        match format {
+            IdsFormat::Pci => &self.pci,
+            IdsFormat::Usb => &self.usb,
+        }
+    }
+
+    /// 查询硬件ID对应的名称；HDAUDIO 编解码器的厂商ID与 PCI 厂商ID相同，使用 pci.ids 查询厂商名称
+    pub fn describe(&self, id: &HardwareId) -> Option<DeviceName> {
+        let (format, vendor, device, subsystem, class) = match id {
+            HardwareId::Pci(pci) => (
+                IdsFormat::Pci,
+                pci.vendor,
+                pci.device,
+                pci.subsystem_vendor().zip(pci.subsystem_device()),
+                pci.class,
+            ),
+            HardwareId::Usb(usb) => (IdsFormat::Usb, usb.vendor, usb.product, None, usb.class),
+            HardwareId::Hid(hid) => (IdsFormat::Usb, hid.vendor, hid.product, None, None),
+            HardwareId::HdAudio(audio) => (IdsFormat::Pci, audio.vendor, None, None, None),
+            HardwareId::Acpi(_) | HardwareId::Root(_) | HardwareId::Other(_) => return None,
+        };
+        if vendor.is_none() && class.is_none() {
+            return None;
+        }
+        let database = self.database(format);
+        let name = DeviceName {
+            vendor: vendor.and_then(|v| database.vendor_name(v)).map(str::to_string),
+            device: vendor.zip(device).and_then(|(v, d)| database.device_name(v, d)).map(str::to_string),
+            subsystem: vendor.zip(device).zip(subsystem)
+                .and_then(|((v, d), (sv, sd))| database.subsystem_name(v, d, sv, sd))
+                .map(str::to_string),
+            class: class
+                .and_then(|class| database.class_name(class.class, class.subclass, class.prog_if))
+                .map(str::to_string),
+        };
+        Some(name)
+    }
+}
+
+fn load_database(format: IdsFormat, user_path: Option<&Path>, system_root: &Path, warnings: &mut Vec<String>) -> IdDatabase {
+    if let Some(path) = user_path {
+        match IdDatabase::from_file(format, path) {
+            Ok(database) => return database,
+            Err(e) => warnings.push(format!("无法使用设置中的 {}，已改用其他来源: {}", format.file_name(), e)),
+        }
+    }
+    let system_file = SYSTEM_DIRS.iter()
+        .map(|dir| system_root.join(dir.trim_start_matches('/')).join(format.file_name()))
+        .find(|path| path.is_file());
+    if let Some(path) = system_file {
+        match IdDatabase::from_file(format, &path) {
+            Ok(database) => return database,
+            Err(e) => warnings.push(format!("无法使用系统的 {}，已改用内置数据库: {}", format.file_name(), e)),
+        }
+    }
+    IdDatabase::bundled(format)
+}
+
+// 比较厂商名称时忽略的公司后缀
+const COMPANY_SUFFIXES: [&str; 10] = ["inc", "co", "ltd", "corp", "corporation", "company", "incorporated", "llc", "gmbh", "limited"];
+
+/// 厂商名称中的单词（小写，去掉公司后缀）和方括号中的简称，如
+/// "Micro-Star International Co., Ltd. [MSI]" → (["micro", "star", "international"], ["msi"])
+fn vendor_words(name: &str) -> (Vec<String>, Vec<String>) {
+    let words = |text: &str| -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .filter(|word| !COMPANY_SUFFIXES.contains(&word.as_str()))
+            .collect()
+    };
+    let mut main = String::new();
+    let mut abbreviations = Vec::new();
+    let mut rest = name;
+    while let Some((before, after)) = rest.split_once('[') {
+        main.push_str(before);
+        let (inside, after) = after.split_once(']').unwrap_or((after, ""));
+        abbreviations.extend(inside.split('/').map(|abbreviation| words(abbreviation).join(" ")));
+        rest = after;
+    }
+    main.push_str(rest);
+    (words(&main), abbreviations)
+}
+
+/// 条目行的ID和名称，两者之间至少一个空格
+fn split_entry(entry: &str) -> Option<(&str, String)> {
+    let (id, name) = entry.split_once([' ', '\t'])?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((id, name.to_string()))
+}
+
+/// 固定长度的十六进制数
+fn parse_hex(value: &str, len: usize) -> Option<u16> {
+    if value.len() != len {
+        return None;
+    }
+    u16::from_str_radix(value, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_vendor_by_company_name() {
+        let pci = IdDatabase::bundled(IdsFormat::Pci);
+        assert_eq!(pci.find_vendor("ASUSTeK COMPUTER INC."), Some(0x1043));
+        assert_eq!(pci.find_vendor("Dell Inc."), Some(0x1028));
+        assert_eq!(pci.find_vendor("LENOVO"), Some(0x17AA));
+        assert_eq!(pci.find_vendor("Micro-Star International Co., Ltd."), Some(0x1462));
+        assert_eq!(pci.find_vendor("MSI"), Some(0x1462));
+        assert_eq!(pci.find_vendor("Advanced Micro Devices, Inc."), Some(0x1002));
+        // 名称中只是包含厂商名的不算匹配
+        assert_eq!(pci.find_vendor("PRIME B460M-A"), None);
+        assert_eq!(pci.find_vendor("Chip Company"), None);
+    }
+
+    #[test]
+    fn unusable_user_file_falls_back_with_warning() {
+        let settings = Settings {
+            pci_ids_path: Some(PathBuf::from("/nonexistent/pci.ids")),
+            ..Settings::default()
+        };
+        let ids = HardwareIds::load(&settings, Path::new("/nonexistent"));
+        assert_eq!(ids.pci.source, IdsSource::Bundled);
+        assert_eq!(ids.usb.source, IdsSource::Bundled);
+        assert_eq!(ids.warnings.len(), 1);
+        assert!(ids.warnings[0].contains("pci.ids"));
+    }
+}
@@ -0,0 +1,538 @@
+// INF 驱动安装文件：在语法层之上读取 [Version]、[Manufacturer]、型号节和 [SourceDisksFiles]，
+// 得到驱动包的版本、提供商、设备类别、支持的系统以及各型号对应的硬件ID和安装节；
+// 其中的 `%键%` 按界面语言替换为 [Strings] 节中的文本
+
+pub mod strings;
+pub mod syntax;
+pub mod version;
+
+use std::fmt;
+use std::path::Path;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::locale::Locale;
+pub use strings::InfStrings;
+pub use syntax::{InfDocument, InfLine, InfSection};
+pub use version::DriverVersion;
+
+/// `DriverVer = 日期[,版本]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriverVer {
+    pub date: NaiveDate,
+    pub version: Option<String>,
+}
+
+impl DriverVer {
+    /// 日期为 `月/日/年`，也接受 `-` 分隔；版本为最多四段的点分数字
+    pub fn parse(values: &[String]) -> HamsterResult<Self> {
+        let text = values.first().map(|value| value.trim()).unwrap_or_default();
+        let invalid = || HamsterError::parse(format!("DriverVer 日期格式错误: {}", text));
+        let parts: Vec<&str> = text.split(['/', '-']).collect();
+        let [month, day, year] = parts.as_slice() else {
+            return Err(invalid());
+        };
+        let date = NaiveDate::from_ymd_opt(
+            year.trim().parse().map_err(|_| invalid())?,
+            month.trim().parse().map_err(|_| invalid())?,
+            day.trim().parse().map_err(|_| invalid())?,
+        ).ok_or_else(invalid)?;
+        let version = values.get(1).map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+        Ok(Self { date, version })
+    }
+}
+
+impl fmt::Display for DriverVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date.format("%m/%d/%Y"))?;
+        if let Some(version) = &self.version {
+            write!(f, ",{}", version)?;
+        }
+        Ok(())
+    }
+}
+
+/// [Version] 节
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InfVersion {
+    /// `$Windows NT$` 或 `$Chicago$`
+    pub signature: Option<String>,
+    pub class: Option<String>,
+    pub class_guid: Option<String>,
+    pub provider: Option<String>,
+    pub driver_ver: Option<DriverVer>,
+    /// 未修饰的 CatalogFile，没有时取第一个带平台修饰的（如 CatalogFile.NTamd64）
+    pub catalog_file: Option<String>,
+}
+
+/// [Manufacturer] 中的目标系统修饰：`NT[架构][.主版本[.次版本[.产品类型[.套件掩码[.内部版本号]]]]]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TargetOs {
+    /// 小写的架构名：x86、amd64、ia64、arm、arm64
+    pub architecture: Option<String>,
+    pub major: Option<u32>,
+    pub minor: Option<u32>,
+    /// 1 为工作站，2 为域控制器，3 为服务器
+    pub product_type: Option<u32>,
+    pub suite_mask: Option<u32>,
+    pub build: Option<u32>,
+}
+
+const ARCHITECTURES: [&str; 5] = ["amd64", "arm64", "ia64", "x86", "arm"];
+
+impl TargetOs {
+    pub fn parse(decoration: &str) -> HamsterResult<Self> {
+        let invalid = || HamsterError::parse(format!("无法识别的目标系统修饰: {}", decoration));
+        let lower = decoration.trim().to_ascii_lowercase();
+        let rest = lower.strip_prefix("nt").ok_or_else(invalid)?;
+        let (architecture, rest) = match rest.split_once('.') {
+            Some((architecture, rest)) => (architecture, Some(rest)),
+            None => (rest, None),
+        };
+        let architecture = match architecture {
+            "" => None,
+            name => Some(ARCHITECTURES.iter().find(|arch| **arch == name).ok_or_else(invalid)?.to_string()),
+        };
+        let mut fields = rest.map(|rest| rest.split('.').collect::<Vec<_>>()).unwrap_or_default().into_iter();
+        let mut next = || -> HamsterResult<Option<u32>> {
+            match fields.next().map(str::trim) {
+                None | Some("") => Ok(None),
+                Some(value) => parse_number(value).map(Some).ok_or_else(invalid),
+            }
+        };
+        Ok(Self {
+            architecture,
+            major: next()?,
+            minor: next()?,
+            product_type: next()?,
+            suite_mask: next()?,
+            build: next()?,
+        })
+    }
+
+    /// 便于阅读的说明，如 “Windows x64 10.0 内部版本 22000 及以上”
+    pub fn describe(&self) -> String {
+        let mut text = "Windows".to_string();
+        if let Some(architecture) = &self.architecture {
+            text.push(' ');
+            text.push_str(match architecture.as_str() {
+                "amd64" => "x64",
+                "arm64" => "ARM64",
+                "ia64" => "Itanium",
+                "arm" => "ARM",
+                other => other,
+            });
+        }
+        if let Some(major) = self.major {
+            text.push_str(&format!(" {}.{}", major, self.minor.unwrap_or(0)));
+        }
+        match self.product_type {
+            Some(1) => text.push_str(" 工作站"),
+            Some(2) => text.push_str(" 域控制器"),
+            Some(3) => text.push_str(" 服务器"),
+            _ => {}
+        }
+        if let Some(build) = self.build {
+            text.push_str(&format!(" 内部版本 {} 及以上", build));
+        }
+        text
+    }
+}
+
+impl fmt::Display for TargetOs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NT{}", self.architecture.as_deref().unwrap_or(""))?;
+        let fields = [self.major, self.minor, self.product_type, self.suite_mask, self.build];
+        let Some(last) = fields.iter().rposition(Option::is_some) else {
+            return Ok(());
+        };
+        for (index, field) in fields[..=last].iter().enumerate() {
+            match (index, field) {
+                (3, Some(mask)) => write!(f, ".0x{:X}", mask)?,
+                (_, Some(value)) => write!(f, ".{}", value)?,
+                (_, None) => write!(f, ".")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [Manufacturer] 中的一行：`厂商名 = 型号节[, 目标系统修饰...]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manufacturer {
+    pub name: String,
+    pub models_section: String,
+    pub targets: Vec<TargetOs>,
+}
+
+/// 型号节中的一行：`设备描述 = 安装节, 硬件ID[, 兼容ID...]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfModel {
+    pub manufacturer: String,
+    pub description: String,
+    pub install_section: String,
+    pub hardware_id: String,
+    pub compatible_ids: Vec<String>,
+    /// 所在型号节的目标系统修饰，未修饰的节为 None
+    pub target: Option<TargetOs>,
+    pub section: String,
+    pub line: usize,
+    /// 安装节（DDInstall）中声明的 FeatureScore，未声明时为 None
+    #[serde(default)]
+    pub feature_score: Option<u8>,
+}
+
+/// [SourceDisksFiles] 中的一行：`文件名 = 磁盘号[, 子目录][, 大小]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceFile {
+    pub name: String,
+    pub disk_id: String,
+    pub subdir: Option<String>,
+    pub size: Option<u64>,
+    /// 节名的平台修饰，如 `SourceDisksFiles.amd64` 中的 amd64
+    pub architecture: Option<String>,
+}
+
+/// 解析后的 INF 文件，文本字段中的 `%键%` 已替换
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InfFile {
+    pub version: InfVersion,
+    pub manufacturers: Vec<Manufacturer>,
+    pub models: Vec<InfModel>,
+    pub source_files: Vec<SourceFile>,
+    /// 替换时使用的字符串节，优先的在前，如 ["Strings.0804", "Strings"]
+    #[serde(default)]
+    pub strings_sections: Vec<String>,
+    /// 解析时跳过的格式错误的行
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl InfFile {
+    /// 读取并解析 INF 文件，字符串按 locale 对应的语言替换
+    pub fn open(path: &Path, locale: Locale) -> HamsterResult<Self> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| HamsterError::io(format!("无法读取 INF 文件 {}", path.display()), e))?;
+        Self::from_bytes(&bytes, locale)
+    }
+
+    pub fn from_bytes(bytes: &[u8], locale: Locale) -> HamsterResult<Self> {
+        Self::from_document(&InfDocument::from_bytes(bytes)?, locale)
+    }
+
+    pub fn parse(content: &str, locale: Locale) -> HamsterResult<Self> {
+        Self::from_document(&InfDocument::parse(content)?, locale)
+    }
+
+    pub fn from_document(document: &InfDocument, locale: Locale) -> HamsterResult<Self> {
+        let version_section = document.section("Version")
+            .ok_or_else(|| HamsterError::parse("INF 文件缺少 [Version] 节"))?;
+        let version = read_version(version_section)?;
+        let manufacturers = read_manufacturers(document)?;
+        let mut models = Vec::new();
+        let mut warnings = Vec::new();
+        for manufacturer in &manufacturers {
+            models.extend(read_models(document, manufacturer, &mut warnings));
+        }
+        let strings = InfStrings::from_document(document, locale);
+        let mut inf = Self {
+            version,
+            manufacturers,
+            models,
+            source_files: read_source_files(document),
+            strings_sections: strings.sections().into_iter().map(str::to_string).collect(),
+            warnings,
+        };
+        inf.resolve_strings(&strings);
+        Ok(inf)
+    }
+
+    /// 替换面向用户的文本和文件名中的字符串键；节名和型号节的对应关系在替换前已确定
+    fn resolve_strings(&mut self, strings: &InfStrings) {
+        let resolve = |value: &mut String| *value = strings.resolve(value);
+        let resolve_option = |value: &mut Option<String>| {
+            if let Some(value) = value {
+                *value = strings.resolve(value);
+            }
+        };
+
+        resolve_option(&mut self.version.provider);
+        resolve_option(&mut self.version.class);
+        resolve_option(&mut self.version.catalog_file);
+        for manufacturer in &mut self.manufacturers {
+            resolve(&mut manufacturer.name);
+        }
+        for model in &mut self.models {
+            resolve(&mut model.manufacturer);
+            resolve(&mut model.description);
+            resolve(&mut model.hardware_id);
+            model.compatible_ids.iter_mut().for_each(resolve);
+        }
+        for file in &mut self.source_files {
+            resolve(&mut file.name);
+            resolve_option(&mut file.subdir);
+        }
+    }
+
+    pub fn driver_version(&self) -> Option<&str> {
+        self.version.driver_ver.as_ref().and_then(|driver_ver| driver_ver.version.as_deref())
+    }
+
+    /// 驱动提供商，未声明时取第一个厂商
+    pub fn provider(&self) -> Option<&str> {
+        self.version.provider.as_deref()
+            .or_else(|| self.manufacturers.first().map(|manufacturer| manufacturer.name.as_str()))
+    }
+
+    /// 第一个型号的设备描述
+    pub fn display_name(&self) -> Option<&str> {
+        self.models.first().map(|model| model.description.as_str())
+    }
+
+    /// 各厂商声明支持的目标系统，去重后的说明；没有修饰时为 “Windows”
+    pub fn supported_os(&self) -> Vec<String> {
+        let mut systems: Vec<String> = Vec::new();
+        for target in self.manufacturers.iter().flat_map(|manufacturer| &manufacturer.targets) {
+            let description = target.describe();
+            if !systems.contains(&description) {
+                systems.push(description);
+            }
+        }
+        if systems.is_empty() {
+            systems.push("Windows".to_string());
+        }
+        systems
+    }
+
+    /// 所有型号声明的硬件ID，去重后保持出现顺序
+    pub fn hardware_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = Vec::new();
+        for model in &self.models {
+            if !ids.iter().any(|id| id.eq_ignore_ascii_case(&model.hardware_id)) {
+                ids.push(&model.hardware_id);
+            }
+        }
+        ids
+    }
+}
+
+fn read_version(section: &InfSection) -> HamsterResult<InfVersion> {
+    let driver_ver = match section.entry("DriverVer") {
+        Some(line) => Some(DriverVer::parse(&line.values)
+            .map_err(|e| HamsterError::parse(format!("INF 第 {} 行: {}", line.line, e)))?),
+        None => None,
+    };
+    // CatalogFile 可带平台修饰，在 [Version] 中表现为不同的键
+    let catalog_file = section.value("CatalogFile").or_else(|| {
+        section.lines.iter()
+            .find(|line| line.key.as_deref().is_some_and(|key| {
+                key.len() > "CatalogFile.".len() && key[.."CatalogFile.".len()].eq_ignore_ascii_case("CatalogFile.")
+            }))
+            .and_then(|line| line.value(0))
+    });
+    Ok(InfVersion {
+        signature: section.value("Signature").map(str::to_string),
+        class: section.value("Class").map(str::to_string),
+        class_guid: section.value("ClassGuid").map(str::to_string),
+        provider: section.value("Provider").map(str::to_string),
+        driver_ver,
+        catalog_file: catalog_file.map(str::to_string),
+    })
+}
+
+fn read_manufacturers(document: &InfDocument) -> HamsterResult<Vec<Manufacturer>> {
+    let Some(section) = document.section("Manufacturer") else {
+        return Ok(Vec::new());
+    };
+    section.lines.iter()
+        .filter_map(|line| {
+            let models_section = line.value(0)?;
+            Some((line, models_section))
+        })
+        .map(|(line, models_section)| {
+            let targets = line.values[1..].iter()
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(|value| TargetOs::parse(value)
+                    .map_err(|e| HamsterError::parse(format!("INF 第 {} 行: {}", line.line, e))))
+                .collect::<HamsterResult<Vec<_>>>()?;
+            Ok(Manufacturer {
+                // 只写了型号节名的行以节名作为厂商名
+                name: line.key.clone().unwrap_or_else(|| models_section.to_string()),
+                models_section: models_section.to_string(),
+                targets,
+            })
+        })
+        .collect()
+}
+
+/// 读取厂商的未修饰型号节和各目标系统对应的 `型号节.修饰` 节；格式错误的行跳过并记入 warnings，
+/// 不影响同一文件中的其他型号
+fn read_models(document: &InfDocument, manufacturer: &Manufacturer, warnings: &mut Vec<String>) -> Vec<InfModel> {
+    let mut sections: Vec<(Option<TargetOs>, &InfSection)> = Vec::new();
+    if let Some(section) = document.section(&manufacturer.models_section) {
+        sections.push((None, section));
+    }
+    for target in &manufacturer.targets {
+        let name = format!("{}.{}", manufacturer.models_section, target);
+        // 修饰的写法以 [Manufacturer] 中的原文为准，按规范写法查不到时再按架构名逐节比较
+        let section = document.section(&name).or_else(|| {
+            document.decorated_sections(&manufacturer.models_section)
+                .find(|(decoration, _)| decoration.is_some_and(|decoration| TargetOs::parse(decoration).ok().as_ref() == Some(target)))
+                .map(|(_, section)| section)
+        });
+        if let Some(section) = section {
+            sections.push((Some(target.clone()), section));
+        }
+    }
+
+    let mut models = Vec::new();
+    for (target, section) in sections {
+        for line in &section.lines {
+            let (Some(description), Some(install_section), Some(hardware_id)) = (line.key.as_ref(), line.value(0), line.value(1)) else {
+                warnings.push(format!(
+                    "INF 第 {} 行: 型号节 [{}] 的条目应为 设备描述 = 安装节, 硬件ID，已跳过", line.line, section.name));
+                continue;
+            };
+            let feature_score = ddinstall_section(document, install_section, target.as_ref())
+                .and_then(|ddinstall| ddinstall.entry("FeatureScore"))
+                .and_then(|entry| {
+                    let score = entry.value(0).and_then(parse_number).and_then(|score| u8::try_from(score).ok());
+                    if score.is_none() {
+                        warnings.push(format!("INF 第 {} 行: FeatureScore 应为 0x00 到 0xFF 之间的数，已忽略", entry.line));
+                    }
+                    score
+                });
+            models.push(InfModel {
+                manufacturer: manufacturer.name.clone(),
+                description: description.clone(),
+                install_section: install_section.to_string(),
+                hardware_id: hardware_id.to_string(),
+                compatible_ids: line.values[2..].iter().filter(|id| !id.is_empty()).cloned().collect(),
+                target: target.clone(),
+                section: section.name.clone(),
+                line: line.line,
+                feature_score,
+            });
+        }
+    }
+    models
+}
+
+fn read_source_files(document: &InfDocument) -> Vec<SourceFile> {
+    document.decorated_sections("SourceDisksFiles")
+        .flat_map(|(architecture, section)| {
+            section.lines.iter().filter_map(move |line| {
+                let name = line.key.clone().or_else(|| line.value(0).map(str::to_string))?;
+                let values = if line.key.is_some() { &line.values[..] } else { &line.values[1..] };
+                let value = |index: usize| values.get(index).map(|value| value.trim()).filter(|value| !value.is_empty());
+                Some(SourceFile {
+                    name,
+                    disk_id: value(0).unwrap_or_default().to_string(),
+                    subdir: value(1).map(str::to_string),
+                    size: value(2).and_then(|size| size.parse().ok()),
+                    architecture: architecture.map(str::to_ascii_lowercase),
+                })
+            })
+        })
+        .collect()
+}
+
+/// 十进制或 0x 开头的十六进制数
+/// 型号的安装节：与 Windows 一样依次查找 `安装节.NT架构`、`安装节.NT` 和未修饰的安装节
+fn ddinstall_section<'a>(document: &'a InfDocument, install_section: &str, target: Option<&TargetOs>) -> Option<&'a InfSection> {
+    target.and_then(|target| target.architecture.as_deref())
+        .and_then(|architecture| document.section(&format!("{}.NT{}", install_section, architecture)))
+        .or_else(|| document.section(&format!("{}.NT", install_section)))
+        .or_else(|| document.section(install_section))
+}
+
+fn parse_number(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_model_line_is_skipped_with_warning() {
+        let inf = InfFile::parse(r#"
+[Version]
+Signature = "$Windows NT$"
+
+[Manufacturer]
+%Mfg% = Models, NTamd64
+
+[Models.NTamd64]
+%Good% = Install, PCI\VEN_10DE&DEV_2504
+%Bad% = Install
+%Other% = Install, PCI\VEN_10DE&DEV_2684
+
+[Strings]
+Mfg = "NVIDIA"
+Good = "Good"
+Bad = "Bad"
+Other = "Other"
+"#, Locale::default()).unwrap();
+        assert_eq!(inf.hardware_ids(), [r"PCI\VEN_10DE&DEV_2504", r"PCI\VEN_10DE&DEV_2684"]);
+        assert_eq!(inf.warnings.len(), 1);
+        assert!(inf.warnings[0].contains("第 10 行"));
+    }
+
+    #[test]
+    fn feature_score_comes_from_decorated_install_section() {
+        let inf = InfFile::parse(r#"
+[Version]
+Signature = "$Windows NT$"
+
+[Manufacturer]
+Vendor = Models, NTamd64
+
+[Models]
+Device = Install, PCI\VEN_8086&DEV_0001
+
+[Models.NTamd64]
+Device = Install, PCI\VEN_8086&DEV_0001
+
+[Install]
+FeatureScore = 0xF0
+
+[Install.NTamd64]
+FeatureScore = 0xE0
+"#, Locale::default()).unwrap();
+        let scores: Vec<Option<u8>> = inf.models.iter().map(|model| model.feature_score).collect();
+        assert_eq!(scores, [Some(0xF0), Some(0xE0)]);
+    }
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/inf").join(name)
+    }
+
+    #[test]
+    fn fixture_infs_parse_with_decorated_models() {
+        let inf = InfFile::open(&fixture("nv_dispi.inf"), Locale::ZhCn).unwrap();
+        assert_eq!(inf.provider(), Some("NVIDIA"));
+        assert_eq!(inf.version.catalog_file.as_deref(), Some("NV_DISP.CAT"));
+        assert_eq!(inf.models.len(), 5);
+        let model = inf.models.iter().find(|model| model.install_section == "Section001_W11").unwrap();
+        assert_eq!(model.hardware_id, "PCI\\VEN_10DE&DEV_2504&SUBSYS_14621043");
+        assert_eq!(model.target.as_ref().and_then(|target| target.build), Some(22000));
+        assert!(inf.warnings.is_empty());
+
+        // ANSI 编码、按语言选择字符串节
+        let inf = InfFile::open(&fixture("hdxrt.inf"), Locale::ZhCn).unwrap();
+        assert_eq!(inf.strings_sections, ["Strings.0804", "Strings"]);
+        assert_eq!(inf.provider(), Some("瑞昱半导体"));
+        assert_eq!(inf.models.len(), 3);
+    }
+
+    #[test]
+    fn broken_fixture_reports_line() {
+        let error = InfFile::open(&fixture("broken.inf"), Locale::ZhCn).unwrap_err();
+        assert!(error.to_string().contains("第 2 行"), "{}", error);
+    }
+}
@@ -0,0 +1,225 @@
+// INF 字符串替换：按界面语言选择 [Strings.LLLL] 节，将 `%键%` 替换为对应的文本，
+// 支持 `%%` 转义、嵌套的字符串键和表示系统目录的 DIRID 数字
+
+use std::collections::HashMap;
+use crate::core::locale::Locale;
+use super::syntax::InfDocument;
+
+// 字符串中引用其他字符串键的最大层数，超过时视为循环引用
+const MAX_DEPTH: usize = 8;
+
+// 未指定时假定的 Windows 目录
+const DEFAULT_WINDOWS_DIR: &str = r"C:\Windows";
+
+/// 一个 INF 文件中可用的字符串，按查找顺序排列
+#[derive(Debug, Clone, Default)]
+pub struct InfStrings {
+    /// (节名, 小写键 → 文本)，前面的节优先
+    tables: Vec<(String, HashMap<String, String>)>,
+    windows_dir: String,
+}
+
+impl InfStrings {
+    /// 查找顺序：与界面语言完全一致的节（如 [Strings.0804]），同一主语言的其他节（如 [Strings.0004]），
+    /// 最后是不带语言的 [Strings]；某个键在前面的节中缺失时继续在后面的节中查找
+    pub fn from_document(document: &InfDocument, locale: Locale) -> Self {
+        let lang_id = locale.lang_id();
+        let mut exact = Vec::new();
+        let mut same_language = Vec::new();
+        let mut neutral = Vec::new();
+
+        for (decoration, section) in document.decorated_sections("Strings") {
+            let table: HashMap<String, String> = section.lines.iter()
+                .filter_map(|line| {
+                    let key = line.key.as_ref()?;
+                    // 值中的逗号不是分隔符，按原样拼回
+                    Some((key.to_ascii_lowercase(), line.values.join(",")))
+                })
+                .collect();
+            let entry = (section.name.clone(), table);
+            match decoration.map(|decoration| u16::from_str_radix(decoration, 16)) {
+                None => neutral.push(entry),
+                Some(Ok(id)) if id == lang_id => exact.push(entry),
+                Some(Ok(id)) if id & 0x3FF == lang_id & 0x3FF => same_language.push(entry),
+                // 其他语言的节不参与查找
+                Some(_) => {}
+            }
+        }
+
+        let mut tables = exact;
+        tables.extend(same_language);
+        tables.extend(neutral);
+        Self { tables, windows_dir: DEFAULT_WINDOWS_DIR.to_string() }
+    }
+
+    /// 替换 DIRID 时使用的 Windows 目录，默认为 `C:\Windows`
+    pub fn with_windows_dir(mut self, windows_dir: impl Into<String>) -> Self {
+        self.windows_dir = windows_dir.into().trim_end_matches('\\').to_string();
+        self
+    }
+
+    /// 实际使用的字符串节，优先的在前
+    pub fn sections(&self) -> Vec<&str> {
+        self.tables.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// 字符串键对应的原始文本，键不区分大小写
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let key = key.to_ascii_lowercase();
+        self.tables.iter().find_map(|(_, table)| table.get(&key)).map(String::as_str)
+    }
+
+    /// 替换文本中的全部 `%键%`：`%%` 为一个百分号；字符串键的文本中如再有 `%键%` 继续替换；
+    /// 纯数字的键在 [Strings] 中没有定义时按 DIRID 替换为目录；无法替换的保持原样
+    pub fn resolve(&self, text: &str) -> String {
+        self.resolve_at(text, 0)
+    }
+
+    fn resolve_at(&self, text: &str, depth: usize) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('%') {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let Some(end) = after.find('%') else {
+                // 没有配对的百分号，按原样保留
+                result.push_str(&rest[start..]);
+                return result;
+            };
+            let key = &after[..end];
+            rest = &after[end + 1..];
+            if key.is_empty() {
+                result.push('%');
+                continue;
+            }
+            match self.lookup(key, depth) {
+                Some(value) => result.push_str(&value),
+                None => {
+                    result.push('%');
+                    result.push_str(key);
+                    result.push('%');
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn lookup(&self, key: &str, depth: usize) -> Option<String> {
+        if let Some(value) = self.get(key) {
+            if depth >= MAX_DEPTH {
+                return None;
+            }
+            return Some(self.resolve_at(value, depth + 1));
+        }
+        key.parse().ok().and_then(|id| self.dirid_path(id))
+    }
+
+    /// 常用的 DIRID 对应的目录
+    pub fn dirid_path(&self, id: i32) -> Option<String> {
+        let windows = &self.windows_dir;
+        let system_drive = windows.split_once('\\').map(|(drive, _)| drive).unwrap_or("C:");
+        let path = match id {
+            10 => windows.to_string(),
+            11 => format!(r"{}\System32", windows),
+            12 => format!(r"{}\System32\drivers", windows),
+            13 => format!(r"{}\System32\DriverStore\FileRepository", windows),
+            17 => format!(r"{}\INF", windows),
+            18 => format!(r"{}\Help", windows),
+            20 => format!(r"{}\Fonts", windows),
+            21 => format!(r"{}\System32\viewers", windows),
+            23 => format!(r"{}\System32\spool\drivers\color", windows),
+            24 | 30 => format!(r"{}\", system_drive),
+            25 => windows.to_string(),
+            50 => format!(r"{}\System", windows),
+            51 => format!(r"{}\System32\spool", windows),
+            52 => format!(r"{}\System32\spool\drivers", windows),
+            53 => format!(r"{}\Users\Default", system_drive),
+            55 => format!(r"{}\System32\spool\prtprocs", windows),
+            16422 => format!(r"{}\Program Files", system_drive),
+            16426 => format!(r"{}\Program Files (x86)", system_drive),
+            16427 => format!(r"{}\Program Files\Common Files", system_drive),
+            16428 => format!(r"{}\Program Files (x86)\Common Files", system_drive),
+            _ => return None,
+        };
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INF: &str = r#"
+[Strings]
+Provider = "Realtek"
+DiskName = "%Provider% Driver Disk"
+ServiceDesc = "%DiskName% Service"
+Loop = "%Loop%"
+Only = "neutral"
+
+[Strings.0804]
+Provider = "瑞昱半导体"
+
+[Strings.0004]
+Region = "中文"
+
+[Strings.0409]
+Provider = "Realtek Semiconductor"
+"#;
+
+    fn strings(locale: Locale) -> InfStrings {
+        InfStrings::from_document(&InfDocument::parse(INF).unwrap(), locale)
+    }
+
+    #[test]
+    fn localized_section_falls_back_to_neutral() {
+        let zh = strings(Locale::ZhCn);
+        assert_eq!(zh.sections(), ["Strings.0804", "Strings.0004", "Strings"]);
+        assert_eq!(zh.get("provider"), Some("瑞昱半导体"));
+        // 同一主语言的节和 [Strings] 补充缺失的键
+        assert_eq!(zh.get("Region"), Some("中文"));
+        assert_eq!(zh.get("ONLY"), Some("neutral"));
+
+        let en = strings(Locale::EnUs);
+        assert_eq!(en.sections(), ["Strings.0409", "Strings"]);
+        assert_eq!(en.resolve("%Provider%"), "Realtek Semiconductor");
+        assert_eq!(en.get("Region"), None);
+    }
+
+    #[test]
+    fn nested_tokens_are_resolved() {
+        let zh = strings(Locale::ZhCn);
+        assert_eq!(zh.resolve("%ServiceDesc%"), "瑞昱半导体 Driver Disk Service");
+        // 循环引用在达到最大层数后停止
+        assert_eq!(zh.resolve("%Loop%"), "%Loop%");
+    }
+
+    #[test]
+    fn undefined_tokens_and_escapes_are_kept() {
+        let zh = strings(Locale::ZhCn);
+        assert_eq!(zh.resolve("%Missing% %Provider%"), "%Missing% 瑞昱半导体");
+        assert_eq!(zh.resolve("100%% %Provider%"), "100% 瑞昱半导体");
+        assert_eq!(zh.resolve("50% off"), "50% off");
+    }
+
+    #[test]
+    fn dirids_are_substituted() {
+        let zh = strings(Locale::ZhCn);
+        assert_eq!(zh.resolve(r"%12%\rt640x64.sys"), r"C:\Windows\System32\drivers\rt640x64.sys");
+        assert_eq!(zh.resolve("%16422%"), r"C:\Program Files");
+        // 未知的 DIRID 保持原样
+        assert_eq!(zh.resolve("%99%"), "%99%");
+
+        let moved = zh.with_windows_dir(r"D:\WINNT\");
+        assert_eq!(moved.resolve("%11%"), r"D:\WINNT\System32");
+        assert_eq!(moved.resolve("%24%"), r"D:\");
+    }
+
+    #[test]
+    fn strings_override_dirids() {
+        let document = InfDocument::parse("[Strings]\n11 = \"custom\"\n").unwrap();
+        let strings = InfStrings::from_document(&document, Locale::ZhCn);
+        assert_eq!(strings.resolve("%11%"), "custom");
+    }
+}
@@ -0,0 +1,287 @@
+// INF 文件的语法层：识别编码，拆分节、续行、注释、引号和逗号分隔的值，不解释各节的含义
+
+use encoding_rs::{Encoding, BIG5, GBK, UTF_16BE, UTF_16LE, WINDOWS_1252};
+use crate::core::error::{HamsterError, HamsterResult};
+
+// 语言ID的低10位为主语言
+const PRIMARY_LANGUAGE_MASK: u16 = 0x03FF;
+const LANG_CHINESE: u16 = 0x0004;
+
+/// 一个逻辑行：`键 = 值, 值, ...` 或不带键的 `值, 值, ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfLine {
+    pub key: Option<String>,
+    /// 已去掉引号和两端空白的值，空值保留以维持位置
+    pub values: Vec<String>,
+    /// 逻辑行起始的物理行号，从1开始
+    pub line: usize,
+}
+
+impl InfLine {
+    /// 第 index 个值，为空时返回 None
+    pub fn value(&self, index: usize) -> Option<&str> {
+        self.values.get(index).map(String::as_str).filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfSection {
+    /// 第一次出现时的写法
+    pub name: String,
+    pub lines: Vec<InfLine>,
+}
+
+impl InfSection {
+    /// 第一个键为 key 的行，键不区分大小写
+    pub fn entry(&self, key: &str) -> Option<&InfLine> {
+        self.lines.iter().find(|line| line.key.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(key)))
+    }
+
+    /// 键为 key 的行的第一个值
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.entry(key).and_then(|line| line.value(0))
+    }
+}
+
+/// 按节组织的 INF 文件；同名的节（不区分大小写）按 Windows 的做法合并
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InfDocument {
+    sections: Vec<InfSection>,
+}
+
+impl InfDocument {
+    /// 识别编码后解析：带 BOM 的 UTF-16/UTF-8、无 BOM 的 UTF-16LE、UTF-8，其余按 ANSI 处理
+    pub fn from_bytes(bytes: &[u8]) -> HamsterResult<Self> {
+        Self::parse(&decode(bytes))
+    }
+
+    pub fn parse(content: &str) -> HamsterResult<Self> {
+        let mut document = InfDocument::default();
+        let mut current: Option<usize> = None;
+
+        for (line_number, text) in logical_lines(content)? {
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if let Some(header) = text.strip_prefix('[') {
+                let name = header.split_once(']')
+                    .map(|(name, _)| name.trim())
+                    .ok_or_else(|| HamsterError::parse(format!("INF 第 {} 行的节名缺少 ]", line_number)))?;
+                current = Some(document.section_index(name));
+                continue;
+            }
+            // 第一个节之前的内容不属于任何节，Windows 会忽略
+            let Some(index) = current else {
+                continue;
+            };
+            let line = parse_line(text, line_number)?;
+            document.sections[index].lines.push(line);
+        }
+        Ok(document)
+    }
+
+    pub fn sections(&self) -> &[InfSection] {
+        &self.sections
+    }
+
+    /// 按名称查找节，不区分大小写
+    pub fn section(&self, name: &str) -> Option<&InfSection> {
+        self.sections.iter().find(|section| section.name.eq_ignore_ascii_case(name))
+    }
+
+    /// 名称为 base 或 `base.修饰` 的节及其修饰部分，如 `SourceDisksFiles.amd64`
+    pub fn decorated_sections<'a>(&'a self, base: &'a str) -> impl Iterator<Item = (Option<&'a str>, &'a InfSection)> + 'a {
+        self.sections.iter().filter_map(move |section| {
+            if section.name.eq_ignore_ascii_case(base) {
+                return Some((None, section));
+            }
+            let prefix = section.name.get(..base.len())?;
+            let decoration = section.name[base.len()..].strip_prefix('.')?;
+            prefix.eq_ignore_ascii_case(base).then_some((Some(decoration), section))
+        })
+    }
+
+    fn section_index(&mut self, name: &str) -> usize {
+        match self.sections.iter().position(|section| section.name.eq_ignore_ascii_case(name)) {
+            Some(index) => index,
+            None => {
+                self.sections.push(InfSection { name: name.to_string(), lines: Vec::new() });
+                self.sections.len() - 1
+            }
+        }
+    }
+}
+
+/// 将文件内容解码为文本
+pub fn decode(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return UTF_16LE.decode_without_bom_handling(rest).0.into_owned();
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return UTF_16BE.decode_without_bom_handling(rest).0.into_owned();
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(rest).into_owned();
+    }
+    // INF 以 ASCII 的注释或节名开头，无 BOM 的 UTF-16LE 第二个字节为0
+    if bytes.len() >= 2 && bytes[0] != 0 && bytes[1] == 0 {
+        return UTF_16LE.decode_without_bom_handling(bytes).0.into_owned();
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+    // ANSI 编码取决于编写时的系统代码页：默认按西欧代码页（Windows-1252）解码，
+    // 只有声明了中文字符串节（如 [Strings.0804]）时才按对应的中文代码页解码
+    let text = WINDOWS_1252.decode_without_bom_handling(bytes).0;
+    if let Some(encoding) = declared_chinese_encoding(&text) {
+        if let Some(text) = encoding.decode_without_bom_handling_and_without_replacement(bytes) {
+            return text.into_owned();
+        }
+    }
+    text.into_owned()
+}
+
+/// 中文字符串节的语言决定代码页：简体（中国大陆、新加坡）为 GBK，繁体（台湾、香港、澳门）为 Big5；
+/// 节名只含 ASCII，按西欧代码页解码的文本即可查找
+fn declared_chinese_encoding(text: &str) -> Option<&'static Encoding> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix('[')?.split_once(']'))
+        .filter_map(|(name, _)| {
+            let (base, decoration) = name.trim().split_once('.')?;
+            if !base.trim().eq_ignore_ascii_case("Strings") {
+                return None;
+            }
+            u16::from_str_radix(decoration.trim(), 16).ok()
+        })
+        .find(|lang_id| lang_id & PRIMARY_LANGUAGE_MASK == LANG_CHINESE)
+        .map(|lang_id| match lang_id {
+            0x0804 | 0x1004 | 0x0004 => GBK,
+            _ => BIG5,
+        })
+}
+
+/// 去掉注释并合并以 `\` 结尾的续行，返回 (起始行号, 内容)
+fn logical_lines(content: &str) -> HamsterResult<Vec<(usize, String)>> {
+    let mut lines = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let text = strip_comment(raw, line_number)?;
+        let trimmed = text.trim_end();
+        let (text, continues) = match trimmed.strip_suffix('\\') {
+            Some(text) => (text, true),
+            None => (trimmed, false),
+        };
+        let (start, mut joined) = pending.take().unwrap_or((line_number, String::new()));
+        joined.push_str(text);
+        if continues {
+            pending = Some((start, joined));
+        } else {
+            lines.push((start, joined));
+        }
+    }
+    // 文件末尾的续行符没有下一行可接
+    lines.extend(pending);
+    Ok(lines)
+}
+
+/// 去掉引号外 `;` 之后的注释；引号必须在同一行内闭合
+fn strip_comment(line: &str, line_number: usize) -> HamsterResult<&str> {
+    let mut in_quotes = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => return Ok(&line[..index]),
+            _ => {}
+        }
+    }
+    if in_quotes {
+        return Err(HamsterError::parse(format!("INF 第 {} 行的引号未闭合", line_number)));
+    }
+    Ok(line)
+}
+
+/// 拆分键和值：第一个引号外的 `=` 之前为键，之后按引号外的逗号拆分
+fn parse_line(text: &str, line_number: usize) -> HamsterResult<InfLine> {
+    let parts = split_unquoted(text, '=', Some(1));
+    let (key, values) = match parts.as_slice() {
+        [key, values] => (Some(unquote(key)), *values),
+        _ => (None, text),
+    };
+    if key.as_deref() == Some("") {
+        return Err(HamsterError::parse(format!("INF 第 {} 行的键为空", line_number)));
+    }
+    let values = if values.trim().is_empty() && key.is_some() {
+        Vec::new()
+    } else {
+        split_unquoted(values, ',', None).into_iter().map(unquote).collect()
+    };
+    Ok(InfLine { key, values, line: line_number })
+}
+
+/// 按引号外的分隔符拆分，limit 为最多拆分的次数
+fn split_unquoted(text: &str, separator: char, limit: Option<usize>) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes && limit.is_none_or(|limit| parts.len() < limit) {
+            parts.push(&text[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// 去掉两端空白和引号；引号内的 `""` 表示一个引号，引号内外的部分直接拼接
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if !value.contains('"') {
+        return value.to_string();
+    }
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    let mut in_quotes = false;
+    while let Some(c) = chars.next() {
+        if c != '"' {
+            result.push(c);
+        } else if in_quotes && chars.peek() == Some(&'"') {
+            chars.next();
+            result.push('"');
+        } else {
+            in_quotes = !in_quotes;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi_defaults_to_windows_1252() {
+        // "Gerät" 中的 ä 与后面的 t 恰好组成合法的 GBK 字符，不能据此猜测为中文
+        let bytes = b"[Strings]\r\nName = \"Ger\xE4t\"\r\n";
+        assert!(GBK.decode_without_bom_handling_and_without_replacement(bytes).is_some());
+        assert!(decode(bytes).contains("Gerät"));
+    }
+
+    #[test]
+    fn ansi_with_chinese_strings_section_uses_chinese_codepage() {
+        let mut bytes = b"[Strings.0804]\r\nName = \"".to_vec();
+        bytes.extend_from_slice(&GBK.encode("显卡").0);
+        bytes.extend_from_slice(b"\"\r\n");
+        assert!(decode(&bytes).contains("显卡"));
+
+        let mut bytes = b"[Strings.0404]\r\nName = \"".to_vec();
+        bytes.extend_from_slice(&BIG5.encode("顯示卡").0);
+        bytes.extend_from_slice(b"\"\r\n");
+        assert!(decode(&bytes).contains("顯示卡"));
+    }
+}
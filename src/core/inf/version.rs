@@ -0,0 +1,170 @@
+// 驱动版本：四段的数字版本加上可选的 DriverVer 日期，按 Windows 为驱动排序的规则比较；
+// 容忍厂商常见的写法差异，如前导零（08.17.0013）、缺少的段（1.2）、逗号分隔和版本后的说明文字
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::core::error::{HamsterError, HamsterResult};
+use super::DriverVer;
+
+/// 与 Windows 为驱动排序的规则一致：先比较日期，日期相同时再逐段比较版本；
+/// 没有日期的版本排在有日期的之前，需要忽略日期时用 [`DriverVersion::cmp_version`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DriverVersion {
+    pub date: Option<NaiveDate>,
+    /// 主版本、次版本、内部版本、修订号；Windows 中每段都是 16 位整数
+    pub parts: [u16; 4],
+}
+
+impl DriverVersion {
+    pub fn new(parts: [u16; 4]) -> Self {
+        Self { date: None, parts }
+    }
+
+    /// 解析版本号（`1.2.3.4`），或 DriverVer 的值（`日期,版本`，日期为 `月/日/年`）；
+    /// 缺少的段视为0，多于四段或某段超出 16 位时报错
+    pub fn parse(text: &str) -> HamsterResult<Self> {
+        let text = text.trim();
+        let invalid = || HamsterError::parse(format!("驱动版本格式错误: {}", text));
+        // DriverVer 的日期中有 `/` 或 `-`，逗号分隔的版本号不会有
+        if let Some((date, version)) = text.split_once(',').filter(|(date, _)| date.contains(['/', '-'])) {
+            let version = if version.trim().is_empty() { Self::default() } else { Self::parse(version)? };
+            return Ok(version.with_date(Some(parse_date(date).ok_or_else(invalid)?)));
+        }
+        if text.contains('/') {
+            return Ok(Self::default().with_date(Some(parse_date(text).ok_or_else(invalid)?)));
+        }
+        // `2023-06-15` 这样的 ISO 日期；`22.20.16.4749-beta` 中连字符后是说明，不是日期
+        if let Some(date) = parse_date(text).filter(|_| text.contains('-')) {
+            return Ok(Self::default().with_date(Some(date)));
+        }
+
+        // 去掉前缀 v 和版本后以空白或连字符分开的说明，如 `v1.2 (WHQL)`、`22.20.16.4749-beta`
+        let numbers = text.strip_prefix(['v', 'V']).unwrap_or(text);
+        let numbers = numbers.split(|c: char| c.is_whitespace() || c == '-').next().unwrap_or_default();
+        if numbers.is_empty() {
+            return Err(invalid());
+        }
+        let separator = if numbers.contains('.') { '.' } else { ',' };
+        let fields: Vec<&str> = numbers.split(separator).collect();
+        if fields.len() > 4 {
+            return Err(invalid());
+        }
+        let mut parts = [0u16; 4];
+        for (part, field) in parts.iter_mut().zip(&fields) {
+            let field = field.trim();
+            // 空的段（`1..2`）视为0
+            if !field.is_empty() {
+                *part = field.parse().map_err(|_| invalid())?;
+            }
+        }
+        Ok(Self::new(parts))
+    }
+
+    /// INF 中的 DriverVer；没有版本时版本为 0.0.0.0
+    pub fn from_driver_ver(driver_ver: &DriverVer) -> HamsterResult<Self> {
+        let version = match &driver_ver.version {
+            Some(version) => Self::parse(version)?,
+            None => Self::default(),
+        };
+        Ok(version.with_date(Some(driver_ver.date)))
+    }
+
+    pub fn with_date(mut self, date: Option<NaiveDate>) -> Self {
+        self.date = date;
+        self
+    }
+
+    /// 只比较版本号，不考虑日期
+    pub fn cmp_version(&self, other: &Self) -> Ordering {
+        self.parts.cmp(&other.parts)
+    }
+
+    /// 双方都有日期时按 Windows 的规则比较，否则只比较版本号
+    pub fn cmp_release(&self, other: &Self) -> Ordering {
+        match (self.date, other.date) {
+            (Some(_), Some(_)) => self.cmp(other),
+            _ => self.cmp_version(other),
+        }
+    }
+
+    /// 版本号部分，如 `31.0.15.4623`
+    pub fn version_string(&self) -> String {
+        let [major, minor, build, revision] = self.parts;
+        format!("{}.{}.{}.{}", major, minor, build, revision)
+    }
+}
+
+impl Ord for DriverVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.date.cmp(&other.date).then_with(|| self.cmp_version(other))
+    }
+}
+
+impl PartialOrd for DriverVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 有日期时与 DriverVer 的写法相同：`月/日/年,版本`
+impl fmt::Display for DriverVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(date) = self.date {
+            write!(f, "{},", date.format("%m/%d/%Y"))?;
+        }
+        write!(f, "{}", self.version_string())
+    }
+}
+
+impl FromStr for DriverVersion {
+    type Err = HamsterError;
+
+    fn from_str(text: &str) -> HamsterResult<Self> {
+        Self::parse(text)
+    }
+}
+
+impl Serialize for DriverVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DriverVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+/// 解析驱动日期：DriverVer 的 `月/日/年`（也接受 `-` 分隔）、ISO 的 `年-月-日`，
+/// 以及 WMI 的 CIM 日期时间（`20230615000000.000000-000`）；无法识别时返回 None
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    let fields: Vec<&str> = text.split(['/', '-']).map(str::trim).collect();
+    let number = |field: &str| field.parse::<u32>().ok();
+    match fields.as_slice() {
+        [year, month, day] if year.len() == 4 => NaiveDate::from_ymd_opt(number(year)? as i32, number(month)?, number(day)?),
+        [month, day, year] => NaiveDate::from_ymd_opt(number(year)? as i32, number(month)?, number(day)?),
+        _ => {
+            let digits = text.get(..8).filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))?;
+            NaiveDate::parse_from_str(digits, "%Y%m%d").ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_dates_are_not_version_numbers() {
+        let version = DriverVersion::parse("2023-06-15").unwrap();
+        assert_eq!(version, DriverVersion::default().with_date(NaiveDate::from_ymd_opt(2023, 6, 15)));
+        let version = DriverVersion::parse("22.20.16.4749-beta").unwrap();
+        assert_eq!(version, DriverVersion::new([22, 20, 16, 4749]));
+    }
+}
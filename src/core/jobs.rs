@@ -0,0 +1,441 @@
+// 后台任务：扫描、搜索、备份、安装、签名验证等耗时操作统一通过 JobRunner 在后台线程执行，
+// 提供进度事件、取消令牌、结果获取和任务列表，图形界面与命令行共用
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Local};
+use crate::core::error::{ErrorCategory, HamsterError, HamsterResult};
+
+pub type JobId = u64;
+
+// 任务列表中最多保留的已结束任务数
+const MAX_FINISHED_JOBS: usize = 50;
+// 可取消等待的轮询间隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 取消令牌，可在线程间共享；任务需要主动检查
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// 任务进度，按步骤计数
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JobProgress {
+    pub step: usize,
+    pub total_steps: usize,
+    pub message: String,
+}
+
+impl JobProgress {
+    /// 完成比例，范围0.0到1.0；总步骤未知时为0
+    pub fn fraction(&self) -> f32 {
+        if self.total_steps == 0 {
+            0.0
+        } else {
+            (self.step as f32 / self.total_steps as f32).clamp(0.0, 1.0)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobState {
+    Running,
+    Succeeded,
+    Failed { category: ErrorCategory, message: String },
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobState::Running)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobState::Running => "运行中",
+            JobState::Succeeded => "已完成",
+            JobState::Failed { .. } => "失败",
+            JobState::Cancelled => "已取消",
+        }
+    }
+}
+
+/// 任务列表中的一项
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    pub id: JobId,
+    pub name: String,
+    pub state: JobState,
+    pub progress: JobProgress,
+    pub started_at: DateTime<Local>,
+    pub finished_at: Option<DateTime<Local>>,
+}
+
+/// 任务状态变化事件
+#[derive(Debug, Clone)]
+pub enum JobEvent {
+    Started { id: JobId, name: String },
+    Progress { id: JobId, progress: JobProgress },
+    Finished { id: JobId, state: JobState },
+}
+
+impl JobEvent {
+    pub fn id(&self) -> JobId {
+        match self {
+            JobEvent::Started { id, .. } | JobEvent::Progress { id, .. } | JobEvent::Finished { id, .. } => *id,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    next_id: AtomicU64,
+    jobs: Mutex<Vec<(JobInfo, CancelToken)>>,
+    subscribers: Mutex<Vec<Sender<JobEvent>>>,
+}
+
+impl Shared {
+    fn publish(&self, event: JobEvent) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+
+    fn update(&self, id: JobId, apply: impl FnOnce(&mut JobInfo)) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if let Some((info, _)) = jobs.iter_mut().find(|(info, _)| info.id == id) {
+                apply(info);
+            }
+        }
+    }
+
+    fn prune_finished(jobs: &mut Vec<(JobInfo, CancelToken)>) {
+        let finished = jobs.iter().filter(|(info, _)| info.state.is_finished()).count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        jobs.retain(|(info, _)| {
+            if excess > 0 && info.state.is_finished() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// 传给任务函数的上下文，用于报告进度和检查取消
+pub struct JobContext {
+    id: JobId,
+    cancel: CancelToken,
+    shared: Arc<Shared>,
+}
+
+impl JobContext {
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// 已请求取消时返回 Cancelled 错误，便于在步骤之间用 `?` 提前结束
+    pub fn check_cancelled(&self) -> HamsterResult<()> {
+        if self.is_cancelled() {
+            Err(HamsterError::cancelled("任务已被用户取消"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 报告当前进度：第 step 步，共 total_steps 步
+    pub fn report(&self, step: usize, total_steps: usize, message: impl Into<String>) {
+        let progress = JobProgress { step, total_steps, message: message.into() };
+        let snapshot = progress.clone();
+        self.shared.update(self.id, |info| info.progress = snapshot);
+        self.shared.publish(JobEvent::Progress { id: self.id, progress });
+    }
+
+    /// 可被取消打断的等待
+    pub fn sleep(&self, duration: Duration) -> HamsterResult<()> {
+        let deadline = Instant::now() + duration;
+        loop {
+            self.check_cancelled()?;
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(());
+            }
+            thread::sleep((deadline - now).min(CANCEL_POLL_INTERVAL));
+        }
+    }
+}
+
+/// 已提交任务的句柄，用于取消和获取结果
+pub struct JobHandle<T> {
+    id: JobId,
+    cancel: CancelToken,
+    shared: Arc<Shared>,
+    result: Receiver<HamsterResult<T>>,
+}
+
+impl<T> JobHandle<T> {
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// 任务列表中的当前快照
+    pub fn info(&self) -> Option<JobInfo> {
+        let jobs = self.shared.jobs.lock().ok()?;
+        jobs.iter().find(|(info, _)| info.id == self.id).map(|(info, _)| info.clone())
+    }
+
+    pub fn progress(&self) -> JobProgress {
+        self.info().map(|info| info.progress).unwrap_or_default()
+    }
+
+    /// 不阻塞地获取结果，任务未结束时返回 None
+    pub fn try_result(&self) -> Option<HamsterResult<T>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(HamsterError::platform("后台任务异常退出"))),
+        }
+    }
+
+    /// 阻塞等待任务结束
+    pub fn wait(self) -> HamsterResult<T> {
+        self.result.recv()
+            .unwrap_or_else(|_| Err(HamsterError::platform("后台任务异常退出")))
+    }
+}
+
+/// 后台任务调度器，克隆后共享同一任务列表
+#[derive(Clone, Default)]
+pub struct JobRunner {
+    shared: Arc<Shared>,
+}
+
+impl JobRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在后台线程中执行任务，任务函数通过 JobContext 报告进度并响应取消
+    pub fn submit<T, F>(&self, name: impl Into<String>, task: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> HamsterResult<T> + Send + 'static,
+    {
+        let name = name.into();
+        let id = self.shared.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let cancel = CancelToken::new();
+        let (result_tx, result_rx) = mpsc::channel();
+
+        if let Ok(mut jobs) = self.shared.jobs.lock() {
+            jobs.push((JobInfo {
+                id,
+                name: name.clone(),
+                state: JobState::Running,
+                progress: JobProgress::default(),
+                started_at: Local::now(),
+                finished_at: None,
+            }, cancel.clone()));
+            Shared::prune_finished(&mut jobs);
+        }
+        self.shared.publish(JobEvent::Started { id, name: name.clone() });
+
+        let context = JobContext { id, cancel: cancel.clone(), shared: self.shared.clone() };
+        thread::Builder::new()
+            .name(format!("job-{}", id))
+            .spawn(move || {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| task(&context)))
+                    .unwrap_or_else(|_| Err(HamsterError::platform(format!("后台任务 {} 发生内部错误", name))));
+
+                let state = match &result {
+                    Ok(_) => JobState::Succeeded,
+                    // 取消后任务仍可能因其他原因失败，这时保留真实的错误
+                    Err(e) if e.is_cancelled() => JobState::Cancelled,
+                    Err(e) => JobState::Failed { category: e.category(), message: e.context().to_string() },
+                };
+                let finished_state = state.clone();
+                context.shared.update(id, |info| {
+                    info.state = finished_state;
+                    info.finished_at = Some(Local::now());
+                });
+                // 先发送结果，保证收到 Finished 事件的订阅者能立即取到结果
+                let _ = result_tx.send(result);
+                context.shared.publish(JobEvent::Finished { id, state });
+            })
+            .expect("无法创建后台任务线程");
+
+        JobHandle { id, cancel, shared: self.shared.clone(), result: result_rx }
+    }
+
+    /// 所有任务的快照，按提交顺序排列
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        self.shared.jobs.lock()
+            .map(|jobs| jobs.iter().map(|(info, _)| info.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.jobs().iter().filter(|info| !info.state.is_finished()).count()
+    }
+
+    /// 请求取消指定任务，任务不存在或已结束时返回 false
+    pub fn cancel(&self, id: JobId) -> bool {
+        let Ok(jobs) = self.shared.jobs.lock() else {
+            return false;
+        };
+        match jobs.iter().find(|(info, _)| info.id == id) {
+            Some((info, token)) if !info.state.is_finished() => {
+                token.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 从任务列表中移除已结束的任务
+    pub fn clear_finished(&self) {
+        if let Ok(mut jobs) = self.shared.jobs.lock() {
+            jobs.retain(|(info, _)| !info.state.is_finished());
+        }
+    }
+
+    /// 订阅此后发生的任务事件
+    pub fn subscribe(&self) -> Receiver<JobEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.shared.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished_state(runner: &JobRunner, id: JobId) -> JobState {
+        runner.jobs().into_iter().find(|info| info.id == id).unwrap().state
+    }
+
+    #[test]
+    fn progress_is_recorded_and_published() {
+        let runner = JobRunner::new();
+        let events = runner.subscribe();
+        let handle = runner.submit("扫描", |job| {
+            job.report(1, 4, "第一步");
+            job.report(3, 4, "第三步");
+            Ok(42)
+        });
+        let id = handle.id();
+        assert_eq!(handle.wait().unwrap(), 42);
+
+        let events: Vec<JobEvent> = events.iter().take_while(|event| !matches!(event, JobEvent::Finished { .. })).collect();
+        assert!(matches!(&events[0], JobEvent::Started { name, .. } if name == "扫描"));
+        let steps: Vec<usize> = events.iter()
+            .filter_map(|event| match event {
+                JobEvent::Progress { progress, .. } => Some(progress.step),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(steps, [1, 3]);
+        let info = runner.jobs().into_iter().find(|info| info.id == id).unwrap();
+        assert_eq!(info.state, JobState::Succeeded);
+        assert_eq!(info.progress.message, "第三步");
+        assert_eq!(info.progress.fraction(), 0.75);
+        assert!(info.finished_at.is_some());
+    }
+
+    #[test]
+    fn cancel_before_first_step_cancels_the_job() {
+        let runner = JobRunner::new();
+        let (start_tx, start_rx) = mpsc::channel::<()>();
+        let handle = runner.submit("备份", move |job| {
+            start_rx.recv().ok();
+            job.check_cancelled()?;
+            Ok(())
+        });
+        assert!(runner.cancel(handle.id()));
+        start_tx.send(()).unwrap();
+        let id = handle.id();
+        assert!(handle.wait().unwrap_err().is_cancelled());
+        assert_eq!(finished_state(&runner, id), JobState::Cancelled);
+        assert!(!runner.cancel(id));
+    }
+
+    #[test]
+    fn cancel_interrupts_a_running_job() {
+        let runner = JobRunner::new();
+        let (running_tx, running_rx) = mpsc::channel();
+        let handle = runner.submit("搜索", move |job| {
+            running_tx.send(()).ok();
+            job.sleep(Duration::from_secs(60))?;
+            Ok(())
+        });
+        running_rx.recv().unwrap();
+        assert_eq!(runner.running_count(), 1);
+        handle.cancel();
+        let id = handle.id();
+        assert!(handle.wait().unwrap_err().is_cancelled());
+        assert_eq!(finished_state(&runner, id), JobState::Cancelled);
+    }
+
+    #[test]
+    fn errors_are_reported_even_after_cancel_was_requested() {
+        let runner = JobRunner::new();
+        let (start_tx, start_rx) = mpsc::channel::<()>();
+        let handle = runner.submit("安装", move |_| -> HamsterResult<()> {
+            start_rx.recv().ok();
+            Err(HamsterError::parse("INF 第 2 行的引号未闭合"))
+        });
+        handle.cancel();
+        start_tx.send(()).unwrap();
+        let id = handle.id();
+        assert_eq!(handle.wait().unwrap_err().category(), ErrorCategory::Parse);
+        assert_eq!(finished_state(&runner, id), JobState::Failed {
+            category: ErrorCategory::Parse,
+            message: "INF 第 2 行的引号未闭合".to_string(),
+        });
+    }
+
+    #[test]
+    fn panics_become_failures() {
+        let runner = JobRunner::new();
+        let handle = runner.submit("验证", |_| -> HamsterResult<()> { panic!("内部错误") });
+        let id = handle.id();
+        assert_eq!(handle.wait().unwrap_err().category(), ErrorCategory::Platform);
+        assert!(matches!(finished_state(&runner, id), JobState::Failed { category: ErrorCategory::Platform, .. }));
+    }
+}
@@ -0,0 +1,46 @@
+// 界面语言：错误信息等面向用户的文本按语言渲染，默认简体中文
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    /// BCP 47 语言标记，如 "zh-CN"
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// Windows 语言标识（LANGID），低10位为主语言，高6位为子语言；INF 的 [Strings.0804] 等节以此命名
+    pub fn lang_id(&self) -> u16 {
+        match self {
+            Locale::ZhCn => 0x0804,
+            Locale::EnUs => 0x0409,
+        }
+    }
+
+    /// 以该语言自身书写的名称，用于语言选择列表
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "简体中文",
+            Locale::EnUs => "English",
+        }
+    }
+
+    /// 按语言标记查找，忽略大小写并接受下划线分隔
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.replace('_', "-");
+        Self::ALL.into_iter().find(|locale| locale.tag().eq_ignore_ascii_case(&tag))
+    }
+}
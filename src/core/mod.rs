@@ -0,0 +1,21 @@
+// mod.rs for core module
+// 重新导出子模块，便于 crate::core::* 统一访问
+
+pub mod audit;
+pub mod cab;
+pub mod driver_manager;
+pub mod edid;
+pub mod error;
+pub mod features;
+pub mod gpu_version;
+pub mod hardware_id;
+pub mod hwids;
+pub mod inf;
+pub mod jobs;
+pub mod locale;
+pub mod pe;
+pub mod platform;
+pub mod settings;
+pub mod signature;
+pub mod sysinfo;
+pub mod windows_api;
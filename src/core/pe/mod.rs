@@ -0,0 +1,508 @@
+// PE（Portable Executable）文件解析：.sys、.dll 和 .exe 共用这一格式；
+// 只读取文件头、节表和资源目录，不调用 Windows API，因此在任何系统上都能读取驱动文件的信息
+
+mod version;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::core::error::{HamsterError, HamsterResult};
+
+pub use version::{FixedVersion, StringTable, VersionInfo};
+
+const DOS_SIGNATURE: &[u8; 2] = b"MZ";
+const PE_SIGNATURE: &[u8; 4] = b"PE\0\0";
+
+const PE32_MAGIC: u16 = 0x010B;
+const PE32_PLUS_MAGIC: u16 = 0x020B;
+
+// COFF 文件头和节表项的大小
+const COFF_HEADER_SIZE: usize = 20;
+const SECTION_HEADER_SIZE: usize = 40;
+
+// 可选头中数据目录表之前部分的大小，PE32 和 PE32+ 不同
+const PE32_DIRECTORIES_OFFSET: usize = 96;
+const PE32_PLUS_DIRECTORIES_OFFSET: usize = 112;
+
+const CHARACTERISTIC_DLL: u16 = 0x2000;
+
+/// 数据目录表的下标
+pub const DIRECTORY_RESOURCE: usize = 2;
+pub const DIRECTORY_SECURITY: usize = 4;
+
+// 资源类型：版本信息
+const RT_VERSION: u32 = 16;
+
+// 资源目录最多三层：类型、名称、语言
+const RESOURCE_DEPTH: usize = 3;
+
+/// 目标处理器架构（COFF 头中的 Machine 字段）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Machine {
+    I386,
+    Amd64,
+    Arm,
+    Arm64,
+    Ia64,
+    Unknown(u16),
+}
+
+impl Machine {
+    fn from_value(value: u16) -> Self {
+        match value {
+            0x014C => Machine::I386,
+            0x8664 => Machine::Amd64,
+            0x01C0 | 0x01C4 => Machine::Arm,
+            0xAA64 => Machine::Arm64,
+            0x0200 => Machine::Ia64,
+            _ => Machine::Unknown(value),
+        }
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Machine::I386 => write!(f, "x86"),
+            Machine::Amd64 => write!(f, "x64"),
+            Machine::Arm => write!(f, "ARM"),
+            Machine::Arm64 => write!(f, "ARM64"),
+            Machine::Ia64 => write!(f, "IA64"),
+            Machine::Unknown(value) => write!(f, "未知架构 0x{:04X}", value),
+        }
+    }
+}
+
+/// 运行所需的子系统；内核驱动为 Native
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Subsystem {
+    Native,
+    WindowsGui,
+    WindowsCui,
+    EfiApplication,
+    EfiBootServiceDriver,
+    EfiRuntimeDriver,
+    BootApplication,
+    Unknown(u16),
+}
+
+impl Subsystem {
+    fn from_value(value: u16) -> Self {
+        match value {
+            1 => Subsystem::Native,
+            2 => Subsystem::WindowsGui,
+            3 => Subsystem::WindowsCui,
+            10 => Subsystem::EfiApplication,
+            11 => Subsystem::EfiBootServiceDriver,
+            12 => Subsystem::EfiRuntimeDriver,
+            16 => Subsystem::BootApplication,
+            _ => Subsystem::Unknown(value),
+        }
+    }
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subsystem::Native => write!(f, "内核模式（Native）"),
+            Subsystem::WindowsGui => write!(f, "Windows 图形界面"),
+            Subsystem::WindowsCui => write!(f, "Windows 控制台"),
+            Subsystem::EfiApplication => write!(f, "EFI 应用程序"),
+            Subsystem::EfiBootServiceDriver => write!(f, "EFI 启动服务驱动"),
+            Subsystem::EfiRuntimeDriver => write!(f, "EFI 运行时驱动"),
+            Subsystem::BootApplication => write!(f, "Windows 启动应用程序"),
+            Subsystem::Unknown(value) => write!(f, "未知子系统 {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DataDirectory {
+    pub virtual_address: u32,
+    pub size: u32,
+}
+
+impl DataDirectory {
+    pub fn is_empty(&self) -> bool {
+        self.virtual_address == 0 || self.size == 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Section {
+    pub name: String,
+    pub virtual_address: u32,
+    pub virtual_size: u32,
+    /// 在文件中的位置和大小
+    pub raw_offset: u32,
+    pub raw_size: u32,
+}
+
+/// 已读入内存的 PE 文件
+#[derive(Debug, Clone)]
+pub struct PeFile {
+    data: Vec<u8>,
+    pub machine: Machine,
+    /// 链接时间戳（Unix 秒）；可重现构建的文件中为内容哈希而非时间
+    pub timestamp: u32,
+    pub characteristics: u16,
+    /// 是否为 64 位的 PE32+ 格式
+    pub pe32_plus: bool,
+    pub subsystem: Subsystem,
+    /// 文件头中记录的校验和，0 表示未设置
+    pub checksum: u32,
+    pub size_of_headers: u32,
+    pub directories: Vec<DataDirectory>,
+    pub sections: Vec<Section>,
+    /// 可选头中 CheckSum 字段和数据目录表在文件中的位置
+    checksum_offset: usize,
+    directories_offset: usize,
+}
+
+impl PeFile {
+    pub fn open(path: &Path) -> HamsterResult<Self> {
+        let data = fs::read(path)
+            .map_err(|e| HamsterError::io(format!("读取文件 {} 失败", path.display()), e))?;
+        Self::parse(data)
+            .map_err(|e| HamsterError::parse(format!("{} 不是有效的 PE 文件", path.display())).with_source(e))
+    }
+
+    pub fn parse(data: Vec<u8>) -> HamsterResult<Self> {
+        if data.get(..2) != Some(DOS_SIGNATURE.as_slice()) {
+            return Err(HamsterError::parse("缺少 MZ 文件头"));
+        }
+        let pe_offset = read_u32(&data, 0x3C).ok_or_else(truncated)? as usize;
+        if data.get(pe_offset..pe_offset + 4) != Some(PE_SIGNATURE.as_slice()) {
+            return Err(HamsterError::parse("缺少 PE 签名"));
+        }
+
+        let coff = pe_offset + 4;
+        let machine = read_u16(&data, coff).ok_or_else(truncated)?;
+        let section_count = read_u16(&data, coff + 2).ok_or_else(truncated)? as usize;
+        let timestamp = read_u32(&data, coff + 4).ok_or_else(truncated)?;
+        let optional_size = read_u16(&data, coff + 16).ok_or_else(truncated)? as usize;
+        let characteristics = read_u16(&data, coff + 18).ok_or_else(truncated)?;
+
+        let optional = coff + COFF_HEADER_SIZE;
+        let pe32_plus = match read_u16(&data, optional).ok_or_else(truncated)? {
+            PE32_MAGIC => false,
+            PE32_PLUS_MAGIC => true,
+            magic => return Err(HamsterError::parse(format!("未知的可选头格式 0x{:04X}", magic))),
+        };
+        let directories_offset = optional + if pe32_plus { PE32_PLUS_DIRECTORIES_OFFSET } else { PE32_DIRECTORIES_OFFSET };
+        if optional_size < directories_offset - optional || data.len() < optional + optional_size {
+            return Err(truncated());
+        }
+        // CheckSum 和 Subsystem 在两种格式中的位置相同
+        let checksum_offset = optional + 64;
+        let checksum = read_u32(&data, checksum_offset).ok_or_else(truncated)?;
+        let subsystem = read_u16(&data, optional + 68).ok_or_else(truncated)?;
+        let size_of_headers = read_u32(&data, optional + 60).ok_or_else(truncated)?;
+
+        // NumberOfRvaAndSizes 可能大于可选头实际容纳的项数，以较小者为准
+        let directory_count = read_u32(&data, directories_offset - 4).ok_or_else(truncated)? as usize;
+        let directory_count = directory_count.min((optional + optional_size - directories_offset) / 8);
+        let directories = (0..directory_count)
+            .map(|index| {
+                let offset = directories_offset + index * 8;
+                DataDirectory {
+                    virtual_address: read_u32(&data, offset).unwrap_or(0),
+                    size: read_u32(&data, offset + 4).unwrap_or(0),
+                }
+            })
+            .collect();
+
+        let section_table = optional + optional_size;
+        let sections = (0..section_count)
+            .map(|index| {
+                let offset = section_table + index * SECTION_HEADER_SIZE;
+                let header = data.get(offset..offset + SECTION_HEADER_SIZE).ok_or_else(truncated)?;
+                let name = &header[..8];
+                let name = &name[..name.iter().position(|&byte| byte == 0).unwrap_or(8)];
+                Ok(Section {
+                    name: String::from_utf8_lossy(name).to_string(),
+                    virtual_size: read_u32(header, 8).unwrap_or(0),
+                    virtual_address: read_u32(header, 12).unwrap_or(0),
+                    raw_size: read_u32(header, 16).unwrap_or(0),
+                    raw_offset: read_u32(header, 20).unwrap_or(0),
+                })
+            })
+            .collect::<HamsterResult<_>>()?;
+
+        Ok(Self {
+            data,
+            machine: Machine::from_value(machine),
+            timestamp,
+            characteristics,
+            pe32_plus,
+            subsystem: Subsystem::from_value(subsystem),
+            checksum,
+            size_of_headers,
+            directories,
+            sections,
+            checksum_offset,
+            directories_offset,
+        })
+    }
+
+    /// 整个文件的内容
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn is_dll(&self) -> bool {
+        self.characteristics & CHARACTERISTIC_DLL != 0
+    }
+
+    /// 链接时间；时间戳为0或超出合理范围时返回 None
+    pub fn link_time(&self) -> Option<DateTime<Utc>> {
+        if self.timestamp == 0 {
+            return None;
+        }
+        DateTime::from_timestamp(self.timestamp as i64, 0)
+    }
+
+    /// 不存在或为空时返回 None
+    pub fn directory(&self, index: usize) -> Option<DataDirectory> {
+        self.directories.get(index).copied().filter(|directory| !directory.is_empty())
+    }
+
+    /// 可选头中 CheckSum 字段在文件中的位置
+    pub fn checksum_offset(&self) -> usize {
+        self.checksum_offset
+    }
+
+    /// 数据目录表第 index 项在文件中的位置，不存在时返回 None
+    pub fn directory_entry_offset(&self, index: usize) -> Option<usize> {
+        (index < self.directories.len()).then(|| self.directories_offset + index * 8)
+    }
+
+    /// 把相对虚拟地址换算为文件中的位置
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        if rva < self.size_of_headers {
+            return Some(rva as usize);
+        }
+        self.sections.iter()
+            .find(|section| {
+                let size = section.virtual_size.max(section.raw_size);
+                rva >= section.virtual_address && rva - section.virtual_address < size
+            })
+            .and_then(|section| {
+                let delta = rva - section.virtual_address;
+                // 超出文件中数据的部分在加载时以0填充，文件里没有对应内容
+                (delta < section.raw_size).then(|| section.raw_offset as usize + delta as usize)
+            })
+    }
+
+    /// 从相对虚拟地址处读取 size 字节
+    fn read_rva(&self, rva: u32, size: u32) -> Option<&[u8]> {
+        let offset = self.rva_to_offset(rva)?;
+        self.data.get(offset..offset.checked_add(size as usize)?)
+    }
+
+    /// 按 imagehlp 的 CheckSumMappedFile 算法重新计算校验和：
+    /// 跳过 CheckSum 字段，按16位小端字求和并折叠进位，最后加上文件长度
+    pub fn compute_checksum(&self) -> u32 {
+        let mut sum: u64 = 0;
+        for (index, chunk) in self.data.chunks(2).enumerate() {
+            let offset = index * 2;
+            if offset >= self.checksum_offset && offset < self.checksum_offset + 4 {
+                continue;
+            }
+            let word = chunk[0] as u64 | (chunk.get(1).copied().unwrap_or(0) as u64) << 8;
+            sum += word;
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+        let sum = (sum & 0xFFFF) + (sum >> 16);
+        (sum as u32 & 0xFFFF).wrapping_add(self.data.len() as u32)
+    }
+
+    /// 文件头中的校验和是否与内容一致；未设置校验和时返回 None。
+    /// 内核驱动必须带有正确的校验和，否则 Windows 拒绝加载
+    pub fn checksum_matches(&self) -> Option<bool> {
+        (self.checksum != 0).then(|| self.checksum == self.compute_checksum())
+    }
+
+    /// 读取版本资源（VS_VERSIONINFO），文件中没有版本资源时返回 None
+    pub fn version_info(&self) -> HamsterResult<Option<VersionInfo>> {
+        let Some(directory) = self.directory(DIRECTORY_RESOURCE) else {
+            return Ok(None);
+        };
+        let resources = self.read_rva(directory.virtual_address, directory.size)
+            .ok_or_else(|| HamsterError::parse("资源目录超出文件范围"))?;
+        let Some(entry) = find_resource(resources, RT_VERSION)? else {
+            return Ok(None);
+        };
+        let size = read_u32(resources, entry + 4).ok_or_else(truncated)?;
+        let rva = read_u32(resources, entry).ok_or_else(truncated)?;
+        let data = self.read_rva(rva, size)
+            .ok_or_else(|| HamsterError::parse("版本资源超出文件范围"))?;
+        VersionInfo::parse(data).map(Some)
+    }
+}
+
+/// 在资源目录中查找指定类型的第一个资源，返回其数据项（IMAGE_RESOURCE_DATA_ENTRY）在资源目录中的位置
+fn find_resource(resources: &[u8], resource_type: u32) -> HamsterResult<Option<usize>> {
+    let mut directory = 0usize;
+    for depth in 0..RESOURCE_DEPTH {
+        let named = read_u16(resources, directory + 12).ok_or_else(truncated)? as usize;
+        let ids = read_u16(resources, directory + 14).ok_or_else(truncated)? as usize;
+        let entries = (0..named + ids).map(|index| directory + 16 + index * 8);
+
+        // 第一层按类型ID查找，名称和语言层取第一项
+        let mut selected = None;
+        for entry in entries {
+            let name = read_u32(resources, entry).ok_or_else(truncated)?;
+            if depth > 0 || (name & 0x8000_0000 == 0 && name == resource_type) {
+                selected = Some(read_u32(resources, entry + 4).ok_or_else(truncated)?);
+                break;
+            }
+        }
+        let Some(target) = selected else {
+            return Ok(None);
+        };
+
+        let offset = (target & 0x7FFF_FFFF) as usize;
+        if target & 0x8000_0000 == 0 {
+            return Ok(Some(offset));
+        }
+        // 子目录必须位于当前目录之后，避免构造的循环引用
+        if offset <= directory {
+            return Err(HamsterError::parse("资源目录结构无效"));
+        }
+        directory = offset;
+    }
+    Err(HamsterError::parse("资源目录层级过深"))
+}
+
+fn truncated() -> HamsterError {
+    HamsterError::parse("PE 文件头不完整")
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset.checked_add(2)?).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset.checked_add(4)?).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::locale::Locale;
+
+    // fixtures/pe/RtkApo.dll 中资源节在文件中的位置，资源目录依次为类型、名称、语言三层，之后是数据项
+    const RESOURCES: usize = 0x400;
+    const DATA_ENTRY: usize = RESOURCES + 72;
+
+    fn fixture() -> Vec<u8> {
+        fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/pe/RtkApo.dll")).unwrap()
+    }
+
+    fn version_error(data: Vec<u8>) -> String {
+        PeFile::parse(data).unwrap().version_info().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn fixture_headers_are_read() {
+        let pe = PeFile::parse(fixture()).unwrap();
+        assert_eq!(pe.machine, Machine::I386);
+        assert!(!pe.pe32_plus);
+        assert!(pe.is_dll());
+        assert_eq!(pe.subsystem, Subsystem::WindowsGui);
+        assert_eq!(pe.link_time().unwrap().to_rfc3339(), "2012-07-26T00:11:44+00:00");
+        let names: Vec<&str> = pe.sections.iter().map(|section| section.name.as_str()).collect();
+        assert_eq!(names, [".text", ".rsrc"]);
+        assert_eq!(pe.rva_to_offset(0x2004), Some(RESOURCES + 4));
+        assert_eq!(pe.checksum_matches(), Some(true));
+        assert!(pe.directory(DIRECTORY_SECURITY).is_none());
+    }
+
+    #[test]
+    fn fixture_version_resource_is_read() {
+        let info = PeFile::parse(fixture()).unwrap().version_info().unwrap().unwrap();
+        assert_eq!(info.file_version_text(Locale::EnUs).as_deref(), Some("6.2.9200.16384"));
+        assert_eq!(info.translations, [(0x0409, 1200), (0x0404, 1200)]);
+        assert_eq!(info.string("FileDescription", Locale::EnUs), Some("Realtek HD Audio Property Page"));
+        // 没有简体中文的表时使用同一主语言的繁体中文表，其中缺少的键再按 Translation 的顺序查找
+        assert_eq!(info.string("FileDescription", Locale::ZhCn), Some("瑞昱高清晰音訊屬性頁"));
+        assert_eq!(info.string("CompanyName", Locale::ZhCn), Some("Realtek Semiconductor Corp."));
+    }
+
+    #[test]
+    fn driver_package_fixture_is_pe32_plus() {
+        let pe = PeFile::open(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/packages/nvidia/nvlddmkm.sys")).unwrap();
+        assert_eq!(pe.machine, Machine::Amd64);
+        assert!(pe.pe32_plus);
+        assert_eq!(pe.subsystem, Subsystem::Native);
+        assert!(pe.directory(DIRECTORY_SECURITY).is_some());
+        let info = pe.version_info().unwrap().unwrap();
+        assert_eq!(info.file_version.unwrap().to_string(), "32.0.15.6094");
+    }
+
+    #[test]
+    fn changed_content_fails_checksum() {
+        let mut data = fixture();
+        data[0x201] ^= 0xFF;
+        assert_eq!(PeFile::parse(data).unwrap().checksum_matches(), Some(false));
+    }
+
+    #[test]
+    fn truncated_files_are_rejected_without_panicking() {
+        let data = fixture();
+        // 资源之后只有节的对齐填充
+        let resources_end = RESOURCES + PeFile::parse(data.clone()).unwrap().directory(DIRECTORY_RESOURCE).unwrap().size as usize;
+        for length in 0..resources_end {
+            let Ok(pe) = PeFile::parse(data[..length].to_vec()) else {
+                continue;
+            };
+            // 节表完整时仍能读取文件头，但资源不完整
+            assert!(length >= 0x80 + 4 + COFF_HEADER_SIZE + 224 + 2 * SECTION_HEADER_SIZE, "{}", length);
+            assert!(pe.version_info().is_err(), "{}", length);
+        }
+        assert!(PeFile::parse(data[..0x100].to_vec()).unwrap_err().to_string().contains("不完整"));
+        assert!(PeFile::parse(b"MZ".to_vec()).is_err());
+        assert!(PeFile::parse(b"not a PE file".to_vec()).unwrap_err().to_string().contains("MZ"));
+    }
+
+    #[test]
+    fn malformed_resource_directories_are_rejected() {
+        // 子目录指回根目录
+        let mut data = fixture();
+        data[RESOURCES + 20..RESOURCES + 24].copy_from_slice(&0x8000_0000u32.to_le_bytes());
+        assert!(version_error(data).contains("资源目录结构无效"));
+
+        // 目录项数超出资源节，且前面的项都不是版本资源
+        let mut data = fixture();
+        data[RESOURCES + 14..RESOURCES + 16].copy_from_slice(&0xFFFFu16.to_le_bytes());
+        data[RESOURCES + 16..RESOURCES + 20].copy_from_slice(&3u32.to_le_bytes());
+        assert!(PeFile::parse(data).unwrap().version_info().is_err());
+
+        // 数据项指向文件之外
+        let mut data = fixture();
+        data[DATA_ENTRY..DATA_ENTRY + 4].copy_from_slice(&0x2F00u32.to_le_bytes());
+        assert!(version_error(data).contains("版本资源超出文件范围"));
+
+        // 数据目录表中记录的资源目录大小超出文件
+        let mut data = fixture();
+        let entry = PeFile::parse(data.clone()).unwrap().directory_entry_offset(DIRECTORY_RESOURCE).unwrap();
+        data[entry + 4..entry + 8].copy_from_slice(&0x10000u32.to_le_bytes());
+        assert!(version_error(data).contains("资源目录超出文件范围"));
+
+        // 版本资源的块长度超出数据
+        let mut data = fixture();
+        data[RESOURCES + 88..RESOURCES + 90].copy_from_slice(&0x7FFFu16.to_le_bytes());
+        assert!(version_error(data).contains("版本资源结构无效"));
+    }
+
+    #[test]
+    fn missing_version_resource_is_none() {
+        let mut data = fixture();
+        // 把类型 RT_VERSION 改为 RT_ICON
+        data[RESOURCES + 16..RESOURCES + 20].copy_from_slice(&3u32.to_le_bytes());
+        assert!(PeFile::parse(data).unwrap().version_info().unwrap().is_none());
+    }
+}
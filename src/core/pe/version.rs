@@ -0,0 +1,334 @@
+// 版本资源（VS_VERSIONINFO）：由嵌套的块组成，每块依次为长度、值长度、类型、UTF-16 键名和值，
+// 子块紧随其后，各部分按4字节对齐。固定部分是数字版本号，StringFileInfo 下按“语言+代码页”分表存放字符串
+
+use std::fmt;
+use serde::Serialize;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::locale::Locale;
+use super::{read_u16, read_u32};
+
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+const FIXED_FILE_INFO_SIZE: usize = 52;
+
+// 块的嵌套层级：根、StringFileInfo、StringTable、String
+const MAX_DEPTH: usize = 4;
+
+// 语言标识的低10位为主语言
+const PRIMARY_LANGUAGE_MASK: u16 = 0x03FF;
+
+// 没有匹配的语言时优先使用美国英语
+const LANG_EN_US: u16 = 0x0409;
+
+/// 四段数字版本号，如 31.0.15.3623
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct FixedVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub build: u16,
+    pub revision: u16,
+}
+
+impl FixedVersion {
+    /// 由 VS_FIXEDFILEINFO 中成对的高、低32位构造
+    fn from_parts(high: u32, low: u32) -> Self {
+        Self {
+            major: (high >> 16) as u16,
+            minor: high as u16,
+            build: (low >> 16) as u16,
+            revision: low as u16,
+        }
+    }
+}
+
+impl fmt::Display for FixedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}.{}", self.major, self.minor, self.build, self.revision)
+    }
+}
+
+/// 一种语言和代码页下的字符串表
+#[derive(Debug, Clone, Serialize)]
+pub struct StringTable {
+    pub language: u16,
+    pub codepage: u16,
+    /// 按资源中的顺序保存的键值对，如 ("CompanyName", "NVIDIA Corporation")
+    pub strings: Vec<(String, String)>,
+}
+
+impl StringTable {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VersionInfo {
+    pub file_version: Option<FixedVersion>,
+    pub product_version: Option<FixedVersion>,
+    /// VS_FIXEDFILEINFO 中的 FileFlags 与 FileFlagsMask 相与后的值（调试版、预发布版等）
+    pub file_flags: u32,
+    pub string_tables: Vec<StringTable>,
+    /// VarFileInfo\Translation 中列出的 (语言, 代码页)
+    pub translations: Vec<(u16, u16)>,
+}
+
+impl VersionInfo {
+    pub fn parse(data: &[u8]) -> HamsterResult<Self> {
+        let root = Block::parse(data, 0)?;
+        if root.key != "VS_VERSION_INFO" {
+            return Err(HamsterError::parse(format!("版本资源的键名无效: {}", root.key)));
+        }
+
+        let mut info = VersionInfo::default();
+        if root.value.len() >= FIXED_FILE_INFO_SIZE && read_u32(root.value, 0) == Some(FIXED_FILE_INFO_SIGNATURE) {
+            let field = |index: usize| read_u32(root.value, index * 4).unwrap_or(0);
+            info.file_version = Some(FixedVersion::from_parts(field(2), field(3)));
+            info.product_version = Some(FixedVersion::from_parts(field(4), field(5)));
+            info.file_flags = field(7) & field(6);
+        }
+
+        for child in root.children(data, 1)? {
+            match child.key.as_str() {
+                "StringFileInfo" => {
+                    for table in child.children(data, 2)? {
+                        let (language, codepage) = parse_table_key(&table.key).unwrap_or((0, 0));
+                        let strings = table.children(data, 3)?
+                            .into_iter()
+                            .map(|entry| {
+                                let text = entry.text();
+                                (entry.key, text)
+                            })
+                            .collect();
+                        info.string_tables.push(StringTable { language, codepage, strings });
+                    }
+                }
+                "VarFileInfo" => {
+                    for var in child.children(data, 2)? {
+                        if var.key == "Translation" {
+                            info.translations.extend(var.value.chunks_exact(4).map(|pair| {
+                                (u16::from_le_bytes([pair[0], pair[1]]), u16::from_le_bytes([pair[2], pair[3]]))
+                            }));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    /// 按界面语言查找字符串：先找同一语言的表，再找同一主语言的表，
+    /// 然后依次为 Translation 中列出的语言、美国英语和其余各表
+    pub fn string(&self, key: &str, locale: Locale) -> Option<&str> {
+        self.tables_by_preference(locale.lang_id())
+            .into_iter()
+            .find_map(|table| table.get(key))
+    }
+
+    fn tables_by_preference(&self, lang_id: u16) -> Vec<&StringTable> {
+        let rank = |table: &StringTable| {
+            if table.language == lang_id {
+                0
+            } else if table.language & PRIMARY_LANGUAGE_MASK == lang_id & PRIMARY_LANGUAGE_MASK {
+                1
+            } else if let Some(position) = self.translations.iter().position(|&(language, codepage)| {
+                language == table.language && codepage == table.codepage
+            }) {
+                2 + position
+            } else if table.language == LANG_EN_US {
+                2 + self.translations.len()
+            } else {
+                3 + self.translations.len()
+            }
+        };
+        let mut tables: Vec<&StringTable> = self.string_tables.iter().collect();
+        // 稳定排序，同等优先级时保持资源中的顺序
+        tables.sort_by_key(|table| rank(table));
+        tables
+    }
+
+    /// 文件版本：优先使用数字版本号，字符串形式的版本常带有额外说明（如 "built by: ..."）
+    pub fn file_version_text(&self, locale: Locale) -> Option<String> {
+        self.file_version.map(|version| version.to_string())
+            .or_else(|| self.string("FileVersion", locale).map(str::to_string))
+    }
+
+    pub fn product_version_text(&self, locale: Locale) -> Option<String> {
+        self.product_version.map(|version| version.to_string())
+            .or_else(|| self.string("ProductVersion", locale).map(str::to_string))
+    }
+}
+
+/// StringTable 的键为8位十六进制数，前4位为语言、后4位为代码页，如 "080404b0"
+fn parse_table_key(key: &str) -> Option<(u16, u16)> {
+    if key.len() != 8 || !key.is_ascii() {
+        return None;
+    }
+    Some((u16::from_str_radix(&key[..4], 16).ok()?, u16::from_str_radix(&key[4..], 16).ok()?))
+}
+
+struct Block<'a> {
+    key: String,
+    value: &'a [u8],
+    /// 子块的起止位置
+    children_start: usize,
+    end: usize,
+}
+
+impl<'a> Block<'a> {
+    fn parse(data: &'a [u8], offset: usize) -> HamsterResult<Self> {
+        let invalid = || HamsterError::parse("版本资源结构无效");
+        let length = read_u16(data, offset).ok_or_else(invalid)? as usize;
+        let value_length = read_u16(data, offset + 2).ok_or_else(invalid)? as usize;
+        let value_type = read_u16(data, offset + 4).ok_or_else(invalid)?;
+        let end = offset + length;
+        if length < 6 || end > data.len() {
+            return Err(invalid());
+        }
+
+        let mut position = offset + 6;
+        let mut units = Vec::new();
+        loop {
+            let unit = read_u16(&data[..end], position).ok_or_else(invalid)?;
+            position += 2;
+            if unit == 0 {
+                break;
+            }
+            units.push(unit);
+        }
+        let key = String::from_utf16_lossy(&units);
+
+        // 文本值的长度以字符计；不少文件把它写成了字节数，所以截断到块的末尾
+        let value_start = align4(position).min(end);
+        let value_size = if value_type == 1 { value_length * 2 } else { value_length };
+        let value_end = (value_start + value_size).min(end);
+        Ok(Self {
+            key,
+            value: &data[value_start..value_end],
+            children_start: align4(value_end).min(end),
+            end,
+        })
+    }
+
+    fn children(&self, data: &'a [u8], depth: usize) -> HamsterResult<Vec<Block<'a>>> {
+        if depth >= MAX_DEPTH {
+            return Ok(Vec::new());
+        }
+        let data = &data[..self.end];
+        let mut children = Vec::new();
+        let mut offset = self.children_start;
+        // 末尾的对齐填充不足一个块头
+        while offset + 6 <= self.end {
+            if read_u16(data, offset) == Some(0) {
+                break;
+            }
+            let child = Block::parse(data, offset)?;
+            offset = align4(child.end);
+            children.push(child);
+        }
+        Ok(children)
+    }
+
+    /// 值按 UTF-16 文本解释，截止到第一个空字符
+    fn text(&self) -> String {
+        let units: Vec<u16> = self.value.chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect();
+        String::from_utf16_lossy(&units).trim().to_string()
+    }
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pad4(mut data: Vec<u8>) -> Vec<u8> {
+        data.resize(align4(data.len()), 0);
+        data
+    }
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect()
+    }
+
+    /// 一个版本资源块；value_length 为块头中记录的值长度
+    fn block(key: &str, value: &[u8], value_length: u16, value_type: u16, children: &[u8]) -> Vec<u8> {
+        let mut data = pad4([[0u8; 2], value_length.to_le_bytes(), value_type.to_le_bytes()].concat().into_iter().chain(utf16(key)).collect());
+        data.extend_from_slice(value);
+        if !children.is_empty() {
+            data = pad4(data);
+            data.extend_from_slice(children);
+        }
+        let length = data.len() as u16;
+        data[..2].copy_from_slice(&length.to_le_bytes());
+        data
+    }
+
+    fn string(key: &str, value: &str) -> Vec<u8> {
+        let value = utf16(value);
+        pad4(block(key, &value, (value.len() / 2) as u16, 1, &[]))
+    }
+
+    fn version_info(tables: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let tables: Vec<u8> = tables.iter().flat_map(|(key, strings)| pad4(block(key, &[], 0, 1, strings))).collect();
+        let string_file_info = pad4(block("StringFileInfo", &[], 0, 1, &tables));
+        block("VS_VERSION_INFO", &[], 0, 0, &string_file_info)
+    }
+
+    #[test]
+    fn strings_without_fixed_info_are_read() {
+        let data = version_info(&[
+            ("080404b0", [string("CompanyName", "瑞昱半导体"), string("FileVersion", " 6.0.1.8 ")].concat()),
+            ("040904b0", string("CompanyName", "Realtek")),
+        ]);
+        let info = VersionInfo::parse(&data).unwrap();
+        assert!(info.file_version.is_none());
+        assert_eq!((info.string_tables[0].language, info.string_tables[0].codepage), (0x0804, 1200));
+        assert_eq!(info.string("CompanyName", Locale::ZhCn), Some("瑞昱半导体"));
+        assert_eq!(info.string("companyname", Locale::EnUs), Some("Realtek"));
+        // 没有数字版本号时使用去掉空白的字符串版本
+        assert_eq!(info.file_version_text(Locale::EnUs).as_deref(), Some("6.0.1.8"));
+    }
+
+    #[test]
+    fn value_length_in_bytes_is_truncated_to_the_block() {
+        let value = utf16("Realtek");
+        // 值长度误写为字节数，按字符数计会超出块
+        let entry = pad4(block("CompanyName", &value, value.len() as u16, 1, &[]));
+        let info = VersionInfo::parse(&version_info(&[("040904b0", entry)])).unwrap();
+        assert_eq!(info.string("CompanyName", Locale::EnUs), Some("Realtek"));
+    }
+
+    #[test]
+    fn malformed_blocks_are_rejected() {
+        let data = version_info(&[("040904b0", string("CompanyName", "Realtek"))]);
+        assert!(VersionInfo::parse(&data[..data.len() - 4]).is_err());
+        assert!(VersionInfo::parse(&[4, 0, 0, 0]).is_err());
+
+        let mut wrong_key = data.clone();
+        wrong_key[6..8].copy_from_slice(&u16::from(b'X').to_le_bytes());
+        assert!(VersionInfo::parse(&wrong_key).unwrap_err().to_string().contains("键名无效"));
+
+        // 子块长度超出父块
+        let mut child_too_long = data.clone();
+        let key: Vec<u8> = "StringFileInfo".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let child = data.windows(key.len()).position(|window| window == key).unwrap() - 6;
+        child_too_long[child..child + 2].copy_from_slice(&0x1000u16.to_le_bytes());
+        assert!(VersionInfo::parse(&child_too_long).is_err());
+    }
+
+    #[test]
+    fn table_keys_are_language_and_codepage() {
+        assert_eq!(parse_table_key("080404b0"), Some((0x0804, 0x04B0)));
+        assert_eq!(parse_table_key("0804"), None);
+        assert_eq!(parse_table_key("0804zzzz"), None);
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::core::driver_manager::DriverInfo;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::features::driver_searcher::DeviceInfo;
+use crate::core::sysinfo::{HardwareItem, SystemInfo};
+use super::{CommandOutput, Platform};
+
+/// 夹具中预先定义的命令及其输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureCommand {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(flatten)]
+    pub output: CommandOutput,
+}
+
+/// 夹具文件的JSON结构
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FixtureData {
+    pub name: Option<String>,
+    pub drivers: Vec<DriverInfo>,
+    pub devices: Vec<DeviceInfo>,
+    pub system_info: Option<SystemInfo>,
+    pub monitors: Vec<HardwareItem>,
+    pub commands: Vec<FixtureCommand>,
+}
+
+/// 从JSON夹具加载数据的平台实现，不访问真实系统
+#[derive(Debug, Default)]
+pub struct FixturePlatform {
+    name: String,
+    data: FixtureData,
+    // 记录已执行的命令，便于检查调用方行为
+    executed_commands: Mutex<Vec<String>>,
+}
+
+impl FixturePlatform {
+    pub fn new(data: FixtureData) -> Self {
+        Self {
+            name: data.name.clone().unwrap_or_else(|| "fixture".to_string()),
+            data,
+            executed_commands: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn from_json_str(json: &str) -> HamsterResult<Self> {
+        let data: FixtureData = serde_json::from_str(json)
+            .map_err(|e| HamsterError::parse("解析夹具数据失败").with_source(e))?;
+        Ok(Self::new(data))
+    }
+
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> HamsterResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| HamsterError::io(format!("无法读取夹具文件 {:?}", path), e))?;
+        Self::from_json_str(&content)
+    }
+
+    pub fn data(&self) -> &FixtureData {
+        &self.data
+    }
+
+    /// 已执行命令的列表，每项为 "程序 参数1 参数2 ..." 形式
+    pub fn executed_commands(&self) -> Vec<String> {
+        self.executed_commands.lock()
+            .map(|commands| commands.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Platform for FixturePlatform {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn enumerate_drivers(&self) -> HamsterResult<Vec<DriverInfo>> {
+        Ok(self.data.drivers.clone())
+    }
+
+    fn enumerate_devices(&self) -> HamsterResult<Vec<DeviceInfo>> {
+        Ok(self.data.devices.clone())
+    }
+
+    fn hardware_inventory(&self) -> HamsterResult<SystemInfo> {
+        let mut info = self.data.system_info.clone()
+            .ok_or_else(|| HamsterError::platform("夹具中没有系统信息"))?;
+        if info.monitor_info.is_empty() {
+            info.monitor_info = self.monitors()?;
+        }
+        Ok(info)
+    }
+
+    fn monitors(&self) -> HamsterResult<Vec<HardwareItem>> {
+        Ok(self.data.monitors.clone())
+    }
+
+    fn run_command(&self, program: &str, args: &[&str]) -> HamsterResult<CommandOutput> {
+        let command_line = std::iter::once(program)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Ok(mut executed) = self.executed_commands.lock() {
+            executed.push(command_line.clone());
+        }
+
+        self.data.commands.iter()
+            .find(|command| {
+                command.program.eq_ignore_ascii_case(program) &&
+                command.args.len() == args.len() &&
+                command.args.iter().zip(args).all(|(expected, actual)| expected == actual)
+            })
+            .map(|command| command.output.clone())
+            .ok_or_else(|| HamsterError::platform(format!("夹具中未定义命令: {}", command_line)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> FixturePlatform {
+        FixturePlatform::from_json_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/platform/sample.json")).unwrap()
+    }
+
+    #[test]
+    fn sample_fixture_provides_system_data() {
+        let platform = sample();
+        assert_eq!(platform.name(), "sample-fixture");
+        assert_eq!(platform.enumerate_drivers().unwrap().len(), 3);
+        let devices = platform.enumerate_devices().unwrap();
+        assert_eq!(devices.len(), 12);
+        assert_eq!(devices[0].hardware_id, "PCI\\VEN_10DE&DEV_2504&SUBSYS_14621043&REV_A1");
+        // 系统信息中没有显示器时取夹具中单独列出的显示器
+        let info = platform.hardware_inventory().unwrap();
+        assert_eq!(info.monitor_info.len(), 1);
+    }
+
+    #[test]
+    fn commands_are_recorded_and_undefined_ones_fail() {
+        let platform = sample();
+        assert!(platform.run_command("sc", &["stop", "MockDriver1"]).unwrap().success);
+        assert!(platform.run_command("sc", &["start", "MockDriver1"]).is_err());
+        assert_eq!(platform.executed_commands(), ["sc stop MockDriver1", "sc start MockDriver1"]);
+    }
+}
@@ -0,0 +1,335 @@
+// 硬件清单：从 /etc/os-release、/proc 和 /sys 读取与 Windows WMI 查询相同的概览信息，
+// 文本格式与 Windows 实现保持一致，概览页无需区分平台
+
+use crate::core::edid::{format_edid_info, parse_edid};
+use crate::core::error::HamsterResult;
+use crate::core::sysinfo::{HardwareItem, SystemInfo};
+use super::LinuxPlatform;
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+pub(super) fn inventory(platform: &LinuxPlatform) -> HamsterResult<SystemInfo> {
+    let os_release = os_release(platform);
+    let os_field = |key: &str| os_release.iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.clone());
+
+    Ok(SystemInfo {
+        os_name: os_field("PRETTY_NAME").or_else(|| os_field("NAME")),
+        os_version: os_field("VERSION_ID").or_else(|| os_field("VERSION")),
+        os_version_formatted: platform.kernel_release().ok(),
+        manufacturer: platform.read_trimmed("/sys/class/dmi/id/sys_vendor"),
+        motherboard: platform.read_trimmed("/sys/class/dmi/id/board_name"),
+        cpu: cpu_name(platform),
+        memory_info: memory_info(platform),
+        disk_info: disk_info(platform),
+        gpu_info: gpu_info(platform),
+        network_adapters: network_adapters(platform),
+        monitor_info: monitors(platform),
+        audio_info: audio_info(platform),
+    })
+}
+
+/// 解析 os-release 的 `键=值` 行，值可带引号
+pub fn parse_os_release(content: &str) -> Vec<(String, String)> {
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            (key.trim().to_string(), value.replace("\\\"", "\""))
+        })
+        .collect()
+}
+
+/// /etc/os-release 不存在时按规范回退到 /usr/lib/os-release
+fn os_release(platform: &LinuxPlatform) -> Vec<(String, String)> {
+    platform.read_file("/etc/os-release")
+        .or_else(|_| platform.read_file("/usr/lib/os-release"))
+        .map(|content| parse_os_release(&content))
+        .unwrap_or_default()
+}
+
+/// /proc/cpuinfo 中第一个处理器的型号；ARM 等平台没有 model name 时使用 Hardware 或 Processor
+fn cpu_name(platform: &LinuxPlatform) -> Option<String> {
+    let content = platform.read_file("/proc/cpuinfo").ok()?;
+    let field = |key: &str| content.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim() == key)
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    field("model name").or_else(|| field("Hardware")).or_else(|| field("Processor"))
+}
+
+/// /proc/meminfo 只提供总量，读取各内存条信息需要 root 权限访问 DMI 表，这里不读取
+fn memory_info(platform: &LinuxPlatform) -> Vec<HardwareItem> {
+    let total_kb = platform.read_file("/proc/meminfo").ok()
+        .and_then(|content| content.lines()
+            .find_map(|line| line.strip_prefix("MemTotal:"))
+            .and_then(|value| value.split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok()));
+
+    let text = match total_kb {
+        Some(kb) => format!("总内存: {} GB", (kb as f64 * 1024.0 / GIB).round() as u32),
+        None => "未知内存".to_string(),
+    };
+    vec![item(text, "assets/icons/memory.svg")]
+}
+
+/// /sys/block 下有 device 链接的块设备，loop、ram、dm 等虚拟设备没有
+fn disk_info(platform: &LinuxPlatform) -> Vec<HardwareItem> {
+    let mut disk_info = Vec::new();
+    for name in platform.list_dir("/sys/block") {
+        let dir = format!("/sys/block/{}", name);
+        if !platform.resolve(&format!("{}/device", dir)).exists() {
+            continue;
+        }
+        // size 以512字节扇区为单位，与设备的逻辑扇区大小无关
+        let capacity_gb = platform.read_trimmed(&format!("{}/size", dir))
+            .and_then(|sectors| sectors.parse::<u64>().ok())
+            .map(|sectors| (sectors as f64 * 512.0 / GIB).round() as u64)
+            .unwrap_or(0);
+        let model = platform.read_trimmed(&format!("{}/device/model", dir))
+            .unwrap_or_else(|| "未知型号".to_string());
+        // SATA 硬盘的 vendor 固定为 "ATA"，NVMe 没有 vendor，都以型号的第一个词作为制造商
+        let manufacturer = platform.read_trimmed(&format!("{}/device/vendor", dir))
+            .filter(|vendor| vendor != "ATA" && !vendor.starts_with("0x"))
+            .or_else(|| model.split_whitespace().next().filter(|_| model != "未知型号").map(str::to_string))
+            .unwrap_or_else(|| "未知制造商".to_string());
+
+        let removable = platform.read_trimmed(&format!("{}/removable", dir)).as_deref() == Some("1");
+        let rotational = platform.read_trimmed(&format!("{}/queue/rotational", dir)).as_deref() == Some("1");
+        let (disk_type, icon_path) = if removable || is_usb_device(platform, &dir) {
+            ("U盘", "assets/icons/usb-symbol.svg")
+        } else if rotational {
+            ("机械", "assets/icons/device-hdd.svg")
+        } else {
+            ("固态", "assets/icons/device-ssd.svg")
+        };
+
+        disk_info.push(item(
+            format!("硬盘{}：{}-{}-{}GB-{}", disk_info.len() + 1, manufacturer, model, capacity_gb, disk_type),
+            icon_path,
+        ));
+    }
+
+    if disk_info.is_empty() {
+        disk_info.push(item("未知硬盘".to_string(), "assets/icons/device-hdd.svg"));
+    }
+    disk_info
+}
+
+/// /sys/class/drm 下的 cardN 为显卡，cardN-接口名 为其输出端口
+fn gpu_info(platform: &LinuxPlatform) -> Vec<HardwareItem> {
+    let mut gpu_info = Vec::new();
+    for card in platform.list_dir("/sys/class/drm").iter().filter(|name| is_card(name)) {
+        let device = format!("/sys/class/drm/{}/device", card);
+        let Some((vendor, device_id)) = pci_id(platform, &device) else {
+            continue;
+        };
+        let mut text = format!("显卡{}：{}+{}", gpu_info.len() + 1,
+            pci_vendor_label(platform, vendor), device_label(platform, &device, vendor, device_id));
+        // 只有 amdgpu 等驱动提供显存大小
+        if let Some(bytes) = platform.read_trimmed(&format!("{}/mem_info_vram_total", device))
+            .and_then(|bytes| bytes.parse::<u64>().ok()) {
+            text.push_str(&format!("+{}GB", (bytes as f64 / GIB).round() as u64));
+        }
+        gpu_info.push(item(text, "assets/icons/gpu-card.svg"));
+    }
+
+    if gpu_info.is_empty() {
+        gpu_info.push(item("未知显卡".to_string(), "assets/icons/gpu-card.svg"));
+    }
+    gpu_info
+}
+
+/// /sys/class/drm 中状态为 connected 的输出端口，型号和分辨率来自其 EDID
+pub(super) fn monitors(platform: &LinuxPlatform) -> Vec<HardwareItem> {
+    let mut monitor_info = Vec::new();
+    for connector in platform.list_dir("/sys/class/drm").iter().filter(|name| name.contains('-')) {
+        let dir = format!("/sys/class/drm/{}", connector);
+        if platform.read_trimmed(&format!("{}/status", dir)).as_deref() != Some("connected") {
+            continue;
+        }
+        let text = match std::fs::read(platform.resolve(&format!("{}/edid", dir))).ok()
+            .and_then(|bytes| parse_edid(&bytes)) {
+            Some(edid) => format!("{}-{}", format_edid_info(&edid),
+                edid.supported_resolutions.first().map(String::as_str).unwrap_or("?x?@?Hz")),
+            // 没有 EDID 时显示接口名，如 card0-eDP-1
+            None => connector.clone(),
+        };
+        monitor_info.push(item(
+            format!("显示器{}：{}", monitor_info.len() + 1, text),
+            "assets/icons/display.svg",
+        ));
+    }
+
+    if monitor_info.is_empty() {
+        monitor_info.push(item("未检测到显示器信息".to_string(), "assets/icons/display.svg"));
+    }
+    monitor_info
+}
+
+/// /sys/class/net 下有 device 链接的接口为物理网卡，另加 /sys/class/bluetooth 中的蓝牙适配器
+fn network_adapters(platform: &LinuxPlatform) -> Vec<HardwareItem> {
+    let mut network_adapters = Vec::new();
+    for interface in platform.list_dir("/sys/class/net") {
+        let dir = format!("/sys/class/net/{}", interface);
+        let device = format!("{}/device", dir);
+        if !platform.resolve(&device).exists() {
+            continue;
+        }
+        // 无线网卡有 phy80211 链接，旧驱动只有 wireless 目录
+        let wireless = ["phy80211", "wireless"].iter()
+            .any(|name| platform.resolve(&format!("{}/{}", dir, name)).exists());
+        let (adapter_type, icon_path) = if wireless {
+            ("WiFi", "assets/icons/wifi.svg")
+        } else {
+            ("网卡", "assets/icons/ethernet.svg")
+        };
+        let manufacturer = pci_id(platform, &device)
+            .map(|(vendor, _)| pci_vendor_label(platform, vendor))
+            .unwrap_or_else(|| "未知制造商".to_string());
+        let name = match uevent_value(platform, &device, "DRIVER") {
+            Some(driver) => format!("{}（{}）", interface, driver),
+            None => interface.clone(),
+        };
+        // 网线未连接时 speed 为 -1 或无法读取
+        let speed_mbps = platform.read_trimmed(&format!("{}/speed", dir))
+            .and_then(|speed| speed.parse::<i64>().ok())
+            .filter(|speed| *speed > 0)
+            .unwrap_or(0);
+
+        network_adapters.push(item(
+            format!("{}：{}-{}-{}Mbps", adapter_type, manufacturer, name, speed_mbps),
+            icon_path,
+        ));
+    }
+
+    for adapter in platform.list_dir("/sys/class/bluetooth").iter().filter(|name| !name.contains(':')) {
+        let device = format!("/sys/class/bluetooth/{}/device", adapter);
+        let manufacturer = usb_vendor(platform, &device)
+            .map(|vendor| usb_vendor_label(platform, vendor))
+            .unwrap_or_else(|| "未知制造商".to_string());
+        network_adapters.push(item(
+            format!("蓝牙：{}-{}-0Mbps", manufacturer, adapter),
+            "assets/icons/bluetooth.svg",
+        ));
+    }
+
+    if network_adapters.is_empty() {
+        network_adapters.push(item("未知网络适配器".to_string(), "assets/icons/ethernet.svg"));
+    }
+    network_adapters
+}
+
+/// /sys/class/sound 下的 cardN；id 为 ALSA 声卡名称，如 PCH、NVidia
+fn audio_info(platform: &LinuxPlatform) -> Vec<HardwareItem> {
+    let mut audio_info = Vec::new();
+    for card in platform.list_dir("/sys/class/sound").iter().filter(|name| is_card(name)) {
+        let dir = format!("/sys/class/sound/{}", card);
+        let device = format!("{}/device", dir);
+        let manufacturer = pci_id(platform, &device)
+            .map(|(vendor, _)| pci_vendor_label(platform, vendor))
+            .or_else(|| usb_vendor(platform, &device).map(|vendor| usb_vendor_label(platform, vendor)))
+            .unwrap_or_else(|| "未知制造商".to_string());
+        let name = platform.read_trimmed(&format!("{}/id", dir)).unwrap_or_else(|| card.clone());
+        let driver = uevent_value(platform, &device, "DRIVER").unwrap_or_else(|| "未知驱动".to_string());
+
+        audio_info.push(item(
+            format!("声卡{}：{}-{}-{}", audio_info.len() + 1, manufacturer, name, driver),
+            "assets/icons/volume-off-fill.svg",
+        ));
+    }
+
+    if audio_info.is_empty() {
+        audio_info.push(item("未检测到声卡信息".to_string(), "assets/icons/volume-off-fill.svg"));
+    }
+    audio_info
+}
+
+/// drm 和 sound 类中的 cardN 条目
+fn is_card(name: &str) -> bool {
+    name.strip_prefix("card").is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 块设备是否挂在 USB 总线上（U盘的 removable 有时为0）；device 链接的实际路径中含有 usbN 控制器目录
+fn is_usb_device(platform: &LinuxPlatform, block_dir: &str) -> bool {
+    std::fs::canonicalize(platform.resolve(&format!("{}/device", block_dir)))
+        .map(|path| path.components().any(|component| component.as_os_str().to_str()
+            .and_then(|name| name.strip_prefix("usb"))
+            .is_some_and(|bus| !bus.is_empty() && bus.chars().all(|c| c.is_ascii_digit()))))
+        .unwrap_or(false)
+}
+
+/// 设备 uevent 文件中的 `键=值`
+fn uevent_value(platform: &LinuxPlatform, device: &str, key: &str) -> Option<String> {
+    platform.read_file(&format!("{}/uevent", device)).ok()?
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value.trim().to_string())
+}
+
+/// PCI 设备的厂商和设备ID，优先读取 vendor/device 文件，其次读取 uevent 的 PCI_ID
+fn pci_id(platform: &LinuxPlatform, device: &str) -> Option<(u16, u16)> {
+    let read_hex = |name: &str| platform.read_trimmed(&format!("{}/{}", device, name))
+        .and_then(|value| u16::from_str_radix(value.trim_start_matches("0x"), 16).ok());
+    if let (Some(vendor), Some(device_id)) = (read_hex("vendor"), read_hex("device")) {
+        return Some((vendor, device_id));
+    }
+    let (vendor, device_id) = uevent_value(platform, device, "PCI_ID")?.split_once(':')
+        .map(|(vendor, device_id)| (vendor.to_string(), device_id.to_string()))?;
+    Some((u16::from_str_radix(&vendor, 16).ok()?, u16::from_str_radix(&device_id, 16).ok()?))
+}
+
+/// USB 接口的 device 链接指向接口目录，厂商ID在上一级的 idVendor 中
+fn usb_vendor(platform: &LinuxPlatform, device: &str) -> Option<u16> {
+    [format!("{}/idVendor", device), format!("{}/../idVendor", device)].iter()
+        .find_map(|path| platform.read_trimmed(path))
+        .and_then(|value| u16::from_str_radix(&value, 16).ok())
+}
+
+/// 设备型号：pci.ids 中的名称，子系统有专门名称时附加在后；数据库中没有时显示 PCI ID
+fn device_label(platform: &LinuxPlatform, device: &str, vendor: u16, device_id: u16) -> String {
+    let pci = &platform.ids().pci;
+    let Some(name) = pci.device_name(vendor, device_id) else {
+        return match uevent_value(platform, device, "DRIVER") {
+            Some(driver) => format!("{:04x}:{:04x}（{}）", vendor, device_id, driver),
+            None => format!("{:04x}:{:04x}", vendor, device_id),
+        };
+    };
+    let subsystem = uevent_value(platform, device, "PCI_SUBSYS_ID")
+        .and_then(|value| {
+            let (subvendor, subdevice) = value.split_once(':')?;
+            Some((u16::from_str_radix(subvendor, 16).ok()?, u16::from_str_radix(subdevice, 16).ok()?))
+        })
+        .and_then(|(subvendor, subdevice)| pci.subsystem_name(vendor, device_id, subvendor, subdevice));
+    match subsystem {
+        Some(subsystem) => format!("{}（{}）", name, subsystem),
+        None => name.to_string(),
+    }
+}
+
+fn pci_vendor_label(platform: &LinuxPlatform, vendor: u16) -> String {
+    platform.ids().pci.vendor_name(vendor)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("厂商 {:04x}", vendor))
+}
+
+fn usb_vendor_label(platform: &LinuxPlatform, vendor: u16) -> String {
+    platform.ids().usb.vendor_name(vendor)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("厂商 {:04x}", vendor))
+}
+
+fn item(text: String, icon_path: &str) -> HardwareItem {
+    HardwareItem {
+        text,
+        icon_path: icon_path.to_string(),
+    }
+}
@@ -0,0 +1,177 @@
+// Linux 平台实现：从 procfs、sysfs 和 /lib/modules 读取系统信息
+//
+// 所有路径都相对于可配置的根目录解析，指向夹具目录树时可在任意系统上运行。
+
+pub mod hardware;
+pub mod modules;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use crate::core::driver_manager::DriverInfo;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::features::driver_searcher::DeviceInfo;
+use crate::core::hwids::HardwareIds;
+use crate::core::sysinfo::{HardwareItem, SystemInfo};
+use super::{CommandOutput, Platform};
+
+pub use modules::{KernelModule, ModuleState};
+
+#[derive(Debug, Clone)]
+pub struct LinuxPlatform {
+    root: PathBuf,
+    ids: Arc<HardwareIds>,
+}
+
+impl Default for LinuxPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinuxPlatform {
+    /// 读取当前系统
+    pub fn new() -> Self {
+        Self::with_root("/")
+    }
+
+    /// 以 root 作为系统根目录，proc、sys、lib/modules、etc 等均在其下查找
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            ids: Arc::new(HardwareIds::bundled()),
+        }
+    }
+
+    /// 使用指定的硬件ID数据库命名设备，默认为内置数据库
+    pub fn with_ids(mut self, ids: HardwareIds) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn ids(&self) -> &HardwareIds {
+        &self.ids
+    }
+
+    /// 根目录下的路径，path 为系统中的绝对路径如 "/proc/modules"
+    pub fn resolve(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    pub(crate) fn read_file(&self, path: &str) -> HamsterResult<String> {
+        let resolved = self.resolve(path);
+        std::fs::read_to_string(&resolved)
+            .map_err(|e| HamsterError::io(format!("无法读取 {}", resolved.display()), e))
+    }
+
+    /// 读取文件并去掉首尾空白，文件不存在、无法读取或内容为空时为 None
+    pub(crate) fn read_trimmed(&self, path: &str) -> Option<String> {
+        self.read_file(path).ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 目录中的条目名称，按名称排序；目录不存在时为空
+    pub(crate) fn list_dir(&self, path: &str) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(self.resolve(path))
+            .map(|entries| entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// 内核版本号，如 "6.8.0-45-generic"
+    pub fn kernel_release(&self) -> HamsterResult<String> {
+        Ok(self.read_file("/proc/sys/kernel/osrelease")?.trim().to_string())
+    }
+
+    /// 当前已加载的内核模块
+    pub fn kernel_modules(&self) -> HamsterResult<Vec<KernelModule>> {
+        modules::load_modules(self)
+    }
+}
+
+impl Platform for LinuxPlatform {
+    fn name(&self) -> &str {
+        "linux"
+    }
+
+    fn warnings(&self) -> Vec<String> {
+        self.ids.warnings.clone()
+    }
+
+    fn enumerate_drivers(&self) -> HamsterResult<Vec<DriverInfo>> {
+        let release = self.kernel_release().ok();
+        Ok(self.kernel_modules()?
+            .iter()
+            .map(|module| module.to_driver_info(release.as_deref()))
+            .collect())
+    }
+
+    fn enumerate_devices(&self) -> HamsterResult<Vec<DeviceInfo>> {
+        Err(HamsterError::platform("Linux 平台暂不支持枚举设备"))
+    }
+
+    fn hardware_inventory(&self) -> HamsterResult<SystemInfo> {
+        hardware::inventory(self)
+    }
+
+    fn monitors(&self) -> HamsterResult<Vec<HardwareItem>> {
+        Ok(hardware::monitors(self))
+    }
+
+    fn run_command(&self, program: &str, args: &[&str]) -> HamsterResult<CommandOutput> {
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| HamsterError::io(format!("执行命令 {} 失败", program), e))?;
+
+        Ok(CommandOutput {
+            success: output.status.success(),
+            status_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> LinuxPlatform {
+        LinuxPlatform::with_root(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/linux"))
+    }
+
+    #[test]
+    fn fixture_root_lists_modules_as_drivers() {
+        let platform = fixture();
+        assert_eq!(platform.kernel_release().unwrap(), "6.8.0-45-generic");
+        let drivers = platform.enumerate_drivers().unwrap();
+        let names: Vec<&str> = drivers.iter().map(|driver| driver.name.as_str()).collect();
+        assert_eq!(names, ["nvidia_drm", "nvidia", "drm_kms_helper", "drm", "iwlmvm", "mac80211", "cfg80211", "e1000e"]);
+        // modules-load.d 和 /etc/modules 中列出的模块开机自动加载
+        let start_type = |name: &str| drivers.iter().find(|driver| driver.name == name).unwrap().start_type.clone();
+        assert_eq!(start_type("nvidia"), "自动");
+        assert_eq!(start_type("e1000e"), "自动");
+        assert_eq!(start_type("drm"), "手动");
+    }
+
+    #[test]
+    fn fixture_root_describes_hardware() {
+        let info = fixture().hardware_inventory().unwrap();
+        assert_eq!(info.os_name.as_deref(), Some("Ubuntu 24.04.1 LTS"));
+        assert_eq!(info.os_version_formatted.as_deref(), Some("6.8.0-45-generic"));
+        assert_eq!(info.motherboard.as_deref(), Some("PRIME B460M-A"));
+        assert_eq!(info.cpu.as_deref(), Some("Intel(R) Core(TM) i5-10400 CPU @ 2.90GHz"));
+        assert_eq!(info.disk_info.len(), 3);
+        assert_eq!(info.monitor_info.len(), 1);
+    }
+}
@@ -0,0 +1,327 @@
+// 内核模块：合并 /proc/modules、/sys/module/<名称>/ 和 /lib/modules/<版本>/modules.dep 中的信息
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use crate::core::driver_manager::{DriverInfo, DriverStatus, DriverType};
+use crate::core::error::HamsterResult;
+use super::LinuxPlatform;
+
+// 声明开机自动加载模块的配置
+const MODULES_LOAD_DIRS: [&str; 3] = ["/etc/modules-load.d", "/usr/lib/modules-load.d", "/lib/modules-load.d"];
+const MODULES_FILE: &str = "/etc/modules";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum ModuleState {
+    Live,
+    Loading,
+    Unloading,
+    #[default]
+    Unknown,
+}
+
+impl ModuleState {
+    fn parse(state: &str) -> Self {
+        match state {
+            "Live" | "live" => ModuleState::Live,
+            "Loading" | "coming" => ModuleState::Loading,
+            "Unloading" | "going" => ModuleState::Unloading,
+            _ => ModuleState::Unknown,
+        }
+    }
+}
+
+/// 一个已加载的内核模块
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KernelModule {
+    pub name: String,
+    /// 模块占用的内存，字节
+    pub size: u64,
+    /// 内核不支持卸载模块时为 None
+    pub refcount: Option<u32>,
+    /// 使用本模块的模块
+    pub holders: Vec<String>,
+    /// 本模块依赖的模块
+    pub dependencies: Vec<String>,
+    pub state: ModuleState,
+    /// 内核污染标记，如 "POE"
+    pub taint: String,
+    pub parameters: BTreeMap<String, String>,
+    pub version: Option<String>,
+    pub srcversion: Option<String>,
+    /// 模块文件在系统中的路径
+    pub path: Option<PathBuf>,
+    pub modified: Option<DateTime<Local>>,
+    /// 是否配置为开机自动加载
+    pub autoload: bool,
+    /// 加载的先后次序，越小加载得越早；/proc/modules 中最后加载的模块在最前
+    pub load_index: usize,
+}
+
+/// modules.dep 中的一项
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleDepEntry {
+    /// 相对于 /lib/modules/<版本>/ 的路径
+    pub path: String,
+    pub dependencies: Vec<String>,
+}
+
+impl KernelModule {
+    pub fn is_unsigned(&self) -> bool {
+        self.taint.contains('E')
+    }
+
+    pub fn is_out_of_tree(&self) -> bool {
+        self.taint.contains('O')
+    }
+
+    pub fn is_proprietary(&self) -> bool {
+        self.taint.contains('P')
+    }
+
+    /// 引用计数、使用者、参数和污染标记的概要
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("大小 {} 字节", self.size)];
+        if let Some(refcount) = self.refcount {
+            parts.push(format!("引用计数 {}", refcount));
+        }
+        if !self.holders.is_empty() {
+            parts.push(format!("被 {} 使用", self.holders.join(", ")));
+        }
+        if !self.parameters.is_empty() {
+            let parameters: Vec<String> = self.parameters.iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            parts.push(format!("参数 {}", parameters.join(" ")));
+        }
+        if !self.taint.is_empty() {
+            parts.push(format!("污染标记 {}", self.taint));
+        }
+        parts.join("；")
+    }
+
+    /// 转换为与 Windows 驱动服务统一的 DriverInfo；release 为内核版本，模块没有自身版本时使用
+    pub fn to_driver_info(&self, release: Option<&str>) -> DriverInfo {
+        DriverInfo {
+            name: self.name.clone(),
+            display_name: self.name.clone(),
+            description: self.summary(),
+            status: match self.state {
+                ModuleState::Live => DriverStatus::Running,
+                _ => DriverStatus::Unknown,
+            },
+            driver_type: DriverType::KernelMode,
+            start_type: if self.autoload { "自动" } else { "手动" }.to_string(),
+            binary_path: self.path.as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "未知".to_string()),
+            version: self.version.clone()
+                .or_else(|| release.map(str::to_string))
+                .unwrap_or_else(|| "未知".to_string()),
+            company: if self.is_out_of_tree() { "第三方" } else { "Linux" }.to_string(),
+            signed: !self.is_unsigned(),
+            signature_status: if self.is_unsigned() {
+                "未签名（内核污染标记 E）"
+            } else {
+                "内核未报告签名问题"
+            }.to_string(),
+            last_updated: self.modified.unwrap_or_else(Local::now),
+            dependencies: self.dependencies.clone(),
+            load_order: self.load_index as u32,
+            group: String::new(),
+        }
+    }
+}
+
+/// 模块文件名对应的模块名：去掉 .ko 及压缩后缀，连字符替换为下划线
+pub fn module_name_from_path(path: &str) -> String {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let stem = [".ko.zst", ".ko.xz", ".ko.gz", ".ko"].iter()
+        .find_map(|suffix| file_name.strip_suffix(suffix))
+        .unwrap_or(file_name);
+    normalize_name(stem)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// 解析 /proc/modules：名称 大小 引用计数 使用者 状态 地址 [污染标记]
+pub fn parse_proc_modules(content: &str) -> Vec<KernelModule> {
+    let mut modules: Vec<KernelModule> = content.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 5 {
+                return None;
+            }
+            let holders = match fields[3] {
+                "-" => Vec::new(),
+                used_by => used_by.split(',').filter(|name| !name.is_empty()).map(str::to_string).collect(),
+            };
+            let taint = fields.iter().skip(5)
+                .find(|field| field.starts_with('('))
+                .map(|field| field.trim_matches(|c| c == '(' || c == ')').to_string())
+                .unwrap_or_default();
+            Some(KernelModule {
+                name: fields[0].to_string(),
+                size: fields[1].parse().unwrap_or(0),
+                refcount: fields[2].parse().ok(),
+                holders,
+                state: ModuleState::parse(fields[4]),
+                taint,
+                ..KernelModule::default()
+            })
+        })
+        .collect();
+    let count = modules.len();
+    for (index, module) in modules.iter_mut().enumerate() {
+        module.load_index = count - 1 - index;
+    }
+    modules
+}
+
+/// 解析 modules.dep：`路径: 依赖路径 依赖路径 ...`，按模块名索引
+pub fn parse_modules_dep(content: &str) -> HashMap<String, ModuleDepEntry> {
+    content.lines()
+        .filter_map(|line| {
+            let (path, dependencies) = line.split_once(':')?;
+            let path = path.trim();
+            if path.is_empty() {
+                return None;
+            }
+            Some((module_name_from_path(path), ModuleDepEntry {
+                path: path.to_string(),
+                dependencies: dependencies.split_whitespace().map(module_name_from_path).collect(),
+            }))
+        })
+        .collect()
+}
+
+/// 解析 modules-load.d 配置，每行一个模块名，# 和 ; 开头为注释
+pub fn parse_modules_load(content: &str) -> Vec<String> {
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';'))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(normalize_name)
+        .collect()
+}
+
+pub(super) fn load_modules(platform: &LinuxPlatform) -> HamsterResult<Vec<KernelModule>> {
+    let mut modules = parse_proc_modules(&platform.read_file("/proc/modules")?);
+
+    // 缺少 modules.dep 时仍可从 /proc/modules 的使用者关系得到依赖
+    let release = platform.kernel_release().ok();
+    let modules_dir = release.as_ref().map(|release| format!("/lib/modules/{}", release));
+    let dep_entries = modules_dir.as_ref()
+        .and_then(|dir| platform.read_file(&format!("{}/modules.dep", dir)).ok())
+        .map(|content| parse_modules_dep(&content))
+        .unwrap_or_default();
+    let autoload = autoload_modules(platform);
+
+    let mut reverse_dependencies: HashMap<String, Vec<String>> = HashMap::new();
+    for module in &modules {
+        for holder in &module.holders {
+            reverse_dependencies.entry(holder.clone()).or_default().push(module.name.clone());
+        }
+    }
+
+    for module in &mut modules {
+        read_sysfs(platform, module);
+        module.autoload = autoload.contains(&module.name);
+
+        if let Some(entry) = dep_entries.get(&module.name) {
+            module.dependencies = entry.dependencies.clone();
+            if let Some(dir) = &modules_dir {
+                let path = if entry.path.starts_with('/') {
+                    entry.path.clone()
+                } else {
+                    format!("{}/{}", dir, entry.path)
+                };
+                module.modified = std::fs::metadata(platform.resolve(&path))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(DateTime::<Local>::from);
+                module.path = Some(PathBuf::from(path));
+            }
+        }
+        for dependency in reverse_dependencies.remove(&module.name).unwrap_or_default() {
+            if !module.dependencies.contains(&dependency) {
+                module.dependencies.push(dependency);
+            }
+        }
+    }
+    Ok(modules)
+}
+
+/// 从 /sys/module/<名称>/ 补充引用计数、使用者、污染标记、参数和版本；读取失败的项保持原值
+fn read_sysfs(platform: &LinuxPlatform, module: &mut KernelModule) {
+    let dir = format!("/sys/module/{}", module.name);
+    let read = |name: &str| platform.read_file(&format!("{}/{}", dir, name))
+        .ok()
+        .map(|value| value.trim().to_string());
+
+    if let Some(refcount) = read("refcnt").and_then(|value| value.parse().ok()) {
+        module.refcount = Some(refcount);
+    }
+    if let Some(taint) = read("taint") {
+        module.taint = taint;
+    }
+    if let Some(state) = read("initstate") {
+        module.state = ModuleState::parse(&state);
+    }
+    module.version = read("version").filter(|value| !value.is_empty());
+    module.srcversion = read("srcversion").filter(|value| !value.is_empty());
+
+    let holders = platform.list_dir(&format!("{}/holders", dir));
+    if !holders.is_empty() {
+        module.holders = holders;
+    }
+    for name in platform.list_dir(&format!("{}/parameters", dir)) {
+        if let Some(value) = read(&format!("parameters/{}", name)) {
+            module.parameters.insert(name, value);
+        }
+    }
+}
+
+fn autoload_modules(platform: &LinuxPlatform) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for dir in MODULES_LOAD_DIRS {
+        for file in platform.list_dir(dir).iter().filter(|file| file.ends_with(".conf")) {
+            if let Ok(content) = platform.read_file(&format!("{}/{}", dir, file)) {
+                names.extend(parse_modules_load(&content));
+            }
+        }
+    }
+    if let Ok(content) = platform.read_file(MODULES_FILE) {
+        names.extend(parse_modules_load(&content));
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proc_modules_lists_latest_first() {
+        let modules = parse_proc_modules(include_str!("../../../../fixtures/linux/proc/modules"));
+        let order = |name: &str| modules.iter().find(|module| module.name == name).unwrap().load_index;
+        assert_eq!(modules.len(), 8);
+        // 依赖总是先于使用它的模块加载
+        assert!(order("drm") < order("drm_kms_helper"));
+        assert!(order("nvidia") < order("nvidia_drm"));
+        assert!(order("cfg80211") < order("mac80211"));
+        assert_eq!(order("e1000e"), 0);
+        assert_eq!(order("nvidia_drm"), 7);
+    }
+
+    #[test]
+    fn load_order_follows_load_index() {
+        let modules = parse_proc_modules("b 1 0 a, Live 0x0\na 1 1 - Live 0x0\n");
+        let info: Vec<DriverInfo> = modules.iter().map(|module| module.to_driver_info(None)).collect();
+        assert_eq!(info[0].name, "b");
+        assert!(info[1].load_order < info[0].load_order);
+    }
+}
@@ -0,0 +1,95 @@
+// 平台抽象层：将驱动、设备、硬件清单、显示器和命令执行等系统访问集中到 Platform trait，
+// core 中的各类型通过注入 Platform 实例工作，便于在非 Windows 环境下使用夹具数据运行
+
+pub mod fixture;
+pub mod linux;
+#[cfg(windows)]
+pub mod windows;
+
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::core::driver_manager::DriverInfo;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::features::driver_searcher::DeviceInfo;
+use crate::core::settings::Settings;
+use crate::core::sysinfo::{HardwareItem, SystemInfo};
+
+pub use fixture::FixturePlatform;
+pub use linux::LinuxPlatform;
+#[cfg(windows)]
+pub use self::windows::WindowsPlatform;
+
+/// 指定夹具文件路径的环境变量，设置后默认平台改为从该文件加载
+pub const FIXTURE_ENV_VAR: &str = "HAMSTER_PLATFORM_FIXTURE";
+
+/// 指定 Linux 系统根目录的环境变量，用于读取夹具目录树或挂载的其他系统
+pub const SYSROOT_ENV_VAR: &str = "HAMSTER_SYSROOT";
+
+/// 外部命令的执行结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub status_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 系统访问接口
+pub trait Platform: Send + Sync {
+    /// 平台名称，用于日志和界面显示
+    fn name(&self) -> &str;
+
+    /// 枚举系统中的驱动服务
+    fn enumerate_drivers(&self) -> HamsterResult<Vec<DriverInfo>>;
+
+    /// 枚举即插即用设备（排除CPU和内存）
+    fn enumerate_devices(&self) -> HamsterResult<Vec<DeviceInfo>>;
+
+    /// 获取电脑概览所需的硬件清单
+    fn hardware_inventory(&self) -> HamsterResult<SystemInfo>;
+
+    /// 获取显示器信息
+    fn monitors(&self) -> HamsterResult<Vec<HardwareItem>>;
+
+    /// 执行外部命令并等待其结束
+    fn run_command(&self, program: &str, args: &[&str]) -> HamsterResult<CommandOutput>;
+
+    /// 初始化时遇到的非致命问题，如无法使用的硬件ID数据库文件
+    fn warnings(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 以管理员身份重新启动当前程序，成功后调用方应退出当前进程
+    fn relaunch_elevated(&self) -> HamsterResult<()> {
+        Err(HamsterError::platform(format!("平台 {} 不支持以管理员身份重新运行", self.name())))
+    }
+}
+
+/// 根据运行环境创建默认平台实现
+///
+/// 设置了 `HAMSTER_PLATFORM_FIXTURE` 时优先加载夹具文件，否则在 Windows 和 Linux 上使用真实实现，
+/// Linux 上的根目录可通过 `HAMSTER_SYSROOT` 指定，设备名称使用设置中指定或根目录下系统自带的硬件ID数据库，
+/// 数据库文件不可用时改用内置数据库，原因见 [`Platform::warnings`]。
+pub fn default_platform(settings: &Settings) -> HamsterResult<Arc<dyn Platform>> {
+    if let Ok(path) = std::env::var(FIXTURE_ENV_VAR) {
+        return Ok(Arc::new(FixturePlatform::from_json_file(&path)?));
+    }
+
+    #[cfg(windows)]
+    {
+        Ok(Arc::new(WindowsPlatform::new().with_locale(settings.language)))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let root = std::path::PathBuf::from(std::env::var_os(SYSROOT_ENV_VAR).unwrap_or_else(|| "/".into()));
+        let ids = crate::core::hwids::HardwareIds::load(settings, &root);
+        Ok(Arc::new(LinuxPlatform::with_root(root).with_ids(ids)))
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        let _ = settings;
+        Ok(Arc::new(FixturePlatform::default()))
+    }
+}
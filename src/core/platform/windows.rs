@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+use wmi::{COMLibrary, Variant, WMIConnection};
+use crate::core::driver_manager::{DriverInfo, DriverStatus, DriverType};
+use crate::core::edid;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::features::driver_searcher::DeviceInfo;
+use crate::core::locale::Locale;
+use crate::core::sysinfo::{HardwareItem, SystemInfo};
+use crate::core::windows_api::driver_file::{resolve_image_path, DriverFileInfo};
+use super::{CommandOutput, Platform};
+
+// 创建进程时不弹出控制台窗口
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// 基于WMI和系统命令的Windows平台实现
+#[derive(Debug, Default)]
+pub struct WindowsPlatform {
+    // 读取驱动文件版本资源时优先选择的语言
+    locale: Locale,
+}
+
+impl WindowsPlatform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    // WMI连接不能跨线程共享，每次调用时单独创建
+    fn connect_wmi() -> HamsterResult<WMIConnection> {
+        let com_lib = COMLibrary::new().map_err(|e| HamsterError::platform("COM初始化失败").with_source(e))?;
+        WMIConnection::new(com_lib).map_err(|e| HamsterError::platform("WMI连接失败").with_source(e))
+    }
+
+    fn variant_string(row: &HashMap<String, Variant>, key: &str) -> Option<String> {
+        match row.get(key) {
+            Some(Variant::String(s)) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// 服务之间的依赖和加载顺序组，来自 WMI 的关联类；查询失败时为空，不影响驱动列表
+#[derive(Debug, Default)]
+struct ServiceRelations {
+    // 以小写的服务名为键；依赖的组名带 `+` 前缀，与服务控制管理器的写法一致
+    dependencies: HashMap<String, Vec<String>>,
+    groups: HashMap<String, String>,
+    // 以小写的组名为键，即 ServiceGroupOrder 中的位置
+    group_order: HashMap<String, u32>,
+}
+
+impl ServiceRelations {
+    fn query(wmi_con: &WMIConnection) -> Self {
+        let rows = |query: &str| -> Vec<HashMap<String, Variant>> { wmi_con.raw_query(query).unwrap_or_default() };
+        let mut relations = Self::default();
+        for row in rows("SELECT Antecedent, Dependent FROM Win32_DependentService") {
+            if let (Some(service), Some(dependent)) = (reference_name(&row, "Antecedent"), reference_name(&row, "Dependent")) {
+                relations.dependencies.entry(dependent.to_lowercase()).or_default().push(service);
+            }
+        }
+        for row in rows("SELECT Antecedent, Dependent FROM Win32_LoadOrderGroupServiceDependencies") {
+            if let (Some(group), Some(dependent)) = (reference_name(&row, "Antecedent"), reference_name(&row, "Dependent")) {
+                relations.dependencies.entry(dependent.to_lowercase()).or_default().push(format!("+{}", group));
+            }
+        }
+        for row in rows("SELECT GroupComponent, PartComponent FROM Win32_LoadOrderGroupServiceMembers") {
+            if let (Some(group), Some(service)) = (reference_name(&row, "GroupComponent"), reference_name(&row, "PartComponent")) {
+                relations.groups.insert(service.to_lowercase(), group);
+            }
+        }
+        for row in rows("SELECT Name, GroupOrder FROM Win32_LoadOrderGroup") {
+            if let (Some(name), Some(Variant::UI4(order))) = (WindowsPlatform::variant_string(&row, "Name"), row.get("GroupOrder")) {
+                relations.group_order.insert(name.to_lowercase(), *order);
+            }
+        }
+        relations
+    }
+
+    /// 所属组在 ServiceGroupOrder 中的位置；不属于任何组的服务在所有组之后加载
+    fn load_order(&self, service: &str) -> u32 {
+        self.groups.get(&service.to_lowercase())
+            .and_then(|group| self.group_order.get(&group.to_lowercase()))
+            .copied()
+            .unwrap_or(self.group_order.len() as u32)
+    }
+}
+
+/// WMI 对象路径中的名称，如 `\\PC\root\cimv2:Win32_SystemDriver.Name="ACPI"` → `ACPI`
+fn reference_name(row: &HashMap<String, Variant>, key: &str) -> Option<String> {
+    let path = WindowsPlatform::variant_string(row, key)?;
+    let (_, name) = path.split_once("Name=\"")?;
+    Some(name.strip_suffix('"')?.replace("\\\\", "\\"))
+}
+
+impl Platform for WindowsPlatform {
+    fn name(&self) -> &str {
+        "windows"
+    }
+
+    fn enumerate_drivers(&self) -> HamsterResult<Vec<DriverInfo>> {
+        let wmi_con = Self::connect_wmi()?;
+        let results: Vec<HashMap<String, Variant>> = wmi_con
+            .raw_query("SELECT Name, DisplayName, Description, State, StartMode, PathName, ServiceType FROM Win32_SystemDriver")
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
+        let system_root = PathBuf::from(std::env::var_os("SystemRoot").unwrap_or_else(|| r"C:\Windows".into()));
+        let relations = ServiceRelations::query(&wmi_con);
+
+        let drivers = results.iter().filter_map(|row| {
+            let name = Self::variant_string(row, "Name")?;
+            let status = match Self::variant_string(row, "State").as_deref() {
+                Some("Running") => DriverStatus::Running,
+                Some("Stopped") => DriverStatus::Stopped,
+                Some("Paused") => DriverStatus::Paused,
+                _ => DriverStatus::Unknown,
+            };
+            let driver_type = match Self::variant_string(row, "ServiceType").as_deref() {
+                Some("Kernel Driver") => DriverType::KernelMode,
+                Some("File System Driver") => DriverType::FileSystem,
+                _ => DriverType::Other,
+            };
+            let start_type = match Self::variant_string(row, "StartMode").as_deref() {
+                Some("Boot") => "引导",
+                Some("System") => "系统",
+                Some("Auto") => "自动",
+                Some("Manual") => "手动",
+                Some("Disabled") => "禁用",
+                _ => "未知",
+            }.to_string();
+
+            // 版本和厂商来自驱动文件的版本资源，文件无法读取时保持未知
+            let binary_path = Self::variant_string(row, "PathName").unwrap_or_default();
+            let file = DriverFileInfo::from_path(&resolve_image_path(&binary_path, &system_root), self.locale).ok();
+            let file_field = |field: fn(&DriverFileInfo) -> &String| file.as_ref()
+                .map(field)
+                .filter(|value| !value.is_empty())
+                .cloned();
+
+            Some(DriverInfo {
+                display_name: Self::variant_string(row, "DisplayName").unwrap_or_else(|| name.clone()),
+                description: Self::variant_string(row, "Description")
+                    .or_else(|| file_field(|file| &file.description))
+                    .unwrap_or_default(),
+                status,
+                driver_type,
+                start_type,
+                version: file_field(|file| &file.version).unwrap_or_else(|| "未知".to_string()),
+                company: file_field(|file| &file.company).unwrap_or_else(|| "未知".to_string()),
+                binary_path,
+                signed: false,
+                signature_status: "未验证".to_string(),
+                last_updated: chrono::Local::now(),
+                dependencies: relations.dependencies.get(&name.to_lowercase()).cloned().unwrap_or_default(),
+                load_order: relations.load_order(&name),
+                group: relations.groups.get(&name.to_lowercase()).cloned().unwrap_or_default(),
+                name,
+            })
+        }).collect();
+
+        Ok(drivers)
+    }
+
+    fn enumerate_devices(&self) -> HamsterResult<Vec<DeviceInfo>> {
+        let wmi_con = Self::connect_wmi()?;
+        let results: Vec<HashMap<String, Variant>> = wmi_con
+            .raw_query("SELECT DeviceName, Manufacturer, HardWareID, CompatID, DeviceClass, ClassGuid, DriverVersion, DriverDate, FriendlyName, Location FROM Win32_PnPSignedDriver")
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
+
+        let devices = results.iter().filter_map(|row| {
+            let device_description = Self::variant_string(row, "DeviceName")?;
+            let device_class = Self::variant_string(row, "DeviceClass").unwrap_or_default();
+            // 排除CPU和内存
+            if device_class.eq_ignore_ascii_case("PROCESSOR") || device_class.eq_ignore_ascii_case("MEMORY") {
+                return None;
+            }
+
+            Some(DeviceInfo {
+                manufacturer: Self::variant_string(row, "Manufacturer").unwrap_or_else(|| "未知厂商".to_string()),
+                hardware_id: Self::variant_string(row, "HardWareID").unwrap_or_default(),
+                compatible_ids: Self::variant_string(row, "CompatID").unwrap_or_default(),
+                device_class,
+                class_guid: Self::variant_string(row, "ClassGuid").unwrap_or_default(),
+                driver_version: Self::variant_string(row, "DriverVersion").unwrap_or_else(|| "未知版本".to_string()),
+                driver_date: Self::variant_string(row, "DriverDate").unwrap_or_default(),
+                friendly_name: Self::variant_string(row, "FriendlyName").unwrap_or_else(|| device_description.clone()),
+                location: Self::variant_string(row, "Location").unwrap_or_default(),
+                device_description,
+            }.with_generated_ids())
+        }).collect();
+
+        Ok(devices)
+    }
+
+    fn hardware_inventory(&self) -> HamsterResult<SystemInfo> {
+        SystemInfo::query_wmi(self)
+    }
+
+    fn monitors(&self) -> HamsterResult<Vec<HardwareItem>> {
+        let wmi_con = Self::connect_wmi()?;
+        edid::get_complete_monitor_info(self, &wmi_con)
+    }
+
+    fn run_command(&self, program: &str, args: &[&str]) -> HamsterResult<CommandOutput> {
+        let output = Command::new(program)
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| HamsterError::io(format!("执行命令 {} 失败", program), e))?;
+
+        Ok(CommandOutput {
+            success: output.status.success(),
+            status_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+
+    fn relaunch_elevated(&self) -> HamsterResult<()> {
+        let exe = std::env::current_exe()
+            .map_err(|e| HamsterError::io("无法获取当前程序路径", e))?;
+        // 通过UAC提示启动新进程，用户取消时Start-Process返回错误
+        let script = format!("Start-Process -FilePath '{}' -Verb RunAs", exe.display().to_string().replace('\'', "''"));
+        let output = self.run_command("powershell", &["-NoProfile", "-Command", &script])?;
+        if output.success {
+            Ok(())
+        } else {
+            Err(HamsterError::permission("以管理员身份启动失败或已被取消").with_source(output.stderr.trim().to_string()))
+        }
+    }
+}
@@ -0,0 +1,349 @@
+// 应用设置：以带版本号的JSON保存在用户配置目录中，读取时按版本迁移并校验，图形界面与命令行共用
+
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::core::audit::AuditJournal;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::locale::Locale;
+use crate::core::signature::trust::TrustStore;
+
+/// 当前设置文件格式版本
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// 设置为该环境变量时，配置和默认数据目录都使用其指定的目录
+pub const CONFIG_DIR_ENV: &str = "HAMSTER_CONFIG_DIR";
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+const JOURNAL_FILE_NAME: &str = "audit.jsonl";
+const SIGNATURE_CACHE_FILE_NAME: &str = "signature_cache.json";
+const APP_DIR_NAME: &str = "HamsterDriverManager";
+
+// 校验范围
+pub const MAX_CONCURRENCY: usize = 32;
+pub const MAX_HTTP_TIMEOUT_SECS: u64 = 600;
+
+/// 驱动更新检查策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdatePolicy {
+    /// 仅在用户点击搜索时检查
+    #[default]
+    Manual,
+    /// 程序启动时自动搜索驱动更新
+    CheckOnStartup,
+}
+
+impl UpdatePolicy {
+    pub const ALL: [UpdatePolicy; 2] = [UpdatePolicy::Manual, UpdatePolicy::CheckOnStartup];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpdatePolicy::Manual => "手动检查",
+            UpdatePolicy::CheckOnStartup => "启动时检查",
+        }
+    }
+}
+
+/// 界面主题
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    /// 跟随系统
+    System,
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "跟随系统",
+            Theme::Light => "浅色",
+            Theme::Dark => "深色",
+        }
+    }
+}
+
+/// 应用设置
+///
+/// 文件中缺失的字段取默认值，因此新增字段不需要提升版本号；
+/// 字段改名或含义变化时提升 [`SETTINGS_VERSION`] 并在 `migrate` 中补充迁移步骤。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// 备份集清单所在目录，单个驱动的备份文件位于其下的 drivers 子目录
+    pub backup_dir: PathBuf,
+    /// 安装驱动时使用的临时目录
+    pub temp_dir: PathBuf,
+    pub language: Locale,
+    /// HTTP/HTTPS 代理地址，如 `http://127.0.0.1:7890`；为空时直连
+    pub proxy: Option<String>,
+    pub http_timeout_secs: u64,
+    pub user_agent: String,
+    /// 签名验证等批量操作的并发数
+    pub concurrency: usize,
+    pub update_policy: UpdatePolicy,
+    pub theme: Theme,
+    /// 用户指定的 pci.ids / usb.ids，为空时使用系统自带或程序内置的数据库
+    pub pci_ids_path: Option<PathBuf>,
+    pub usb_ids_path: Option<PathBuf>,
+    /// 受信任的根证书（.cer/.crt/.pem）和证书吊销列表（.crl）所在目录，为空时不验证证书链
+    pub trust_store_dir: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            backup_dir: data_dir().join("backups"),
+            temp_dir: std::env::temp_dir().join("hamster_drivers"),
+            language: Locale::default(),
+            proxy: None,
+            http_timeout_secs: 30,
+            user_agent: "HamsterDriverManager/1.0 (compatible; Windows NT)".to_string(),
+            concurrency: 4,
+            update_policy: UpdatePolicy::default(),
+            theme: Theme::default(),
+            pci_ids_path: None,
+            usb_ids_path: None,
+            trust_store_dir: None,
+        }
+    }
+}
+
+impl Settings {
+    /// 默认设置文件路径
+    pub fn path() -> PathBuf {
+        config_dir().join(SETTINGS_FILE_NAME)
+    }
+
+    /// 从默认路径读取设置，文件不存在时返回默认设置
+    pub fn load() -> HamsterResult<Self> {
+        Self::load_from(&Self::path())
+    }
+
+    pub fn load_from(path: &Path) -> HamsterResult<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(HamsterError::io(format!("无法读取设置文件 {:?}", path), e)),
+        };
+        let value: Value = serde_json::from_str(&content)
+            .map_err(|e| HamsterError::parse(format!("设置文件格式错误 {:?}", path)).with_source(e))?;
+        let settings: Settings = serde_json::from_value(migrate(value)?)
+            .map_err(|e| HamsterError::parse(format!("设置文件内容无效 {:?}", path)).with_source(e))?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// 校验后保存到默认路径
+    pub fn save(&self) -> HamsterResult<()> {
+        self.save_to(&Self::path())
+    }
+
+    /// 先写入临时文件再替换，避免写入中断时损坏原设置
+    pub fn save_to(&self, path: &Path) -> HamsterResult<()> {
+        self.validate()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| HamsterError::io(format!("无法创建配置目录 {:?}", parent), e))?;
+        }
+        let content = serde_json::to_string_pretty(&Settings { version: SETTINGS_VERSION, ..self.clone() })
+            .map_err(|e| HamsterError::parse("序列化设置失败").with_source(e))?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)
+            .map_err(|e| HamsterError::io(format!("无法写入设置文件 {:?}", temp_path), e))?;
+        std::fs::rename(&temp_path, path)
+            .map_err(|e| HamsterError::io(format!("无法保存设置文件 {:?}", path), e))
+    }
+
+    /// 列出所有不合法的设置项，为空表示设置有效
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.backup_dir.as_os_str().is_empty() {
+            problems.push("备份位置不能为空".to_string());
+        }
+        if self.temp_dir.as_os_str().is_empty() {
+            problems.push("临时目录不能为空".to_string());
+        }
+        if let Some(proxy) = &self.proxy {
+            if !(proxy.starts_with("http://") || proxy.starts_with("https://")) {
+                problems.push(format!("代理地址必须以 http:// 或 https:// 开头: {}", proxy));
+            } else if reqwest::Proxy::all(proxy.as_str()).is_err() {
+                problems.push(format!("代理地址无效: {}", proxy));
+            }
+        }
+        if !(1..=MAX_HTTP_TIMEOUT_SECS).contains(&self.http_timeout_secs) {
+            problems.push(format!("网络超时必须在 1 到 {} 秒之间", MAX_HTTP_TIMEOUT_SECS));
+        }
+        if self.user_agent.trim().is_empty() {
+            problems.push("User-Agent 不能为空".to_string());
+        }
+        if !(1..=MAX_CONCURRENCY).contains(&self.concurrency) {
+            problems.push(format!("并发数必须在 1 到 {} 之间", MAX_CONCURRENCY));
+        }
+        // ID 数据库文件可能被移走或暂时不可用，由 HardwareIds::load 提示并改用其他来源，不影响其他设置
+        problems
+    }
+
+    pub fn validate(&self) -> HamsterResult<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(HamsterError::policy(format!("设置无效：{}", problems.join("；"))))
+        }
+    }
+
+    /// 单个驱动备份文件所在目录
+    pub fn driver_backup_dir(&self) -> PathBuf {
+        self.backup_dir.join("drivers")
+    }
+
+    /// 审计日志文件，位于用户数据目录，不随备份位置移动
+    pub fn journal_path(&self) -> PathBuf {
+        data_dir().join(JOURNAL_FILE_NAME)
+    }
+
+    pub fn journal(&self) -> AuditJournal {
+        AuditJournal::new(self.journal_path())
+    }
+
+    /// 签名验证缓存，位于用户数据目录
+    pub fn signature_cache_path(&self) -> PathBuf {
+        data_dir().join(SIGNATURE_CACHE_FILE_NAME)
+    }
+
+    /// 设置了受信任证书目录时读取其中的证书和吊销列表
+    pub fn trust_store(&self) -> Option<TrustStore> {
+        self.trust_store_dir.as_deref().map(TrustStore::load_dir)
+    }
+}
+
+/// 将旧版本的设置文件逐步升级到当前版本
+fn migrate(mut value: Value) -> HamsterResult<Value> {
+    let Some(object) = value.as_object_mut() else {
+        return Err(HamsterError::parse("设置文件顶层必须是对象"));
+    };
+    // 早期文件没有版本号，视为版本0
+    let version = match object.get("version") {
+        None => 0,
+        Some(version) => version.as_u64()
+            .ok_or_else(|| HamsterError::parse("设置文件版本号无效"))?,
+    };
+    if version > SETTINGS_VERSION as u64 {
+        return Err(HamsterError::policy(format!(
+            "设置文件版本 {} 高于程序支持的版本 {}，请升级程序",
+            version, SETTINGS_VERSION
+        )));
+    }
+
+    // 版本0 → 1：字段与版本1相同，只补充版本号
+    object.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+    Ok(value)
+}
+
+/// 用户配置目录：Windows 为 %APPDATA%，macOS 为 ~/Library/Application Support，其他系统遵循 XDG 规范
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    user_dir("APPDATA", "XDG_CONFIG_HOME", ".config")
+}
+
+/// 用户数据目录，存放默认的备份位置
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    user_dir("LOCALAPPDATA", "XDG_DATA_HOME", ".local/share")
+}
+
+fn user_dir(windows_var: &str, xdg_var: &str, xdg_fallback: &str) -> PathBuf {
+    let base = if cfg!(windows) {
+        std::env::var_os(windows_var).map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os(xdg_var)
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(xdg_fallback)))
+    };
+    // 无法确定用户目录时退回当前目录
+    base.unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_json(name: &str, json: &str) -> HamsterResult<Settings> {
+        let path = std::env::temp_dir().join(format!("hamster-settings-{}-{}.json", name, std::process::id()));
+        std::fs::write(&path, json).unwrap();
+        let settings = Settings::load_from(&path);
+        std::fs::remove_file(&path).ok();
+        settings
+    }
+
+    #[test]
+    fn version_0_file_is_migrated() {
+        let settings = load_json("v0", r#"{"backup_dir": "/srv/backups", "concurrency": 8}"#).unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.backup_dir, PathBuf::from("/srv/backups"));
+        assert_eq!(settings.concurrency, 8);
+        // 文件中缺失的字段取默认值
+        assert_eq!(settings.http_timeout_secs, Settings::default().http_timeout_secs);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let error = load_json("newer", &format!(r#"{{"version": {}}}"#, SETTINGS_VERSION + 1)).unwrap_err();
+        assert_eq!(error.category(), crate::core::error::ErrorCategory::Policy);
+        assert!(error.context().contains("请升级程序"), "{}", error);
+        assert!(load_json("invalid-version", r#"{"version": "1"}"#).is_err());
+        assert!(load_json("not-object", "[]").is_err());
+    }
+
+    #[test]
+    fn saved_settings_load_back() {
+        let path = std::env::temp_dir().join(format!("hamster-settings-roundtrip-{}.json", std::process::id()));
+        let settings = Settings { concurrency: 2, proxy: Some("http://127.0.0.1:7890".to_string()), ..Settings::default() };
+        settings.save_to(&path).unwrap();
+        let loaded = Settings::load_from(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.unwrap(), settings);
+    }
+
+    #[test]
+    fn missing_ids_file_does_not_reset_other_settings() {
+        let settings = load_json("missing-ids", r#"{"version": 1, "backup_dir": "/srv/backups", "pci_ids_path": "/nonexistent/pci.ids"}"#).unwrap();
+        assert_eq!(settings.backup_dir, PathBuf::from("/srv/backups"));
+        assert_eq!(settings.pci_ids_path, Some(PathBuf::from("/nonexistent/pci.ids")));
+    }
+
+    #[test]
+    fn each_problem_names_its_field() {
+        assert!(Settings::default().problems().is_empty());
+        let cases: [(Settings, &str); 7] = [
+            (Settings { backup_dir: PathBuf::new(), ..Settings::default() }, "备份位置"),
+            (Settings { temp_dir: PathBuf::new(), ..Settings::default() }, "临时目录"),
+            (Settings { proxy: Some("socks5://127.0.0.1".to_string()), ..Settings::default() }, "代理地址"),
+            (Settings { http_timeout_secs: 0, ..Settings::default() }, "网络超时"),
+            (Settings { http_timeout_secs: MAX_HTTP_TIMEOUT_SECS + 1, ..Settings::default() }, "网络超时"),
+            (Settings { user_agent: " ".to_string(), ..Settings::default() }, "User-Agent"),
+            (Settings { concurrency: 0, ..Settings::default() }, "并发数"),
+        ];
+        for (settings, field) in cases {
+            let problems = settings.problems();
+            assert_eq!(problems.len(), 1, "{:?}", problems);
+            assert!(problems[0].contains(field), "{} 不含 {}", problems[0], field);
+            assert!(settings.validate().unwrap_err().context().contains(field));
+        }
+    }
+}
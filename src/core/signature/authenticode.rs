@@ -0,0 +1,231 @@
+// Authenticode：PE 文件的证书表中保存 PKCS#7 签名，被签名的内容（SpcIndirectDataContent）记录映像摘要；
+// 签名者的未签名属性中还可能带有时间戳（旧式副署签名或 RFC 3161 令牌）和嵌套的附加签名
+
+use std::fmt;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::pe::{PeFile, DIRECTORY_SECURITY};
+use super::der::{self, DerReader, Tlv};
+use super::pkcs7::{SignedData, SignerInfo};
+use super::x509::{algorithm_oid, Certificate};
+
+pub const OID_SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+pub const OID_SPC_PE_IMAGE_DATA: &str = "1.3.6.1.4.1.311.2.1.15";
+const OID_NESTED_SIGNATURE: &str = "1.3.6.1.4.1.311.2.4.1";
+const OID_COUNTER_SIGNATURE: &str = "1.2.840.113549.1.9.6";
+const OID_RFC3161_TIMESTAMP: &str = "1.3.6.1.4.1.311.3.3.1";
+const OID_SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const OID_TST_INFO: &str = "1.2.840.113549.1.9.16.1.4";
+
+// WIN_CERTIFICATE 的修订号和类型
+const WIN_CERT_REVISION_2: u16 = 0x0200;
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+const WIN_CERTIFICATE_HEADER: usize = 8;
+
+// 嵌套签名的层数上限，正常文件只有一层
+const MAX_NESTING: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimestampKind {
+    /// 旧式 Authenticode 副署签名（PKCS#9 countersignature）
+    Authenticode,
+    Rfc3161,
+}
+
+impl fmt::Display for TimestampKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampKind::Authenticode => write!(f, "Authenticode 副署签名"),
+            TimestampKind::Rfc3161 => write!(f, "RFC 3161"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timestamp {
+    pub kind: TimestampKind,
+    pub time: DateTime<Utc>,
+    /// 时间戳颁发机构的签名者信息
+    pub signer: SignerInfo,
+    pub certificate: Option<Certificate>,
+    /// RFC 3161 时间戳令牌本身，其中带有颁发机构的证书；旧式副署签名为 None
+    pub token: Option<SignedData>,
+}
+
+impl Timestamp {
+    /// 读取签名者未签名属性中的全部时间戳，副署签名的证书在外层签名的证书集合中
+    pub fn from_signer(signer: &SignerInfo, certificates: &[Certificate]) -> HamsterResult<Vec<Self>> {
+        let mut timestamps = Vec::new();
+        for value in signer.unauthenticated(OID_COUNTER_SIGNATURE).flat_map(|attribute| &attribute.values) {
+            let counter_signer = SignerInfo::parse(Tlv::parse(value)?)?;
+            let time = counter_signer.authenticated(OID_SIGNING_TIME)
+                .and_then(|attribute| attribute.values.first())
+                .ok_or_else(|| HamsterError::parse("副署签名缺少签名时间"))
+                .and_then(|value| Tlv::parse(value)?.time())?;
+            timestamps.push(Timestamp {
+                kind: TimestampKind::Authenticode,
+                time,
+                certificate: counter_signer.find_certificate(certificates).cloned(),
+                signer: counter_signer,
+                token: None,
+            });
+        }
+        for value in signer.unauthenticated(OID_RFC3161_TIMESTAMP).flat_map(|attribute| &attribute.values) {
+            let token = SignedData::parse(value)?;
+            if token.content_type != OID_TST_INFO {
+                return Err(HamsterError::parse(format!("时间戳令牌的内容类型 {} 无效", token.content_type)));
+            }
+            let (token_signer, certificate) = token.signer()
+                .ok_or_else(|| HamsterError::parse("时间戳令牌中没有签名者"))?;
+            let (token_signer, certificate) = (token_signer.clone(), certificate.cloned());
+            let time = tst_info_time(&token.content)?;
+            timestamps.push(Timestamp {
+                kind: TimestampKind::Rfc3161,
+                time,
+                signer: token_signer,
+                certificate,
+                token: Some(token),
+            });
+        }
+        Ok(timestamps)
+    }
+}
+
+/// TSTInfo 包装在 OCTET STRING 中，其中第5个字段为 GeneralizedTime 形式的时间
+fn tst_info_time(content: &[u8]) -> HamsterResult<DateTime<Utc>> {
+    let mut fields = tst_info_fields(content)?;
+    fields.read()?.small_integer()?;
+    fields.read()?.oid()?;
+    fields.expect(der::TAG_SEQUENCE)?;
+    fields.read()?.integer_bytes()?;
+    fields.expect(der::TAG_GENERALIZED_TIME)?.time()
+}
+
+/// TSTInfo 中的消息印记：被加盖时间戳的数据（签名值）的摘要算法和摘要
+pub fn tst_message_imprint(content: &[u8]) -> HamsterResult<(String, Vec<u8>)> {
+    let mut fields = tst_info_fields(content)?;
+    fields.read()?.small_integer()?;
+    fields.read()?.oid()?;
+    let mut imprint = fields.expect(der::TAG_SEQUENCE)?.reader();
+    let algorithm = algorithm_oid(imprint.read()?)?;
+    let digest = imprint.read()?.octet_string()?.to_vec();
+    Ok((algorithm, digest))
+}
+
+fn tst_info_fields(content: &[u8]) -> HamsterResult<DerReader<'_>> {
+    let wrapper = Tlv::parse(content)?;
+    let encoded = if wrapper.tag == der::TAG_OCTET_STRING { wrapper.contents } else { wrapper.raw };
+    Ok(Tlv::parse(encoded)?.expect(der::TAG_SEQUENCE)?.reader())
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthenticodeSignature {
+    pub signed_data: SignedData,
+    /// SpcIndirectDataContent 中记录的映像摘要算法和摘要值
+    pub digest_algorithm: String,
+    pub image_digest: Vec<u8>,
+    pub timestamps: Vec<Timestamp>,
+    /// 附加的嵌套签名（如同时带有 SHA-1 和 SHA-256 签名的文件）
+    pub nested: Vec<AuthenticodeSignature>,
+}
+
+impl AuthenticodeSignature {
+    /// 从 ContentInfo 编码解析
+    pub fn parse(data: &[u8]) -> HamsterResult<Self> {
+        Self::parse_nested(data, 0)
+    }
+
+    fn parse_nested(data: &[u8], depth: usize) -> HamsterResult<Self> {
+        let signed_data = SignedData::parse(data)?;
+        if signed_data.content_type != OID_SPC_INDIRECT_DATA {
+            return Err(HamsterError::parse(format!("签名内容类型 {} 不是 Authenticode", signed_data.content_type)));
+        }
+        let indirect = parse_indirect_data(&signed_data.content)
+            .map_err(|e| HamsterError::parse("Authenticode 签名内容无效").with_source(e))?;
+
+        let mut timestamps = Vec::new();
+        let mut nested = Vec::new();
+        if let Some(signer) = signed_data.signers.first() {
+            timestamps = Timestamp::from_signer(signer, &signed_data.certificates)?;
+            if depth < MAX_NESTING {
+                for value in signer.unauthenticated(OID_NESTED_SIGNATURE).flat_map(|attribute| &attribute.values) {
+                    nested.push(Self::parse_nested(value, depth + 1)?);
+                }
+            }
+        }
+        Ok(Self {
+            signed_data,
+            digest_algorithm: indirect.digest_algorithm,
+            image_digest: indirect.digest,
+            timestamps,
+            nested,
+        })
+    }
+
+    pub fn signer(&self) -> Option<&SignerInfo> {
+        self.signed_data.signers.first()
+    }
+
+    pub fn signer_certificate(&self) -> Option<&Certificate> {
+        self.signed_data.signer().and_then(|(_, certificate)| certificate)
+    }
+
+    /// 本签名及其中全部嵌套签名，主签名在前
+    pub fn flatten(&self) -> Vec<&AuthenticodeSignature> {
+        let mut all = vec![self];
+        for nested in &self.nested {
+            all.extend(nested.flatten());
+        }
+        all
+    }
+}
+
+/// SpcIndirectDataContent：被签名对象的类型和摘要。嵌入签名和编录成员使用相同的结构
+#[derive(Debug, Clone)]
+pub struct IndirectData {
+    /// 对象类型，PE 映像为 SPC_PE_IMAGE_DATA，其他文件通常为 SPC_CAB_DATA
+    pub data_type: String,
+    pub digest_algorithm: String,
+    pub digest: Vec<u8>,
+}
+
+/// SpcIndirectDataContent ::= SEQUENCE { data SpcAttributeTypeAndOptionalValue, messageDigest DigestInfo }
+pub fn parse_indirect_data(content: &[u8]) -> HamsterResult<IndirectData> {
+    let mut fields = Tlv::parse(content)?.expect(der::TAG_SEQUENCE)?.reader();
+    let data_type = fields.expect(der::TAG_SEQUENCE)?.reader().read()?.oid()?;
+    let mut digest_info = fields.expect(der::TAG_SEQUENCE)?.reader();
+    let digest_algorithm = algorithm_oid(digest_info.read()?)?;
+    let digest = digest_info.read()?.octet_string()?.to_vec();
+    Ok(IndirectData { data_type, digest_algorithm, digest })
+}
+
+/// 读取 PE 文件证书表中的全部 Authenticode 签名，没有证书表时返回空列表
+pub fn read_pe_signatures(pe: &PeFile) -> HamsterResult<Vec<AuthenticodeSignature>> {
+    let Some(directory) = pe.directory(DIRECTORY_SECURITY) else {
+        return Ok(Vec::new());
+    };
+    // 证书表的地址是文件偏移而不是相对虚拟地址
+    let start = directory.virtual_address as usize;
+    let table = start.checked_add(directory.size as usize)
+        .and_then(|end| pe.data().get(start..end))
+        .ok_or_else(|| HamsterError::parse("证书表超出文件范围"))?;
+
+    let mut signatures = Vec::new();
+    let mut offset = 0;
+    while offset + WIN_CERTIFICATE_HEADER <= table.len() {
+        let header = &table[offset..offset + WIN_CERTIFICATE_HEADER];
+        let length = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let revision = u16::from_le_bytes([header[4], header[5]]);
+        let certificate_type = u16::from_le_bytes([header[6], header[7]]);
+        if length < WIN_CERTIFICATE_HEADER || length > table.len() - offset {
+            return Err(HamsterError::parse("证书表项的长度无效"));
+        }
+        if revision == WIN_CERT_REVISION_2 && certificate_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+            signatures.push(AuthenticodeSignature::parse(&table[offset + WIN_CERTIFICATE_HEADER..offset + length])?);
+        }
+        // 各项按8字节对齐
+        offset += (length + 7) & !7;
+    }
+    Ok(signatures)
+}
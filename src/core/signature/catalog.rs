@@ -0,0 +1,480 @@
+// 安全编录文件（.cat）：PKCS#7 签名的证书信任列表（CTL），每个成员记录一个文件的摘要和属性。
+// 大多数驱动的 .sys 文件本身不带签名，而是由驱动包中的编录文件（安装后位于 CatRoot）统一签名
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::pe::PeFile;
+use super::authenticode::{parse_indirect_data, Timestamp, OID_SPC_INDIRECT_DATA, OID_SPC_PE_IMAGE_DATA};
+use super::der::{self, context_tag, Tlv};
+use super::image_hash::image_digest;
+use super::pkcs7::{parse_attributes, SignedData};
+use super::x509::{algorithm_oid, hex};
+use super::{OID_SHA1, OID_SHA256};
+
+const OID_CTL: &str = "1.3.6.1.4.1.311.10.1";
+const OID_CATALOG_LIST: &str = "1.3.6.1.4.1.311.12.1.1";
+// 成员列表的类型：第2版编录（Windows 8 起）使用 SHA-256 摘要
+const OID_CATALOG_LIST_MEMBER2: &str = "1.3.6.1.4.1.311.12.1.3";
+const OID_CAT_NAME_VALUE: &str = "1.3.6.1.4.1.311.12.2.1";
+const OID_CAT_MEMBER_INFO: &str = "1.3.6.1.4.1.311.12.2.2";
+
+// CatRoot 下存放系统编录文件的目录（DRIVER_ACTION_VERIFY 对应的 GUID）
+const CATROOT_SYSTEM_GUID: &str = "{F750E6C3-38EE-11D1-85E5-00C04FC295EE}";
+
+/// 编录文件中的一个成员，即被签名的一个文件
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogMember {
+    /// 成员标记，按哈希生成的编录中为摘要的十六进制形式
+    pub tag: String,
+    /// 被签名对象的类型，PE 映像的摘要按 Authenticode 规则计算，其他文件为整个文件的摘要
+    pub data_type: Option<String>,
+    pub digest_algorithm: Option<String>,
+    #[serde(serialize_with = "serialize_hex")]
+    pub digest: Vec<u8>,
+    /// 名称-值属性，如 ("File", "nvlddmkm.sys")、("OSAttr", "2:10.0")
+    pub attributes: Vec<(String, String)>,
+    /// 成员信息中的主体 GUID
+    pub subject_guid: Option<String>,
+}
+
+impl CatalogMember {
+    fn parse(tlv: Tlv) -> HamsterResult<Self> {
+        let mut fields = tlv.expect(der::TAG_SEQUENCE)?.reader();
+        let identifier = fields.read()?.octet_string()?;
+        let mut member = Self {
+            tag: decode_tag(identifier),
+            data_type: None,
+            digest_algorithm: None,
+            digest: Vec::new(),
+            attributes: Vec::new(),
+            subject_guid: None,
+        };
+        let Some(set) = fields.next_if(der::TAG_SET)? else {
+            return Ok(member);
+        };
+        for attribute in parse_attributes(set)? {
+            for value in &attribute.values {
+                match attribute.oid.as_str() {
+                    OID_SPC_INDIRECT_DATA => {
+                        let indirect = parse_indirect_data(value)?;
+                        member.data_type = Some(indirect.data_type);
+                        member.digest_algorithm = Some(indirect.digest_algorithm);
+                        member.digest = indirect.digest;
+                    }
+                    OID_CAT_NAME_VALUE => member.attributes.push(parse_name_value(value)?),
+                    OID_CAT_MEMBER_INFO => {
+                        let mut info = Tlv::parse(value)?.expect(der::TAG_SEQUENCE)?.reader();
+                        member.subject_guid = Some(info.read()?.string()?);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(member)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+
+    /// 成员对应的文件名（File 属性）
+    pub fn file_name(&self) -> Option<&str> {
+        self.attribute("File")
+    }
+
+    pub fn os_attributes(&self) -> Option<&str> {
+        self.attribute("OSAttr")
+    }
+
+    pub fn is_pe_image(&self) -> bool {
+        self.data_type.as_deref() == Some(OID_SPC_PE_IMAGE_DATA)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    pub signed_data: SignedData,
+    /// 列表标识，通常为 16 字节的 GUID
+    pub identifier: Option<String>,
+    pub this_update: DateTime<Utc>,
+    /// 成员列表的类型（CTL 的 subjectAlgorithm），区分第1版和第2版编录
+    pub member_type: String,
+    pub members: Vec<CatalogMember>,
+    /// 编录级别的名称-值属性，如 OSAttr、HWID1
+    pub attributes: Vec<(String, String)>,
+    pub timestamps: Vec<Timestamp>,
+}
+
+impl Catalog {
+    pub fn open(path: &Path) -> HamsterResult<Self> {
+        let data = fs::read(path)
+            .map_err(|e| HamsterError::io(format!("无法读取编录文件 {}", path.display()), e))?;
+        Self::parse(&data)
+            .map_err(|e| HamsterError::parse(format!("{} 不是有效的编录文件: {}", path.display(), e.context())).with_source(e))
+    }
+
+    pub fn parse(data: &[u8]) -> HamsterResult<Self> {
+        let signed_data = SignedData::parse(data)?;
+        if signed_data.content_type != OID_CTL {
+            return Err(HamsterError::parse(format!("签名内容类型 {} 不是证书信任列表", signed_data.content_type)));
+        }
+        let mut fields = Tlv::parse(&signed_data.content)?.expect(der::TAG_SEQUENCE)?.reader();
+        fields.next_if(der::TAG_INTEGER)?;
+        let mut usages = fields.expect(der::TAG_SEQUENCE)?.reader();
+        let mut is_catalog = false;
+        while !usages.is_empty() {
+            is_catalog |= usages.read()?.oid()? == OID_CATALOG_LIST;
+        }
+        if !is_catalog {
+            return Err(HamsterError::parse("证书信任列表的用途不是安全编录"));
+        }
+        let identifier = fields.next_if(der::TAG_OCTET_STRING)?.map(|identifier| hex(identifier.contents));
+        fields.next_if(der::TAG_INTEGER)?;
+        let this_update = fields.read()?.time()?;
+        if matches!(fields.peek_tag(), Some(der::TAG_UTC_TIME | der::TAG_GENERALIZED_TIME)) {
+            fields.read()?;
+        }
+        let member_type = algorithm_oid(fields.read()?)?;
+
+        let mut members = Vec::new();
+        if let Some(list) = fields.next_if(der::TAG_SEQUENCE)? {
+            let mut reader = list.reader();
+            while !reader.is_empty() {
+                members.push(CatalogMember::parse(reader.read()?)?);
+            }
+        }
+        // 编录级别的属性以扩展的形式保存
+        let mut attributes = Vec::new();
+        if let Some(wrapper) = fields.next_if(context_tag(0, true))? {
+            let mut extensions = wrapper.reader().expect(der::TAG_SEQUENCE)?.reader();
+            while !extensions.is_empty() {
+                let mut extension = extensions.expect(der::TAG_SEQUENCE)?.reader();
+                let oid = extension.read()?.oid()?;
+                extension.next_if(der::TAG_BOOLEAN)?;
+                let value = extension.read()?.octet_string()?;
+                if oid == OID_CAT_NAME_VALUE {
+                    attributes.push(parse_name_value(value)?);
+                }
+            }
+        }
+
+        let timestamps = match signed_data.signers.first() {
+            Some(signer) => Timestamp::from_signer(signer, &signed_data.certificates)?,
+            None => Vec::new(),
+        };
+        Ok(Self { signed_data, identifier, this_update, member_type, members, attributes, timestamps })
+    }
+
+    /// 编录格式版本：第1版成员摘要为 SHA-1，第2版为 SHA-256
+    pub fn version(&self) -> u8 {
+        if self.member_type == OID_CATALOG_LIST_MEMBER2 { 2 } else { 1 }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+
+    /// 编录适用的系统版本，如 "2:6.1,2:6.2,2:10.0"
+    pub fn os_attributes(&self) -> Option<&str> {
+        self.attribute("OSAttr")
+    }
+
+    pub fn find_digest(&self, digest: &[u8]) -> Option<&CatalogMember> {
+        self.members.iter().find(|member| member.digest == digest)
+    }
+
+    /// 按 File 属性查找成员，不区分大小写
+    pub fn find_file_name(&self, name: &str) -> Option<&CatalogMember> {
+        self.members.iter().find(|member| member.file_name().is_some_and(|file| file.eq_ignore_ascii_case(name)))
+    }
+}
+
+/// 成员标记：按哈希生成的编录中是 UTF-16LE 编码、以0结尾的十六进制文本，其他情况按字节显示
+fn decode_tag(identifier: &[u8]) -> String {
+    let text = identifier.strip_suffix(&[0, 0]).unwrap_or(identifier);
+    if !text.is_empty() && text.len().is_multiple_of(2) && text.chunks_exact(2).all(|pair| pair[1] == 0 && pair[0].is_ascii_graphic()) {
+        text.chunks_exact(2).map(|pair| pair[0] as char).collect()
+    } else {
+        hex(identifier)
+    }
+}
+
+/// CatNameValue ::= SEQUENCE { tag BMPString, flags INTEGER, value OCTET STRING（UTF-16LE，以0结尾） }
+fn parse_name_value(encoded: &[u8]) -> HamsterResult<(String, String)> {
+    let mut fields = Tlv::parse(encoded)?.expect(der::TAG_SEQUENCE)?.reader();
+    let name = fields.read()?.string()?;
+    fields.expect(der::TAG_INTEGER)?;
+    let units: Vec<u16> = fields.read()?.octet_string()?
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let value = String::from_utf16_lossy(&units).trim_end_matches('\0').to_string();
+    Ok((name, value))
+}
+
+fn find_attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+}
+
+fn serialize_hex<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex(bytes))
+}
+
+/// 找到的覆盖某个文件的编录
+#[derive(Debug, Clone, Copy)]
+pub struct CatalogMatch<'a> {
+    pub path: &'a Path,
+    pub catalog: &'a Catalog,
+    pub member: &'a CatalogMember,
+}
+
+/// 一组已加载的编录文件，按成员摘要建立索引
+#[derive(Debug, Default)]
+pub struct CatalogIndex {
+    catalogs: Vec<(PathBuf, Catalog)>,
+    by_digest: HashMap<Vec<u8>, (usize, usize)>,
+    /// 无法解析而被跳过的编录文件及原因
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+impl CatalogIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 系统已安装的编录（%SystemRoot%\System32\CatRoot），目录不存在时返回空索引
+    pub fn system(system_root: &Path) -> Self {
+        let mut index = Self::new();
+        let catroot = system_root.join("System32").join("CatRoot");
+        let system = catroot.join(CATROOT_SYSTEM_GUID);
+        index.load_dir(if system.is_dir() { &system } else { &catroot });
+        index
+    }
+
+    pub fn add(&mut self, path: PathBuf, catalog: Catalog) {
+        let position = self.catalogs.len();
+        for (member_position, member) in catalog.members.iter().enumerate() {
+            if !member.digest.is_empty() {
+                self.by_digest.entry(member.digest.clone()).or_insert((position, member_position));
+            }
+        }
+        self.catalogs.push((path, catalog));
+    }
+
+    pub fn load_file(&mut self, path: &Path) -> HamsterResult<()> {
+        let catalog = Catalog::open(path)?;
+        self.add(path.to_path_buf(), catalog);
+        Ok(())
+    }
+
+    /// 递归加载目录中的全部 .cat 文件，无法解析的记入 errors
+    pub fn load_dir(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+        paths.sort();
+        for path in paths {
+            if path.is_dir() {
+                self.load_dir(&path);
+            } else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("cat")) {
+                if let Err(e) = self.load_file(&path) {
+                    self.errors.push((path, e.context().to_string()));
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.catalogs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.catalogs.is_empty()
+    }
+
+    pub fn catalogs(&self) -> impl Iterator<Item = (&Path, &Catalog)> {
+        self.catalogs.iter().map(|(path, catalog)| (path.as_path(), catalog))
+    }
+
+    pub fn find_digest(&self, digest: &[u8]) -> Option<CatalogMatch<'_>> {
+        self.by_digest.get(digest).map(|&(position, member)| {
+            let (path, catalog) = &self.catalogs[position];
+            CatalogMatch { path, catalog, member: &catalog.members[member] }
+        })
+    }
+
+    /// 查找覆盖该文件的编录：PE 文件按 Authenticode 映像摘要，其他文件按整个文件的摘要，
+    /// 分别用 SHA-1（第1版编录）和 SHA-256（第2版编录）匹配成员
+    pub fn find_file(&self, path: &Path) -> HamsterResult<Option<CatalogMatch<'_>>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let digests = match PeFile::open(path) {
+            Ok(pe) => [image_digest(&pe, OID_SHA1)?, image_digest(&pe, OID_SHA256)?],
+            Err(_) => {
+                let data = fs::read(path)
+                    .map_err(|e| HamsterError::io(format!("无法读取文件 {}", path.display()), e))?;
+                [Sha1::digest(&data).to_vec(), Sha256::digest(&data).to_vec()]
+            }
+        };
+        Ok(digests.iter().find_map(|digest| self.find_digest(digest)))
+    }
+
+    /// 按文件名查找列出该文件的编录成员，用于在摘要不符时提示文件可能已被修改
+    pub fn find_file_name(&self, name: &str) -> Option<CatalogMatch<'_>> {
+        self.catalogs.iter().find_map(|(path, catalog)| {
+            catalog.find_file_name(name).map(|member| CatalogMatch { path, catalog, member })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::authenticode::TimestampKind;
+
+    const PE_SIP: &str = "{C689AAB8-8E78-11D0-8C47-00C04FC295EE}";
+    const FLAT_SIP: &str = "{DE351A42-8E59-11D0-8C47-00C04FC295EE}";
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+    }
+
+    /// 第1版编录：示例驱动包的 NV_DISP.CAT，成员摘要为 SHA-1
+    fn nvidia() -> Catalog {
+        Catalog::open(&fixture("packages/nvidia/NV_DISP.CAT")).unwrap()
+    }
+
+    /// 第2版编录：成员摘要为 SHA-256，其中一个成员按文件名而不是摘要标记
+    fn hamster_v2() -> Catalog {
+        Catalog::open(&fixture("catalog/hamster_v2.cat")).unwrap()
+    }
+
+    #[test]
+    fn ctl_header_and_catalog_attributes_are_read() {
+        let catalog = nvidia();
+        assert_eq!(catalog.version(), 1);
+        assert_eq!(catalog.member_type, "1.3.6.1.4.1.311.12.1.2");
+        assert_eq!(catalog.identifier.as_deref(), Some("5A1F3C0E9B2D4E7F8A6B1C2D3E4F5061"));
+        assert_eq!(catalog.this_update.to_rfc3339(), "2024-08-15T03:12:07+00:00");
+        let names: Vec<&str> = catalog.attributes.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["HWID1", "HWID2", "OS", "OSAttr"]);
+        assert_eq!(catalog.attribute("hwid1"), Some("pci\\ven_10de&dev_2504&subsys_14621043"));
+        assert_eq!(catalog.os_attributes(), Some("2:10.0"));
+        assert_eq!(catalog.attribute("HWID3"), None);
+
+        let [timestamp] = catalog.timestamps.as_slice() else { panic!("{:?}", catalog.timestamps) };
+        assert_eq!(timestamp.kind, TimestampKind::Rfc3161);
+        assert_eq!(timestamp.time.to_rfc3339(), "2024-08-15T03:12:10+00:00");
+
+        let v2 = hamster_v2();
+        assert_eq!(v2.version(), 2);
+        assert_eq!(v2.os_attributes(), Some("2:6.1,2:10.0"));
+        assert_eq!(v2.attribute("HWID1"), Some("hdaudio\\func_01&ven_10ec&dev_0897"));
+    }
+
+    #[test]
+    fn ctl_members_are_read() {
+        let catalog = nvidia();
+        let mut files: Vec<&str> = catalog.members.iter().filter_map(CatalogMember::file_name).collect();
+        files.sort();
+        assert_eq!(files, ["nv_dispi.inf", "nvapi64.dll", "nvlddmkm.sys"]);
+
+        let driver = catalog.find_file_name("NVLDDMKM.SYS").unwrap();
+        assert!(driver.is_pe_image());
+        assert_eq!(driver.digest_algorithm.as_deref(), Some(OID_SHA1));
+        assert_eq!(driver.digest.len(), 20);
+        // 按哈希生成的编录以摘要的十六进制形式作为成员标记
+        assert_eq!(driver.tag, hex(&driver.digest));
+        assert_eq!(driver.subject_guid.as_deref(), Some(PE_SIP));
+        assert_eq!(driver.os_attributes(), Some("2:10.0"));
+
+        let inf = catalog.find_file_name("nv_dispi.inf").unwrap();
+        assert!(!inf.is_pe_image());
+        assert_eq!(inf.subject_guid.as_deref(), Some(FLAT_SIP));
+
+        let v2 = hamster_v2();
+        let named = v2.find_file_name("hdxrt.inf").unwrap();
+        assert_eq!(named.tag, "hdxrt.inf");
+        assert_eq!(named.digest_algorithm.as_deref(), Some(OID_SHA256));
+        assert_eq!(named.os_attributes(), Some("2:6.1,2:10.0"));
+        assert!(v2.find_file_name("nvlddmkm.sys").is_none());
+    }
+
+    #[test]
+    fn members_are_found_by_digest() {
+        let catalog = nvidia();
+        let driver = PeFile::open(&fixture("packages/nvidia/nvlddmkm.sys")).unwrap();
+        let member = catalog.find_digest(&image_digest(&driver, OID_SHA1).unwrap()).unwrap();
+        assert_eq!(member.file_name(), Some("nvlddmkm.sys"));
+        // PE 文件的成员摘要是映像摘要而不是整个文件的摘要
+        assert!(catalog.find_digest(&Sha1::digest(driver.data())).is_none());
+
+        let inf = fs::read(fixture("packages/nvidia/nv_dispi.inf")).unwrap();
+        assert_eq!(catalog.find_digest(&Sha1::digest(&inf)).unwrap().file_name(), Some("nv_dispi.inf"));
+
+        let v2 = hamster_v2();
+        let dll = PeFile::open(&fixture("pe/RtkApo.dll")).unwrap();
+        assert_eq!(v2.find_digest(&image_digest(&dll, OID_SHA256).unwrap()).unwrap().file_name(), Some("RtkApo.dll"));
+        let inf = fs::read(fixture("inf/hdxrt.inf")).unwrap();
+        assert_eq!(v2.find_digest(&Sha256::digest(&inf)).unwrap().file_name(), Some("hdxrt.inf"));
+    }
+
+    #[test]
+    fn index_looks_up_files_across_catalogs() {
+        let mut index = CatalogIndex::new();
+        index.load_dir(&fixture("catalog"));
+        index.load_file(&fixture("packages/nvidia/NV_DISP.CAT")).unwrap();
+        assert_eq!(index.len(), 2);
+        assert!(index.errors.is_empty(), "{:?}", index.errors);
+
+        for (file, member, catalog) in [
+            ("packages/nvidia/nvlddmkm.sys", "nvlddmkm.sys", "NV_DISP.CAT"),
+            ("packages/nvidia/nv_dispi.inf", "nv_dispi.inf", "NV_DISP.CAT"),
+            ("pe/RtkApo.dll", "RtkApo.dll", "hamster_v2.cat"),
+            ("inf/hdxrt.inf", "hdxrt.inf", "hamster_v2.cat"),
+        ] {
+            let found = index.find_file(&fixture(file)).unwrap().unwrap_or_else(|| panic!("{}", file));
+            assert_eq!(found.member.file_name(), Some(member));
+            assert_eq!(found.path.file_name().unwrap(), catalog);
+        }
+        assert!(index.find_file(&fixture("inf/broken.inf")).unwrap().is_none());
+        assert!(index.find_file(&fixture("missing.sys")).is_err());
+        assert_eq!(index.find_file_name("RTKAPO.DLL").unwrap().catalog.version(), 2);
+    }
+
+    #[test]
+    fn other_signed_data_is_reported() {
+        let dir = std::env::temp_dir().join(format!("hamster-catalog-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::copy(fixture("pkcs7/no-signed-attributes.p7"), dir.join("nested/signed.cat")).unwrap();
+        fs::write(dir.join("empty.cat"), b"").unwrap();
+        fs::write(dir.join("notes.txt"), b"").unwrap();
+
+        let mut index = CatalogIndex::new();
+        index.load_dir(&dir);
+        assert!(index.is_empty());
+        let errors: Vec<String> = index.errors.iter()
+            .map(|(path, error)| format!("{}: {}", path.file_name().unwrap().to_string_lossy(), error))
+            .collect();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].starts_with("empty.cat: "), "{:?}", errors);
+        assert!(errors[1].starts_with("signed.cat: ") && errors[1].contains("不是证书信任列表"), "{:?}", errors);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn member_tags_that_are_not_text_are_shown_as_hex() {
+        assert_eq!(decode_tag(&[b'A', 0, b'1', 0, 0, 0]), "A1");
+        assert_eq!(decode_tag(&[0x5A, 0x1F, 0x3C]), "5A1F3C");
+        assert_eq!(decode_tag(&[b'A', 0, b' ', 0]), "41002000");
+        assert_eq!(decode_tag(&[]), "");
+    }
+}
@@ -0,0 +1,399 @@
+// 签名分类：按签名者证书的扩展密钥用法、已验证证书链的根证书和编录的 OSAttr 判断驱动的签名来源
+// （WHQL、证明签名、交叉签名、测试签名、自签名），并预测 Windows 10 1607 起的内核模式代码签名策略是否允许加载。
+// 证书的名称可以随意伪造，Microsoft 根证书按指纹识别，且只有证书链通过验证时才给出 Microsoft 签名的结论
+
+use std::fmt;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use super::catalog::Catalog;
+use super::pkcs7::SignedData;
+use super::trust::{EKU_ATTESTATION, EKU_SYSTEM_COMPONENT, EKU_WHQL};
+use super::x509::Certificate;
+use super::Timestamp;
+
+/// 签发 WHQL、证明签名和 Windows 组件签名的 Microsoft 生产根证书的 SHA-1 指纹
+const MICROSOFT_ROOTS: &[&str] = &[
+    // Microsoft Root Authority
+    "A43489159A520F0D93D032CCAF37E7FE20A8B419",
+    // Microsoft Root Certificate Authority
+    "CDD4EEAE6000AC7F40C3802C171E30148030C072",
+    // Microsoft Root Certificate Authority 2010
+    "3B1EFD3A66EA28B16697394703A72CA340A05BD5",
+    // Microsoft Root Certificate Authority 2011
+    "8F43288AD272F3103B6FB1428485EA3014C0BCFE",
+];
+
+/// Microsoft Code Verification Root 的 SHA-1 指纹，Windows 10 1607 之前第三方内核驱动依靠它交叉签名链接到 Microsoft
+const CROSS_SIGNING_ROOT: &str = "8FBE4D070EF8AB1BCCAF2A9D5CCAE7282A2C66B3";
+const CROSS_SIGNING_ROOT_NAME: &str = "Microsoft Code Verification Root";
+
+/// Windows 测试版本和 WDK 使用的测试根证书。按名称识别只会得出“测试签名”的结论，伪造名称不会得到更高的信任
+const TEST_ROOTS: &[&str] = &[
+    "Microsoft Testing Root Certificate Authority 2010",
+    "Microsoft Test Root Authority",
+];
+
+// 交叉签名例外：在此之前颁发的证书签名的驱动在 1607 之后仍可加载
+const CROSS_SIGNING_CUTOFF: (i32, u32, u32) = (2015, 7, 29);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureClass {
+    /// 通过硬件兼容性测试，由 Windows Hardware Compatibility Publisher 签名
+    Whql,
+    /// 经硬件开发者中心证明签名，未经兼容性测试
+    Attestation,
+    /// 由 Microsoft Windows 证书签名的系统组件
+    WindowsComponent,
+    /// 第三方证书经 Microsoft Code Verification Root 交叉签名
+    CrossSigned,
+    /// 证书链终止于测试根证书或本地添加的私有根证书
+    TestSigned,
+    /// 签名者证书为自签名证书
+    SelfSigned,
+    /// 普通的第三方代码签名
+    Authenticode,
+    Unsigned,
+}
+
+impl fmt::Display for SignatureClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureClass::Whql => write!(f, "WHQL 认证"),
+            SignatureClass::Attestation => write!(f, "证明签名"),
+            SignatureClass::WindowsComponent => write!(f, "Windows 组件"),
+            SignatureClass::CrossSigned => write!(f, "交叉签名"),
+            SignatureClass::TestSigned => write!(f, "测试签名"),
+            SignatureClass::SelfSigned => write!(f, "自签名"),
+            SignatureClass::Authenticode => write!(f, "第三方签名"),
+            SignatureClass::Unsigned => write!(f, "未签名"),
+        }
+    }
+}
+
+/// 按内核模式代码签名策略预测驱动能否加载（启用安全启动的 Windows 10 1607 及更高版本）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoadPrediction {
+    Loads,
+    /// 需要开启测试签名模式并安装测试根证书
+    TestModeOnly,
+    Blocked,
+    /// 证书链未经验证，无法确认签名来源
+    Unknown,
+}
+
+impl fmt::Display for LoadPrediction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadPrediction::Loads => write!(f, "可以加载"),
+            LoadPrediction::TestModeOnly => write!(f, "仅在测试模式下加载"),
+            LoadPrediction::Blocked => write!(f, "无法加载"),
+            LoadPrediction::Unknown => write!(f, "无法判断"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Classification {
+    pub class: SignatureClass,
+    /// 签名者证书已过期，但签名时加盖的时间戳在证书有效期内
+    pub expired_but_timestamped: bool,
+    pub load: LoadPrediction,
+    /// 加载预测的依据
+    pub reason: String,
+    /// 证书链的根证书；证书链未经验证时为按名称找到的颁发者，仅供显示，未签名时为空
+    pub root: String,
+    /// 编录的 OSAttr 对应的系统版本，如 Windows 10/11
+    pub os_versions: Vec<String>,
+}
+
+impl Classification {
+    pub fn unsigned() -> Self {
+        Self {
+            class: SignatureClass::Unsigned,
+            expired_but_timestamped: false,
+            load: LoadPrediction::Blocked,
+            reason: "内核模式驱动必须签名".to_string(),
+            root: String::new(),
+            os_versions: Vec::new(),
+        }
+    }
+
+    /// 文件被篡改或签名值无效时，无论签名来源如何都无法加载
+    pub fn block(&mut self, reason: impl Into<String>) {
+        self.load = LoadPrediction::Blocked;
+        self.reason = reason.into();
+    }
+
+    /// 分类名称，证书过期但有时间戳时附加说明
+    pub fn label(&self) -> String {
+        if self.expired_but_timestamped {
+            format!("{}（证书已过期，时间戳有效）", self.class)
+        } else {
+            self.class.to_string()
+        }
+    }
+}
+
+/// 对签名（文件的嵌入签名或编录的签名）分类；verified_chain 为信任库验证通过（状态为受信任）的证书链，
+/// 从签名者到根证书，没有信任库或验证未通过时为 None。调用前应已验证签名者的签名值；now 用于判断证书是否已过期
+pub fn classify(signed_data: &SignedData, timestamps: &[Timestamp], catalog: Option<&Catalog>, verified_chain: Option<&[Certificate]>, now: DateTime<Utc>) -> Classification {
+    let Some(certificate) = signed_data.signer().and_then(|(_, certificate)| certificate) else {
+        let mut classification = Classification::unsigned();
+        classification.reason = "签名中没有签名者的证书".to_string();
+        return classification;
+    };
+    let usages = certificate.extended_key_usages().ok().flatten().unwrap_or_default();
+    let has_usage = |usage: &str| usages.iter().any(|granted| granted == usage);
+    let chain = verified_chain.unwrap_or_default();
+    let verified_root = chain.last();
+    let root = match verified_root {
+        Some(root) => root.subject.display_name(),
+        None => issuer_path(certificate, &signed_data.certificates).last()
+            .map(|cert| cert.issuer.display_name())
+            .unwrap_or_default(),
+    };
+    let thumbprint = verified_root.map(Certificate::thumbprint).unwrap_or_default();
+    let microsoft_root = MICROSOFT_ROOTS.contains(&thumbprint.as_str());
+
+    let class = if certificate.is_self_issued() {
+        SignatureClass::SelfSigned
+    } else if verified_root.is_none() {
+        SignatureClass::Authenticode
+    } else if microsoft_root && has_usage(EKU_ATTESTATION) {
+        SignatureClass::Attestation
+    } else if microsoft_root && has_usage(EKU_WHQL) {
+        SignatureClass::Whql
+    } else if microsoft_root && has_usage(EKU_SYSTEM_COMPONENT) {
+        SignatureClass::WindowsComponent
+    } else if microsoft_root {
+        SignatureClass::Authenticode
+    } else if thumbprint == CROSS_SIGNING_ROOT || is_cross_signed(chain, &signed_data.certificates) {
+        SignatureClass::CrossSigned
+    } else if verified_root.is_some_and(|root| is_test_root(root, chain)) {
+        SignatureClass::TestSigned
+    } else {
+        // 公共证书颁发机构签发的第三方证书
+        SignatureClass::Authenticode
+    };
+
+    let (load, reason) = match class {
+        SignatureClass::Whql | SignatureClass::WindowsComponent => (LoadPrediction::Loads, "由 Microsoft 签名".to_string()),
+        SignatureClass::Attestation => (LoadPrediction::Loads, "由 Microsoft 证明签名，Windows Server 不加载证明签名的驱动".to_string()),
+        SignatureClass::CrossSigned => {
+            let (year, month, day) = CROSS_SIGNING_CUTOFF;
+            let cutoff = Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap();
+            if certificate.not_before < cutoff {
+                (LoadPrediction::Loads, format!("签名证书在 {} 之前颁发，属于交叉签名的兼容例外", cutoff.format("%Y-%m-%d")))
+            } else {
+                (LoadPrediction::Blocked, format!("签名证书在 {} 之后颁发，交叉签名的驱动只能在关闭安全启动时加载", cutoff.format("%Y-%m-%d")))
+            }
+        }
+        SignatureClass::TestSigned => (LoadPrediction::TestModeOnly, format!("证书链终止于测试根证书或私有根证书 {}", root)),
+        SignatureClass::SelfSigned => (LoadPrediction::TestModeOnly, "签名者证书为自签名证书".to_string()),
+        SignatureClass::Authenticode if verified_root.is_none() => (LoadPrediction::Unknown, "证书链未经验证，无法确认是否由 Microsoft 签名".to_string()),
+        SignatureClass::Authenticode => (LoadPrediction::Blocked, "内核模式驱动必须由 Microsoft 签名，第三方证书的签名只适用于用户模式".to_string()),
+        SignatureClass::Unsigned => (LoadPrediction::Blocked, "内核模式驱动必须签名".to_string()),
+    };
+
+    let timestamp = timestamps.first().map(|timestamp| timestamp.time);
+    let expired = !certificate.is_valid_at(now);
+    Classification {
+        class,
+        expired_but_timestamped: expired && timestamp.is_some_and(|time| certificate.is_valid_at(time)),
+        load,
+        reason,
+        root,
+        os_versions: catalog.and_then(Catalog::os_attributes).map(os_versions).unwrap_or_default(),
+    }
+}
+
+/// 签名附带的证书中有 Microsoft Code Verification Root 颁发的交叉证书，且其使用者和公钥与已验证证书链中的某个证书相同
+fn is_cross_signed(chain: &[Certificate], certificates: &[Certificate]) -> bool {
+    certificates.iter()
+        .filter(|cross| cross.issuer.common_name() == Some(CROSS_SIGNING_ROOT_NAME))
+        .any(|cross| chain.iter().any(|cert| cert.subject.raw == cross.subject.raw && cert.public_key == cross.public_key))
+}
+
+/// 已知的测试根证书，或不具备公共证书颁发机构特征的根证书（通常是本地生成的私有根证书）。
+/// 公共根证书的名称带有国家和组织，是基本约束中标明的 CA，且不直接签发代码签名证书
+fn is_test_root(root: &Certificate, chain: &[Certificate]) -> bool {
+    if root.subject.common_name().is_some_and(|name| TEST_ROOTS.contains(&name)) {
+        return true;
+    }
+    let public_ca = root.subject.get("2.5.4.6").is_some()
+        && root.subject.organization().is_some()
+        && root.is_ca().ok().flatten() == Some(true)
+        && chain.len() >= 3;
+    !public_ca
+}
+
+/// 按颁发者名称在签名附带的证书中向上查找，返回从签名者开始的证书序列；未经验证，只用于显示根证书名称
+fn issuer_path<'a>(certificate: &'a Certificate, pool: &'a [Certificate]) -> Vec<&'a Certificate> {
+    let mut path = vec![certificate];
+    let mut current = certificate;
+    while !current.is_self_issued() && path.len() < pool.len() + 1 {
+        // 同名的颁发者证书有多个时优先取非自签名的证书
+        let mut candidates: Vec<&Certificate> = pool.iter()
+            .filter(|cert| cert.subject.raw == current.issuer.raw && !path.iter().any(|seen| seen.raw == cert.raw))
+            .collect();
+        candidates.sort_by_key(|cert| cert.is_self_issued());
+        let Some(&issuer) = candidates.first() else {
+            break;
+        };
+        path.push(issuer);
+        current = issuer;
+    }
+    path
+}
+
+/// 把 OSAttr（如 `2:6.1,2:10.0`）转换为系统名称，无法识别的版本原样保留
+pub fn os_versions(os_attributes: &str) -> Vec<String> {
+    os_attributes.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let version = entry.split_once(':').map(|(_, version)| version).unwrap_or(entry);
+            match version {
+                "5.1" => "Windows XP".to_string(),
+                "5.2" => "Windows Server 2003".to_string(),
+                "6.0" => "Windows Vista".to_string(),
+                "6.1" => "Windows 7".to_string(),
+                "6.2" => "Windows 8".to_string(),
+                "6.3" => "Windows 8.1".to_string(),
+                "10.0" => "Windows 10/11".to_string(),
+                other => format!("Windows NT {}", other),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use crate::core::pe::PeFile;
+    use crate::core::signature::authenticode::read_pe_signatures;
+    use crate::core::signature::der::Tlv;
+    use crate::core::signature::pkcs7::SignerIdentifier;
+    use crate::core::signature::trust::TrustStore;
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+    }
+
+    fn certificate(name: &str) -> Certificate {
+        let data = std::fs::read(fixture("classify").join(name)).unwrap();
+        Certificate::parse(Tlv::parse(&data).unwrap()).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap()
+    }
+
+    /// 示例驱动的签名
+    fn driver_signature() -> SignedData {
+        let pe = PeFile::open(&fixture("packages/nvidia/nvlddmkm.sys")).unwrap();
+        read_pe_signatures(&pe).unwrap().remove(0).signed_data
+    }
+
+    /// 把示例驱动签名的签名者换成 signer，附带的证书换成 certificates；classify 不重新验证签名值
+    fn signed_by(signer: &Certificate, certificates: Vec<Certificate>) -> SignedData {
+        let mut signed_data = driver_signature();
+        signed_data.signers[0].identifier = SignerIdentifier::IssuerAndSerial { issuer: signer.issuer.clone(), serial: signer.serial.clone() };
+        signed_data.certificates = certificates;
+        signed_data
+    }
+
+    /// Fabrikam 的代码签名证书，由公共证书颁发机构 Contoso 签发
+    fn fabrikam(extra: Option<Certificate>) -> (SignedData, Vec<Certificate>) {
+        let signer = certificate("fabrikam.der");
+        let ca = certificate("contoso-ca.der");
+        let mut certificates = vec![signer.clone(), ca.clone()];
+        certificates.extend(extra);
+        (signed_by(&signer, certificates), vec![signer, ca, certificate("contoso-root.der")])
+    }
+
+    #[test]
+    fn private_root_is_test_signed() {
+        let signed_data = driver_signature();
+        let (_, signer) = signed_data.signer().unwrap();
+        let signer = signer.unwrap().clone();
+        let ca = signed_data.certificates.iter().find(|cert| cert.subject.raw == signer.issuer.raw).unwrap().clone();
+        let root = TrustStore::load_dir(&fixture("trust")).roots()[0].clone();
+        let classification = classify(&signed_data, &[], None, Some(&[signer, ca, root]), now());
+        assert_eq!(classification.class, SignatureClass::TestSigned);
+        assert_eq!(classification.load, LoadPrediction::TestModeOnly);
+        assert_eq!(classification.root, "Hamster Test Root CA");
+    }
+
+    #[test]
+    fn public_root_is_third_party_authenticode() {
+        let (signed_data, chain) = fabrikam(None);
+        let classification = classify(&signed_data, &[], None, Some(&chain), now());
+        assert_eq!(classification.class, SignatureClass::Authenticode);
+        assert_eq!(classification.load, LoadPrediction::Blocked);
+        assert_eq!(classification.root, "Contoso Public Root CA");
+
+        // 根证书直接签发签名者证书，不符合公共证书颁发机构的做法
+        let classification = classify(&signed_data, &[], None, Some(&[chain[0].clone(), chain[2].clone()]), now());
+        assert_eq!(classification.class, SignatureClass::TestSigned);
+
+        // 证书链未经验证时无法判断
+        let classification = classify(&signed_data, &[], None, None, now());
+        assert_eq!(classification.class, SignatureClass::Authenticode);
+        assert_eq!(classification.load, LoadPrediction::Unknown);
+        assert_eq!(classification.root, "Contoso Public Root CA");
+    }
+
+    #[test]
+    fn known_test_root_is_test_signed() {
+        // 测试根证书具有公共证书颁发机构的全部特征，但按名称识别为测试根证书
+        let (signed_data, mut chain) = fabrikam(None);
+        chain[2] = certificate("test-root.der");
+        let classification = classify(&signed_data, &[], None, Some(&chain), now());
+        assert_eq!(classification.class, SignatureClass::TestSigned);
+        assert_eq!(classification.root, "Microsoft Testing Root Certificate Authority 2010");
+    }
+
+    #[test]
+    fn cross_certificate_marks_cross_signing() {
+        let cross = certificate("contoso-cross.der");
+        assert_eq!(cross.issuer.common_name(), Some("Microsoft Code Verification Root"));
+        let (signed_data, chain) = fabrikam(Some(cross.clone()));
+        let classification = classify(&signed_data, &[], None, Some(&chain), now());
+        assert_eq!(classification.class, SignatureClass::CrossSigned);
+        // 签名者证书在 2015-07-29 之前颁发
+        assert_eq!(classification.load, LoadPrediction::Loads);
+        assert_eq!(classification.label(), "交叉签名");
+
+        // 交叉证书的公钥与证书链中的根证书不同
+        let mut other_key = cross;
+        other_key.public_key = certificate("contoso-ca.der").public_key;
+        let (signed_data, chain) = fabrikam(Some(other_key));
+        assert_eq!(classify(&signed_data, &[], None, Some(&chain), now()).class, SignatureClass::Authenticode);
+
+        // 证书链未经验证时不根据交叉证书下结论
+        let (signed_data, _) = fabrikam(Some(certificate("contoso-cross.der")));
+        assert_eq!(classify(&signed_data, &[], None, None, now()).load, LoadPrediction::Unknown);
+    }
+
+    #[test]
+    fn self_signed_and_missing_signers() {
+        let root = certificate("contoso-root.der");
+        let signed_data = signed_by(&root, vec![root.clone()]);
+        let classification = classify(&signed_data, &[], None, Some(&[root]), now());
+        assert_eq!(classification.class, SignatureClass::SelfSigned);
+        assert_eq!(classification.load, LoadPrediction::TestModeOnly);
+
+        let signed_data = signed_by(&certificate("fabrikam.der"), Vec::new());
+        let classification = classify(&signed_data, &[], None, None, now());
+        assert_eq!(classification.class, SignatureClass::Unsigned);
+        assert_eq!(classification.reason, "签名中没有签名者的证书");
+    }
+
+    #[test]
+    fn os_attributes_are_named() {
+        assert_eq!(os_versions("2:6.1, 2:10.0,,2:11.0"), ["Windows 7", "Windows 10/11", "Windows NT 11.0"]);
+        assert!(os_versions("").is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+// 证书吊销列表（CRL）：由 CA 签名，列出已吊销证书的序列号和吊销时间；只读取本地文件，不联网下载
+
+use chrono::{DateTime, Utc};
+use crate::core::error::{HamsterError, HamsterResult};
+use super::der::{self, context_tag, Tlv};
+use super::x509::{algorithm_oid, Certificate, Name};
+use super::crypto;
+
+#[derive(Debug, Clone)]
+pub struct RevokedCertificate {
+    pub serial: Vec<u8>,
+    pub revoked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Crl {
+    pub issuer: Name,
+    pub this_update: DateTime<Utc>,
+    pub next_update: Option<DateTime<Utc>>,
+    pub revoked: Vec<RevokedCertificate>,
+    signature_algorithm: String,
+    tbs: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl Crl {
+    pub fn parse(data: &[u8]) -> HamsterResult<Self> {
+        Self::parse_tlv(Tlv::parse(data)?)
+            .map_err(|e| HamsterError::parse("证书吊销列表无效").with_source(e))
+    }
+
+    fn parse_tlv(tlv: Tlv) -> HamsterResult<Self> {
+        let mut list = tlv.expect(der::TAG_SEQUENCE)?.reader();
+        let tbs = list.expect(der::TAG_SEQUENCE)?;
+        let signature_algorithm = algorithm_oid(list.read()?)?;
+        let signature = list.read()?.bit_string()?.to_vec();
+
+        let mut fields = tbs.reader();
+        fields.next_if(der::TAG_INTEGER)?;
+        fields.expect(der::TAG_SEQUENCE)?;
+        let issuer = Name::parse(fields.read()?)?;
+        let this_update = fields.read()?.time()?;
+        let next_update = match fields.peek_tag() {
+            Some(der::TAG_UTC_TIME | der::TAG_GENERALIZED_TIME) => Some(fields.read()?.time()?),
+            _ => None,
+        };
+        let mut revoked = Vec::new();
+        if let Some(entries) = fields.next_if(der::TAG_SEQUENCE)? {
+            let mut reader = entries.reader();
+            while !reader.is_empty() {
+                let mut entry = reader.expect(der::TAG_SEQUENCE)?.reader();
+                let serial = entry.read()?.integer_bytes()?.to_vec();
+                let revoked_at = entry.read()?.time()?;
+                revoked.push(RevokedCertificate { serial, revoked_at });
+            }
+        }
+        fields.next_if(context_tag(0, true))?;
+
+        Ok(Self { issuer, this_update, next_update, revoked, signature_algorithm, tbs: tbs.raw.to_vec(), signature })
+    }
+
+    /// 是否由该 CA 签发：名称相符且签名有效
+    pub fn is_issued_by(&self, issuer: &Certificate) -> bool {
+        self.issuer.raw == issuer.subject.raw
+            && crypto::verify(issuer, &self.signature_algorithm, None, &self.tbs, &self.signature).unwrap_or(false)
+    }
+
+    /// 证书的吊销时间，未被吊销时返回 None
+    pub fn revocation(&self, certificate: &Certificate) -> Option<DateTime<Utc>> {
+        if certificate.issuer.raw != self.issuer.raw {
+            return None;
+        }
+        self.revoked.iter().find(|entry| entry.serial == certificate.serial).map(|entry| entry.revoked_at)
+    }
+}
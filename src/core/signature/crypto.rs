@@ -0,0 +1,67 @@
+// 签名值的密码学校验：按签名算法和证书公钥选择 RSA PKCS#1 v1.5 或 ECDSA 验证
+
+use ring::signature::{self, UnparsedPublicKey, VerificationAlgorithm};
+use crate::core::error::{HamsterError, HamsterResult};
+use super::x509::Certificate;
+use super::{algorithm_name, OID_SHA1, OID_SHA256};
+
+const OID_SHA384: &str = "2.16.840.1.101.3.4.2.2";
+const OID_SHA512: &str = "2.16.840.1.101.3.4.2.3";
+const OID_RSA: &str = "1.2.840.113549.1.1.1";
+const OID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+const OID_CURVE_P256: &str = "1.2.840.10045.3.1.7";
+const OID_CURVE_P384: &str = "1.3.132.0.34";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KeyType {
+    Rsa,
+    Ec,
+}
+
+/// 签名算法 OID 对应的密钥类型和摘要算法；SignerInfo 中的签名算法常为单纯的 rsaEncryption，
+/// 此时摘要算法取 SignerInfo 的摘要算法
+fn split_algorithm<'a>(signature_algorithm: &'a str, digest_algorithm: Option<&'a str>) -> Option<(KeyType, &'a str)> {
+    match signature_algorithm {
+        "1.2.840.113549.1.1.5" | "1.3.14.3.2.29" => Some((KeyType::Rsa, OID_SHA1)),
+        "1.2.840.113549.1.1.11" => Some((KeyType::Rsa, OID_SHA256)),
+        "1.2.840.113549.1.1.12" => Some((KeyType::Rsa, OID_SHA384)),
+        "1.2.840.113549.1.1.13" => Some((KeyType::Rsa, OID_SHA512)),
+        "1.2.840.10045.4.1" => Some((KeyType::Ec, OID_SHA1)),
+        "1.2.840.10045.4.3.2" => Some((KeyType::Ec, OID_SHA256)),
+        "1.2.840.10045.4.3.3" => Some((KeyType::Ec, OID_SHA384)),
+        "1.2.840.10045.4.3.4" => Some((KeyType::Ec, OID_SHA512)),
+        OID_RSA => digest_algorithm.map(|digest| (KeyType::Rsa, digest)),
+        OID_EC_PUBLIC_KEY => digest_algorithm.map(|digest| (KeyType::Ec, digest)),
+        _ => None,
+    }
+}
+
+fn ring_algorithm(key: KeyType, curve: Option<&str>, digest: &str) -> Option<&'static dyn VerificationAlgorithm> {
+    Some(match (key, curve, digest) {
+        // 旧的根证书和时间戳证书仍有 1024 位密钥
+        (KeyType::Rsa, _, OID_SHA1) => &signature::RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
+        (KeyType::Rsa, _, OID_SHA256) => &signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
+        (KeyType::Rsa, _, OID_SHA384) => &signature::RSA_PKCS1_2048_8192_SHA384,
+        (KeyType::Rsa, _, OID_SHA512) => &signature::RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
+        (KeyType::Ec, Some(OID_CURVE_P256), OID_SHA256) => &signature::ECDSA_P256_SHA256_ASN1,
+        (KeyType::Ec, Some(OID_CURVE_P256), OID_SHA384) => &signature::ECDSA_P256_SHA384_ASN1,
+        (KeyType::Ec, Some(OID_CURVE_P384), OID_SHA256) => &signature::ECDSA_P384_SHA256_ASN1,
+        (KeyType::Ec, Some(OID_CURVE_P384), OID_SHA384) => &signature::ECDSA_P384_SHA384_ASN1,
+        _ => return None,
+    })
+}
+
+/// 用证书的公钥验证签名；算法不受支持时返回错误，签名不符时返回 false
+pub fn verify(certificate: &Certificate, signature_algorithm: &str, digest_algorithm: Option<&str>, message: &[u8], signature: &[u8]) -> HamsterResult<bool> {
+    let unsupported = || HamsterError::parse(format!("不支持的签名算法 {}", algorithm_name(signature_algorithm)));
+    let (key, digest) = split_algorithm(signature_algorithm, digest_algorithm).ok_or_else(unsupported)?;
+    let key_matches = match key {
+        KeyType::Rsa => certificate.public_key_algorithm == OID_RSA,
+        KeyType::Ec => certificate.public_key_algorithm == OID_EC_PUBLIC_KEY,
+    };
+    if !key_matches {
+        return Ok(false);
+    }
+    let algorithm = ring_algorithm(key, certificate.public_key_parameters.as_deref(), digest).ok_or_else(unsupported)?;
+    Ok(UnparsedPublicKey::new(algorithm, &certificate.public_key).verify(message, signature).is_ok())
+}
@@ -0,0 +1,377 @@
+// ASN.1 DER 解码：按顺序读取 TLV（标签、长度、内容），并解释签名数据中用到的几种基本类型。
+// 一些旧的签名工具会输出 BER 的不定长编码，这里一并接受
+
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::core::error::{HamsterError, HamsterResult};
+
+pub const TAG_BOOLEAN: u8 = 0x01;
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_UTF8_STRING: u8 = 0x0C;
+pub const TAG_PRINTABLE_STRING: u8 = 0x13;
+pub const TAG_T61_STRING: u8 = 0x14;
+pub const TAG_IA5_STRING: u8 = 0x16;
+pub const TAG_UTC_TIME: u8 = 0x17;
+pub const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub const TAG_VISIBLE_STRING: u8 = 0x1A;
+pub const TAG_UNIVERSAL_STRING: u8 = 0x1C;
+pub const TAG_BMP_STRING: u8 = 0x1E;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_SET: u8 = 0x31;
+
+// 不定长编码的嵌套层数上限，防止构造的数据耗尽栈空间
+const MAX_DEPTH: usize = 64;
+
+/// 上下文相关标签 [n]，constructed 表示内容由其他 TLV 组成
+pub const fn context_tag(number: u8, constructed: bool) -> u8 {
+    if constructed { 0xA0 | number } else { 0x80 | number }
+}
+
+/// 一个完整的 TLV
+#[derive(Debug, Clone, Copy)]
+pub struct Tlv<'a> {
+    pub tag: u8,
+    pub contents: &'a [u8],
+    /// 包括标签和长度在内的完整编码，计算签名摘要时需要
+    pub raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// 解析 data 开头的一个 TLV
+    pub fn parse(data: &'a [u8]) -> HamsterResult<Self> {
+        DerReader::new(data).read()
+    }
+
+    /// 逐个读取内容中的子 TLV
+    pub fn reader(&self) -> DerReader<'a> {
+        DerReader::new(self.contents)
+    }
+
+    pub fn expect(self, tag: u8) -> HamsterResult<Self> {
+        if self.tag == tag {
+            Ok(self)
+        } else {
+            Err(HamsterError::parse(format!("ASN.1 标签不符：应为 0x{:02X}，实际为 0x{:02X}", tag, self.tag)))
+        }
+    }
+
+    /// 点分形式的对象标识符，如 "1.2.840.113549.1.7.2"
+    pub fn oid(&self) -> HamsterResult<String> {
+        let contents = self.expect(TAG_OID)?.contents;
+        let invalid = || HamsterError::parse("对象标识符编码无效");
+        let mut arcs: Vec<u64> = Vec::new();
+        let mut value: u64 = 0;
+        for (index, &byte) in contents.iter().enumerate() {
+            if value > u64::MAX >> 7 {
+                return Err(invalid());
+            }
+            value = (value << 7) | (byte & 0x7F) as u64;
+            if byte & 0x80 != 0 {
+                if index + 1 == contents.len() {
+                    return Err(invalid());
+                }
+                continue;
+            }
+            if arcs.is_empty() {
+                // 第一个子标识符合并了前两段
+                let first = (value / 40).min(2);
+                arcs.push(first);
+                arcs.push(value - first * 40);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        }
+        if arcs.is_empty() {
+            return Err(invalid());
+        }
+        Ok(arcs.iter().map(u64::to_string).collect::<Vec<_>>().join("."))
+    }
+
+    /// 整数的大端字节，去掉用于表示正数的前导0
+    pub fn integer_bytes(&self) -> HamsterResult<&'a [u8]> {
+        let contents = self.expect(TAG_INTEGER)?.contents;
+        if contents.is_empty() {
+            return Err(HamsterError::parse("整数编码为空"));
+        }
+        match contents {
+            [0, rest @ ..] if !rest.is_empty() => Ok(rest),
+            _ => Ok(contents),
+        }
+    }
+
+    /// 不超过64位的非负整数，如版本号
+    pub fn small_integer(&self) -> HamsterResult<u64> {
+        let bytes = self.integer_bytes()?;
+        if bytes.len() > 8 || self.contents[0] & 0x80 != 0 {
+            return Err(HamsterError::parse("整数超出范围"));
+        }
+        Ok(bytes.iter().fold(0u64, |value, &byte| (value << 8) | byte as u64))
+    }
+
+    pub fn boolean(&self) -> HamsterResult<bool> {
+        match self.expect(TAG_BOOLEAN)?.contents {
+            [value] => Ok(*value != 0),
+            _ => Err(HamsterError::parse("布尔值编码无效")),
+        }
+    }
+
+    /// 位串的内容，去掉开头表示未用位数的字节
+    pub fn bit_string(&self) -> HamsterResult<&'a [u8]> {
+        match self.expect(TAG_BIT_STRING)?.contents {
+            [_, rest @ ..] => Ok(rest),
+            [] => Err(HamsterError::parse("位串编码为空")),
+        }
+    }
+
+    pub fn octet_string(&self) -> HamsterResult<&'a [u8]> {
+        Ok(self.expect(TAG_OCTET_STRING)?.contents)
+    }
+
+    /// UTCTime 或 GeneralizedTime，只接受以 Z 结尾的 UTC 时间
+    pub fn time(&self) -> HamsterResult<DateTime<Utc>> {
+        let text = std::str::from_utf8(self.contents).map_err(|_| HamsterError::parse("时间编码无效"))?;
+        let invalid = || HamsterError::parse(format!("时间格式无效: {}", text));
+        let text = text.strip_suffix('Z').ok_or_else(invalid)?;
+        let (year, rest) = match self.tag {
+            TAG_UTC_TIME => {
+                let year: i32 = text.get(..2).and_then(|year| year.parse().ok()).ok_or_else(invalid)?;
+                // RFC 5280：50 及以上为 19xx 年
+                (if year >= 50 { 1900 + year } else { 2000 + year }, &text[2..])
+            }
+            TAG_GENERALIZED_TIME => {
+                let year = text.get(..4).and_then(|year| year.parse().ok()).ok_or_else(invalid)?;
+                (year, &text[4..])
+            }
+            _ => return Err(invalid()),
+        };
+        // 秒之后可能带有小数部分
+        let rest = rest.split('.').next().unwrap_or_default();
+        if !rest.is_ascii() {
+            return Err(invalid());
+        }
+        let field = |start: usize| rest.get(start..start + 2).and_then(|value| value.parse::<u32>().ok());
+        let date = NaiveDate::from_ymd_opt(year, field(0).ok_or_else(invalid)?, field(2).ok_or_else(invalid)?).ok_or_else(invalid)?;
+        let time = date.and_hms_opt(
+            field(4).ok_or_else(invalid)?,
+            field(6).ok_or_else(invalid)?,
+            if rest.len() >= 10 { field(8).ok_or_else(invalid)? } else { 0 },
+        ).ok_or_else(invalid)?;
+        Ok(time.and_utc())
+    }
+
+    /// 证书名称等处使用的各种字符串类型
+    pub fn string(&self) -> HamsterResult<String> {
+        match self.tag {
+            TAG_UTF8_STRING | TAG_PRINTABLE_STRING | TAG_IA5_STRING | TAG_VISIBLE_STRING => {
+                Ok(String::from_utf8_lossy(self.contents).to_string())
+            }
+            // T61String 实际上多为 Latin-1
+            TAG_T61_STRING => Ok(self.contents.iter().map(|&byte| byte as char).collect()),
+            TAG_BMP_STRING => {
+                let units: Vec<u16> = self.contents.chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                Ok(String::from_utf16_lossy(&units))
+            }
+            TAG_UNIVERSAL_STRING => Ok(self.contents.chunks_exact(4)
+                .filter_map(|bytes| char::from_u32(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])))
+                .collect()),
+            tag => Err(HamsterError::parse(format!("不支持的字符串类型 0x{:02X}", tag))),
+        }
+    }
+}
+
+/// 顺序读取一段数据中的 TLV
+#[derive(Debug, Clone)]
+pub struct DerReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    /// 下一个 TLV 的标签，不消耗数据
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    pub fn read(&mut self) -> HamsterResult<Tlv<'a>> {
+        let (tlv, length) = read_tlv(&self.data[self.position..], 0)?;
+        self.position += length;
+        Ok(tlv)
+    }
+
+    /// 读取指定标签的 TLV，并检查标签是否相符
+    pub fn expect(&mut self, tag: u8) -> HamsterResult<Tlv<'a>> {
+        self.read()?.expect(tag)
+    }
+
+    /// 下一个 TLV 的标签相符时读取，否则返回 None（用于可选字段）
+    pub fn next_if(&mut self, tag: u8) -> HamsterResult<Option<Tlv<'a>>> {
+        if self.peek_tag() == Some(tag) {
+            self.read().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// 读取一个 TLV，返回它和它占用的字节数
+fn read_tlv(data: &[u8], depth: usize) -> HamsterResult<(Tlv<'_>, usize)> {
+    let truncated = || HamsterError::parse("ASN.1 数据不完整");
+    if depth > MAX_DEPTH {
+        return Err(HamsterError::parse("ASN.1 数据嵌套过深"));
+    }
+    let tag = *data.first().ok_or_else(truncated)?;
+    if tag & 0x1F == 0x1F {
+        return Err(HamsterError::parse("不支持多字节的 ASN.1 标签"));
+    }
+    let first = *data.get(1).ok_or_else(truncated)?;
+
+    let (header, length) = match first {
+        0x00..=0x7F => (2, first as usize),
+        0x80 => {
+            // 不定长编码：内容为若干子 TLV，以两个0字节结束
+            if tag & 0x20 == 0 {
+                return Err(HamsterError::parse("基本类型不能使用不定长编码"));
+            }
+            let mut position = 2;
+            loop {
+                if data.get(position..position + 2) == Some(&[0, 0]) {
+                    let tlv = Tlv { tag, contents: &data[2..position], raw: &data[..position + 2] };
+                    return Ok((tlv, position + 2));
+                }
+                let (_, child) = read_tlv(data.get(position..).ok_or_else(truncated)?, depth + 1)?;
+                position += child;
+            }
+        }
+        _ => {
+            let count = (first & 0x7F) as usize;
+            if count > 4 {
+                return Err(HamsterError::parse("ASN.1 长度超出范围"));
+            }
+            let bytes = data.get(2..2 + count).ok_or_else(truncated)?;
+            (2 + count, bytes.iter().fold(0usize, |length, &byte| (length << 8) | byte as usize))
+        }
+    };
+    let end = header.checked_add(length).filter(|&end| end <= data.len()).ok_or_else(truncated)?;
+    Ok((Tlv { tag, contents: &data[header..end], raw: &data[..end] }, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_contents(header: &[u8], length: usize) -> Vec<u8> {
+        let mut data = header.to_vec();
+        data.extend((0..length).map(|index| index as u8));
+        data
+    }
+
+    #[test]
+    fn long_form_lengths_are_read() {
+        let data = with_contents(&[TAG_OCTET_STRING, 0x81, 0x80], 0x80);
+        let tlv = Tlv::parse(&data).unwrap();
+        assert_eq!(tlv.contents.len(), 0x80);
+        assert_eq!(tlv.raw.len(), 0x83);
+
+        let mut data = with_contents(&[TAG_OCTET_STRING, 0x82, 0x01, 0x2C], 0x12C);
+        // 后面的数据不属于这个 TLV
+        data.extend([TAG_NULL, 0x00]);
+        let mut reader = DerReader::new(&data);
+        let tlv = reader.expect(TAG_OCTET_STRING).unwrap();
+        assert_eq!(tlv.contents.len(), 0x12C);
+        assert_eq!(tlv.contents[0x12B], 0x2B);
+        assert_eq!(reader.expect(TAG_NULL).unwrap().contents, b"");
+        assert!(reader.is_empty());
+
+        // 长度字节超过4个时拒绝
+        assert!(Tlv::parse(&[TAG_OCTET_STRING, 0x85, 0, 0, 0, 0, 1, 0]).is_err());
+    }
+
+    #[test]
+    fn truncated_tlvs_are_rejected() {
+        let cases: [&[u8]; 6] = [
+            &[],
+            &[TAG_SEQUENCE],
+            &[TAG_SEQUENCE, 0x05, TAG_INTEGER, 0x01, 0x05],
+            &[TAG_OCTET_STRING, 0x82, 0x01],
+            &[TAG_OCTET_STRING, 0x81, 0x03, 0xAA, 0xBB],
+            &[TAG_OCTET_STRING, 0x84, 0xFF, 0xFF, 0xFF, 0xFF, 0x00],
+        ];
+        for data in cases {
+            let error = Tlv::parse(data).unwrap_err();
+            assert!(error.to_string().contains("不完整"), "{:02X?}: {}", data, error);
+        }
+
+        // 外层完整但内层被截断时，读取子 TLV 时报错
+        let outer = Tlv::parse(&[TAG_SEQUENCE, 0x03, TAG_INTEGER, 0x02, 0x05]).unwrap();
+        assert!(outer.reader().read().is_err());
+    }
+
+    #[test]
+    fn indefinite_length_is_limited_to_constructed_types() {
+        // 构造类型的不定长编码：内容为子 TLV，以两个0字节结束
+        let data = [TAG_SEQUENCE, 0x80, TAG_INTEGER, 0x01, 0x05, 0x00, 0x00, TAG_NULL, 0x00];
+        let mut reader = DerReader::new(&data);
+        let tlv = reader.expect(TAG_SEQUENCE).unwrap();
+        assert_eq!(tlv.contents, &[TAG_INTEGER, 0x01, 0x05]);
+        assert_eq!(tlv.raw.len(), 7);
+        assert_eq!(tlv.reader().read().unwrap().small_integer().unwrap(), 5);
+        assert_eq!(reader.peek_tag(), Some(TAG_NULL));
+
+        let error = Tlv::parse(&[TAG_OCTET_STRING, 0x80, 0x01, 0x00, 0x00]).unwrap_err();
+        assert!(error.to_string().contains("不定长"), "{}", error);
+        // 缺少结束标记
+        assert!(Tlv::parse(&[TAG_SEQUENCE, 0x80, TAG_INTEGER, 0x01, 0x05]).is_err());
+
+        let mut nested = [TAG_SEQUENCE, 0x80].repeat(MAX_DEPTH + 2);
+        nested.extend([0x00, 0x00].repeat(MAX_DEPTH + 2));
+        let error = Tlv::parse(&nested).unwrap_err();
+        assert!(error.to_string().contains("嵌套过深"), "{}", error);
+    }
+
+    #[test]
+    fn optional_fields_are_read_by_tag() {
+        let data = [context_tag(0, true), 0x03, TAG_INTEGER, 0x01, 0x02, TAG_INTEGER, 0x02, 0x00, 0x80];
+        let mut reader = DerReader::new(&data);
+        assert!(reader.next_if(context_tag(1, true)).unwrap().is_none());
+        let version = reader.next_if(context_tag(0, true)).unwrap().unwrap();
+        assert_eq!(version.reader().read().unwrap().small_integer().unwrap(), 2);
+        let serial = reader.read().unwrap();
+        // 前导0只用于表示正数
+        assert_eq!(serial.integer_bytes().unwrap(), &[0x80]);
+        assert_eq!(serial.small_integer().unwrap(), 0x80);
+        assert!(reader.read().is_err());
+        assert!(reader.expect(TAG_INTEGER).is_err());
+    }
+
+    #[test]
+    fn oids_and_times_are_decoded() {
+        let oid = [TAG_OID, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+        assert_eq!(Tlv::parse(&oid).unwrap().oid().unwrap(), "1.2.840.113549.1.7.2");
+        // 最后一个字节仍带有延续位
+        assert!(Tlv::parse(&[TAG_OID, 0x02, 0x2A, 0x86]).unwrap().oid().is_err());
+
+        let utc = [&[TAG_UTC_TIME, 0x0D][..], b"491231235959Z"].concat();
+        assert_eq!(Tlv::parse(&utc).unwrap().time().unwrap().to_rfc3339(), "2049-12-31T23:59:59+00:00");
+        let utc = [&[TAG_UTC_TIME, 0x0B][..], b"5001010000Z"].concat();
+        assert_eq!(Tlv::parse(&utc).unwrap().time().unwrap().to_rfc3339(), "1950-01-01T00:00:00+00:00");
+        let generalized = [&[TAG_GENERALIZED_TIME, 0x13][..], b"20450101000000.123Z"].concat();
+        assert_eq!(Tlv::parse(&generalized).unwrap().time().unwrap().to_rfc3339(), "2045-01-01T00:00:00+00:00");
+        // 只接受 UTC 时间
+        let local = [&[TAG_GENERALIZED_TIME, 0x0E][..], b"20450101000000"].concat();
+        assert!(Tlv::parse(&local).unwrap().time().is_err());
+    }
+}
@@ -0,0 +1,164 @@
+// Authenticode 映像摘要：按 Authenticode 规范计算 PE 文件的摘要，跳过校验和字段、证书表目录项和证书表本身，
+// 因此签名前后摘要不变；编录文件中 PE 成员的摘要也按这一规则计算
+
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::pe::{PeFile, DIRECTORY_SECURITY};
+use super::authenticode::AuthenticodeSignature;
+use super::{algorithm_name, hasher};
+
+/// 签名中记录的映像摘要与文件的比较结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DigestStatus {
+    Valid,
+    /// 文件在签名后被修改
+    Tampered,
+    /// 签名使用了无法计算的摘要算法（如 MD5）
+    Unsupported,
+}
+
+impl fmt::Display for DigestStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigestStatus::Valid => write!(f, "有效"),
+            DigestStatus::Tampered => write!(f, "已被篡改（与签名中的摘要不符）"),
+            DigestStatus::Unsupported => write!(f, "无法校验（不支持的摘要算法）"),
+        }
+    }
+}
+
+/// 计算 PE 文件的 Authenticode 映像摘要，algorithm 为摘要算法 OID
+pub fn image_digest(pe: &PeFile, algorithm: &str) -> HamsterResult<Vec<u8>> {
+    let mut digest = hasher(algorithm)
+        .ok_or_else(|| HamsterError::parse(format!("不支持的摘要算法 {}", algorithm_name(algorithm))))?;
+    let data = pe.data();
+    let out_of_range = || HamsterError::parse("PE 文件头或节超出文件范围");
+    let headers_end = pe.size_of_headers as usize;
+    let checksum = pe.checksum_offset();
+    if headers_end > data.len() || checksum + 4 > headers_end {
+        return Err(out_of_range());
+    }
+
+    // 文件头：跳过校验和字段和证书表目录项
+    digest.update(&data[..checksum]);
+    match pe.directory_entry_offset(DIRECTORY_SECURITY).filter(|&entry| entry + 8 <= headers_end) {
+        Some(entry) => {
+            digest.update(&data[checksum + 4..entry]);
+            digest.update(&data[entry + 8..headers_end]);
+        }
+        None => digest.update(&data[checksum + 4..headers_end]),
+    }
+
+    // 各节按在文件中的位置排序后依次计入；与 Windows 相同，超出文件末尾的部分按文件长度截断，
+    // 被截短的文件因此得到与签名不符的摘要，而不是无法计算
+    let mut sections: Vec<_> = pe.sections.iter().filter(|section| section.raw_size > 0).collect();
+    sections.sort_by_key(|section| section.raw_offset);
+    let mut hashed = headers_end;
+    for section in sections {
+        let start = (section.raw_offset as usize).min(data.len());
+        let end = start.saturating_add(section.raw_size as usize).min(data.len());
+        let bytes = &data[start..end];
+        digest.update(bytes);
+        hashed += bytes.len();
+    }
+
+    // 节之后的附加数据：文件大小减去证书表大小和已计入的字节数
+    let certificates = pe.directory(DIRECTORY_SECURITY).map(|directory| directory.size as usize).unwrap_or(0);
+    let remaining = data.len().saturating_sub(certificates).saturating_sub(hashed);
+    if remaining > 0 {
+        digest.update(data.get(hashed..hashed + remaining).ok_or_else(out_of_range)?);
+    }
+    Ok(digest.finalize().to_vec())
+}
+
+/// 用签名中记录的算法重新计算映像摘要，与签名中的摘要比较
+pub fn check_image_digest(pe: &PeFile, signature: &AuthenticodeSignature) -> HamsterResult<DigestStatus> {
+    if hasher(&signature.digest_algorithm).is_none() {
+        return Ok(DigestStatus::Unsupported);
+    }
+    let actual = image_digest(pe, &signature.digest_algorithm)?;
+    Ok(if actual == signature.image_digest { DigestStatus::Valid } else { DigestStatus::Tampered })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use crate::core::signature::authenticode::read_pe_signatures;
+    use crate::core::signature::{OID_SHA1, OID_SHA256};
+
+    fn fixture(path: &str) -> Vec<u8> {
+        std::fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)).unwrap()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// 修改第 index 个节头中的 SizeOfRawData
+    fn set_raw_size(data: &mut [u8], index: usize, size: u32) {
+        let pe_offset = u32::from_le_bytes(data[0x3C..0x40].try_into().unwrap()) as usize;
+        let optional_size = u16::from_le_bytes(data[pe_offset + 20..pe_offset + 22].try_into().unwrap()) as usize;
+        let header = pe_offset + 24 + optional_size + index * 40;
+        data[header + 16..header + 20].copy_from_slice(&size.to_le_bytes());
+    }
+
+    #[test]
+    fn fixture_digests_match_signatures() {
+        let pe = PeFile::parse(fixture("packages/nvidia/nvlddmkm.sys")).unwrap();
+        assert_eq!(hex(&image_digest(&pe, OID_SHA1).unwrap()), "519a015599738cf07c3f3541d70761497a69fcd7");
+        assert_eq!(
+            hex(&image_digest(&pe, OID_SHA256).unwrap()),
+            "59c2adfc605bd06aac6786759ec1e701fbb05e172d4dc3652f4b8f4cf408d5d5",
+        );
+        let signatures = read_pe_signatures(&pe).unwrap();
+        assert_eq!(check_image_digest(&pe, &signatures[0]).unwrap(), DigestStatus::Valid);
+
+        let pe = PeFile::parse(fixture("pe/RtkApo.dll")).unwrap();
+        assert_eq!(hex(&image_digest(&pe, OID_SHA1).unwrap()), "8dde10b5e77d16e701b899dba907f14546c59821");
+        assert!(image_digest(&pe, "1.2.840.113549.2.5").is_err());
+    }
+
+    #[test]
+    fn checksum_and_certificate_table_are_excluded() {
+        let original = fixture("packages/nvidia/nvlddmkm.sys");
+        let expected = image_digest(&PeFile::parse(original.clone()).unwrap(), OID_SHA256).unwrap();
+
+        let mut data = original.clone();
+        let checksum = PeFile::parse(data.clone()).unwrap().checksum_offset();
+        data[checksum] ^= 0xFF;
+        // 证书表中的字节不计入摘要
+        *data.last_mut().unwrap() ^= 0xFF;
+        let pe = PeFile::parse(data).unwrap();
+        assert_eq!(image_digest(&pe, OID_SHA256).unwrap(), expected);
+
+        let mut data = original;
+        let code = PeFile::parse(data.clone()).unwrap().sections[0].raw_offset as usize;
+        data[code] ^= 0xFF;
+        let pe = PeFile::parse(data).unwrap();
+        let signatures = read_pe_signatures(&pe).unwrap();
+        assert_eq!(check_image_digest(&pe, &signatures[0]).unwrap(), DigestStatus::Tampered);
+    }
+
+    #[test]
+    fn sections_past_end_of_file_are_clamped() {
+        let original = fixture("pe/RtkApo.dll");
+        let full = PeFile::parse(original.clone()).unwrap();
+        let resources = full.sections.last().unwrap();
+        let end = (resources.raw_offset + resources.raw_size) as usize;
+        assert_eq!(end, original.len());
+
+        // 截掉最后一个节的末尾：摘要按剩余的数据计算
+        let truncated = PeFile::parse(original[..end - 0x100].to_vec()).unwrap();
+        assert_eq!(hex(&image_digest(&truncated, OID_SHA1).unwrap()), "aca5120016fbcce883184be923642135896eb110");
+
+        // 签名文件的节声明的大小超出文件末尾时，得到的是摘要不符而不是错误
+        let mut data = fixture("packages/nvidia/nvlddmkm.sys");
+        let count = PeFile::parse(data.clone()).unwrap().sections.len();
+        set_raw_size(&mut data, count - 1, 0x0100_0000);
+        let pe = PeFile::parse(data).unwrap();
+        let signatures = read_pe_signatures(&pe).unwrap();
+        assert_eq!(check_image_digest(&pe, &signatures[0]).unwrap(), DigestStatus::Tampered);
+    }
+}
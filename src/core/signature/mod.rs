@@ -0,0 +1,60 @@
+// 数字签名解析：ASN.1 DER、X.509 证书、PKCS#7 SignedData、PE 文件的 Authenticode 签名和安全编录文件，
+// 全部离线完成，不依赖 Windows 的 WinVerifyTrust，在任何系统上都能查看驱动的签名信息
+
+pub mod authenticode;
+pub mod catalog;
+pub mod classify;
+pub mod crl;
+pub mod crypto;
+pub mod der;
+pub mod image_hash;
+pub mod pkcs7;
+pub mod trust;
+pub mod x509;
+
+pub use authenticode::{read_pe_signatures, AuthenticodeSignature, Timestamp, TimestampKind};
+pub use catalog::{Catalog, CatalogIndex, CatalogMatch, CatalogMember};
+pub use classify::{classify, Classification, LoadPrediction, SignatureClass};
+pub use image_hash::{check_image_digest, image_digest, DigestStatus};
+pub use pkcs7::{SignedData, SignerInfo};
+pub use x509::{Certificate, Name};
+
+use sha1::Sha1;
+use sha2::digest::DynDigest;
+use sha2::{Sha256, Sha384, Sha512};
+
+pub const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+pub const OID_SHA1: &str = "1.3.14.3.2.26";
+pub const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+
+/// 按摘要算法 OID 创建摘要计算器，不支持的算法（如 MD5）返回 None
+pub fn hasher(algorithm: &str) -> Option<Box<dyn DynDigest>> {
+    match algorithm {
+        OID_SHA1 => Some(Box::new(Sha1::default())),
+        OID_SHA256 => Some(Box::new(Sha256::default())),
+        "2.16.840.1.101.3.4.2.2" => Some(Box::new(Sha384::default())),
+        "2.16.840.1.101.3.4.2.3" => Some(Box::new(Sha512::default())),
+        _ => None,
+    }
+}
+
+/// 摘要和签名算法 OID 对应的常用名称，未知算法返回 OID 本身
+pub fn algorithm_name(oid: &str) -> &str {
+    match oid {
+        "1.2.840.113549.2.5" => "MD5",
+        "1.3.14.3.2.26" => "SHA-1",
+        "2.16.840.1.101.3.4.2.1" => "SHA-256",
+        "2.16.840.1.101.3.4.2.2" => "SHA-384",
+        "2.16.840.1.101.3.4.2.3" => "SHA-512",
+        "1.2.840.113549.1.1.1" => "RSA",
+        "1.2.840.113549.1.1.4" => "MD5 RSA",
+        "1.2.840.113549.1.1.5" => "SHA-1 RSA",
+        "1.2.840.113549.1.1.11" => "SHA-256 RSA",
+        "1.2.840.113549.1.1.12" => "SHA-384 RSA",
+        "1.2.840.113549.1.1.13" => "SHA-512 RSA",
+        "1.2.840.10045.2.1" => "ECC",
+        "1.2.840.10045.4.3.2" => "SHA-256 ECDSA",
+        "1.2.840.10045.4.3.3" => "SHA-384 ECDSA",
+        other => other,
+    }
+}
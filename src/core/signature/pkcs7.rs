@@ -0,0 +1,306 @@
+// PKCS#7 / CMS SignedData：Authenticode 签名、时间戳令牌和安全编录文件都采用这一结构
+
+use crate::core::error::{HamsterError, HamsterResult};
+use super::der::{self, context_tag, Tlv};
+use super::crypto;
+use super::x509::{algorithm_oid, Certificate, Name};
+use super::{algorithm_name, hasher, OID_SIGNED_DATA};
+
+const OID_SUBJECT_KEY_ID: &str = "2.5.29.14";
+const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+
+/// 签名者证书的标识方式
+#[derive(Debug, Clone)]
+pub enum SignerIdentifier {
+    IssuerAndSerial { issuer: Name, serial: Vec<u8> },
+    SubjectKeyId(Vec<u8>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub oid: String,
+    /// 各个值的完整编码
+    pub values: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignerInfo {
+    pub version: u64,
+    pub identifier: SignerIdentifier,
+    pub digest_algorithm: String,
+    pub authenticated_attributes: Vec<Attribute>,
+    /// 已签名属性的原始编码（标签为 [0]）；计算签名摘要时需把标签换成 SET
+    pub authenticated_raw: Option<Vec<u8>>,
+    pub signature_algorithm: String,
+    pub signature: Vec<u8>,
+    pub unauthenticated_attributes: Vec<Attribute>,
+}
+
+impl SignerInfo {
+    pub fn parse(tlv: Tlv) -> HamsterResult<Self> {
+        let mut fields = tlv.expect(der::TAG_SEQUENCE)?.reader();
+        let version = fields.read()?.small_integer()?;
+        let identifier = match fields.next_if(context_tag(0, false))? {
+            Some(key_id) => SignerIdentifier::SubjectKeyId(key_id.contents.to_vec()),
+            None => {
+                let mut issuer_serial = fields.expect(der::TAG_SEQUENCE)?.reader();
+                let issuer = Name::parse(issuer_serial.read()?)?;
+                let serial = issuer_serial.read()?.integer_bytes()?.to_vec();
+                SignerIdentifier::IssuerAndSerial { issuer, serial }
+            }
+        };
+        let digest_algorithm = algorithm_oid(fields.read()?)?;
+        let authenticated = fields.next_if(context_tag(0, true))?;
+        let signature_algorithm = algorithm_oid(fields.read()?)?;
+        let signature = fields.read()?.octet_string()?.to_vec();
+        let unauthenticated = fields.next_if(context_tag(1, true))?;
+
+        Ok(Self {
+            version,
+            identifier,
+            digest_algorithm,
+            authenticated_attributes: authenticated.map(parse_attributes).transpose()?.unwrap_or_default(),
+            authenticated_raw: authenticated.map(|attributes| attributes.raw.to_vec()),
+            signature_algorithm,
+            signature,
+            unauthenticated_attributes: unauthenticated.map(parse_attributes).transpose()?.unwrap_or_default(),
+        })
+    }
+
+    pub fn authenticated(&self, oid: &str) -> Option<&Attribute> {
+        self.authenticated_attributes.iter().find(|attribute| attribute.oid == oid)
+    }
+
+    pub fn unauthenticated<'a>(&'a self, oid: &'a str) -> impl Iterator<Item = &'a Attribute> {
+        self.unauthenticated_attributes.iter().filter(move |attribute| attribute.oid == oid)
+    }
+
+    /// 签名实际覆盖的数据：把已签名属性的 [0] 标签换成 SET 后的编码
+    pub fn signed_attributes_der(&self) -> Option<Vec<u8>> {
+        self.authenticated_raw.as_ref().map(|raw| {
+            let mut encoded = raw.clone();
+            encoded[0] = der::TAG_SET;
+            encoded
+        })
+    }
+
+    /// 验证签名者对内容的签名：有已签名属性时先比较其中的消息摘要，再验证属性的签名；
+    /// 算法不受支持时返回错误，摘要或签名不符时返回 false
+    pub fn verify(&self, certificate: &Certificate, content: &[u8]) -> HamsterResult<bool> {
+        let Some(signed_attributes) = self.signed_attributes_der() else {
+            return crypto::verify(certificate, &self.signature_algorithm, Some(&self.digest_algorithm), content, &self.signature);
+        };
+        let mut digest = hasher(&self.digest_algorithm)
+            .ok_or_else(|| HamsterError::parse(format!("不支持的摘要算法 {}", algorithm_name(&self.digest_algorithm))))?;
+        digest.update(content);
+        let expected = match self.authenticated(OID_MESSAGE_DIGEST).and_then(|attribute| attribute.values.first()) {
+            Some(value) => Tlv::parse(value)?.octet_string()?.to_vec(),
+            None => return Ok(false),
+        };
+        if digest.finalize().as_ref() != expected.as_slice() {
+            return Ok(false);
+        }
+        crypto::verify(certificate, &self.signature_algorithm, Some(&self.digest_algorithm), &signed_attributes, &self.signature)
+    }
+
+    /// 在证书集合中查找签名者的证书
+    pub fn find_certificate<'c>(&self, certificates: &'c [Certificate]) -> Option<&'c Certificate> {
+        certificates.iter().find(|certificate| match &self.identifier {
+            SignerIdentifier::IssuerAndSerial { issuer, serial } => {
+                certificate.serial == *serial && certificate.issuer.raw == issuer.raw
+            }
+            SignerIdentifier::SubjectKeyId(key_id) => certificate.extension(OID_SUBJECT_KEY_ID)
+                .and_then(|extension| Tlv::parse(&extension.value).ok())
+                .is_some_and(|value| value.contents == key_id.as_slice()),
+        })
+    }
+}
+
+/// 解析属性集合（SET OF Attribute），编录文件的成员属性也使用这一结构
+pub fn parse_attributes(tlv: Tlv) -> HamsterResult<Vec<Attribute>> {
+    let mut attributes = Vec::new();
+    let mut reader = tlv.reader();
+    while !reader.is_empty() {
+        let mut attribute = reader.expect(der::TAG_SEQUENCE)?.reader();
+        let oid = attribute.read()?.oid()?;
+        let mut values = attribute.expect(der::TAG_SET)?.reader();
+        let mut encoded = Vec::new();
+        while !values.is_empty() {
+            encoded.push(values.read()?.raw.to_vec());
+        }
+        attributes.push(Attribute { oid, values: encoded });
+    }
+    Ok(attributes)
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedData {
+    pub version: u64,
+    pub digest_algorithms: Vec<String>,
+    /// 被签名内容的类型，如 Authenticode 的 SpcIndirectDataContent
+    pub content_type: String,
+    /// 被签名内容的完整编码（[0] 显式标签内的部分），没有内容时为空
+    pub content: Vec<u8>,
+    pub certificates: Vec<Certificate>,
+    pub signers: Vec<SignerInfo>,
+}
+
+impl SignedData {
+    /// 从 ContentInfo 编码解析，内容类型必须为 SignedData
+    pub fn parse(data: &[u8]) -> HamsterResult<Self> {
+        Self::from_content_info(Tlv::parse(data)?)
+            .map_err(|e| HamsterError::parse("PKCS#7 签名数据无效").with_source(e))
+    }
+
+    pub fn from_content_info(tlv: Tlv) -> HamsterResult<Self> {
+        let mut content_info = tlv.expect(der::TAG_SEQUENCE)?.reader();
+        let content_type = content_info.read()?.oid()?;
+        if content_type != OID_SIGNED_DATA {
+            return Err(HamsterError::parse(format!("内容类型 {} 不是 SignedData", content_type)));
+        }
+        let wrapper = content_info.expect(context_tag(0, true))?;
+        let mut fields = wrapper.reader().expect(der::TAG_SEQUENCE)?.reader();
+
+        let version = fields.read()?.small_integer()?;
+        let mut digest_algorithms = Vec::new();
+        let mut algorithms = fields.expect(der::TAG_SET)?.reader();
+        while !algorithms.is_empty() {
+            digest_algorithms.push(algorithm_oid(algorithms.read()?)?);
+        }
+
+        let mut encapsulated = fields.expect(der::TAG_SEQUENCE)?.reader();
+        let inner_type = encapsulated.read()?.oid()?;
+        let content = match encapsulated.next_if(context_tag(0, true))? {
+            Some(wrapper) => wrapper.reader().read()?.raw.to_vec(),
+            None => Vec::new(),
+        };
+
+        let mut certificates = Vec::new();
+        if let Some(set) = fields.next_if(context_tag(0, true))? {
+            let mut reader = set.reader();
+            while !reader.is_empty() {
+                let entry = reader.read()?;
+                // 属性证书等其他类型与签名验证无关
+                if entry.tag == der::TAG_SEQUENCE {
+                    certificates.push(Certificate::parse(entry)?);
+                }
+            }
+        }
+        fields.next_if(context_tag(1, true))?;
+
+        let mut signers = Vec::new();
+        let mut signer_infos = fields.expect(der::TAG_SET)?.reader();
+        while !signer_infos.is_empty() {
+            signers.push(SignerInfo::parse(signer_infos.read()?)?);
+        }
+
+        Ok(Self { version, digest_algorithms, content_type: inner_type, content, certificates, signers })
+    }
+
+    /// 签名者的消息摘要所覆盖的数据：被签名内容去掉标签和长度后的部分
+    pub fn signed_content(&self) -> HamsterResult<&[u8]> {
+        Ok(Tlv::parse(&self.content)?.contents)
+    }
+
+    /// 第一个签名者及其证书
+    pub fn signer(&self) -> Option<(&SignerInfo, Option<&Certificate>)> {
+        self.signers.first().map(|signer| (signer, signer.find_certificate(&self.certificates)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use crate::core::pe::PeFile;
+    use crate::core::signature::authenticode::read_pe_signatures;
+
+    const OID_COUNTER_SIGNATURE: &str = "1.2.840.113549.1.9.6";
+
+    fn fixture(path: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+    }
+
+    /// 示例驱动的 Authenticode 签名：签名者带有已签名属性，并附有时间戳副署
+    fn driver_signature() -> SignedData {
+        let pe = PeFile::open(&fixture("packages/nvidia/nvlddmkm.sys")).unwrap();
+        read_pe_signatures(&pe).unwrap().remove(0).signed_data
+    }
+
+    #[test]
+    fn signer_with_signed_attributes() {
+        let signed_data = driver_signature();
+        assert_eq!(signed_data.version, 1);
+        assert_eq!(signed_data.content_type, "1.3.6.1.4.1.311.2.1.4");
+        assert_eq!(signed_data.certificates.len(), 3);
+        assert_eq!(signed_data.signers.len(), 1);
+
+        let (signer, certificate) = signed_data.signer().unwrap();
+        let certificate = certificate.unwrap();
+        assert_eq!(certificate.subject.common_name(), Some("NVIDIA Corporation"));
+        assert!(matches!(&signer.identifier, SignerIdentifier::IssuerAndSerial { serial, .. } if serial == &certificate.serial));
+        assert_eq!(signer.digest_algorithm, crate::core::signature::OID_SHA1);
+        let oids: Vec<&str> = signer.authenticated_attributes.iter().map(|attribute| attribute.oid.as_str()).collect();
+        assert_eq!(oids, ["1.3.6.1.4.1.311.2.1.12", "1.2.840.113549.1.9.3", "1.3.6.1.4.1.311.2.1.11", OID_MESSAGE_DIGEST]);
+
+        // 签名覆盖的是把 [0] 换成 SET 后的属性编码
+        let raw = signer.authenticated_raw.as_ref().unwrap();
+        let encoded = signer.signed_attributes_der().unwrap();
+        assert_eq!((raw[0], encoded[0]), (context_tag(0, true), der::TAG_SET));
+        assert_eq!(raw[1..], encoded[1..]);
+
+        let content = signed_data.signed_content().unwrap();
+        assert!(signer.verify(certificate, content).unwrap());
+        // 内容与消息摘要不符
+        let mut changed = content.to_vec();
+        changed[0] ^= 1;
+        assert!(!signer.verify(certificate, &changed).unwrap());
+        // 属性被改动后签名不符
+        let mut tampered = signer.clone();
+        *tampered.authenticated_raw.as_mut().unwrap().last_mut().unwrap() ^= 1;
+        assert!(!tampered.verify(certificate, content).unwrap());
+
+        // 时间戳副署本身也是 SignerInfo
+        let counter = signer.unauthenticated(OID_COUNTER_SIGNATURE).next().unwrap();
+        let counter = SignerInfo::parse(Tlv::parse(&counter.values[0]).unwrap()).unwrap();
+        assert!(counter.authenticated(OID_MESSAGE_DIGEST).is_some());
+        assert_eq!(counter.find_certificate(&signed_data.certificates).unwrap().subject.common_name(), Some("Hamster Test Timestamp Authority"));
+    }
+
+    #[test]
+    fn signer_without_signed_attributes() {
+        let data = std::fs::read(fixture("pkcs7/no-signed-attributes.p7")).unwrap();
+        let signed_data = SignedData::parse(&data).unwrap();
+        assert_eq!(signed_data.content_type, "1.2.840.113549.1.7.1");
+        assert!(signed_data.certificates.is_empty());
+        assert_eq!(signed_data.signed_content().unwrap(), b"Hamster test content\n");
+
+        let signer = &signed_data.signers[0];
+        assert_eq!(signer.version, 3);
+        assert!(matches!(signer.identifier, SignerIdentifier::SubjectKeyId(_)));
+        assert!(signer.authenticated_attributes.is_empty() && signer.authenticated_raw.is_none());
+        assert!(signer.signed_attributes_der().is_none());
+        assert!(signer.unauthenticated_attributes.is_empty());
+        assert!(signed_data.signer().unwrap().1.is_none());
+
+        // 按使用者密钥标识在驱动签名的证书中找到签名者证书，签名直接覆盖内容
+        let certificates = driver_signature().certificates;
+        let certificate = signer.find_certificate(&certificates).unwrap();
+        assert_eq!(certificate.subject.common_name(), Some("NVIDIA Corporation"));
+        assert!(signer.verify(certificate, b"Hamster test content\n").unwrap());
+        assert!(!signer.verify(certificate, b"Hamster test content").unwrap());
+    }
+
+    #[test]
+    fn other_content_types_and_truncated_data_are_rejected() {
+        let data = std::fs::read(fixture("pkcs7/no-signed-attributes.p7")).unwrap();
+        for length in [0, 20, 100, data.len() - 1] {
+            assert!(SignedData::parse(&data[..length]).is_err(), "{}", length);
+        }
+        // ContentInfo 中的内容类型改为 pkcs7-data
+        let mut data = data;
+        data[14] = 0x01;
+        let error = SignedData::parse(&data).unwrap_err();
+        assert!(format!("{:?}", error).contains("不是 SignedData"), "{:?}", error);
+    }
+}
@@ -0,0 +1,412 @@
+// 离线信任验证：用签名中附带的证书和本地受信任证书目录构建从签名者到根证书的证书链，
+// 按时间戳时间检查有效期，检查扩展密钥用法和本地证书吊销列表，全程不联网
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::error::{HamsterError, HamsterResult};
+use super::authenticode::{tst_message_imprint, Timestamp, TimestampKind};
+use super::crl::Crl;
+use super::der::Tlv;
+use super::pkcs7::{SignedData, SignerInfo};
+use super::x509::Certificate;
+use super::{algorithm_name, hasher};
+
+pub const EKU_ANY: &str = "2.5.29.37.0";
+pub const EKU_CODE_SIGNING: &str = "1.3.6.1.5.5.7.3.3";
+pub const EKU_TIME_STAMPING: &str = "1.3.6.1.5.5.7.3.8";
+/// Windows 硬件驱动程序验证（WHQL）
+pub const EKU_WHQL: &str = "1.3.6.1.4.1.311.10.3.5";
+/// Windows 硬件驱动程序证明签名
+pub const EKU_ATTESTATION: &str = "1.3.6.1.4.1.311.10.3.5.1";
+/// Windows 系统组件验证
+pub const EKU_SYSTEM_COMPONENT: &str = "1.3.6.1.4.1.311.10.3.6";
+
+/// 驱动签名证书可接受的用途，满足其一即可
+pub const DRIVER_SIGNING_USAGES: &[&str] = &[EKU_CODE_SIGNING, EKU_WHQL, EKU_ATTESTATION, EKU_SYSTEM_COMPONENT];
+
+// 证书链的长度上限，防止交叉签名形成的环路
+const MAX_CHAIN_LENGTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrustStatus {
+    Trusted,
+    /// 签名值与被签名的内容不符
+    BadSignature,
+    /// 签名或证书使用了无法验证的算法
+    UnsupportedAlgorithm,
+    /// 找不到某个证书的颁发者
+    PartialChain,
+    /// 证书链终止于不受信任的根证书
+    UntrustedRoot,
+    /// 证书链中的签名或 CA 约束无效
+    InvalidChain,
+    Expired,
+    /// 证书的扩展密钥用法不允许此用途
+    WrongUsage,
+    Revoked,
+}
+
+impl fmt::Display for TrustStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrustStatus::Trusted => write!(f, "受信任"),
+            TrustStatus::BadSignature => write!(f, "签名无效"),
+            TrustStatus::UnsupportedAlgorithm => write!(f, "不支持的算法"),
+            TrustStatus::PartialChain => write!(f, "证书链不完整"),
+            TrustStatus::UntrustedRoot => write!(f, "根证书不受信任"),
+            TrustStatus::InvalidChain => write!(f, "证书链无效"),
+            TrustStatus::Expired => write!(f, "证书已过期"),
+            TrustStatus::WrongUsage => write!(f, "证书用途不符"),
+            TrustStatus::Revoked => write!(f, "证书已被吊销"),
+        }
+    }
+}
+
+/// 一个签名的信任验证结果
+#[derive(Debug, Clone)]
+pub struct SignatureTrust {
+    pub status: TrustStatus,
+    /// 不受信任的具体原因，受信任时为空
+    pub detail: String,
+    /// 从签名者到根证书的证书链；构建失败时只有已找到的部分
+    pub chain: Vec<Certificate>,
+    /// 检查有效期和吊销状态所用的时间：时间戳通过验证时为时间戳时间，否则为当前时间
+    pub verified_at: DateTime<Utc>,
+    pub timestamp_trusted: bool,
+}
+
+type Failure = (TrustStatus, String);
+
+/// 本地信任库：受信任的根证书和证书吊销列表
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    roots: Vec<Certificate>,
+    crls: Vec<Crl>,
+    /// 无法读取的文件及原因，不影响其余文件
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+impl TrustStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 读取目录中的证书（.cer/.crt/.der/.pem）和吊销列表（.crl），DER 和 PEM 格式均可
+    pub fn load_dir(dir: &Path) -> Self {
+        let mut store = Self::new();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                store.errors.push((dir.to_path_buf(), e.to_string()));
+                return store;
+            }
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| path.is_file()).collect();
+        paths.sort();
+        for path in paths {
+            let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_ascii_lowercase();
+            let result = match extension.as_str() {
+                "cer" | "crt" | "der" | "pem" => store.load_certificates(&path),
+                "crl" => store.load_crls(&path),
+                _ => continue,
+            };
+            if let Err(e) = result {
+                store.errors.push((path, e.to_string()));
+            }
+        }
+        store
+    }
+
+    fn load_certificates(&mut self, path: &Path) -> HamsterResult<()> {
+        for block in read_blocks(path, "CERTIFICATE")? {
+            self.add_root(Certificate::parse(Tlv::parse(&block)?)?);
+        }
+        Ok(())
+    }
+
+    fn load_crls(&mut self, path: &Path) -> HamsterResult<()> {
+        for block in read_blocks(path, "X509 CRL")? {
+            self.crls.push(Crl::parse(&block)?);
+        }
+        Ok(())
+    }
+
+    pub fn add_root(&mut self, certificate: Certificate) {
+        if !self.is_root(&certificate) {
+            self.roots.push(certificate);
+        }
+    }
+
+    pub fn add_crl(&mut self, crl: Crl) {
+        self.crls.push(crl);
+    }
+
+    pub fn roots(&self) -> &[Certificate] {
+        &self.roots
+    }
+
+    pub fn crls(&self) -> &[Crl] {
+        &self.crls
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    fn is_root(&self, certificate: &Certificate) -> bool {
+        self.roots.iter().any(|root| root.raw == certificate.raw)
+    }
+
+    /// 验证 PKCS#7 签名：签名值、时间戳、证书链、有效期、用途和吊销状态；now 为没有可信时间戳时使用的时间
+    pub fn verify_signed_data(&self, signed_data: &SignedData, timestamps: &[Timestamp], usages: &[&str], now: DateTime<Utc>) -> SignatureTrust {
+        let mut trust = SignatureTrust {
+            status: TrustStatus::Trusted,
+            detail: String::new(),
+            chain: Vec::new(),
+            verified_at: now,
+            timestamp_trusted: false,
+        };
+        let mut timestamp_failure = None;
+        for timestamp in timestamps {
+            match self.verify_timestamp(timestamp, signed_data) {
+                Ok(()) => {
+                    trust.verified_at = timestamp.time;
+                    trust.timestamp_trusted = true;
+                    break;
+                }
+                Err(failure) => {
+                    timestamp_failure.get_or_insert(failure);
+                }
+            }
+        }
+
+        let result = self.verify_signer(signed_data, usages, trust.verified_at, &mut trust.chain);
+        if let Err((status, mut detail)) = result {
+            // 时间戳无效时按当前时间检查，证书过期的原因多半在时间戳
+            if let (TrustStatus::Expired, Some((_, reason))) = (status, &timestamp_failure) {
+                detail = format!("{}；时间戳未通过验证：{}", detail, reason);
+            }
+            trust.status = status;
+            trust.detail = detail;
+        }
+        trust
+    }
+
+    fn verify_signer<'a>(&'a self, signed_data: &'a SignedData, usages: &[&str], time: DateTime<Utc>, chain: &mut Vec<Certificate>) -> Result<(), Failure> {
+        let certificate = signer_certificate(signed_data)?;
+        let mut path = vec![certificate];
+        let result = self.build_chain(&mut path, &signed_data.certificates)
+            .and_then(|()| self.check_chain(&path, time, usages));
+        chain.extend(path.into_iter().cloned());
+        result
+    }
+
+    /// 验证时间戳：颁发机构对外层签名值的签名，以及颁发机构证书链在时间戳时间的有效性
+    fn verify_timestamp(&self, timestamp: &Timestamp, signed_data: &SignedData) -> Result<(), Failure> {
+        let (outer, _) = signed_data.signer()
+            .ok_or_else(|| (TrustStatus::BadSignature, "签名中没有签名者".to_string()))?;
+        let certificate = timestamp.certificate.as_ref()
+            .ok_or_else(|| (TrustStatus::PartialChain, "签名中没有时间戳颁发机构的证书".to_string()))?;
+        let mut pool = signed_data.certificates.clone();
+        match (timestamp.kind, &timestamp.token) {
+            (TimestampKind::Rfc3161, Some(token)) => {
+                let content = token.signed_content().map_err(|e| (TrustStatus::BadSignature, e.to_string()))?;
+                check_signer(&timestamp.signer, certificate, content, "时间戳令牌的签名无效")?;
+                check_imprint(&token.content, &outer.signature)?;
+                pool.extend(token.certificates.iter().cloned());
+            }
+            _ => check_signer(&timestamp.signer, certificate, &outer.signature, "副署签名与签名值不符")?,
+        }
+        let mut path = vec![certificate];
+        self.build_chain(&mut path, &pool)?;
+        self.check_chain(&path, timestamp.time, &[EKU_TIME_STAMPING])
+    }
+
+    /// 从链尾的证书向上查找颁发者，直到受信任的根证书；遇到多个候选时逐个尝试
+    fn build_chain<'a>(&'a self, chain: &mut Vec<&'a Certificate>, pool: &'a [Certificate]) -> Result<(), Failure> {
+        let current = *chain.last().expect("证书链至少包含签名者证书");
+        if self.is_root(current) {
+            return Ok(());
+        }
+        if chain.len() >= MAX_CHAIN_LENGTH {
+            return Err((TrustStatus::PartialChain, format!("证书链超过 {} 层", MAX_CHAIN_LENGTH)));
+        }
+        let mut failure = None;
+        for candidate in pool.iter().chain(&self.roots) {
+            if candidate.subject.raw != current.issuer.raw || chain.iter().any(|cert| cert.raw == candidate.raw) {
+                continue;
+            }
+            match current.is_issued_by(candidate) {
+                Ok(true) => {}
+                Ok(false) => {
+                    failure.get_or_insert((TrustStatus::InvalidChain, format!(
+                        "证书 {} 的签名无法用 {} 的公钥验证",
+                        current.subject.display_name(), candidate.subject.display_name(),
+                    )));
+                    continue;
+                }
+                Err(e) => {
+                    failure.get_or_insert((TrustStatus::UnsupportedAlgorithm, e.to_string()));
+                    continue;
+                }
+            }
+            chain.push(candidate);
+            match self.build_chain(chain, pool) {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    chain.pop();
+                    failure = Some(e);
+                }
+            }
+        }
+        Err(failure.unwrap_or_else(|| if current.is_self_issued() {
+            (TrustStatus::UntrustedRoot, format!("根证书 {} 不在受信任的证书目录中", current.subject.display_name()))
+        } else {
+            (TrustStatus::PartialChain, format!(
+                "找不到证书 {} 的颁发者 {}",
+                current.subject.display_name(), current.issuer.display_name(),
+            ))
+        }))
+    }
+
+    fn check_chain(&self, chain: &[&Certificate], time: DateTime<Utc>, usages: &[&str]) -> Result<(), Failure> {
+        for (index, certificate) in chain.iter().enumerate() {
+            let name = certificate.subject.display_name();
+            if !certificate.is_valid_at(time) {
+                return Err((TrustStatus::Expired, format!(
+                    "证书 {} 在 {} 不在有效期内（{} 至 {}）",
+                    name, format_time(time), format_time(certificate.not_before), format_time(certificate.not_after),
+                )));
+            }
+            let invalid = |e: HamsterError| (TrustStatus::InvalidChain, format!("证书 {} 的扩展无效：{}", name, e));
+            if index > 0 && certificate.is_ca().map_err(invalid)? == Some(false) {
+                return Err((TrustStatus::InvalidChain, format!("证书 {} 不是 CA 证书，不能签发其他证书", name)));
+            }
+            if let Some(granted) = certificate.extended_key_usages().map_err(invalid)? {
+                if !granted.iter().any(|usage| usage == EKU_ANY || usages.contains(&usage.as_str())) {
+                    let wanted: Vec<&str> = usages.iter().map(|usage| usage_name(usage)).collect();
+                    return Err((TrustStatus::WrongUsage, format!("证书 {} 的用途不包括{}", name, wanted.join("或"))));
+                }
+            }
+            if let Some(issuer) = chain.get(index + 1) {
+                let revoked_at = self.crls.iter()
+                    .filter(|crl| crl.is_issued_by(issuer))
+                    .filter_map(|crl| crl.revocation(certificate))
+                    .min();
+                if let Some(revoked_at) = revoked_at.filter(|&revoked_at| revoked_at <= time) {
+                    return Err((TrustStatus::Revoked, format!("证书 {} 已于 {} 被吊销", name, format_time(revoked_at))));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 只验证签名者对内容的签名，不构建证书链；没有信任库时也应调用，签名值无效时返回状态和原因
+pub fn verify_signature(signed_data: &SignedData) -> Result<(), (TrustStatus, String)> {
+    signer_certificate(signed_data).map(|_| ())
+}
+
+/// 签名者的证书，签名者对内容的签名已通过验证
+fn signer_certificate(signed_data: &SignedData) -> Result<&Certificate, Failure> {
+    let (signer, certificate) = signed_data.signer()
+        .ok_or_else(|| (TrustStatus::BadSignature, "签名中没有签名者".to_string()))?;
+    let certificate = certificate
+        .ok_or_else(|| (TrustStatus::PartialChain, "签名中没有签名者的证书".to_string()))?;
+    let content = signed_data.signed_content()
+        .map_err(|e| (TrustStatus::BadSignature, e.to_string()))?;
+    check_signer(signer, certificate, content, "签名与被签名的内容不符")?;
+    Ok(certificate)
+}
+
+fn check_signer(signer: &SignerInfo, certificate: &Certificate, content: &[u8], mismatch: &str) -> Result<(), Failure> {
+    match signer.verify(certificate, content) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err((TrustStatus::BadSignature, mismatch.to_string())),
+        Err(e) => Err((TrustStatus::UnsupportedAlgorithm, e.to_string())),
+    }
+}
+
+/// RFC 3161 令牌中的消息印记必须是外层签名值的摘要
+fn check_imprint(tst_info: &[u8], signature: &[u8]) -> Result<(), Failure> {
+    let (algorithm, expected) = tst_message_imprint(tst_info).map_err(|e| (TrustStatus::BadSignature, e.to_string()))?;
+    let mut digest = hasher(&algorithm).ok_or_else(|| (
+        TrustStatus::UnsupportedAlgorithm,
+        format!("不支持的摘要算法 {}", algorithm_name(&algorithm)),
+    ))?;
+    digest.update(signature);
+    if digest.finalize().as_ref() != expected.as_slice() {
+        return Err((TrustStatus::BadSignature, "时间戳令牌不是为此签名颁发的".to_string()));
+    }
+    Ok(())
+}
+
+/// 文件中的 DER 数据；PEM 格式时取出所有指定类型的块
+fn read_blocks(path: &Path, label: &str) -> HamsterResult<Vec<Vec<u8>>> {
+    let data = fs::read(path).map_err(|e| HamsterError::io(format!("读取 {} 失败", path.display()), e))?;
+    let Ok(text) = std::str::from_utf8(&data) else {
+        return Ok(vec![data]);
+    };
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    if !text.contains(&begin) {
+        return Ok(vec![data]);
+    }
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(&begin) {
+        let body = &rest[start + begin.len()..];
+        let stop = body.find(&end).ok_or_else(|| HamsterError::parse(format!("PEM 块缺少 {}", end)))?;
+        let encoded: String = body[..stop].chars().filter(|c| !c.is_whitespace()).collect();
+        let decoded = base64::engine::general_purpose::STANDARD.decode(encoded)
+            .map_err(|e| HamsterError::parse(format!("PEM 块的 Base64 编码无效：{}", e)))?;
+        blocks.push(decoded);
+        rest = &body[stop + end.len()..];
+    }
+    Ok(blocks)
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// 用途 OID 对应的名称
+pub fn usage_name(oid: &str) -> &str {
+    match oid {
+        EKU_ANY => "任意用途",
+        EKU_CODE_SIGNING => "代码签名",
+        EKU_TIME_STAMPING => "时间戳",
+        EKU_WHQL => "Windows 硬件驱动程序验证",
+        EKU_ATTESTATION => "Windows 硬件驱动程序证明签名",
+        EKU_SYSTEM_COMPONENT => "Windows 系统组件验证",
+        other => other,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_directory_loads_root_and_crl() {
+        let store = TrustStore::load_dir(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/trust"));
+        assert!(store.errors.is_empty(), "{:?}", store.errors);
+        assert_eq!(store.roots().len(), 1);
+        assert_eq!(store.roots()[0].subject.display_name(), "Hamster Test Root CA");
+        assert_eq!(store.roots()[0].thumbprint(), "402387E0B5DD31D6E0830DAD14097ECA57BC7B02");
+        assert_eq!(store.crls().len(), 1);
+        assert_eq!(store.crls()[0].issuer.display_name(), "Hamster Test Code Signing CA");
+    }
+
+    #[test]
+    fn missing_directory_is_reported() {
+        let store = TrustStore::load_dir(Path::new("/nonexistent/hamster-trust"));
+        assert!(store.roots().is_empty());
+        assert_eq!(store.errors.len(), 1);
+    }
+}
@@ -0,0 +1,354 @@
+// X.509 证书解析：只取出显示签名者和构建证书链所需的字段
+
+use std::fmt;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use crate::core::error::{HamsterError, HamsterResult};
+use super::crypto;
+use super::der::{self, context_tag, Tlv};
+
+const OID_BASIC_CONSTRAINTS: &str = "2.5.29.19";
+const OID_EXTENDED_KEY_USAGE: &str = "2.5.29.37";
+
+/// 证书中的名称（颁发者或使用者），按编码顺序保存各属性
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Name {
+    /// (属性 OID, 值)，如 ("2.5.4.3", "Microsoft Windows Hardware Compatibility Publisher")
+    pub attributes: Vec<(String, String)>,
+    /// 原始编码，比较颁发者和使用者时按编码比较
+    #[serde(skip)]
+    pub raw: Vec<u8>,
+}
+
+impl Name {
+    pub fn parse(tlv: Tlv) -> HamsterResult<Self> {
+        let tlv = tlv.expect(der::TAG_SEQUENCE)?;
+        let mut attributes = Vec::new();
+        let mut rdns = tlv.reader();
+        while !rdns.is_empty() {
+            let mut set = rdns.expect(der::TAG_SET)?.reader();
+            while !set.is_empty() {
+                let mut pair = set.expect(der::TAG_SEQUENCE)?.reader();
+                let oid = pair.read()?.oid()?;
+                let value = pair.read()?;
+                // 无法识别的值类型保留为十六进制，不影响其余属性
+                let value = value.string().unwrap_or_else(|_| format!("#{}", hex(value.raw)));
+                attributes.push((oid, value));
+            }
+        }
+        Ok(Self { attributes, raw: tlv.raw.to_vec() })
+    }
+
+    pub fn get(&self, oid: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == oid).map(|(_, value)| value.as_str())
+    }
+
+    pub fn common_name(&self) -> Option<&str> {
+        self.get("2.5.4.3")
+    }
+
+    pub fn organization(&self) -> Option<&str> {
+        self.get("2.5.4.10")
+    }
+
+    /// 用于显示的简短名称：优先通用名，其次组织名
+    pub fn display_name(&self) -> String {
+        self.common_name().or_else(|| self.organization()).map(str::to_string).unwrap_or_else(|| self.to_string())
+    }
+}
+
+/// 与 Windows 证书对话框相同，从最具体的属性（通常为 CN）开始显示
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.attributes.iter().rev()
+            .map(|(oid, value)| format!("{}={}", attribute_label(oid), value))
+            .collect();
+        f.write_str(&parts.join(", "))
+    }
+}
+
+fn attribute_label(oid: &str) -> &str {
+    match oid {
+        "2.5.4.3" => "CN",
+        "2.5.4.5" => "SERIALNUMBER",
+        "2.5.4.6" => "C",
+        "2.5.4.7" => "L",
+        "2.5.4.8" => "S",
+        "2.5.4.9" => "STREET",
+        "2.5.4.10" => "O",
+        "2.5.4.11" => "OU",
+        "1.2.840.113549.1.9.1" => "E",
+        other => other,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Extension {
+    pub oid: String,
+    pub critical: bool,
+    /// 扩展值（OCTET STRING 中包装的编码）
+    #[serde(skip)]
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Certificate {
+    /// 序列号的大端字节
+    pub serial: Vec<u8>,
+    pub issuer: Name,
+    pub subject: Name,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    /// 颁发者签名所用的算法 OID
+    pub signature_algorithm: String,
+    pub public_key_algorithm: String,
+    /// 公钥算法参数，椭圆曲线公钥为曲线 OID
+    pub public_key_parameters: Option<String>,
+    #[serde(skip)]
+    pub public_key: Vec<u8>,
+    pub extensions: Vec<Extension>,
+    /// 被签名的 TBSCertificate 部分的编码
+    #[serde(skip)]
+    pub tbs: Vec<u8>,
+    #[serde(skip)]
+    pub signature: Vec<u8>,
+    #[serde(skip)]
+    pub raw: Vec<u8>,
+}
+
+impl Certificate {
+    pub fn parse(tlv: Tlv) -> HamsterResult<Self> {
+        let tlv = tlv.expect(der::TAG_SEQUENCE)?;
+        let mut certificate = tlv.reader();
+        let tbs = certificate.expect(der::TAG_SEQUENCE)?;
+        let signature_algorithm = algorithm_oid(certificate.read()?)?;
+        let signature = certificate.read()?.bit_string()?.to_vec();
+
+        let mut fields = tbs.reader();
+        // 版本 [0] 可选，缺省为 v1
+        fields.next_if(context_tag(0, true))?;
+        let serial = fields.read()?.integer_bytes()?.to_vec();
+        fields.expect(der::TAG_SEQUENCE)?;
+        let issuer = Name::parse(fields.read()?)?;
+        let mut validity = fields.expect(der::TAG_SEQUENCE)?.reader();
+        let not_before = validity.read()?.time()?;
+        let not_after = validity.read()?.time()?;
+        let subject = Name::parse(fields.read()?)?;
+
+        let mut key_info = fields.expect(der::TAG_SEQUENCE)?.reader();
+        let mut algorithm = key_info.expect(der::TAG_SEQUENCE)?.reader();
+        let public_key_algorithm = algorithm.read()?.oid()?;
+        let public_key_parameters = match algorithm.next_if(der::TAG_OID)? {
+            Some(parameters) => Some(parameters.oid()?),
+            None => None,
+        };
+        let public_key = key_info.read()?.bit_string()?.to_vec();
+
+        // 颁发者和使用者唯一标识 [1] [2] 基本不再使用，跳过
+        fields.next_if(context_tag(1, false))?;
+        fields.next_if(context_tag(2, false))?;
+        let mut extensions = Vec::new();
+        if let Some(wrapper) = fields.next_if(context_tag(3, true))? {
+            let mut list = wrapper.reader().expect(der::TAG_SEQUENCE)?.reader();
+            while !list.is_empty() {
+                let mut extension = list.expect(der::TAG_SEQUENCE)?.reader();
+                let oid = extension.read()?.oid()?;
+                let critical = match extension.next_if(der::TAG_BOOLEAN)? {
+                    Some(flag) => flag.boolean()?,
+                    None => false,
+                };
+                let value = extension.read()?.octet_string()?.to_vec();
+                extensions.push(Extension { oid, critical, value });
+            }
+        }
+
+        Ok(Self {
+            serial,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            signature_algorithm,
+            public_key_algorithm,
+            public_key_parameters,
+            public_key,
+            extensions,
+            tbs: tbs.raw.to_vec(),
+            signature,
+            raw: tlv.raw.to_vec(),
+        })
+    }
+
+    pub fn extension(&self, oid: &str) -> Option<&Extension> {
+        self.extensions.iter().find(|extension| extension.oid == oid)
+    }
+
+    /// 十六进制序列号，与 Windows 证书对话框中的形式相同
+    pub fn serial_hex(&self) -> String {
+        hex(&self.serial)
+    }
+
+    /// SHA-1 指纹（整个证书 DER 编码的摘要），与 Windows 证书对话框中的“指纹”相同
+    pub fn thumbprint(&self) -> String {
+        hex(&Sha1::digest(&self.raw))
+    }
+
+    pub fn is_self_issued(&self) -> bool {
+        self.issuer.raw == self.subject.raw
+    }
+
+    pub fn is_valid_at(&self, time: DateTime<Utc>) -> bool {
+        self.not_before <= time && time <= self.not_after
+    }
+
+    /// 扩展密钥用法（EKU）中的各项用途 OID；没有该扩展时返回 None，表示不限用途
+    pub fn extended_key_usages(&self) -> HamsterResult<Option<Vec<String>>> {
+        let Some(extension) = self.extension(OID_EXTENDED_KEY_USAGE) else {
+            return Ok(None);
+        };
+        let mut usages = Vec::new();
+        let mut reader = Tlv::parse(&extension.value)?.expect(der::TAG_SEQUENCE)?.reader();
+        while !reader.is_empty() {
+            usages.push(reader.read()?.oid()?);
+        }
+        Ok(Some(usages))
+    }
+
+    /// 基本约束中的 CA 标志；没有该扩展时返回 None（v1 证书）
+    pub fn is_ca(&self) -> HamsterResult<Option<bool>> {
+        let Some(extension) = self.extension(OID_BASIC_CONSTRAINTS) else {
+            return Ok(None);
+        };
+        let mut reader = Tlv::parse(&extension.value)?.expect(der::TAG_SEQUENCE)?.reader();
+        match reader.next_if(der::TAG_BOOLEAN)? {
+            Some(flag) => flag.boolean().map(Some),
+            None => Ok(Some(false)),
+        }
+    }
+
+    /// 本证书是否由 issuer 签发：名称相符且签名可以用 issuer 的公钥验证
+    pub fn is_issued_by(&self, issuer: &Certificate) -> HamsterResult<bool> {
+        if self.issuer.raw != issuer.subject.raw {
+            return Ok(false);
+        }
+        crypto::verify(issuer, &self.signature_algorithm, None, &self.tbs, &self.signature)
+    }
+}
+
+/// AlgorithmIdentifier 中的算法 OID，忽略参数
+pub fn algorithm_oid(tlv: Tlv) -> HamsterResult<String> {
+    tlv.expect(der::TAG_SEQUENCE)?
+        .reader()
+        .read()
+        .and_then(|oid| oid.oid())
+        .map_err(|e| HamsterError::parse("算法标识无效").with_source(e))
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use base64::Engine;
+    use chrono::TimeZone;
+    use crate::core::pe::PeFile;
+    use crate::core::signature::authenticode::read_pe_signatures;
+
+    fn fixture(path: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+    }
+
+    fn test_root() -> Certificate {
+        let pem = std::fs::read_to_string(fixture("trust/hamster-test-root.pem")).unwrap();
+        let encoded: String = pem.lines().filter(|line| !line.starts_with("-----")).collect();
+        let der = base64::engine::general_purpose::STANDARD.decode(encoded).unwrap();
+        Certificate::parse(Tlv::parse(&der).unwrap()).unwrap()
+    }
+
+    /// 示例驱动签名中的证书：签名者、代码签名 CA、时间戳颁发机构
+    fn driver_certificates() -> Vec<Certificate> {
+        let pe = PeFile::open(&fixture("packages/nvidia/nvlddmkm.sys")).unwrap();
+        read_pe_signatures(&pe).unwrap().remove(0).signed_data.certificates
+    }
+
+    fn utc(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn root_certificate_fields_are_read() {
+        let root = test_root();
+        assert_eq!(root.serial_hex(), "1001");
+        assert_eq!(root.thumbprint(), "402387E0B5DD31D6E0830DAD14097ECA57BC7B02");
+        assert_eq!(root.subject.common_name(), Some("Hamster Test Root CA"));
+        assert_eq!(root.subject.organization(), Some("Hamster Test"));
+        assert_eq!(root.subject.to_string(), "CN=Hamster Test Root CA, O=Hamster Test");
+        assert!(root.is_self_issued());
+        assert_eq!(root.signature_algorithm, "1.2.840.113549.1.1.11");
+        assert_eq!(root.public_key_algorithm, "1.2.840.113549.1.1.1");
+        assert_eq!((root.not_before, root.not_after), (utc(2020, 1, 1), utc(2045, 1, 1)));
+        assert!(root.is_valid_at(utc(2030, 6, 1)) && !root.is_valid_at(utc(2019, 12, 31)));
+        assert!(root.is_issued_by(&root).unwrap());
+    }
+
+    #[test]
+    fn extensions_are_read() {
+        let root = test_root();
+        let oids: Vec<(&str, bool)> = root.extensions.iter().map(|extension| (extension.oid.as_str(), extension.critical)).collect();
+        assert_eq!(oids, [("2.5.29.19", true), ("2.5.29.15", true), ("2.5.29.14", false)]);
+        assert_eq!(root.is_ca().unwrap(), Some(true));
+        // 根证书没有扩展密钥用法，不限用途
+        assert_eq!(root.extended_key_usages().unwrap(), None);
+        let key_id = Tlv::parse(&root.extension("2.5.29.14").unwrap().value).unwrap();
+        assert_eq!(hex(key_id.octet_string().unwrap()), "69279F11F1FA83BE3A5CF05E1BE764D1F25F4352");
+    }
+
+    #[test]
+    fn signer_certificate_names_and_usages_are_read() {
+        let certificates = driver_certificates();
+        let signer = certificates.iter().find(|certificate| certificate.subject.common_name() == Some("NVIDIA Corporation")).unwrap();
+        assert_eq!(signer.serial_hex(), "0A1B2C3D4E5F60718293");
+        assert_eq!(signer.thumbprint(), "63A829551E64DFA6B0E5D80BB8482202665C111C");
+        assert_eq!(signer.subject.get("2.5.4.7"), Some("Santa Clara"));
+        assert_eq!(
+            signer.subject.to_string(),
+            "CN=NVIDIA Corporation, O=NVIDIA Corporation, L=Santa Clara, S=California, C=US",
+        );
+        assert_eq!(signer.issuer.display_name(), "Hamster Test Code Signing CA");
+        assert_eq!(signer.is_ca().unwrap(), Some(false));
+        assert_eq!(signer.extended_key_usages().unwrap(), Some(vec!["1.3.6.1.5.5.7.3.3".to_string()]));
+        assert!(!signer.is_self_issued());
+
+        let ca = certificates.iter().find(|certificate| certificate.subject.raw == signer.issuer.raw).unwrap();
+        assert!(signer.is_issued_by(ca).unwrap());
+        assert!(ca.is_issued_by(&test_root()).unwrap());
+        // 名称相符但签名不符
+        let mut forged = signer.clone();
+        *forged.tbs.last_mut().unwrap() ^= 1;
+        assert!(!forged.is_issued_by(ca).unwrap());
+        assert!(!signer.is_issued_by(&test_root()).unwrap());
+    }
+
+    #[test]
+    fn names_keep_unknown_attributes() {
+        // SET { SEQ { 2.5.4.3, BMPString "测试" } }, SET { SEQ { 2.5.4.97, INTEGER 5 } }
+        let name = [
+            0x30, 0x1B,
+            0x31, 0x0D, 0x30, 0x0B, 0x06, 0x03, 0x55, 0x04, 0x03, 0x1E, 0x04, 0x6D, 0x4B, 0x8B, 0xD5,
+            0x31, 0x0A, 0x30, 0x08, 0x06, 0x03, 0x55, 0x04, 0x61, 0x02, 0x01, 0x05,
+        ];
+        let name = Name::parse(Tlv::parse(&name).unwrap()).unwrap();
+        assert_eq!(name.common_name(), Some("测试"));
+        assert_eq!(name.get("2.5.4.97"), Some("#020105"));
+        assert_eq!(name.to_string(), "2.5.4.97=#020105, CN=测试");
+        assert_eq!(name.display_name(), "测试");
+
+        let empty = Name::parse(Tlv::parse(&[0x30, 0x00]).unwrap()).unwrap();
+        assert_eq!(empty.display_name(), "");
+        assert!(Name::parse(Tlv::parse(&[0x31, 0x00]).unwrap()).is_err());
+    }
+}
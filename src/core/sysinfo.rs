@@ -1,9 +1,17 @@
+#[cfg(windows)]
 use std::collections::HashMap;
+#[cfg(windows)]
 use wmi::{WMIConnection, COMLibrary};
+#[cfg(windows)]
 use hardware_query::HardwareInfo;
-use super::edid;
+use serde::{Deserialize, Serialize};
+#[cfg(windows)]
+use super::error::HamsterError;
+use super::error::HamsterResult;
+use super::platform::Platform;
 
 // 辅助函数：获取SVG图标路径
+#[allow(dead_code)]
  fn get_svg_icon_path(name: &str) -> String {
      // 根据图标名称返回对应的SVG文件路径
      match name {
@@ -33,13 +41,14 @@ use super::edid;
      }.to_string()
  }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HardwareItem {
     pub text: String,
     pub icon_path: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SystemInfo {
     pub os_name: Option<String>,
     pub os_version: Option<String>,
@@ -56,9 +65,15 @@ pub struct SystemInfo {
 }
 
 impl SystemInfo {
-    pub fn new() -> Result<Self, String> {
-        let com_con = COMLibrary::new().map_err(|e| format!("COM initialization failed: {}", e))?;
-        let wmi_con = WMIConnection::new(com_con.into()).map_err(|e| format!("WMI connection failed: {}", e))?;
+    pub fn new(platform: &dyn Platform) -> HamsterResult<Self> {
+        platform.hardware_inventory()
+    }
+
+    /// 通过WMI查询硬件清单，显示器信息由平台提供
+    #[cfg(windows)]
+    pub fn query_wmi(platform: &dyn Platform) -> HamsterResult<Self> {
+        let com_con = COMLibrary::new().map_err(|e| HamsterError::platform("COM初始化失败").with_source(e))?;
+        let wmi_con = WMIConnection::new(com_con.into()).map_err(|e| HamsterError::platform("WMI连接失败").with_source(e))?;
 
         let os_info = Self::get_os_info(&wmi_con)?;
         let manufacturer = Self::get_manufacturer(&wmi_con)?;
@@ -68,7 +83,7 @@ impl SystemInfo {
         let disk_info = Self::get_disk_info(&wmi_con)?;
         let gpu_info = Self::get_gpu_info(&wmi_con)?;
         let network_adapters = Self::get_network_adapters(&wmi_con)?;
-        let monitor_info = Self::get_monitor_info(&wmi_con)?;
+        let monitor_info = platform.monitors()?;
         let audio_info = Self::get_audio_info(&wmi_con)?;
 
         Ok(SystemInfo {
@@ -87,10 +102,11 @@ impl SystemInfo {
         })
     }
 
-    fn get_os_info(wmi_con: &WMIConnection) -> Result<(String, String, String), String> {
+    #[cfg(windows)]
+    fn get_os_info(wmi_con: &WMIConnection) -> HamsterResult<(String, String, String)> {
         let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
             .raw_query("SELECT Caption, Version, BuildNumber FROM Win32_OperatingSystem")
-            .map_err(|e| format!("WMI query failed: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
 
         if let Some(os) = results.first() {
             let os_name = os.get("Caption")
@@ -111,10 +127,11 @@ impl SystemInfo {
         }
     }
 
-    fn get_manufacturer(wmi_con: &WMIConnection) -> Result<String, String> {
+    #[cfg(windows)]
+    fn get_manufacturer(wmi_con: &WMIConnection) -> HamsterResult<String> {
         let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
             .raw_query("SELECT Manufacturer FROM Win32_ComputerSystem")
-            .map_err(|e| format!("WMI query failed: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
 
         if let Some(system) = results.first() {
             Ok(system.get("Manufacturer")
@@ -125,10 +142,11 @@ impl SystemInfo {
         }
     }
 
-    fn get_motherboard(wmi_con: &WMIConnection) -> Result<String, String> {
+    #[cfg(windows)]
+    fn get_motherboard(wmi_con: &WMIConnection) -> HamsterResult<String> {
         let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
             .raw_query("SELECT Product FROM Win32_BaseBoard")
-            .map_err(|e| format!("WMI query failed: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
 
         if let Some(board) = results.first() {
             Ok(board.get("Product")
@@ -139,10 +157,11 @@ impl SystemInfo {
         }
     }
 
-    fn get_cpu(wmi_con: &WMIConnection) -> Result<String, String> {
+    #[cfg(windows)]
+    fn get_cpu(wmi_con: &WMIConnection) -> HamsterResult<String> {
         let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
             .raw_query("SELECT Name FROM Win32_Processor")
-            .map_err(|e| format!("WMI query failed: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
 
         if let Some(cpu) = results.first() {
             Ok(cpu.get("Name")
@@ -153,10 +172,11 @@ impl SystemInfo {
         }
     }
 
-    fn get_memory_info(wmi_con: &WMIConnection) -> Result<Vec<HardwareItem>, String> {
+    #[cfg(windows)]
+    fn get_memory_info(wmi_con: &WMIConnection) -> HamsterResult<Vec<HardwareItem>> {
         let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
             .raw_query("SELECT Manufacturer, Capacity, MemoryType, Speed, SMBIOSMemoryType FROM Win32_PhysicalMemory")
-            .map_err(|e| format!("WMI query失败: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
 
         let mut memory_info = Vec::new();
         let mut total_memory = 0u64;
@@ -259,10 +279,11 @@ impl SystemInfo {
         Ok(memory_info)
     }
 
-    fn get_disk_info(wmi_con: &WMIConnection) -> Result<Vec<HardwareItem>, String> {
+    #[cfg(windows)]
+    fn get_disk_info(wmi_con: &WMIConnection) -> HamsterResult<Vec<HardwareItem>> {
         let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
             .raw_query("SELECT Manufacturer, Model, Size, MediaType FROM Win32_DiskDrive")
-            .map_err(|e| format!("WMI query失败: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
 
         let mut disk_info = Vec::new();
         for (i, disk) in results.iter().enumerate() {
@@ -383,7 +404,8 @@ impl SystemInfo {
         Ok(disk_info)
     }
 
-    fn get_gpu_info(_wmi_con: &WMIConnection) -> Result<Vec<HardwareItem>, String> {
+    #[cfg(windows)]
+    fn get_gpu_info(_wmi_con: &WMIConnection) -> HamsterResult<Vec<HardwareItem>> {
         let mut gpu_info = Vec::new();
         
         // 使用hardware-query库获取显卡信息
@@ -429,7 +451,7 @@ impl SystemInfo {
                 // 如果hardware-query失败，回退到WMI查询
                 let results: Vec<HashMap<String, wmi::Variant>> = _wmi_con
                     .raw_query("SELECT Name FROM Win32_VideoController WHERE Name != 'Microsoft Basic Display Adapter'")
-                    .map_err(|e| format!("WMI query failed: {}", e))?;
+                    .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
                 
                 for (i, gpu) in results.iter().enumerate() {
                     let name = gpu.get("Name")
@@ -471,6 +493,7 @@ impl SystemInfo {
     }
     
     // 辅助函数：根据显卡型号获取显存
+    #[cfg(windows)]
     fn get_vram_by_model(name: &str) -> u32 {
         let name_lower = name.to_lowercase();
         
@@ -563,10 +586,11 @@ impl SystemInfo {
         }
     }
 
-    fn get_network_adapters(wmi_con: &WMIConnection) -> Result<Vec<HardwareItem>, String> {
+    #[cfg(windows)]
+    fn get_network_adapters(wmi_con: &WMIConnection) -> HamsterResult<Vec<HardwareItem>> {
         let results: Vec<HashMap<String, wmi::Variant>> = wmi_con
             .raw_query("SELECT Name, Manufacturer, Speed FROM Win32_NetworkAdapter WHERE PhysicalAdapter = TRUE")
-            .map_err(|e| format!("WMI query failed: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
 
         let mut network_adapters = Vec::new();
         for adapter in results {
@@ -632,12 +656,12 @@ impl SystemInfo {
         Ok(network_adapters)
     }
 
-    fn get_monitor_info(wmi_con: &WMIConnection) -> Result<Vec<HardwareItem>, String> {
-        // 使用EDID模块获取完整的显示器信息
-        edid::get_complete_monitor_info(wmi_con)
-    }
-
     pub fn get_windows_version_display(os_name: &str, os_version: &str) -> String {
+        // 其他系统（如 Linux 发行版）直接显示其版本号
+        if !os_name.to_lowercase().contains("windows") {
+            return os_version.to_string();
+        }
+
         // 解析版本号，例如 "10.0.19045"
         let version_parts: Vec<&str> = os_version.split('.').collect();
         
@@ -715,13 +739,14 @@ impl SystemInfo {
         }
     }
 
-    fn get_audio_info(wmi_con: &WMIConnection) -> Result<Vec<HardwareItem>, String> {
+    #[cfg(windows)]
+    fn get_audio_info(wmi_con: &WMIConnection) -> HamsterResult<Vec<HardwareItem>> {
         let mut audio_info = Vec::new();
         
         // 查询声卡设备信息
         let query = "SELECT Name, Manufacturer, Status FROM Win32_SoundDevice";
         let result: Vec<HashMap<String, String>> = wmi_con.raw_query(query)
-            .map_err(|e| format!("WMI query failed: {}", e))?;
+            .map_err(|e| HamsterError::platform("WMI查询失败").with_source(e))?;
         
         for (i, device) in result.iter().enumerate() {
             let name = device.get("Name").unwrap_or(&"未知声卡".to_string()).to_string();
@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::core::error::HamsterResult;
+use crate::core::locale::Locale;
+use crate::core::pe::{Machine, PeFile, Subsystem, VersionInfo};
+
+/// 驱动二进制文件（.sys/.dll/.exe）的元数据，直接从 PE 文件中读取
+#[derive(Debug, Clone, Serialize)]
+pub struct DriverFileInfo {
+	pub path: String,
+	pub version: String,
+	pub product_version: String,
+	pub company: String,
+	pub description: String,
+	pub original_filename: String,
+	pub machine: Machine,
+	pub subsystem: Subsystem,
+	/// 链接时间，可重现构建的文件中不是真实时间
+	pub link_time: Option<DateTime<Utc>>,
+	/// 文件头中记录的校验和，及其是否与文件内容一致（未设置时为 None）
+	pub checksum: u32,
+	pub checksum_valid: Option<bool>,
+	pub signed: bool,
+	pub signature_status: String,
+	pub signature_hash: Vec<u8>,
+	/// 完整的版本资源，包括各语言的字符串表
+	pub version_info: Option<VersionInfo>,
+}
+
+impl DriverFileInfo {
+	/// 读取文件的版本资源和文件头，字符串按界面语言选择对应的语言版本；
+	/// 签名由签名验证模块单独检查，这里不做判断
+	pub fn from_path(path: &Path, locale: Locale) -> HamsterResult<Self> {
+		let pe = PeFile::open(path)?;
+		let version_info = pe.version_info()?;
+		let string = |key: &str| version_info.as_ref()
+			.and_then(|info| info.string(key, locale))
+			.unwrap_or_default()
+			.to_string();
+
+		Ok(Self {
+			path: path.display().to_string(),
+			version: version_info.as_ref().and_then(|info| info.file_version_text(locale)).unwrap_or_default(),
+			product_version: version_info.as_ref().and_then(|info| info.product_version_text(locale)).unwrap_or_default(),
+			company: string("CompanyName"),
+			description: string("FileDescription"),
+			original_filename: string("OriginalFilename"),
+			machine: pe.machine,
+			subsystem: pe.subsystem,
+			link_time: pe.link_time(),
+			checksum: pe.checksum,
+			checksum_valid: pe.checksum_matches(),
+			signed: false,
+			signature_status: "未验证".to_string(),
+			signature_hash: vec![],
+			version_info,
+		})
+	}
+}
+
+/// 把服务配置中的映像路径换算为可以直接打开的文件路径：
+/// 处理 `\SystemRoot\`、`\??\` 前缀、`%SystemRoot%` 变量和相对于系统目录的 `System32\...`
+pub fn resolve_image_path(image_path: &str, system_root: &Path) -> PathBuf {
+	let path = image_path.trim().trim_matches('"');
+	let path = path.strip_prefix(r"\??\").unwrap_or(path);
+	let lower = path.to_ascii_lowercase();
+	for prefix in [r"\systemroot\", r"%systemroot%\", r"%windir%\"] {
+		if lower.starts_with(prefix) {
+			return system_root.join(&path[prefix.len()..]);
+		}
+	}
+	if lower.starts_with(r"system32\") || lower.starts_with(r"syswow64\") {
+		return system_root.join(path);
+	}
+	PathBuf::from(path)
+}
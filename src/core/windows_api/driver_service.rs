@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use crate::core::audit::{AuditAction, AuditEvent, AuditJournal};
+use crate::core::driver_manager::DriverInfo;
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::platform::Platform;
+use crate::core::settings::Settings;
+
+#[allow(dead_code)]
+pub struct DriverService {
+	platform: Arc<dyn Platform>,
+	journal: AuditJournal,
+}
+
+#[allow(dead_code)]
+impl DriverService {
+	pub fn new(platform: Arc<dyn Platform>, settings: &Settings) -> HamsterResult<Self> {
+		Ok(Self { platform, journal: settings.journal() })
+	}
+
+	pub fn enumerate_drivers(&self) -> HamsterResult<Vec<DriverInfo>> {
+		self.platform.enumerate_drivers()
+	}
+
+	pub fn start_driver(&self, service_name: &str) -> HamsterResult<()> {
+		self.run_sc(&["start", service_name])
+	}
+
+	pub fn stop_driver(&self, service_name: &str) -> HamsterResult<()> {
+		self.run_sc(&["stop", service_name])
+	}
+
+	pub fn set_startup_type(&self, service_name: &str, start_type: u32) -> HamsterResult<()> {
+		// 启动类型取值与服务控制管理器一致：0引导 1系统 2自动 3手动 4禁用
+		let start = match start_type {
+			0 => "boot",
+			1 => "system",
+			2 => "auto",
+			3 => "demand",
+			4 => "disabled",
+			_ => return Err(HamsterError::policy(format!("无效的启动类型: {}", start_type))),
+		};
+		// 记录修改前的启动类型，查询失败不影响修改本身
+		let before = self.enumerate_drivers().ok()
+			.and_then(|drivers| drivers.into_iter().find(|d| d.name.eq_ignore_ascii_case(service_name)))
+			.map(|driver| driver.start_type);
+
+		let result = self.run_sc(&["config", service_name, "start=", start]);
+		let mut event = AuditEvent::new(AuditAction::StartTypeChange, service_name).after(start);
+		if let Some(before) = before {
+			event = event.before(before);
+		}
+		event = match &result {
+			Ok(()) => event.outcome(true, "启动类型已修改", false),
+			Err(e) => event.outcome(false, e.to_string(), e.requires_elevation()),
+		};
+		let recorded = self.journal.record(event);
+		result?;
+		// 修改已生效但没有留下记录时，把日志错误交给调用方显示
+		recorded.map(|_| ()).map_err(|e| HamsterError::platform("启动类型已修改，但写入审计日志失败").with_source(e))
+	}
+
+	fn run_sc(&self, args: &[&str]) -> HamsterResult<()> {
+		let output = self.platform.run_command("sc", args)?;
+		if output.success {
+			Ok(())
+		} else {
+			Err(HamsterError::command_failed(
+				format!("sc {} 执行失败", args.join(" ")),
+				output.status_code,
+				&format!("{}{}", output.stdout.trim(), output.stderr.trim()),
+			))
+		}
+	}
+}
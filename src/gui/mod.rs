@@ -3,62 +3,104 @@ use eframe::egui;
 use crate::core::sysinfo::{SystemInfo, HardwareItem};
 use std::collections::HashMap;
 
-// 辅助函数：根据主板制造商获取对应的logo图标路径
-fn get_motherboard_logo_path(motherboard: &str) -> String {
-    let motherboard_lower = motherboard.to_lowercase();
-    
-    // 主板制造商logo映射 - 使用SVG图标
-    if motherboard_lower.contains("asus") || motherboard_lower.contains("华硕") {
-        // ASUS品牌，使用电脑图标
-        "assets/icons/computer.svg".to_string()
-    } else if motherboard_lower.contains("gigabyte") || motherboard_lower.contains("技嘉") {
-        // 技嘉品牌，使用主板图标
-        "assets/icons/motherboard.svg".to_string()
-    } else if motherboard_lower.contains("msi") || motherboard_lower.contains("微星") {
-        // 微星品牌，使用星星图标
-        "assets/icons/star.svg".to_string()
-    } else if motherboard_lower.contains("intel") {
-        // Intel品牌，使用芯片图标
-        "assets/icons/cpu.svg".to_string()
-    } else if motherboard_lower.contains("amd") {
-        // AMD品牌，使用处理器图标
-        "assets/icons/cpu.svg".to_string()
-    } else if motherboard_lower.contains("dell") {
-        // Dell品牌，使用电脑图标
-        "assets/icons/computer.svg".to_string()
-    } else if motherboard_lower.contains("hp") || motherboard_lower.contains("惠普") {
-        // HP品牌，使用电脑图标
-        "assets/icons/computer.svg".to_string()
-    } else if motherboard_lower.contains("lenovo") || motherboard_lower.contains("联想") {
-        // 联想品牌，使用电脑图标
-        "assets/icons/computer.svg".to_string()
-    } else if motherboard_lower.contains("acer") || motherboard_lower.contains("宏碁") {
-        // Acer品牌，使用电脑图标
-        "assets/icons/computer.svg".to_string()
-    } else {
-        // 默认使用主板图标
-        "assets/icons/motherboard.svg".to_string()
-    }
+// 辅助函数：根据主板制造商获取对应的logo图标路径，制造商由硬件ID数据库按名称识别
+fn get_motherboard_logo_path(ids: &HardwareIds, manufacturer: Option<&str>, motherboard: &str) -> String {
+    let vendor = manufacturer.and_then(|name| ids.pci.find_vendor(name))
+        .or_else(|| ids.pci.find_vendor(motherboard));
+    match vendor {
+        // 华硕、戴尔、惠普、联想、宏碁等整机品牌，使用电脑图标
+        Some(0x1043 | 0x1028 | 0x103C | 0x17AA | 0x1025) => "assets/icons/computer.svg",
+        // 微星，使用星星图标
+        Some(0x1462) => "assets/icons/star.svg",
+        // Intel、AMD，使用芯片图标
+        Some(0x8086 | 0x1022 | 0x1002) => "assets/icons/cpu.svg",
+        // 技嘉及其他品牌，使用主板图标
+        _ => "assets/icons/motherboard.svg",
+    }.to_string()
 }
 
 
 // 新增导入 image crate
-use webbrowser;
 // SVG支持
 use resvg::usvg::{self, TreeParsing};
 use tiny_skia::{Pixmap, Transform};
-use crate::core::features::driver_installer::{DriverInstaller, InstallableDriver};
+use crate::core::audit::{AuditAction, AuditEntry, AuditVerification};
+use crate::core::driver_manager::DriverInfo;
+use crate::core::features::backup_manager::{BackupManager, BackupManifest};
+use crate::core::features::driver_installer::{DriverInstaller, DriverScan, InstallableDriver, InstallationResult};
+use crate::core::features::driver_matcher::{DeviceMatch, DriverMatcher, SystemTarget, Verdict};
 use crate::core::features::driver_manager::DriverManagement;
-use crate::core::features::driver_searcher::{DriverSearcher, OnlineDriverInfo, DriverSearchProgress};
+use crate::core::features::driver_searcher::{DriverSearcher, OnlineDriverInfo, UpdateState};
+use crate::core::features::signature_validator::{SignatureResult, SignatureValidator};
+use crate::core::error::{HamsterError, HamsterResult};
+use crate::core::hwids::HardwareIds;
+use crate::core::jobs::{JobContext, JobHandle, JobRunner, JobState};
+use crate::core::locale::Locale;
+use crate::core::platform::{self, Platform};
+use crate::core::settings::{Settings, Theme, UpdatePolicy, MAX_CONCURRENCY, MAX_HTTP_TIMEOUT_SECS};
+use crate::core::windows_api::driver_service::DriverService;
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// 界面跟踪的单个后台任务，任务失败时保留错误供页面显示
+struct TrackedJob<T> {
+    handle: Option<JobHandle<T>>,
+    error: Option<HamsterError>,
+}
+
+impl<T> Default for TrackedJob<T> {
+    fn default() -> Self {
+        Self { handle: None, error: None }
+    }
+}
+
+impl<T: Send + 'static> TrackedJob<T> {
+    fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// 提交任务；已有同类任务在运行时忽略
+    fn start<F>(&mut self, runner: &JobRunner, name: &str, task: F)
+    where
+        F: FnOnce(&JobContext) -> HamsterResult<T> + Send + 'static,
+    {
+        if self.is_running() {
+            return;
+        }
+        self.error = None;
+        self.handle = Some(runner.submit(name, task));
+    }
 
-use std::sync::mpsc;
-use std::thread;
+    /// 取出已结束任务的结果；被用户取消的任务不记录错误
+    fn poll(&mut self) -> Option<T> {
+        let result = self.handle.as_ref()?.try_result()?;
+        self.handle = None;
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                if !e.is_cancelled() {
+                    self.error = Some(e);
+                }
+                None
+            }
+        }
+    }
+}
 
 
 #[allow(dead_code)]
 pub struct GuiApp {
+    // 系统访问平台，由core中的各类型共享
+    pub platform: Arc<dyn Platform>,
+    // 已保存的设置；设置页编辑的是副本，保存后才生效
+    pub settings: Settings,
+    settings_draft: Settings,
+    settings_error: Option<HamsterError>,
+    settings_saved: bool,
+    applied_theme: Option<Theme>,
     // 使用core模块中的类型
-    pub driver_service: crate::core::windows_api::driver_service::DriverService,
+    pub driver_service: DriverService,
     pub dependency_analyzer: crate::core::features::dependency_analyzer::DependencyAnalyzer,
     pub signature_validator: crate::core::features::signature_validator::SignatureValidator,
     pub backup_manager: crate::core::features::backup_manager::BackupManager,
@@ -71,26 +113,46 @@ pub struct GuiApp {
     pub scan_in_progress: bool,
     pub selected_driver: Option<usize>,
     pub system_info: Option<SystemInfo>,
-    system_info_loading: bool,
-    system_info_error: Option<String>,
-    system_info_rx: Option<std::sync::mpsc::Receiver<Result<SystemInfo, String>>>,
+    // 后台任务调度器及界面跟踪的任务
+    pub jobs: JobRunner,
+    show_job_list: bool,
+    system_info_job: TrackedJob<SystemInfo>,
+    driver_scan_job: TrackedJob<(Vec<DriverInfo>, Vec<String>)>,
+    // 扫描进行中已完成的签名验证结果
+    driver_scan_results: Arc<Mutex<Vec<SignatureResult>>>,
+    // 上次扫描中不影响结果的问题，如无法保存签名验证缓存
+    driver_scan_warnings: Vec<String>,
+    backup_job: TrackedJob<BackupManifest>,
+    last_backup: Option<BackupManifest>,
     title_icon: Option<egui::TextureHandle>,
     github_icon: Option<egui::TextureHandle>,
     // 驱动安装相关状态
     scanned_drivers: Vec<InstallableDriver>,
+    // 扫描时跳过的文件及原因
+    scan_skipped: Vec<String>,
     selected_install_driver: Option<usize>,
     scan_directory: String,
+    install_scan_job: TrackedJob<DriverScan>,
+    install_job: TrackedJob<InstallationResult>,
+    // 扫描到的驱动与本机设备的匹配结果
+    driver_matches: Vec<DeviceMatch>,
+    driver_match_job: TrackedJob<Vec<DeviceMatch>>,
+    // 审计日志缓存，发生修改系统的操作后重新读取
+    audit_entries: Vec<AuditEntry>,
+    audit_verification: Option<AuditVerification>,
+    audit_error: Option<HamsterError>,
+    audit_dirty: bool,
     // 在线驱动搜索相关状态
-    pub online_drivers_searching: bool,
     pub online_drivers: Vec<OnlineDriverInfo>,
-    pub driver_search_progress: Option<DriverSearchProgress>,
-    pub driver_search_rx: Option<std::sync::mpsc::Receiver<Result<Vec<OnlineDriverInfo>, String>>>,
+    driver_search_job: TrackedJob<Vec<OnlineDriverInfo>>,
     // 驱动管理相关状态
   pub driver_management_subtab: DriverManagementSubTab,
     selected_backup_file: Option<usize>,
     driver_management_expanded: bool,
     // SVG图标缓存
     icon_cache: HashMap<String, Option<egui::TextureHandle>>,
+    // 识别主板制造商用的硬件ID数据库
+    hardware_ids: HardwareIds,
 }
 
 #[derive(PartialEq)]
@@ -113,54 +175,191 @@ pub enum DriverManagementSubTab {
 }
 
 impl GuiApp {
-    pub fn new() -> Result<Self, String> {
-        // 创建通道用于异步获取系统信息
-        let (tx, rx) = mpsc::channel();
+    pub fn new() -> HamsterResult<Self> {
+        // 设置文件损坏时使用默认设置启动，并在设置页显示错误；不会覆盖原文件，直到用户保存
+        let (settings, settings_error) = match Settings::load() {
+            Ok(settings) => (settings, None),
+            Err(e) => (Settings::default(), Some(e)),
+        };
+        let platform = platform::default_platform(&settings)?;
+        let system_root = std::env::var_os(platform::SYSROOT_ENV_VAR).unwrap_or_else(|| "/".into());
+        let hardware_ids = HardwareIds::load(&settings, std::path::Path::new(&system_root));
+        let jobs = JobRunner::new();
         
-        // 在后台线程中获取系统信息
-        thread::spawn(move || {
-            let result = SystemInfo::new();
-            let _ = tx.send(result);
-        });
+        // 在后台任务中获取系统信息
+        let mut system_info_job = TrackedJob::default();
+        let inventory_platform = platform.clone();
+        system_info_job.start(&jobs, "读取系统信息", move |_| SystemInfo::new(inventory_platform.as_ref()));
+        
+        // 按更新策略在启动时搜索驱动更新
+        let mut driver_search_job = TrackedJob::default();
+        if settings.update_policy == UpdatePolicy::CheckOnStartup {
+            let searcher = DriverSearcher::new(platform.clone(), &settings);
+            driver_search_job.start(&jobs, "搜索驱动", move |job| searcher.search_online_drivers(job));
+        }
         
         Ok(Self {
-            driver_service: crate::core::windows_api::driver_service::DriverService::new()?,
+            driver_service: DriverService::new(platform.clone(), &settings)?,
             dependency_analyzer: crate::core::features::dependency_analyzer::DependencyAnalyzer::new(),
             signature_validator: crate::core::features::signature_validator::SignatureValidator::new(),
-            backup_manager: crate::core::features::backup_manager::BackupManager::new()?,
-            driver_installer: DriverInstaller::new(),
-            driver_management: DriverManagement::new(),
-            driver_searcher: DriverSearcher::new(),
+            backup_manager: BackupManager::new(&settings)?,
+            driver_installer: DriverInstaller::new(platform.clone(), &settings),
+            driver_management: DriverManagement::new(platform.clone(), &settings),
+            driver_searcher: DriverSearcher::new(platform.clone(), &settings),
+            platform,
+            settings_draft: settings.clone(),
+            settings,
+            settings_error,
+            settings_saved: false,
+            applied_theme: None,
             selected_tab: AppTab::Overview,
             drivers: Vec::new(),
             backup_history: Vec::new(),
             scan_in_progress: false,
             selected_driver: None,
             system_info: None,
-            system_info_loading: true,  // 开始时设为true，表示正在加载
-            system_info_error: None,
-            system_info_rx: Some(rx),
+            jobs,
+            show_job_list: false,
+            system_info_job,
+            driver_scan_job: TrackedJob::default(),
+            driver_scan_results: Arc::default(),
+            driver_scan_warnings: Vec::new(),
+            backup_job: TrackedJob::default(),
+            last_backup: None,
             title_icon: None,
             github_icon: None,
             // 驱动安装相关状态
             scanned_drivers: Vec::new(),
+            scan_skipped: Vec::new(),
             selected_install_driver: None,
             scan_directory: "./".to_string(),
+            install_scan_job: TrackedJob::default(),
+            install_job: TrackedJob::default(),
+            driver_matches: Vec::new(),
+            driver_match_job: TrackedJob::default(),
+            audit_entries: Vec::new(),
+            audit_verification: None,
+            audit_error: None,
+            audit_dirty: true,
             // 在线驱动搜索相关状态
-            online_drivers_searching: false,
             online_drivers: Vec::new(),
-            driver_search_progress: None,
-            driver_search_rx: None,
+            driver_search_job,
             // 驱动管理相关状态
             driver_management_subtab: DriverManagementSubTab::Backup,
             selected_backup_file: None,
             driver_management_expanded: false,
             // SVG图标缓存
             icon_cache: HashMap::new(),
+            hardware_ids,
             // window drag handled natively on Windows
         })
     }
 
+    /// 收取已结束的后台任务结果
+    fn poll_jobs(&mut self) {
+        if let Some(info) = self.system_info_job.poll() {
+            self.system_info = Some(info);
+        }
+        if let Some((drivers, warnings)) = self.driver_scan_job.poll() {
+            self.selected_driver = None;
+            self.drivers = drivers;
+            self.driver_scan_warnings = warnings;
+        }
+        if self.backup_job.handle.is_some() {
+            if let Some(manifest) = self.backup_job.poll() {
+                self.last_backup = Some(manifest);
+            }
+            // 部分驱动备份后任务才失败或被取消，也需要刷新日志
+            self.audit_dirty |= self.backup_job.handle.is_none();
+        }
+        if let Some(drivers) = self.driver_search_job.poll() {
+            self.online_drivers = drivers;
+        }
+        if let Some(DriverScan { drivers, skipped }) = self.install_scan_job.poll() {
+            self.selected_install_driver = None;
+            self.driver_matches.clear();
+            // 扫描完成后在后台枚举设备，按 Windows 的规则为每个设备挑选驱动
+            let searcher = DriverSearcher::new(self.platform.clone(), &self.settings);
+            let match_drivers = drivers.clone();
+            self.driver_match_job.start(&self.jobs, "匹配设备驱动", move |_| {
+                let devices = searcher.get_device_info()?;
+                Ok(DriverMatcher::new(SystemTarget::current()).match_devices(&devices, &match_drivers))
+            });
+            self.scanned_drivers = drivers;
+            self.scan_skipped = skipped;
+        }
+        if let Some(matches) = self.driver_match_job.poll() {
+            self.driver_matches = matches;
+        }
+        if self.install_job.handle.is_some() {
+            self.install_job.poll();
+            self.audit_dirty |= self.install_job.handle.is_none();
+        }
+    }
+
+    /// 重新读取审计日志并校验哈希链
+    fn reload_audit(&mut self) {
+        self.audit_dirty = false;
+        let journal = self.settings.journal();
+        match journal.entries().and_then(|entries| Ok((entries, journal.verify()?))) {
+            Ok((entries, verification)) => {
+                self.audit_entries = entries;
+                self.audit_verification = Some(verification);
+                self.audit_error = None;
+            }
+            Err(e) => self.audit_error = Some(e),
+        }
+    }
+
+    /// 校验并保存设置副本，成功后立即应用到各功能模块
+    fn save_settings(&mut self) {
+        self.settings_saved = false;
+        if let Err(e) = self.settings_draft.save() {
+            self.settings_error = Some(e);
+            return;
+        }
+        self.settings = self.settings_draft.clone();
+        self.settings_error = None;
+        self.settings_saved = true;
+        
+        match BackupManager::new(&self.settings) {
+            Ok(backup_manager) => self.backup_manager = backup_manager,
+            Err(e) => self.settings_error = Some(e),
+        }
+        self.driver_installer = DriverInstaller::new(self.platform.clone(), &self.settings);
+        self.driver_searcher = DriverSearcher::new(self.platform.clone(), &self.settings);
+        self.driver_management.apply_settings(&self.settings);
+        match DriverService::new(self.platform.clone(), &self.settings) {
+            Ok(driver_service) => self.driver_service = driver_service,
+            Err(e) => self.settings_error = Some(e),
+        }
+    }
+
+    /// 在后台扫描系统驱动并验证签名
+    fn start_driver_scan(&mut self) {
+        let platform = self.platform.clone();
+        let settings = self.settings.clone();
+        let validator = SignatureValidator::new().with_cache(settings.signature_cache_path());
+        self.driver_scan_results = validator.results();
+        self.driver_scan_job.start(&self.jobs, "扫描系统驱动", move |job| {
+            job.report(0, 1, "正在枚举系统驱动");
+            let mut drivers = DriverService::new(platform, &settings)?.enumerate_drivers()?;
+            job.check_cancelled()?;
+            job.report(0, 1, "正在加载系统编录文件");
+            let mut validator = validator.with_system_catalogs();
+            if let Some(trust_store) = settings.trust_store() {
+                validator = validator.with_trust_store(trust_store);
+            }
+            let batch = validator.validate_batch(&drivers, settings.concurrency, job)?;
+            // 无法检查的文件（不存在，或不是 PE 文件且没有编录）保留平台提供的签名状态
+            for (driver, result) in drivers.iter_mut().zip(batch.results).filter(|(_, result)| result.checked) {
+                driver.signed = result.is_valid;
+                driver.signature_status = result.summary();
+            }
+            Ok((drivers, batch.warnings))
+        });
+    }
+
     // pub fn scan_drivers(&mut self) {
     //     // 对于UI流畅性，最重要的是避免在UI线程上执行长时间运行的操作
     //     // 我们可以将扫描操作放到后台线程，但需要正确的线程安全实现
@@ -314,26 +513,20 @@ impl eframe::App for GuiApp {
             }
         }
         
-        // 检查系统信息是否已异步加载完成
-        if let Some(ref receiver) = self.system_info_rx {
-            // 尝试接收结果，不阻塞UI线程
-            if let Ok(result) = receiver.try_recv() {
-                match result {
-                    Ok(info) => {
-                        println!("Received system info: {:?}", info);
-                        println!("Monitor info: {:?}", info.monitor_info);
-                        self.system_info = Some(info);
-                        self.system_info_loading = false;
-                    }
-                    Err(e) => {
-                        println!("System info error: {:?}", e);
-                        self.system_info_error = Some(e);
-                        self.system_info_loading = false;
-                    }
-                }
-                // 移除receiver，因为我们已经收到了结果
-                self.system_info_rx = None;
-            }
+        // 收取后台任务结果，不阻塞UI线程
+        self.poll_jobs();
+        if self.audit_dirty {
+            self.reload_audit();
+        }
+        
+        // 应用主题设置
+        if self.applied_theme != Some(self.settings.theme) {
+            ctx.set_theme(match self.settings.theme {
+                Theme::System => egui::ThemePreference::System,
+                Theme::Light => egui::ThemePreference::Light,
+                Theme::Dark => egui::ThemePreference::Dark,
+            });
+            self.applied_theme = Some(self.settings.theme);
         }
         
         // 请求定期重绘以确保UI响应
@@ -474,7 +667,7 @@ impl eframe::App for GuiApp {
                         ui.painter().text(
                             rect.center(),
                             egui::Align2::CENTER_CENTER,
-                            &text,
+                            text,
                             font_id.clone(),
                             if _is_selected || response.hovered() { selected_fg_color } else { egui::Color32::from_rgb(242, 242, 242) }
                         );
@@ -503,7 +696,7 @@ impl eframe::App for GuiApp {
                             ui.painter().text(
                                 rect.center(),
                                 egui::Align2::CENTER_CENTER,
-                                format!("备份驱动"),
+                                "备份驱动",
                                 font_id.clone(),
                                 if _is_selected || response.hovered() { selected_fg_color } else { egui::Color32::from_rgb(242, 242, 242) }
                             );
@@ -845,8 +1038,8 @@ impl eframe::App for GuiApp {
                                     .fill(egui::Color32::from_rgb(248, 248, 248)),
                             );
                             if menu_response.clicked() {
-                                // 这里可以添加菜单功能
-                                println!("菜单按钮被点击");
+                                // 显示或隐藏后台任务列表
+                                self.show_job_list = !self.show_job_list;
                             }
                             if menu_response.hovered() {
                                 let painter = ui.painter_at(menu_response.rect);
@@ -905,16 +1098,10 @@ impl eframe::App for GuiApp {
                                                 ui.heading("电脑概览");
                                             });
                                     
-                                            // 检查是否需要加载系统信息（仅在首次显示概览页面时）
-                                            if self.system_info.is_none() && !self.system_info_loading {
-                                                self.system_info_loading = true;
-                                                // 在后台线程中加载系统信息，避免UI卡顿
-                                                let (tx, rx) = mpsc::channel();
-                                                thread::spawn(move || {
-                                                    let result = SystemInfo::new();
-                                                    let _ = tx.send(result);
-                                                });
-                                                self.system_info_rx = Some(rx);
+                                            // 检查是否需要加载系统信息（仅在首次显示概览页面或加载被取消时）
+                                            if self.system_info.is_none() && !self.system_info_job.is_running() && self.system_info_job.error.is_none() {
+                                                let platform = self.platform.clone();
+                                                self.system_info_job.start(&self.jobs, "读取系统信息", move |_| SystemInfo::new(platform.as_ref()));
                                             }
                                         
                                             if let Some(ref sys_info) = self.system_info {
@@ -1014,7 +1201,7 @@ impl eframe::App for GuiApp {
                                         if let Some(ref motherboard) = motherboard {
                                             ui.horizontal(|ui| {
                                                 // 添加主板制造商logo图标 (18x18px)
-                                                let logo_path = get_motherboard_logo_path(motherboard);
+                                                let logo_path = get_motherboard_logo_path(&self.hardware_ids, manufacturer.as_deref(), motherboard);
                                                 if let Some(icon) = self.get_or_load_icon(&logo_path, (18, 18), ctx) {
                                                     ui.image((icon.id(), egui::Vec2::new(18.0, 18.0)));
                                                 } else {
@@ -1075,11 +1262,15 @@ impl eframe::App for GuiApp {
                                         for audio in &audio_info {
                                             self.show_hardware_item(ui, audio, ctx);
                                         }
-                                            } else if self.system_info_loading {
+                                            } else if let Some(ref handle) = self.system_info_job.handle {
                                                 ui.label("正在加载系统信息...");
-                                            } else if let Some(ref error) = self.system_info_error {
+                                                show_job_progress(ui, handle);
+                                            } else if let Some(ref error) = self.system_info_job.error {
                                                 ui.label("加载系统信息失败:");
-                                                ui.label(format!("  {}", error));
+                                                show_error(ui, &self.platform, self.settings.language, error);
+                                                if ui.button("重新加载").clicked() {
+                                                    self.system_info_job.error = None;
+                                                }
                                             } else {
                                                 ui.label("点击切换到此页面以加载系统信息");
                                             }
@@ -1095,10 +1286,115 @@ impl eframe::App for GuiApp {
                     });
             });
 
+        // 后台任务列表窗口
+        if self.show_job_list {
+            let mut open = true;
+            egui::Window::new("后台任务")
+                .open(&mut open)
+                .resizable(false)
+                .show(ctx, |ui| show_job_list(ui, &self.jobs));
+            self.show_job_list = open;
+        }
+
         // 只在需要时刷新UI，避免不必要的重绘
     }
 }
 
+/// 显示任务的实际进度、当前步骤和取消按钮
+fn show_job_progress<T>(ui: &mut egui::Ui, handle: &JobHandle<T>) {
+    let progress = handle.progress();
+    ui.vertical(|ui| {
+        if !progress.message.is_empty() {
+            ui.label(egui::RichText::new(&progress.message).size(14.0));
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::ProgressBar::new(progress.fraction()).desired_width(600.0));
+            ui.label(format!("{:.0}%", progress.fraction() * 100.0));
+            if handle.is_cancel_requested() {
+                ui.label("正在取消...");
+            } else if ui.button("取消").clicked() {
+                handle.cancel();
+            }
+        });
+    });
+}
+
+/// 列出所有后台任务及其状态
+fn show_job_list(ui: &mut egui::Ui, runner: &JobRunner) {
+    let jobs = runner.jobs();
+    if jobs.is_empty() {
+        ui.label("暂无后台任务");
+        return;
+    }
+
+    for info in jobs.iter().rev() {
+        ui.horizontal(|ui| {
+            ui.label(&info.name);
+            ui.label(info.state.label());
+            if !info.state.is_finished() {
+                ui.add(egui::ProgressBar::new(info.progress.fraction()).desired_width(160.0).show_percentage());
+                if ui.small_button("取消").clicked() {
+                    runner.cancel(info.id);
+                }
+            }
+        });
+        match &info.state {
+            JobState::Failed { message, .. } => {
+                ui.colored_label(egui::Color32::RED, message);
+            }
+            JobState::Running if !info.progress.message.is_empty() => {
+                ui.label(egui::RichText::new(&info.progress.message).size(12.0));
+            }
+            _ => {}
+        }
+        ui.separator();
+    }
+
+    if ui.button("清除已完成").clicked() {
+        runner.clear_finished();
+    }
+}
+
+/// 扫描系统驱动按钮，扫描进行中时显示进度
+fn show_driver_scan(ui: &mut egui::Ui, state: &mut GuiApp) {
+    if let Some(ref handle) = state.driver_scan_job.handle {
+        show_job_progress(ui, handle);
+        // 签名验证的结果边完成边显示最近几个
+        if let Ok(results) = state.driver_scan_results.lock() {
+            for result in results.iter().rev().take(5) {
+                ui.label(format!("{}: {}", result.driver_name, result.summary()));
+            }
+        }
+    } else if ui.button(if state.drivers.is_empty() { "扫描系统驱动" } else { "重新扫描" }).clicked() {
+        state.start_driver_scan();
+    }
+    if let Some(ref error) = state.driver_scan_job.error {
+        ui.label("扫描驱动失败:");
+        show_error(ui, &state.platform, state.settings.language, error);
+    }
+    for warning in &state.driver_scan_warnings {
+        ui.colored_label(egui::Color32::YELLOW, warning);
+    }
+}
+
+/// 显示错误信息，权限不足时提供以管理员身份重试
+fn show_error(ui: &mut egui::Ui, platform: &Arc<dyn Platform>, locale: Locale, error: &HamsterError) {
+    ui.colored_label(egui::Color32::RED, error.localized(locale));
+    if error.requires_elevation() {
+        show_elevation_button(ui, platform);
+    }
+}
+
+/// 以管理员身份重新启动程序，新进程启动后关闭当前窗口
+fn show_elevation_button(ui: &mut egui::Ui, platform: &Arc<dyn Platform>) {
+    if ui.button("以管理员身份重试").clicked() {
+        match platform.relaunch_elevated() {
+            Ok(()) => ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close),
+            Err(e) => eprintln!("{}", e.localized(Locale::default())),
+        }
+    }
+}
+
 // UI中的高级功能界面
 fn show_advanced_features(ui: &mut egui::Ui, state: &mut GuiApp) {
     match state.selected_tab {
@@ -1108,6 +1404,7 @@ fn show_advanced_features(ui: &mut egui::Ui, state: &mut GuiApp) {
         AppTab::DriverUninstall => show_uninstall_driver_view(ui.ctx(), state),
         AppTab::SystemGameComponents => show_system_game_components_view(ui.ctx(), state),
         AppTab::BackupRestore => show_backup_view(ui.ctx(), state),
+        AppTab::Settings => show_settings_view(ui, state),
         _ => {}
     }
 }
@@ -1204,6 +1501,7 @@ fn show_backup_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
         .show(ctx, |ui| {
         ui.heading("备份驱动");
         
+        show_driver_scan(ui, state);
         if state.drivers.is_empty() {
             ui.label("没有可用的驱动信息，请先扫描系统驱动");
             return;
@@ -1235,6 +1533,7 @@ fn show_backup_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
                 // 备份按钮
                 if ui.button("备份").clicked() {
                     let result = state.driver_management.backup_driver(driver);
+                    state.audit_dirty = true;
                     if result.success {
                         ui.colored_label(egui::Color32::GREEN, "✓ 备份成功");
                     } else {
@@ -1245,46 +1544,31 @@ fn show_backup_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
             ui.separator();
         }
         
-        // 批量备份按钮
-        if ui.button("备份所有驱动").clicked() {
-            for driver in &state.drivers {
-                let result = state.driver_management.backup_driver(driver);
-                if result.success {
-                    ui.colored_label(egui::Color32::GREEN, format!("✓ {} 备份成功", driver.name));
-                } else {
-                    ui.colored_label(egui::Color32::RED, format!("✗ {} 备份失败: {}", driver.name, result.message));
-                }
-            }
+        // 批量备份在后台任务中进行，结果合并到备份历史
+        if let Some(ref handle) = state.backup_job.handle {
+            show_job_progress(ui, handle);
+        } else if ui.button("备份所有驱动").clicked() {
+            let platform = state.platform.clone();
+            let settings = state.settings.clone();
+            let drivers = state.drivers.clone();
+            state.backup_job.start(&state.jobs, "备份所有驱动", move |job| {
+                let mut management = DriverManagement::new(platform, &settings);
+                BackupManager::new(&settings)?.create_backup(&mut management, &drivers, job)
+            });
+        }
+        if let Some(ref manifest) = state.last_backup {
+            ui.colored_label(egui::Color32::GREEN, format!("✓ 已创建备份 {}，共 {} 个驱动", manifest.backup_id, manifest.drivers.len()));
+        }
+        if let Some(ref error) = state.backup_job.error {
+            ui.label("备份失败:");
+            show_error(ui, &state.platform, state.settings.language, error);
         }
         
         ui.separator();
         
-        // 备份历史记录
+        // 备份历史记录，读取自审计日志
         ui.heading("备份历史记录");
-        let history = state.driver_management.get_backup_history();
-        if !history.is_empty() {
-            for record in history.iter() {
-                ui.horizontal(|ui| {
-                    if record.success {
-                        ui.colored_label(egui::Color32::GREEN, "✓");
-                    } else {
-                        ui.colored_label(egui::Color32::RED, "✗");
-                    }
-                    ui.vertical(|ui| {
-                        ui.label(format!("驱动: {}", record.driver_name));
-                        ui.label(format!("时间: {}", record.timestamp));
-                        ui.label(format!("结果: {}", record.message));
-                    });
-                });
-                ui.separator();
-            }
-            
-            if ui.button("清空备份历史").clicked() {
-                state.driver_management.clear_backup_history();
-            }
-        } else {
-            ui.label("暂无备份记录");
-        }
+        show_audit_history(ui, state, AuditAction::Backup);
     });
 }
 
@@ -1332,6 +1616,7 @@ fn show_restore_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
                 // 恢复按钮
                 if ui.button("恢复").clicked() {
                     let result = state.driver_management.restore_driver(backup_file);
+                    state.audit_dirty = true;
                     if result.success {
                         ui.colored_label(egui::Color32::GREEN, "✓ 恢复成功");
                     } else {
@@ -1344,32 +1629,9 @@ fn show_restore_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
         
         ui.separator();
         
-        // 恢复历史记录
+        // 恢复历史记录，读取自审计日志
         ui.heading("恢复历史记录");
-        let history = state.driver_management.get_restore_history();
-        if !history.is_empty() {
-            for record in history.iter() {
-                ui.horizontal(|ui| {
-                    if record.success {
-                        ui.colored_label(egui::Color32::GREEN, "✓");
-                    } else {
-                        ui.colored_label(egui::Color32::RED, "✗");
-                    }
-                    ui.vertical(|ui| {
-                        ui.label(format!("驱动: {}", record.driver_name));
-                        ui.label(format!("时间: {}", record.timestamp));
-                        ui.label(format!("结果: {}", record.message));
-                    });
-                });
-                ui.separator();
-            }
-            
-            if ui.button("清空恢复历史").clicked() {
-                state.driver_management.clear_restore_history();
-            }
-        } else {
-            ui.label("暂无恢复记录");
-        }
+        show_audit_history(ui, state, AuditAction::Restore);
     });
 }
 
@@ -1379,6 +1641,7 @@ fn show_uninstall_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
         .show(ctx, |ui| {
         ui.heading("卸载驱动");
         
+        show_driver_scan(ui, state);
         if state.drivers.is_empty() {
             ui.label("没有可用的驱动信息，请先扫描系统驱动");
             return;
@@ -1409,6 +1672,7 @@ fn show_uninstall_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
                 // 卸载按钮
                 if ui.button("卸载").clicked() {
                     let result = state.driver_management.uninstall_driver(driver);
+                    state.audit_dirty = true;
                     if result.success {
                         ui.colored_label(egui::Color32::GREEN, "✓ 卸载成功");
                     } else {
@@ -1421,32 +1685,9 @@ fn show_uninstall_driver_view(ctx: &egui::Context, state: &mut GuiApp) {
         
         ui.separator();
         
-        // 卸载历史记录
+        // 卸载历史记录，读取自审计日志
         ui.heading("卸载历史记录");
-        let history = state.driver_management.get_uninstall_history();
-        if !history.is_empty() {
-            for record in history.iter() {
-                ui.horizontal(|ui| {
-                    if record.success {
-                        ui.colored_label(egui::Color32::GREEN, "✓");
-                    } else {
-                        ui.colored_label(egui::Color32::RED, "✗");
-                    }
-                    ui.vertical(|ui| {
-                        ui.label(format!("驱动: {}", record.driver_name));
-                        ui.label(format!("时间: {}", record.timestamp));
-                        ui.label(format!("结果: {}", record.message));
-                    });
-                });
-                ui.separator();
-            }
-            
-            if ui.button("清空卸载历史").clicked() {
-                state.driver_management.clear_uninstall_history();
-            }
-        } else {
-            ui.label("暂无卸载记录");
-        }
+        show_audit_history(ui, state, AuditAction::Uninstall);
     });
 }
 
@@ -1454,107 +1695,34 @@ fn show_driver_install_view(ui: &mut egui::Ui, state: &mut GuiApp) {
     // 主内容区域已经包含ScrollArea和内边距，这里直接显示内容
     ui.heading("驱动安装");
     
-    // 检查搜索进度更新
-    if let Some(ref rx) = state.driver_search_rx {
-        // 检查是否有最终结果
-        if let Ok(result) = rx.try_recv() {
-            state.online_drivers_searching = false;
-            state.driver_search_rx = None;
-            state.driver_search_progress = None;
-            
-            match result {
-                Ok(drivers) => {
-                    state.online_drivers = drivers;
-                }
-                Err(e) => {
-                    ui.colored_label(egui::Color32::RED, format!("搜索失败: {}", e));
-                }
-            }
-        }
-    }
-    
     // 在线驱动搜索按钮和进度条
     ui.horizontal(|ui| {
         // 搜索按钮
-                let button = egui::Button::new(egui::RichText::new("搜索驱动").size(18.0).color(egui::Color32::WHITE))
-                    .fill(egui::Color32::from_rgb(0, 111, 201));
-                
-                if ui.add(button).clicked() && !state.online_drivers_searching {
-            state.online_drivers_searching = true;
+        let button = egui::Button::new(egui::RichText::new("搜索驱动").size(18.0).color(egui::Color32::WHITE))
+            .fill(egui::Color32::from_rgb(0, 111, 201));
+        
+        if ui.add(button).clicked() && !state.driver_search_job.is_running() {
             state.online_drivers.clear();
-            
-            // 初始化进度状态
-            state.driver_search_progress = Some(DriverSearchProgress {
-                status: "开始搜索...".to_string(),
-                progress: 0.0,
-                current_step: "正在扫描电脑硬件... ...".to_string(),
-                total_steps: 4,
-                current_step_index: 0,
-            });
-            
-            // 在后台线程中搜索驱动
-            let (tx, rx) = mpsc::channel();
-            state.driver_search_rx = Some(rx);
-            
-            let ctx = ui.ctx().clone();
-            
-            thread::spawn(move || {
-                let searcher = DriverSearcher::new();
-                let (progress_tx, progress_rx) = mpsc::channel();
-                
-                // 启动进度更新线程
-                let ctx_clone = ctx.clone();
-                thread::spawn(move || {
-                    while let Ok(_progress) = progress_rx.recv() {
-                        // 这里应该更新UI状态，但需要共享状态
-                        ctx_clone.request_repaint();
-                    }
-                });
-                
-                let result = searcher.search_online_drivers(Some(progress_tx));
-                let _ = tx.send(result);
-                ctx.request_repaint();
-            });
+            let searcher = DriverSearcher::new(state.platform.clone(), &state.settings);
+            state.driver_search_job.start(&state.jobs, "搜索驱动", move |job| searcher.search_online_drivers(job));
         }
         
         // 搜索进度条（放在按钮右边）
-        if state.online_drivers_searching {
-            // 添加间距
+        if let Some(ref handle) = state.driver_search_job.handle {
             ui.add_space(16.0);
-            
-            // 显示搜索状态提示
-            ui.vertical(|ui| {
-                // 当前步骤状态在进度条上方
-                if let Some(ref progress) = state.driver_search_progress {
-                    ui.label(egui::RichText::new(&progress.current_step).size(14.0));
-                } else {
-                    ui.label(egui::RichText::new("正在扫描电脑硬件... ...").size(14.0));
-                }
-                
-                // 进度条和百分比在同一行，宽度增加到原来的3倍（450px）
-                ui.horizontal(|ui| {
-                    // 动态进度条：基于时间模拟进度变化
-                    let progress_value = if state.online_drivers_searching {
-                        // 模拟动态进度：从0.01到0.99循环变化（1%-99%）
-                        let elapsed = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs_f32();
-                        
-                        // 使用正弦函数创建平滑的进度动画
-                        let base_progress = (elapsed * 0.3).sin() * 0.49 + 0.5; // 在0.01到0.99之间变化
-                        base_progress.clamp(0.01, 0.99)
-                    } else {
-                        0.0
-                    };
-                    
-                    ui.add(egui::ProgressBar::new(progress_value).desired_width(600.0)); // 宽度增加到600px
-                    ui.label(format!("{:.0}%", progress_value * 100.0));
-                });
-            });
+            show_job_progress(ui, handle);
         }
     });
     
+    if let Some(ref error) = state.driver_search_job.error {
+        ui.label("搜索失败:");
+        show_error(ui, &state.platform, state.settings.language, error);
+    }
+    
+    ui.separator();
+    
+    show_local_install(ui, state);
+    
     ui.separator();
     
     // 显示搜索到的在线驱动
@@ -1564,7 +1732,7 @@ fn show_driver_install_view(ui: &mut egui::Ui, state: &mut GuiApp) {
         // 使用Phosphor Icons作为状态指示图标
         // 不再需要预先加载SVG图标
         
-        for (_i, driver) in state.online_drivers.iter().enumerate() {
+        for driver in state.online_drivers.iter() {
 
             ui.horizontal(|ui| {
                 // 驱动信息 - 单行显示模式
@@ -1580,27 +1748,23 @@ fn show_driver_install_view(ui: &mut egui::Ui, state: &mut GuiApp) {
                     }
                     
                     // 最新版本
-                    ui.label(format!("最新: {}", driver.version));
+                    ui.label(format!("最新: {}", driver.version.as_deref().unwrap_or("未知")));
                     
-                    // 状态指示 - 使用SVG图标
-                    if let Some(current_version) = &driver.current_version {
-                if current_version == &driver.version {
-                    // 当前版本与最新版本一致 - 使用绿色勾选图标
-                    ui.colored_label(egui::Color32::GREEN, "✅");
-                } else {
-                    // 需要更新 - 使用黄色感叹号图标
-                    ui.colored_label(egui::Color32::YELLOW, "⚠️");
-                }
-            } else {
-                // 未安装驱动 - 使用蓝色问号图标
-                ui.colored_label(egui::Color32::BLUE, "❓");
-            }
+                    // 状态指示 - 按日期和版本比较的结果
+                    let (color, icon) = match driver.update_state {
+                        UpdateState::Same | UpdateState::NewerInstalled => (egui::Color32::GREEN, "✅"),
+                        UpdateState::UpdateAvailable => (egui::Color32::YELLOW, "⚠️"),
+                        UpdateState::Downgrade => (egui::Color32::RED, "⚠️"),
+                        UpdateState::NotInstalled | UpdateState::Unknown => (egui::Color32::BLUE, "❓"),
+                    };
+                    ui.colored_label(color, icon).on_hover_text(driver.update_state.label());
                 });
                 
                 // 操作按钮 - 在同一行显示
                 ui.horizontal(|ui| {
-                    // 更新按钮 - 只有在需要更新时才可点击
-                    let update_enabled = driver.current_version.as_ref().map_or(true, |v| v != &driver.version);
+                    // 更新按钮 - 只有在线驱动较新、未安装或无法比较时才可点击
+                    let update_enabled = matches!(driver.update_state,
+                        UpdateState::UpdateAvailable | UpdateState::NotInstalled | UpdateState::Unknown);
                     let update_button = ui.add_enabled(update_enabled, egui::Button::new("更新"));
                     
                     // 重装按钮 - 总是可点击
@@ -1622,3 +1786,312 @@ fn show_driver_install_view(ui: &mut egui::Ui, state: &mut GuiApp) {
     }
 }
 
+
+// 从本地目录扫描并安装驱动
+fn show_local_install(ui: &mut egui::Ui, state: &mut GuiApp) {
+    ui.heading("本地驱动安装");
+    
+    ui.horizontal(|ui| {
+        ui.label("驱动目录或压缩包:");
+        ui.text_edit_singleline(&mut state.scan_directory);
+        if ui.add_enabled(!state.install_scan_job.is_running(), egui::Button::new("扫描目录")).clicked() {
+            let installer = DriverInstaller::new(state.platform.clone(), &state.settings);
+            let directory = PathBuf::from(&state.scan_directory);
+            state.install_scan_job.start(&state.jobs, "扫描驱动文件", move |job| {
+                installer.scan_drivers_in_directory(&directory, job)
+            });
+        }
+    });
+    
+    if let Some(ref handle) = state.install_scan_job.handle {
+        show_job_progress(ui, handle);
+    }
+    if let Some(ref error) = state.install_scan_job.error {
+        ui.label("扫描目录失败:");
+        show_error(ui, &state.platform, state.settings.language, error);
+    }
+    
+    for (i, driver) in state.scanned_drivers.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut state.selected_install_driver, Some(i), &driver.display_name);
+            ui.label(format!("{:?}", driver.install_method));
+            ui.label(format!("{} {}", driver.manufacturer, driver.version));
+            ui.label(&driver.signature_status);
+        });
+        for warning in &driver.warnings {
+            ui.indent(("warning", i), |ui| {
+                ui.colored_label(egui::Color32::YELLOW, warning);
+            });
+        }
+        if let Some(inf) = &driver.inf {
+            ui.indent(("inf", i), |ui| {
+                ui.label(format!("类别: {}  支持: {}",
+                    inf.version.class.as_deref().unwrap_or("未知"),
+                    driver.supported_os.join("，")));
+                ui.label(format!("硬件ID: {}", inf.hardware_ids().join(", ")));
+            });
+        }
+        if let Some(package) = &driver.package {
+            ui.indent(("package", i), |ui| {
+                ui.label(format!("来源: {}（{} 个文件，共 {} 字节）", package.inf, package.files.len(), package.total_size()));
+                if !package.missing_files.is_empty() {
+                    ui.colored_label(egui::Color32::YELLOW, format!("缺少文件: {}", package.missing_files.join(", ")));
+                }
+                for duplicate in &package.duplicates {
+                    ui.label(format!("相同的包: {}", duplicate));
+                }
+            });
+        }
+    }
+    
+    if !state.scan_skipped.is_empty() {
+        egui::CollapsingHeader::new(format!("已跳过 {} 项", state.scan_skipped.len()))
+            .id_salt("scan_skipped")
+            .show(ui, |ui| {
+                for skipped in &state.scan_skipped {
+                    ui.label(skipped);
+                }
+            });
+    }
+    
+    show_driver_matches(ui, state);
+    
+    if !state.scanned_drivers.is_empty() {
+        if let Some(ref handle) = state.install_job.handle {
+            show_job_progress(ui, handle);
+        } else if ui.add_enabled(state.selected_install_driver.is_some(), egui::Button::new("安装选中驱动")).clicked() {
+            if let Some(driver) = state.selected_install_driver.and_then(|i| state.scanned_drivers.get(i)).cloned() {
+                let mut installer = DriverInstaller::new(state.platform.clone(), &state.settings);
+                state.install_job.start(&state.jobs, "安装驱动", move |job| {
+                    job.report(0, 1, format!("正在安装 {}", driver.display_name));
+                    let result = installer.install_driver(&driver);
+                    job.report(1, 1, "安装结束");
+                    Ok(result)
+                });
+            }
+        }
+    }
+    if let Some(ref error) = state.install_job.error {
+        show_error(ui, &state.platform, state.settings.language, error);
+    }
+    
+    ui.label("安装历史记录:");
+    show_audit_history(ui, state, AuditAction::Install);
+    
+    ui.separator();
+}
+
+// 扫描到的驱动与本机设备的匹配结果，列出每个候选的等级以及被选中或落选的原因
+fn show_driver_matches(ui: &mut egui::Ui, state: &GuiApp) {
+    if let Some(ref handle) = state.driver_match_job.handle {
+        show_job_progress(ui, handle);
+    }
+    if let Some(ref error) = state.driver_match_job.error {
+        ui.label("无法为设备匹配驱动:");
+        show_error(ui, &state.platform, state.settings.language, error);
+    }
+    if state.driver_matches.is_empty() {
+        return;
+    }
+    
+    egui::CollapsingHeader::new(format!("设备匹配（{} 个设备）", state.driver_matches.len()))
+        .default_open(true)
+        .show(ui, |ui| {
+            for device_match in &state.driver_matches {
+                let title = match device_match.selected() {
+                    Some(selected) => format!("{} → {}", device_match.device_name, selected.model.description),
+                    None => format!("{} → 无适用驱动", device_match.device_name),
+                };
+                egui::CollapsingHeader::new(title)
+                    .id_salt(("driver_match", &device_match.hardware_id))
+                    .show(ui, |ui| {
+                        ui.label(format!("硬件ID: {}", device_match.hardware_id));
+                        ui.label(format!("已安装: {}", device_match.installed_version));
+                        for candidate in &device_match.candidates {
+                            let (color, verdict) = match candidate.verdict {
+                                Verdict::Selected => (egui::Color32::GREEN, "选中"),
+                                Verdict::Outranked => (egui::Color32::GRAY, "落选"),
+                                Verdict::NotApplicable => (egui::Color32::from_rgb(200, 120, 0), "不适用"),
+                            };
+                            ui.horizontal(|ui| {
+                                ui.colored_label(color, verdict);
+                                ui.label(candidate.rank_hex());
+                                ui.label(format!("{} [{}]", candidate.model.description, candidate.model.section));
+                                if let Some(version) = device_match.candidate_version(candidate) {
+                                    ui.label(version);
+                                }
+                            });
+                            ui.indent(("match_reasons", candidate.driver_index, &candidate.model.section, candidate.model.line), |ui| {
+                                for reason in &candidate.reasons {
+                                    ui.label(reason);
+                                }
+                            });
+                        }
+                    });
+            }
+        });
+}
+
+// 设置页：编辑设置副本，校验通过后保存到用户配置目录
+fn show_settings_view(ui: &mut egui::Ui, state: &mut GuiApp) {
+    ui.heading("设置");
+    ui.label(format!("设置文件: {}", Settings::path().display()));
+    
+    if let Some(ref error) = state.settings_error {
+        show_error(ui, &state.platform, state.settings.language, error);
+    }
+    for warning in state.platform.warnings() {
+        ui.colored_label(egui::Color32::YELLOW, warning);
+    }
+    
+    ui.separator();
+    
+    let draft = &mut state.settings_draft;
+    egui::Grid::new("settings_grid").num_columns(2).spacing([16.0, 8.0]).show(ui, |ui| {
+        ui.label("备份位置");
+        edit_path(ui, &mut draft.backup_dir);
+        ui.end_row();
+        
+        ui.label("临时目录");
+        edit_path(ui, &mut draft.temp_dir);
+        ui.end_row();
+        
+        ui.label("界面语言");
+        egui::ComboBox::from_id_salt("settings_language")
+            .selected_text(draft.language.native_name())
+            .show_ui(ui, |ui| {
+                for locale in Locale::ALL {
+                    ui.selectable_value(&mut draft.language, locale, locale.native_name());
+                }
+            });
+        ui.end_row();
+        
+        ui.label("代理");
+        let mut proxy = draft.proxy.clone().unwrap_or_default();
+        if ui.add(egui::TextEdit::singleline(&mut proxy).hint_text("不使用代理")).changed() {
+            let proxy = proxy.trim();
+            draft.proxy = if proxy.is_empty() { None } else { Some(proxy.to_string()) };
+        }
+        ui.end_row();
+        
+        ui.label("网络超时（秒）");
+        ui.add(egui::DragValue::new(&mut draft.http_timeout_secs).range(1..=MAX_HTTP_TIMEOUT_SECS));
+        ui.end_row();
+        
+        ui.label("User-Agent");
+        ui.text_edit_singleline(&mut draft.user_agent);
+        ui.end_row();
+        
+        ui.label("并发数");
+        ui.add(egui::DragValue::new(&mut draft.concurrency).range(1..=MAX_CONCURRENCY));
+        ui.end_row();
+        
+        ui.label("更新策略");
+        ui.horizontal(|ui| {
+            for policy in UpdatePolicy::ALL {
+                ui.radio_value(&mut draft.update_policy, policy, policy.label());
+            }
+        });
+        ui.end_row();
+        
+        ui.label("主题");
+        ui.horizontal(|ui| {
+            for theme in Theme::ALL {
+                ui.radio_value(&mut draft.theme, theme, theme.label());
+            }
+        });
+        ui.end_row();
+        
+        // 平台在启动时加载硬件ID数据库
+        ui.label("pci.ids");
+        edit_optional_path(ui, &mut draft.pci_ids_path, "使用系统自带或内置数据库，重启后生效");
+        ui.end_row();
+        
+        ui.label("usb.ids");
+        edit_optional_path(ui, &mut draft.usb_ids_path, "使用系统自带或内置数据库，重启后生效");
+        ui.end_row();
+        
+        ui.label("受信任证书目录");
+        edit_optional_path(ui, &mut draft.trust_store_dir, "不验证证书链");
+        ui.end_row();
+    });
+    
+    let problems = state.settings_draft.problems();
+    for problem in &problems {
+        ui.colored_label(egui::Color32::RED, problem);
+    }
+    
+    ui.separator();
+    
+    let changed = state.settings_draft != state.settings;
+    ui.horizontal(|ui| {
+        if ui.add_enabled(changed && problems.is_empty(), egui::Button::new("保存")).clicked() {
+            state.save_settings();
+        }
+        if ui.add_enabled(changed, egui::Button::new("撤销修改")).clicked() {
+            state.settings_draft = state.settings.clone();
+        }
+        if ui.button("恢复默认").clicked() {
+            state.settings_draft = Settings::default();
+        }
+    });
+    if state.settings_saved && !changed {
+        ui.colored_label(egui::Color32::GREEN, "✓ 设置已保存");
+    }
+}
+
+// 以文本框编辑路径
+fn edit_path(ui: &mut egui::Ui, path: &mut PathBuf) {
+    let mut text = path.display().to_string();
+    if ui.add(egui::TextEdit::singleline(&mut text).desired_width(360.0)).changed() {
+        *path = PathBuf::from(text);
+    }
+}
+
+// 以文本框编辑可选路径，留空表示不指定
+fn edit_optional_path(ui: &mut egui::Ui, path: &mut Option<PathBuf>, hint: &str) {
+    let mut text = path.as_ref().map(|path| path.display().to_string()).unwrap_or_default();
+    if ui.add(egui::TextEdit::singleline(&mut text).hint_text(hint).desired_width(360.0)).changed() {
+        let text = text.trim();
+        *path = if text.is_empty() { None } else { Some(PathBuf::from(text)) };
+    }
+}
+
+/// 显示审计日志中指定类型的操作记录，最新的在前；哈希链断开时给出警告
+fn show_audit_history(ui: &mut egui::Ui, state: &GuiApp, action: AuditAction) {
+    if let Some(ref error) = state.audit_error {
+        show_error(ui, &state.platform, state.settings.language, error);
+    }
+    if let Some(broken) = state.audit_verification.as_ref().and_then(|v| v.first_break.as_ref()) {
+        ui.colored_label(egui::Color32::RED, format!("⚠ 审计日志在第 {} 条记录处被篡改或损坏: {}", broken.line, broken.reason));
+    }
+    
+    let mut entries = state.audit_entries.iter().rev().filter(|entry| entry.action == action).peekable();
+    if entries.peek().is_none() {
+        ui.label(format!("暂无{}记录", action.label()));
+        return;
+    }
+    for entry in entries {
+        ui.horizontal(|ui| {
+            if entry.success {
+                ui.colored_label(egui::Color32::GREEN, "✓");
+            } else {
+                ui.colored_label(egui::Color32::RED, "✗");
+            }
+            ui.vertical(|ui| {
+                ui.label(format!("驱动: {}", entry.target));
+                ui.label(format!("时间: {}  用户: {}", entry.timestamp.format("%Y-%m-%d %H:%M:%S"), entry.user));
+                if entry.before.is_some() || entry.after.is_some() {
+                    ui.label(format!("变更: {} → {}",
+                        entry.before.as_deref().unwrap_or("-"),
+                        entry.after.as_deref().unwrap_or("-")));
+                }
+                ui.label(format!("结果: {}", entry.message));
+                if entry.requires_elevation {
+                    show_elevation_button(ui, &state.platform);
+                }
+            });
+        });
+        ui.separator();
+    }
+}
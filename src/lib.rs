@@ -0,0 +1,2 @@
+// src/lib.rs - 核心库入口，图形界面与其他可执行程序共享同一份core实现
+pub mod core;
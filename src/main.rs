@@ -1,7 +1,7 @@
 // src/main.rs - 前后端分离架构入口
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod core;
+use hamster_driver_manager::core;
 mod gui;
 
 use eframe::egui;
@@ -66,7 +66,7 @@ fn find_system_font_path() -> Option<PathBuf> {
         // Linux 字体路径 (更分散)
         use std::process::Command;
         // 尝试通过 `fc-match` 命令查找中文字体
-        if let Ok(output) = Command::new("fc-match").args(&["-f", "%{file}", "serif:lang=zh"]).output() {
+        if let Ok(output) = Command::new("fc-match").args(["-f", "%{file}", "serif:lang=zh"]).output() {
             let path_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
             if !path_str.is_empty() {
                 return Some(PathBuf::from(path_str));
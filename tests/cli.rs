@@ -0,0 +1,54 @@
+// 以子进程运行 hamster-cli，平台数据来自夹具，设置目录指向空的临时目录
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use serde_json::Value;
+
+fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+}
+
+fn cli(env: &[(&str, PathBuf)], args: &[&str]) -> Output {
+    let config_dir = std::env::temp_dir().join(format!("hamster-cli-test-{}", std::process::id()));
+    let mut command = Command::new(env!("CARGO_BIN_EXE_hamster-cli"));
+    command.args(args)
+        .env("HAMSTER_CONFIG_DIR", &config_dir)
+        .env_remove("HAMSTER_PLATFORM_FIXTURE")
+        .env_remove("HAMSTER_SYSROOT");
+    for (name, value) in env {
+        command.env(name, value);
+    }
+    command.output().unwrap()
+}
+
+fn json(output: &Output) -> Value {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn match_uses_fixture_platform_devices() {
+    let output = cli(&[("HAMSTER_PLATFORM_FIXTURE", fixture("platform/sample.json"))], &["--json", "match", fixture("packages").to_str().unwrap()]);
+    let matches = json(&output);
+    let matches = matches.as_array().unwrap();
+    assert_eq!(matches.len(), 3);
+    let nvidia = matches.iter().find(|device_match| device_match["device_name"] == "NVIDIA GeForce RTX 3060").unwrap();
+    assert_eq!(nvidia["installed_version"], "546.23 (31.0.15.4623)");
+    assert!(nvidia["candidates"].as_array().unwrap().iter().any(|candidate| candidate["verdict"] == "Selected"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn drivers_list_reads_linux_sysroot() {
+    let output = cli(&[("HAMSTER_SYSROOT", fixture("linux"))], &["--json", "drivers", "list"]);
+    let drivers = json(&output);
+    let names: Vec<&str> = drivers.as_array().unwrap().iter().map(|driver| driver["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["nvidia_drm", "nvidia", "drm_kms_helper", "drm", "iwlmvm", "mac80211", "cfg80211", "e1000e"]);
+}
+
+#[test]
+fn broken_inf_fails_with_line_number() {
+    let output = cli(&[], &["inf", "show", fixture("inf/broken.inf").to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("第 2 行"));
+}
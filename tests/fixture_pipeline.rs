@@ -0,0 +1,90 @@
+// 用夹具数据运行完整流程：夹具平台的设备、fixtures/packages 中的驱动包、fixtures/trust 中的信任库，
+// 不访问真实系统，任何环境下结果都相同
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use hamster_driver_manager::core::features::dependency_analyzer::DependencyAnalyzer;
+use hamster_driver_manager::core::features::driver_installer::{DriverInstaller, InstallableDriver, SignatureState};
+use hamster_driver_manager::core::features::driver_matcher::{DeviceMatch, DriverMatcher, SignatureScore, SystemTarget};
+use hamster_driver_manager::core::features::driver_searcher::DriverSearcher;
+use hamster_driver_manager::core::jobs::JobRunner;
+use hamster_driver_manager::core::platform::{FixturePlatform, Platform};
+use hamster_driver_manager::core::settings::Settings;
+
+fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+}
+
+fn platform() -> Arc<dyn Platform> {
+    Arc::new(FixturePlatform::from_json_file(fixture("platform/sample.json")).unwrap())
+}
+
+fn settings(trust_store: bool) -> Settings {
+    Settings {
+        temp_dir: std::env::temp_dir().join(format!("hamster-fixture-pipeline-{}", std::process::id())),
+        trust_store_dir: trust_store.then(|| fixture("trust")),
+        ..Settings::default()
+    }
+}
+
+fn scan(trust_store: bool) -> Vec<InstallableDriver> {
+    let installer = DriverInstaller::new(platform(), &settings(trust_store));
+    let packages = fixture("packages");
+    JobRunner::new()
+        .submit("扫描驱动文件", move |job| installer.scan_drivers_in_directory(&packages, job))
+        .wait()
+        .unwrap()
+        .drivers
+}
+
+// Windows 11 23H2 工作站，不依赖运行测试的系统
+fn windows_11() -> SystemTarget {
+    SystemTarget { architecture: "amd64".to_string(), major: 10, minor: 0, product_type: 1, build: Some(22631) }
+}
+
+fn match_devices(trust_store: bool) -> Vec<DeviceMatch> {
+    let devices = DriverSearcher::new(platform(), &settings(trust_store)).get_device_info().unwrap();
+    DriverMatcher::new(windows_11()).match_devices(&devices, &scan(trust_store))
+}
+
+#[test]
+fn fixture_devices_match_fixture_packages() {
+    let matches = match_devices(false);
+    let selected: Vec<(&str, &str)> = matches.iter()
+        .filter_map(|device_match| device_match.selected().map(|candidate| (device_match.device_name.as_str(), candidate.model.install_section.as_str())))
+        .collect();
+    assert_eq!(selected.len(), 3, "{:?}", selected);
+    assert!(selected.contains(&("NVIDIA GeForce RTX 3060", "Section001_W11")));
+
+    let nvidia = matches.iter().find(|device_match| device_match.device_name == "NVIDIA GeForce RTX 3060").unwrap();
+    let candidate = nvidia.selected().unwrap();
+    assert_eq!(nvidia.candidate_version(candidate).as_deref(), Some("08/14/2024,560.94 (32.0.15.6094)"));
+    // 没有信任库时证书链未验证
+    assert_eq!(candidate.signature, SignatureScore::Unverified);
+}
+
+#[test]
+fn fixture_trust_store_verifies_catalog_signature() {
+    let drivers = scan(true);
+    let nvidia = drivers.iter().find(|driver| driver.source().ends_with("nv_dispi.inf") && !driver.source().contains('!')).unwrap();
+    assert_eq!(nvidia.signature_state, SignatureState::Signed { catalog: true, trusted: true }, "{:?}", nvidia.warnings);
+    // 测试根证书签名的驱动可以信任，但不是 Microsoft 签名
+    assert_eq!(SignatureScore::from_driver(nvidia), SignatureScore::Unsigned);
+}
+
+#[test]
+fn pipeline_results_are_reproducible() {
+    let first = serde_json::to_string(&match_devices(true)).unwrap();
+    let second = serde_json::to_string(&match_devices(true)).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn fixture_drivers_have_a_start_order() {
+    let mut analyzer = DependencyAnalyzer::new();
+    analyzer.analyze_dependencies(&platform().enumerate_drivers().unwrap()).unwrap();
+    let report = analyzer.report();
+    assert_eq!(report.start_order, ["MockDriver1", "1394ohci", "MockDriver2"]);
+    assert!(report.cycles.is_empty());
+    assert!(report.issues.is_empty());
+}